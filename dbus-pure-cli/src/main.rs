@@ -0,0 +1,459 @@
+#![deny(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::manual_let_else,
+	clippy::too_many_lines,
+	clippy::uninlined_format_args,
+	clippy::unnecessary_debug_formatting,
+)]
+
+// A `busctl`-like command-line tool for inspecting and calling services on a message bus.
+//
+// Run with `--help` for usage.
+
+mod print;
+
+const USAGE: &str = "\
+Usage: dbus-pure [--session | --system | --address <address>] <command>
+
+Commands:
+    list
+        List the names on the bus, and the PIDs and unique names of their owners.
+
+    status <name>
+        Show the unique name, PID, UID and command line of the owner of the given name.
+
+    introspect [--xml] <destination> <path>
+        Show the interfaces, methods, signals and properties of the given object.
+
+    call <destination> <path> <interface> <member> [<signature> <argument>...]
+        Call the given method and print its response.
+
+    get-property <destination> <path> <interface> <property>
+        Print the value of the given property.
+
+    set-property <destination> <path> <interface> <property> <signature> <argument>...
+        Set the value of the given property.
+
+    emit <path> <interface> <member> [<signature> <argument>...]
+        Emit the given signal.
+
+    monitor [<name>...]
+        Print all messages on the bus, or only those sent to or from the given names.
+
+Arguments and responses use the same syntax as `busctl`. For example, a method that takes
+a string and an `a{sv}` is called with `sa{sv} hello 2 key1 s value key2 u 5`.
+";
+
+fn main() -> Result<(), Error> {
+	let args: Result<Vec<String>, _> = std::env::args_os().skip(1).map(std::ffi::OsString::into_string).collect();
+	let args = args.map_err(|arg| format!("argument {:?} is not valid UTF-8", arg))?;
+	let mut args: &[String] = &args;
+
+	let mut bus_path = dbus_pure::BusPath::Session;
+
+	let (command, args) = loop {
+		match args {
+			[arg, rest @ ..] if arg == "--session" => {
+				bus_path = dbus_pure::BusPath::Session;
+				args = rest;
+			},
+
+			[arg, rest @ ..] if arg == "--system" => {
+				bus_path = dbus_pure::BusPath::System;
+				args = rest;
+			},
+
+			[arg, address, rest @ ..] if arg == "--address" => {
+				bus_path = dbus_pure::BusPath::Address(address.as_ref());
+				args = rest;
+			},
+
+			[arg, ..] if arg == "--help" || arg == "-h" => {
+				print!("{}", USAGE);
+				return Ok(());
+			},
+
+			[command, rest @ ..] if !command.starts_with('-') => break (command, rest),
+
+			[arg, ..] => return Err(format!("unexpected argument {:?}; run with --help for usage", arg).into()),
+
+			[] => return Err("no command specified; run with --help for usage".into()),
+		}
+	};
+	let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+	let connection = dbus_pure::Connection::new(bus_path, dbus_pure::SaslAuthType::Uid)?;
+	let mut client = dbus_pure::Client::new(connection)?;
+
+	match (&**command, &*args) {
+		("list", []) => list(&mut client),
+
+		("status", [name]) => status(&mut client, name),
+
+		("introspect", ["--xml", destination, path]) => introspect(&mut client, destination, path, true),
+
+		("introspect", [destination, path]) => introspect(&mut client, destination, path, false),
+
+		("call", [destination, path, interface, member, body @ ..]) => {
+			let body = parse_body(body)?;
+
+			let response = client.method_call(bus_name(destination)?, object_path(path)?, interface_name(interface)?, member_name(member)?, body.as_ref())?;
+			if let Some(response) = response {
+				println!("{}", print::Value(&response));
+			}
+
+			Ok(())
+		},
+
+		("get-property", [destination, path, interface, property]) => {
+			let value =
+				client.method_call(
+					bus_name(destination)?,
+					object_path(path)?,
+					interface_name("org.freedesktop.DBus.Properties")?,
+					member_name("Get")?,
+					Some(&dbus_pure::proto::Variant::Tuple {
+						elements: (&[
+							dbus_pure::proto::Variant::String((*interface).into()),
+							dbus_pure::proto::Variant::String((*property).into()),
+						][..]).into(),
+					}),
+				)?;
+			let value = match value {
+				Some(dbus_pure::proto::Variant::Variant(value)) => value,
+				_ => return Err("Get response is not a variant".into()),
+			};
+			println!("{}", print::Value(&value));
+
+			Ok(())
+		},
+
+		("set-property", [destination, path, interface, property, value @ ..]) => {
+			let value = parse_body(value)?.ok_or("no value specified")?;
+
+			let _ =
+				client.method_call(
+					bus_name(destination)?,
+					object_path(path)?,
+					interface_name("org.freedesktop.DBus.Properties")?,
+					member_name("Set")?,
+					Some(&dbus_pure::proto::Variant::Tuple {
+						elements: (&[
+							dbus_pure::proto::Variant::String((*interface).into()),
+							dbus_pure::proto::Variant::String((*property).into()),
+							dbus_pure::proto::Variant::Variant((&value).into()),
+						][..]).into(),
+					}),
+				)?;
+
+			Ok(())
+		},
+
+		("emit", [path, interface, member, body @ ..]) => {
+			let mut signal = dbus_pure::proto::Message::signal(object_path(path)?, interface_name(interface)?, member_name(member)?);
+			signal.body = parse_body(body)?;
+			let _ = client.send(&mut signal)?;
+
+			Ok(())
+		},
+
+		("monitor", names) => monitor(&mut client, names),
+
+		(command, _) => Err(format!("unknown command {:?} or wrong number of arguments; run with --help for usage", command).into()),
+	}
+}
+
+fn list(client: &mut dbus_pure::Client) -> Result<(), Error> {
+	let obj = OrgFreeDesktopDbusObject::bus();
+
+	let names = obj.list_names(client)?;
+	let activatable_names = obj.list_activatable_names(client)?;
+
+	let mut rows = vec![("NAME", "PID".to_owned(), "CONNECTION".to_owned())];
+
+	let all_names: std::collections::BTreeSet<&str> = names.iter().chain(&activatable_names).map(String::as_str).collect();
+	for name in all_names {
+		if !names.iter().any(|running_name| running_name == name) {
+			rows.push((name, "-".to_owned(), "(activatable)".to_owned()));
+			continue;
+		}
+
+		// The name may have lost its owner since it was listed.
+		let owner = ignore_error_reply(obj.get_name_owner(client, name))?;
+		let pid = ignore_error_reply(obj.get_connection_unix_process_id(client, name))?;
+		rows.push((
+			name,
+			pid.map_or_else(|| "-".to_owned(), |pid| pid.to_string()),
+			owner.unwrap_or_else(|| "-".to_owned()),
+		));
+	}
+
+	let name_width = rows.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+	let pid_width = rows.iter().map(|(_, pid, _)| pid.len()).max().unwrap_or(0);
+
+	for (name, pid, owner) in rows {
+		println!("{:name_width$} {:>pid_width$} {}", name, pid, owner, name_width = name_width, pid_width = pid_width);
+	}
+
+	Ok(())
+}
+
+fn status(client: &mut dbus_pure::Client, name: &str) -> Result<(), Error> {
+	let obj = OrgFreeDesktopDbusObject::bus();
+
+	let owner = obj.get_name_owner(client, name)?;
+	let pid = obj.get_connection_unix_process_id(client, name)?;
+	let uid = obj.get_connection_unix_user(client, name)?;
+
+	println!("UniqueName={}", owner);
+	println!("PID={}", pid);
+	println!("UID={}", uid);
+
+	// The process is only visible in /proc if the bus is on the local machine and in the same PID namespace.
+	let proc_dir = std::path::Path::new("/proc").join(pid.to_string());
+	if let Ok(comm) = std::fs::read_to_string(proc_dir.join("comm")) {
+		println!("Comm={}", comm.trim_end());
+	}
+	if let Ok(cmdline) = std::fs::read(proc_dir.join("cmdline")) {
+		let cmdline: Vec<_> = cmdline.split(|&b| b == b'\0').filter(|arg| !arg.is_empty()).map(String::from_utf8_lossy).collect();
+		println!("CommandLine={}", cmdline.join(" "));
+	}
+
+	Ok(())
+}
+
+fn introspect(client: &mut dbus_pure::Client, destination: &str, path: &str, xml: bool) -> Result<(), Error> {
+	fn args_signature<'a>(args: impl Iterator<Item = &'a dbus_pure::introspection::Arg>) -> String {
+		let signature: String = args.map(|arg| arg.r#type.to_string()).collect();
+		if signature.is_empty() { "-".to_owned() } else { signature }
+	}
+
+	let node = client.introspect(bus_name(destination)?, object_path(path)?)?;
+
+	if xml {
+		print!("{}", node);
+		return Ok(());
+	}
+
+	let mut rows = vec![];
+
+	for interface in &node.interfaces {
+		rows.push([interface.name.clone(), "interface".to_owned(), "-".to_owned(), "-".to_owned()]);
+
+		for method in &interface.methods {
+			let in_args = method.args.iter().filter(|arg| arg.direction == dbus_pure::introspection::Direction::In);
+			let out_args = method.args.iter().filter(|arg| arg.direction == dbus_pure::introspection::Direction::Out);
+			rows.push([format!(".{}", method.name), "method".to_owned(), args_signature(in_args), args_signature(out_args)]);
+		}
+
+		for signal in &interface.signals {
+			rows.push([format!(".{}", signal.name), "signal".to_owned(), args_signature(signal.args.iter()), "-".to_owned()]);
+		}
+
+		for property in &interface.properties {
+			let access = match property.access {
+				dbus_pure::introspection::Access::Read => "read",
+				dbus_pure::introspection::Access::ReadWrite => "readwrite",
+				dbus_pure::introspection::Access::Write => "write",
+			};
+			rows.push([format!(".{}", property.name), "property".to_owned(), property.r#type.to_string(), access.to_owned()]);
+		}
+	}
+
+	let header = ["NAME", "TYPE", "SIGNATURE", "RESULT/ACCESS"];
+	let mut widths = [0; 3];
+	for (i, width) in widths.iter_mut().enumerate() {
+		*width = rows.iter().map(|row| row[i].len()).max().unwrap_or(0).max(header[i].len());
+	}
+
+	println!("{:w0$} {:w1$} {:w2$} {}", header[0], header[1], header[2], header[3], w0 = widths[0], w1 = widths[1], w2 = widths[2]);
+	for row in rows {
+		println!("{:w0$} {:w1$} {:w2$} {}", row[0], row[1], row[2], row[3], w0 = widths[0], w1 = widths[1], w2 = widths[2]);
+	}
+
+	Ok(())
+}
+
+fn monitor(client: &mut dbus_pure::Client, names: &[&str]) -> Result<(), Error> {
+	let rules: Vec<String> =
+		names.iter()
+		.flat_map(|name| vec![format!("sender='{}'", name), format!("destination='{}'", name)])
+		.collect();
+
+	let obj = OrgFreeDesktopDbusMonitoringObject {
+		name: dbus_pure::proto::BusName::new("org.freedesktop.DBus")?,
+		path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus")?,
+	};
+	let () = obj.become_monitor(client, &rules, 0)?;
+
+	loop {
+		let message = client.recv()?;
+
+		let sender = message.sender().unwrap_or("-");
+		let destination = message.destination().unwrap_or("-");
+		let serial = message.header.serial;
+		match &message.header.r#type {
+			dbus_pure::proto::MessageType::Error { name, reply_serial } =>
+				println!("error {} -> {} serial={} reply_serial={} error_name={}", sender, destination, serial, reply_serial, name),
+
+			dbus_pure::proto::MessageType::MethodCall { member, path } =>
+				println!(
+					"method call {} -> {} serial={} path={} interface={} member={}",
+					sender, destination, serial, path, message.interface().unwrap_or("-"), member,
+				),
+
+			dbus_pure::proto::MessageType::MethodReturn { reply_serial } =>
+				println!("method return {} -> {} serial={} reply_serial={}", sender, destination, serial, reply_serial),
+
+			dbus_pure::proto::MessageType::Signal { interface, member, path } =>
+				println!("signal {} -> {} serial={} path={} interface={} member={}", sender, destination, serial, path, interface, member),
+		}
+
+		if let Some(body) = &message.body {
+			println!("  {}", print::Value(body));
+		}
+		println!();
+	}
+}
+
+fn bus_name(name: &str) -> Result<dbus_pure::proto::BusName<'_>, Error> {
+	let name = dbus_pure::proto::BusName::new(name).map_err(|err| format!("{:?} is not a valid bus name: {}", name, err))?;
+	Ok(name)
+}
+
+fn interface_name(name: &str) -> Result<dbus_pure::proto::InterfaceName<'_>, Error> {
+	let name = dbus_pure::proto::InterfaceName::new(name).map_err(|err| format!("{:?} is not a valid interface name: {}", name, err))?;
+	Ok(name)
+}
+
+fn member_name(name: &str) -> Result<dbus_pure::proto::MemberName<'_>, Error> {
+	let name = dbus_pure::proto::MemberName::new(name).map_err(|err| format!("{:?} is not a valid member name: {}", name, err))?;
+	Ok(name)
+}
+
+fn object_path(path: &str) -> Result<dbus_pure::proto::ObjectPath<'_>, Error> {
+	let path = dbus_pure::proto::ObjectPath::new(path).map_err(|err| format!("{:?} is not a valid object path: {}", path, err))?;
+	Ok(path)
+}
+
+/// Parse a message body from its signature followed by its arguments, or no body if there are no arguments or the signature is empty.
+fn parse_body<'a>(args: &[&str]) -> Result<Option<dbus_pure::proto::Variant<'a>>, Error> {
+	let (signature, args) = match args {
+		[signature, args @ ..] => (signature, args),
+		[] => return Ok(None),
+	};
+
+	let signature: dbus_pure::proto::Signature = signature.parse().map_err(|err| format!("{:?} is not a valid signature: {}", signature, err))?;
+	let body = dbus_pure::proto::parse_args(&signature, args)?;
+	let body = match body {
+		dbus_pure::proto::Variant::Tuple { elements } if elements.is_empty() => None,
+		body => Some(body),
+	};
+	Ok(body)
+}
+
+/// Convert an error returned by the remote to `None`, and pass through all other errors.
+fn ignore_error_reply<T>(result: Result<T, dbus_pure::MethodCallError>) -> Result<Option<T>, dbus_pure::MethodCallError> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(dbus_pure::MethodCallError::Error(_, _)) => Ok(None),
+		Err(err) => Err(err),
+	}
+}
+
+struct Error(Box<dyn std::error::Error>);
+
+impl<E> From<E> for Error where E: Into<Box<dyn std::error::Error>> {
+	fn from(err: E) -> Self {
+		Error(err.into())
+	}
+}
+
+impl std::fmt::Debug for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{}", self.0)?;
+
+		let mut source = self.0.source();
+		while let Some(err) = source {
+			writeln!(f, "caused by: {}", err)?;
+			source = err.source();
+		}
+
+		Ok(())
+	}
+}
+
+#[dbus_pure_macros::interface("org.freedesktop.DBus")]
+trait OrgFreeDesktopDbusInterface {
+	#[name = "GetConnectionUnixProcessID"]
+	fn get_connection_unix_process_id(name: &str) -> u32;
+
+	#[name = "GetConnectionUnixUser"]
+	fn get_connection_unix_user(name: &str) -> u32;
+
+	#[name = "GetNameOwner"]
+	fn get_name_owner(name: &str) -> String;
+
+	#[name = "ListActivatableNames"]
+	fn list_activatable_names() -> Vec<String>;
+
+	#[name = "ListNames"]
+	fn list_names() -> Vec<String>;
+}
+
+#[dbus_pure_macros::object(OrgFreeDesktopDbusInterface)]
+struct OrgFreeDesktopDbusObject;
+
+impl OrgFreeDesktopDbusObject<'static> {
+	fn bus() -> Self {
+		OrgFreeDesktopDbusObject {
+			name: dbus_pure::proto::BusName::new("org.freedesktop.DBus").expect("hard-coded name is valid"),
+			path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus").expect("hard-coded path is valid"),
+		}
+	}
+}
+
+#[dbus_pure_macros::interface("org.freedesktop.DBus.Monitoring")]
+trait OrgFreeDesktopDbusMonitoringInterface {
+	#[name = "BecomeMonitor"]
+	fn become_monitor(rules: &[String], flags: u32);
+}
+
+#[dbus_pure_macros::object(OrgFreeDesktopDbusMonitoringInterface)]
+struct OrgFreeDesktopDbusMonitoringObject;
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse_body() {
+		assert_eq!(super::parse_body(&[]).unwrap(), None);
+		assert_eq!(super::parse_body(&[""]).unwrap(), None);
+		assert_eq!(super::parse_body(&["u", "5"]).unwrap(), Some(dbus_pure::proto::Variant::U32(5)));
+		assert_eq!(
+			super::parse_body(&["su", "hello", "5"]).unwrap(),
+			Some(dbus_pure::proto::Variant::Tuple {
+				elements: vec![dbus_pure::proto::Variant::String("hello".into()), dbus_pure::proto::Variant::U32(5)].into(),
+			}),
+		);
+
+		assert_eq!(super::parse_body(&["(s", "hello"]).unwrap_err().0.to_string(), r#""(s" is not a valid signature: unexpected end of signature"#);
+		assert_eq!(
+			super::parse_body(&["u", "five"]).unwrap_err().0.to_string(),
+			r#"invalid argument "five" at index 0: expected a value of type "u""#,
+		);
+	}
+
+	#[test]
+	fn test_names() {
+		assert_eq!(super::bus_name("org.freedesktop.DBus").unwrap().as_str(), "org.freedesktop.DBus");
+		assert_eq!(super::interface_name("org.freedesktop.DBus.Properties").unwrap().as_str(), "org.freedesktop.DBus.Properties");
+		assert_eq!(super::member_name("Get").unwrap().as_str(), "Get");
+		assert_eq!(super::object_path("/org/freedesktop/DBus").unwrap().as_str(), "/org/freedesktop/DBus");
+
+		assert!(super::bus_name("org").unwrap_err().0.to_string().starts_with(r#""org" is not a valid bus name: "#));
+		assert!(super::interface_name("org").unwrap_err().0.to_string().starts_with(r#""org" is not a valid interface name: "#));
+		assert!(super::member_name("Get.All").unwrap_err().0.to_string().starts_with(r#""Get.All" is not a valid member name: "#));
+		assert!(super::object_path("foo").unwrap_err().0.to_string().starts_with(r#""foo" is not a valid object path: "#));
+	}
+}
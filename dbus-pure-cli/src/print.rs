@@ -0,0 +1,150 @@
+use dbus_pure::proto::Variant;
+
+/// Displays a value as its signature followed by the value, in the syntax that [`dbus_pure::proto::parse_args`] accepts.
+///
+/// For example, a message body of signature `sas` is displayed as `sas hello 2 a 'b c'`.
+/// Signatures, strings and object paths are single-quoted if they are empty or contain characters that a POSIX shell would interpret,
+/// so that the output can be pasted into a shell command line and splits into the same arguments.
+pub(crate) struct Value<'a, 'b>(pub(crate) &'a Variant<'b>);
+
+impl std::fmt::Display for Value<'_, '_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if matches!(self.0, Variant::Tuple { elements } if elements.is_empty()) {
+			return Ok(());
+		}
+
+		write_word(f, &self.0.inner_signature().to_string())?;
+		f.write_str(" ")?;
+		write_value(f, self.0)
+	}
+}
+
+fn write_value(f: &mut std::fmt::Formatter<'_>, value: &Variant<'_>) -> std::fmt::Result {
+	match value {
+		Variant::Array { element_signature: _, elements } => write_array(f, elements, |f, element| write_value(f, element)),
+		Variant::ArrayBool(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayF64(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayI16(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayI32(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayI64(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayString(elements) => write_array(f, elements, |f, element| write_word(f, element)),
+		Variant::ArrayU8(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayU16(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayU32(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayU64(elements) => write_array(f, elements, |f, element| write!(f, "{}", element)),
+		Variant::ArrayUnixFd(elements) => write_array(f, elements, |f, element| write!(f, "{}", element.0)),
+		Variant::Bool(value) => write!(f, "{}", value),
+		Variant::DictEntry { key, value } => {
+			write_value(f, key)?;
+			f.write_str(" ")?;
+			write_value(f, value)
+		},
+		Variant::F64(value) => write!(f, "{}", value),
+		Variant::I16(value) => write!(f, "{}", value),
+		Variant::I32(value) => write!(f, "{}", value),
+		Variant::I64(value) => write!(f, "{}", value),
+		Variant::Maybe { element_signature: _, value: None } => f.write_str("0"),
+		Variant::Maybe { element_signature: _, value: Some(value) } => {
+			f.write_str("1 ")?;
+			write_value(f, value)
+		},
+		Variant::ObjectPath(value) => write_word(f, value.as_str()),
+		Variant::Signature(value) => write_word(f, &value.to_string()),
+		Variant::String(value) => write_word(f, value),
+		Variant::Struct { fields: elements } |
+		Variant::Tuple { elements } => {
+			for (i, element) in elements.iter().enumerate() {
+				if i > 0 {
+					f.write_str(" ")?;
+				}
+				write_value(f, element)?;
+			}
+			Ok(())
+		},
+		Variant::U8(value) => write!(f, "{}", value),
+		Variant::U16(value) => write!(f, "{}", value),
+		Variant::U32(value) => write!(f, "{}", value),
+		Variant::U64(value) => write!(f, "{}", value),
+		Variant::UnixFd(value) => write!(f, "{}", value.0),
+		Variant::Variant(value) => {
+			write_word(f, &value.inner_signature().to_string())?;
+			f.write_str(" ")?;
+			write_value(f, value)
+		},
+	}
+}
+
+fn write_array<T>(
+	f: &mut std::fmt::Formatter<'_>,
+	elements: &[T],
+	mut write_element: impl FnMut(&mut std::fmt::Formatter<'_>, &T) -> std::fmt::Result,
+) -> std::fmt::Result {
+	write!(f, "{}", elements.len())?;
+	for element in elements {
+		f.write_str(" ")?;
+		write_element(f, element)?;
+	}
+	Ok(())
+}
+
+/// Writes the given argument as a single shell word. It is written as-is if it is not empty and does not contain any characters
+/// that the shell would interpret. Otherwise it is single-quoted, and any single quotes inside it are written as `'\''`.
+fn write_word(f: &mut std::fmt::Formatter<'_>, word: &str) -> std::fmt::Result {
+	let is_plain = !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || "%+,-./:=@_".contains(c));
+	if is_plain {
+		return f.write_str(word);
+	}
+
+	f.write_str("'")?;
+	for (i, part) in word.split('\'').enumerate() {
+		if i > 0 {
+			f.write_str("'\\''")?;
+		}
+		f.write_str(part)?;
+	}
+	f.write_str("'")
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_value() {
+		fn display(signature: &str, args: &[&str]) -> String {
+			let signature: dbus_pure::proto::Signature = signature.parse().unwrap();
+			let value = dbus_pure::proto::parse_args(&signature, args).unwrap();
+			super::Value(&value).to_string()
+		}
+
+		assert_eq!(display("", &[]), "");
+		assert_eq!(display("u", &["5"]), "u 5");
+		assert_eq!(display("sas", &["hello", "2", "a", "b c"]), "sas hello 2 a 'b c'");
+		assert_eq!(display("ss", &["", "it's"]), r"ss '' 'it'\''s'");
+		assert_eq!(display("a{sv}", &["1", "key", "(ob)", "/foo", "yes"]), "'a{sv}' 1 key '(ob)' /foo true");
+		assert_eq!(display("(gsi)", &["a{sv}", "$HOME", "-1"]), "'(gsi)' 'a{sv}' '$HOME' -1");
+	}
+
+	#[test]
+	fn test_value_round_trip() {
+		let signature: dbus_pure::proto::Signature = "sasa{sv}(gsi)".parse().unwrap();
+		let args = &[
+			"", "4", "it's", "a b", "\"quoted\"", "\\n\n", "2", "*", "s", "$HOME", "key", "ao", "1", "/", "a{sv}", "`id`", "0",
+		];
+		let value = dbus_pure::proto::parse_args(&signature, args).unwrap();
+		let displayed = super::Value(&value).to_string();
+
+		// Let the shell split the displayed value back into arguments.
+		let output =
+			std::process::Command::new("sh")
+			.arg("-c")
+			.arg(format!("printf '%s\\0' {}", displayed))
+			.output()
+			.unwrap();
+		assert!(output.status.success());
+		let output = String::from_utf8(output.stdout).unwrap();
+		let words: Vec<&str> = output.strip_suffix('\0').unwrap().split('\0').collect();
+
+		assert_eq!(words[0], signature.to_string());
+		assert_eq!(words[1..], args[..]);
+		assert_eq!(dbus_pure::proto::parse_args(&signature, &words[1..]).unwrap(), value);
+	}
+}
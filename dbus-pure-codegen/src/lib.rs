@@ -0,0 +1,381 @@
+#![deny(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::must_use_candidate,
+	clippy::uninlined_format_args,
+)]
+
+//! Generates `#[dbus_pure_macros::interface]` traits and `#[dbus_pure_macros::object]` structs from D-Bus introspection XML.
+//!
+//! This can be used as a library, such as from a build script, or as the `dbus-pure-codegen` binary which reads the XML from a file or stdin
+//! and writes the code to stdout.
+//!
+//! - One trait is generated for every interface of the node. The trait is named after the interface, such as `OrgFreedesktopDBusInterface`
+//!   for `org.freedesktop.DBus`, and has one `fn` for every method of the interface.
+//!
+//! - Methods are named in snake case, such as `get_name_owner` for `GetNameOwner`. The original name is kept in the `#[name = "..."]` attribute.
+//!   Methods named `Name` and `Path` are emitted as `name_` and `path_` so that they do not conflict with `dbus_pure::proto::Object`.
+//!   Likewise, `_` is appended to the names of methods and parameters until they are unique, such as `get_id_` for `GetId` after `GetID`,
+//!   or `client_` for a parameter named `client` that would conflict with the `dbus_pure::Client` parameter.
+//!
+//! - Parameters are borrowed, such as `&str` for `s`, and return values are owned, such as `String` for `s`. Arrays map to slices and `Vec`s,
+//!   dicts map to `std::collections::HashMap`, structs map to tuples, and variants map to `dbus_pure::proto::Variant`.
+//!   Methods with more than one output argument return a tuple.
+//!
+//! - `org.gtk.GDBus.DocString` annotations are emitted as doc comments, and `org.freedesktop.DBus.Deprecated` annotations as `#[deprecated]`.
+//!
+//! - Each trait is followed by a `const` that asserts at compile time that the type of every argument has the argument's signature,
+//!   using `dbus_pure::proto::AsVariant::SIGNATURE_STR`.
+//!
+//! - Signals and properties are not emitted, since the macros do not support them.
+//!
+//! The generated code refers to the `dbus_pure`, `dbus_pure_macros` and `serde` crates, so the crate that includes it must depend on them.
+//!
+//! # Example
+//!
+//! In `build.rs`:
+//!
+//! ```rust,no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #
+//! let xml = std::fs::read_to_string("org.example.Foo.xml")?;
+//! let node: dbus_pure::introspection::Node = xml.parse()?;
+//! let code = dbus_pure_codegen::generate(&node, Some("FooObject"));
+//!
+//! let out_dir: std::path::PathBuf = std::env::var_os("OUT_DIR").ok_or("OUT_DIR is not set")?.into();
+//! std::fs::write(out_dir.join("foo.rs"), code)?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Then in the crate, `include!(concat!(env!("OUT_DIR"), "/foo.rs"));`
+
+use dbus_pure::introspection;
+use dbus_pure::proto::Signature;
+
+/// Generate the code for the interfaces of the given node.
+///
+/// If `object_name` is set, a struct with that name that implements all the interfaces is generated as well.
+pub fn generate(node: &introspection::Node, object_name: Option<&str>) -> String {
+	Code { node, object_name }.to_string()
+}
+
+struct Code<'a> {
+	node: &'a introspection::Node,
+	object_name: Option<&'a str>,
+}
+
+impl std::fmt::Display for Code<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let Code { node, object_name } = self;
+
+		for (i, interface) in node.interfaces.iter().enumerate() {
+			if i > 0 {
+				f.write_str("\n")?;
+			}
+
+			write_interface(f, interface)?;
+		}
+
+		if let Some(object_name) = object_name {
+			if !node.interfaces.is_empty() {
+				f.write_str("\n")?;
+			}
+
+			let trait_names: Vec<_> = node.interfaces.iter().map(|interface| trait_name(&interface.name)).collect();
+
+			f.write_str("/// An object that implements ")?;
+			match &*node.interfaces {
+				[] => f.write_str("no interfaces.\n")?,
+				[interface] => writeln!(f, "the `{}` interface.", interface.name)?,
+				interfaces => {
+					let names: Vec<_> = interfaces.iter().map(|interface| format!("`{}`", interface.name)).collect();
+					writeln!(f, "the {} interfaces.", names.join(", "))?;
+				},
+			}
+			writeln!(f, "#[dbus_pure_macros::object({})]", trait_names.join(", "))?;
+			writeln!(f, "pub struct {};", object_name)?;
+		}
+
+		Ok(())
+	}
+}
+
+fn write_interface(f: &mut std::fmt::Formatter<'_>, interface: &introspection::Interface) -> std::fmt::Result {
+	if !write_doc(f, "", &interface.annotations)? {
+		writeln!(f, "/// The `{}` interface.", interface.name)?;
+	}
+	write_deprecated(f, "", &interface.annotations)?;
+	writeln!(f, "#[dbus_pure_macros::interface({:?})]", interface.name)?;
+	writeln!(f, "pub trait {} {{", trait_name(&interface.name))?;
+
+	let mut fn_names = vec!["name".to_owned(), "path".to_owned()];
+
+	for (i, method) in interface.methods.iter().enumerate() {
+		if i > 0 {
+			f.write_str("\n")?;
+		}
+
+		write_method(f, method, &mut fn_names)?;
+	}
+
+	f.write_str("}\n")?;
+
+	write_signature_checks(f, interface)
+}
+
+/// Writes the `fn` for the given method. `fn_names` holds the names of the interface's `fn`s that have already been written.
+fn write_method(f: &mut std::fmt::Formatter<'_>, method: &introspection::Method, fn_names: &mut Vec<String>) -> std::fmt::Result {
+	let _ = write_doc(f, "\t", &method.annotations)?;
+	write_deprecated(f, "\t", &method.annotations)?;
+	writeln!(f, "\t#[name = {:?}]", method.name)?;
+
+	let fn_name = unique_ident(ident(&snake_case(&method.name)), fn_names);
+
+	// The macro adds a `client` parameter
+	let mut param_names = vec!["client".to_owned()];
+
+	let params: Vec<_> =
+		method.args.iter()
+		.filter(|arg| arg.direction == introspection::Direction::In)
+		.enumerate()
+		.map(|(i, arg)| {
+			let name = match arg.name.as_deref().map(snake_case) {
+				Some(name) if !name.is_empty() => ident(&name),
+				_ => format!("arg{}", i),
+			};
+			let name = unique_ident(name, &mut param_names);
+			format!("{}: {}", name, param_type(&arg.r#type))
+		})
+		.collect();
+
+	let return_types: Vec<_> =
+		method.args.iter()
+		.filter(|arg| arg.direction == introspection::Direction::Out)
+		.map(|arg| owned_type(&arg.r#type))
+		.collect();
+
+	write!(f, "\tfn {}({})", fn_name, params.join(", "))?;
+	match &*return_types {
+		[] => (),
+		[return_type] => write!(f, " -> {}", return_type)?,
+		return_types => write!(f, " -> ({})", return_types.join(", "))?,
+	}
+	f.write_str(";\n")
+}
+
+/// Writes a `const` that checks at compile time that the type of every argument of the interface's methods has the argument's signature.
+fn write_signature_checks(f: &mut std::fmt::Formatter<'_>, interface: &introspection::Interface) -> std::fmt::Result {
+	let mut checks: Vec<(String, String)> = vec![];
+	for arg in interface.methods.iter().flat_map(|method| &method.args) {
+		let check = (owned_type(&arg.r#type), arg.r#type.to_string());
+		if !checks.contains(&check) {
+			checks.push(check);
+		}
+	}
+
+	if checks.is_empty() {
+		return Ok(());
+	}
+
+	f.write_str("const _: () = {\n")?;
+	for (ty, signature) in checks {
+		writeln!(f, "\tassert!(dbus_pure::proto::SignatureStr::matches(<{} as dbus_pure::proto::AsVariant>::SIGNATURE_STR, {:?}));", ty, signature)?;
+	}
+	f.write_str("};\n")
+}
+
+/// Writes the `org.gtk.GDBus.DocString` annotation as a doc comment. Returns `false` if there is no such annotation.
+fn write_doc(f: &mut std::fmt::Formatter<'_>, indent: &str, annotations: &[introspection::Annotation]) -> Result<bool, std::fmt::Error> {
+	let doc = annotations.iter().find(|annotation| annotation.name == "org.gtk.GDBus.DocString");
+	let doc = match doc {
+		Some(doc) => doc.value.trim(),
+		None => return Ok(false),
+	};
+
+	for line in doc.lines() {
+		let line = line.trim();
+		if line.is_empty() {
+			writeln!(f, "{}///", indent)?;
+		}
+		else {
+			writeln!(f, "{}/// {}", indent, line)?;
+		}
+	}
+
+	Ok(true)
+}
+
+fn write_deprecated(f: &mut std::fmt::Formatter<'_>, indent: &str, annotations: &[introspection::Annotation]) -> std::fmt::Result {
+	let is_deprecated =
+		annotations.iter()
+		.any(|annotation| annotation.name == "org.freedesktop.DBus.Deprecated" && annotation.value == "true");
+	if is_deprecated {
+		writeln!(f, "{}#[deprecated]", indent)?;
+	}
+
+	Ok(())
+}
+
+/// The type of a parameter with the given signature. Types that are not `Copy` are borrowed.
+fn param_type(signature: &Signature) -> String {
+	match signature {
+		Signature::Array { element } => match &**element {
+			Signature::DictEntry { .. } => format!("&{}", owned_type(signature)),
+			element => format!("&[{}]", owned_type(element)),
+		},
+		Signature::ObjectPath => "&dbus_pure::proto::ObjectPath<'_>".to_owned(),
+		Signature::Signature => "&dbus_pure::proto::Signature".to_owned(),
+		Signature::String => "&str".to_owned(),
+		Signature::Struct { .. } => format!("&{}", owned_type(signature)),
+		Signature::Variant => "&dbus_pure::proto::Variant<'_>".to_owned(),
+		signature => owned_type(signature),
+	}
+}
+
+/// The type of a return value with the given signature, or of an element of a container.
+fn owned_type(signature: &Signature) -> String {
+	match signature {
+		Signature::Array { element } => match &**element {
+			Signature::DictEntry { key, value } => format!("std::collections::HashMap<{}, {}>", owned_type(key), owned_type(value)),
+			element => format!("Vec<{}>", owned_type(element)),
+		},
+		Signature::Bool => "bool".to_owned(),
+		Signature::DictEntry { key, value } => format!("({}, {})", owned_type(key), owned_type(value)),
+		Signature::F64 => "f64".to_owned(),
+		Signature::I16 => "i16".to_owned(),
+		Signature::I32 => "i32".to_owned(),
+		Signature::I64 => "i64".to_owned(),
+		Signature::Maybe { element } => format!("Option<{}>", owned_type(element)),
+		Signature::ObjectPath => "dbus_pure::proto::ObjectPath<'static>".to_owned(),
+		Signature::Signature => "dbus_pure::proto::Signature".to_owned(),
+		Signature::String => "String".to_owned(),
+		Signature::Struct { fields } | Signature::Tuple { elements: fields } => {
+			let fields: Vec<_> = fields.iter().map(owned_type).collect();
+			if fields.len() == 1 {
+				format!("({},)", fields[0])
+			}
+			else {
+				format!("({})", fields.join(", "))
+			}
+		},
+		Signature::U8 => "u8".to_owned(),
+		Signature::U16 => "u16".to_owned(),
+		Signature::U32 => "u32".to_owned(),
+		Signature::U64 => "u64".to_owned(),
+		Signature::UnixFd => "dbus_pure::proto::UnixFd".to_owned(),
+		Signature::Variant => "dbus_pure::proto::Variant<'static>".to_owned(),
+	}
+}
+
+/// The name of the trait for the given interface, such as `OrgFreedesktopDBusPropertiesInterface` for `org.freedesktop.DBus.Properties`
+fn trait_name(interface_name: &str) -> String {
+	let mut result: String = words(interface_name).iter().map(|word| capitalize(word)).collect();
+	result.push_str("Interface");
+	result
+}
+
+fn snake_case(name: &str) -> String {
+	let words: Vec<_> = words(name).iter().map(|word| word.to_lowercase()).collect();
+	words.join("_")
+}
+
+fn capitalize(word: &str) -> String {
+	let mut chars = word.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+/// Split the given name into words, at separators like `.` and `_`, and at case changes.
+///
+/// A run of uppercase letters is one word, except for its last letter if that is followed by a lowercase letter,
+/// so `GetConnectionUnixProcessID` is split into `Get`, `Connection`, `Unix`, `Process`, `ID` and `DBusName` into `D`, `Bus`, `Name`.
+fn words(name: &str) -> Vec<&str> {
+	let mut result = vec![];
+
+	for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+		let chars: Vec<_> = part.char_indices().collect();
+		let mut start = 0;
+
+		for (i, &(pos, c)) in chars.iter().enumerate().skip(1) {
+			let prev = chars[i - 1].1;
+			let next = chars.get(i + 1).map(|&(_, c)| c);
+
+			let is_boundary =
+				c.is_ascii_uppercase() &&
+				(prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && matches!(next, Some(next) if next.is_ascii_lowercase())));
+			if is_boundary {
+				result.push(&part[start..pos]);
+				start = pos;
+			}
+		}
+
+		if start < part.len() {
+			result.push(&part[start..]);
+		}
+	}
+
+	result
+}
+
+/// Escape the given identifier if it's a keyword.
+fn ident(name: &str) -> String {
+	const KEYWORDS: &[&str] = &[
+		"abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false",
+		"final", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+		"return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+	];
+
+	// These cannot be raw identifiers
+	const RESERVED: &[&str] = &["crate", "self", "super", "Self"];
+
+	if KEYWORDS.contains(&name) {
+		format!("r#{}", name)
+	}
+	else if RESERVED.contains(&name) {
+		format!("{}_", name)
+	}
+	else if name.starts_with(|c: char| c.is_ascii_digit()) {
+		format!("_{}", name)
+	}
+	else {
+		name.to_owned()
+	}
+}
+
+/// Append `_` to the given identifier until it's not one of the `used` identifiers, then add it to them.
+fn unique_ident(mut ident: String, used: &mut Vec<String>) -> String {
+	while used.contains(&ident) {
+		// The identifier is no longer a keyword once `_` is appended, so it does not need to be raw.
+		if let Some(name) = ident.strip_prefix("r#") {
+			ident = name.to_owned();
+		}
+		ident.push('_');
+	}
+
+	used.push(ident.clone());
+	ident
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_names() {
+		assert_eq!(super::snake_case("GetConnectionUnixProcessID"), "get_connection_unix_process_id");
+		assert_eq!(super::snake_case("ListNames"), "list_names");
+		assert_eq!(super::snake_case("interface_name"), "interface_name");
+		assert_eq!(super::trait_name("org.freedesktop.DBus"), "OrgFreedesktopDBusInterface");
+		assert_eq!(super::trait_name("org.mpris.MediaPlayer2.Player"), "OrgMprisMediaPlayer2PlayerInterface");
+		assert_eq!(super::ident("type"), "r#type");
+		assert_eq!(super::ident("self"), "self_");
+
+		let mut used = vec!["name".to_owned()];
+		assert_eq!(super::unique_ident("name".to_owned(), &mut used), "name_");
+		assert_eq!(super::unique_ident("name".to_owned(), &mut used), "name__");
+		assert_eq!(super::unique_ident("r#type".to_owned(), &mut used), "r#type");
+		assert_eq!(super::unique_ident("r#type".to_owned(), &mut used), "type_");
+	}
+}
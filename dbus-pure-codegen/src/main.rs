@@ -0,0 +1,73 @@
+#![deny(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::single_match_else,
+	clippy::uninlined_format_args,
+	clippy::unnecessary_debug_formatting,
+)]
+
+// Reads D-Bus introspection XML from the given file, or from stdin if no file is given, and writes the generated code to stdout.
+//
+// Usage: dbus-pure-codegen [--object <name>] [<file>]
+
+fn main() -> Result<(), Error> {
+	let mut object_name = None;
+	let mut file = None;
+
+	let mut args = std::env::args_os().skip(1);
+	while let Some(arg) = args.next() {
+		if arg == "--object" {
+			let value = args.next().ok_or("--object requires a value")?;
+			let value = value.into_string().map_err(|_| "--object value is not valid UTF-8")?;
+			object_name = Some(value);
+		}
+		else if arg == "--help" || arg == "-h" {
+			println!("Usage: dbus-pure-codegen [--object <name>] [<file>]");
+			return Ok(());
+		}
+		else if file.is_none() {
+			file = Some(std::path::PathBuf::from(arg));
+		}
+		else {
+			return Err(format!("unexpected argument {:?}", arg).into());
+		}
+	}
+
+	let xml = match file {
+		Some(file) => std::fs::read_to_string(file)?,
+		None => {
+			let mut xml = String::new();
+			let _ = std::io::Read::read_to_string(&mut std::io::stdin(), &mut xml)?;
+			xml
+		},
+	};
+
+	let node: dbus_pure::introspection::Node = xml.parse()?;
+
+	let code = dbus_pure_codegen::generate(&node, object_name.as_deref());
+	print!("{}", code);
+
+	Ok(())
+}
+
+struct Error(Box<dyn std::error::Error>);
+
+impl<E> From<E> for Error where E: Into<Box<dyn std::error::Error>> {
+	fn from(err: E) -> Self {
+		Error(err.into())
+	}
+}
+
+impl std::fmt::Debug for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{}", self.0)?;
+
+		let mut source = self.0.source();
+		while let Some(err) = source {
+			writeln!(f, "caused by: {}", err)?;
+			source = err.source();
+		}
+
+		Ok(())
+	}
+}
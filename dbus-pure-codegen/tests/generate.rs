@@ -0,0 +1,60 @@
+#![deny(rust_2018_idioms, warnings)]
+
+#[test]
+fn generate() {
+	let xml = r#"
+		<node>
+			<interface name="org.example.Foo">
+				<annotation name="org.gtk.GDBus.DocString" value="An example interface."/>
+				<method name="GetAll">
+					<arg name="interface_name" type="s" direction="in"/>
+					<arg name="type" type="u" direction="in"/>
+					<arg name="properties" type="a{sv}" direction="out"/>
+				</method>
+				<method name="Name">
+					<annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+					<arg type="ao" direction="in"/>
+					<arg type="(si)" direction="out"/>
+					<arg type="ay" direction="out"/>
+				</method>
+				<method name="Ping"/>
+				<method name="GetID">
+					<arg name="FooBar" type="s" direction="in"/>
+					<arg name="foo_bar" type="s" direction="in"/>
+					<arg name="arg3" type="u" direction="in"/>
+					<arg type="u" direction="in"/>
+					<arg name="client" type="s" direction="in"/>
+					<arg name="_" type="s" direction="in"/>
+				</method>
+				<method name="GetId">
+					<arg name="type" type="s" direction="in"/>
+					<arg name="Type" type="s" direction="in"/>
+				</method>
+				<method name="Name_"/>
+			</interface>
+			<interface name="org.example.Bar">
+				<method name="Echo">
+					<arg name="value" type="v" direction="in"/>
+					<arg name="signature" type="g" direction="in"/>
+					<arg name="value" type="v" direction="out"/>
+				</method>
+			</interface>
+		</node>
+	"#;
+	let node: dbus_pure::introspection::Node = xml.parse().unwrap();
+
+	let code = dbus_pure_codegen::generate(&node, Some("FooObject"));
+
+	// Names that collide are disambiguated
+	assert!(code.contains("fn get_id(foo_bar: &str, foo_bar_: &str, arg3: u32, arg3_: u32, client_: &str, arg5: &str);"), "{}", code);
+	assert!(code.contains("fn get_id_(r#type: &str, type_: &str);"), "{}", code);
+	assert!(code.contains("fn name_("), "{}", code);
+	assert!(code.contains("fn name__();"), "{}", code);
+
+	// Compile the generated code as its own crate, the same way a user would `include!` it.
+	let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("generate.rs");
+	std::fs::write(&path, format!("{}\nfn main() {{\n}}\n", code)).unwrap();
+
+	let t = trybuild::TestCases::new();
+	t.pass(path);
+}
@@ -8,70 +8,458 @@ pub(super) fn run(input: proc_macro::TokenStream) -> Result<proc_macro2::TokenSt
 	let input: syn::DeriveInput = syn::parse2(input)?;
 
 	let ident = &input.ident;
-	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-	let (signature_body, as_variant_body) = match input.data {
-		syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(syn::FieldsNamed { named: fields, .. }), .. }) => {
-			// Variant::Struct
+	let super::attrs::ContainerAttrs { repr, dict } = super::attrs::ContainerAttrs::parse(&input.attrs)?;
 
-			let fields_signature =
-				fields.iter()
-				.map(|syn::Field { ty, .. }| quote::quote! { <#ty as dbus_pure_proto::AsVariant>::signature() });
+	// The bounds required by the impl on the types of the fields
+	let mut predicates = vec![];
 
-			let fields_as_variant =
-				fields.iter()
-				.map(|syn::Field { ident, .. }| quote::quote! { <_ as dbus_pure_proto::AsVariant>::as_variant(&self.#ident) });
+	// The compile-time checks of the `#[dbus(signature = "...")]` attributes on the fields
+	let mut checks = vec![];
+
+	let (signature_str, signature_body, as_variant_body, try_as_variant_body) = match &input.data {
+		syn::Data::Struct(_) if repr.is_some() =>
+			return Err(r#"`#[dbus(repr = "...")]` can only be used on enums"#).spanning(&tokens),
+
+		syn::Data::Struct(syn::DataStruct { fields, .. }) if dict.is_some() => {
+			let parsed = super::attrs::fields(fields, true)?;
+			predicates.extend(parsed.iter().filter_map(field_predicate));
+			checks.extend(fields_signature_checks(&parsed, &input.generics)?);
+			dict_bodies(&parsed)
+		},
+
+		syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+			let parsed = super::attrs::fields(fields, false)?;
+			let is_newtype = super::attrs::is_newtype(fields, &parsed);
+			predicates.extend(parsed.iter().filter_map(field_predicate));
+			checks.extend(fields_signature_checks(&parsed, &input.generics)?);
+			fields_bodies(&parsed, is_newtype, |_, member| quote::quote! { &self.#member })
+		},
+
+		syn::Data::Enum(_) if dict.is_some() =>
+			return Err("`#[dbus(dict)]` can only be used on structs").spanning(&tokens),
+
+		syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+			let repr = repr.ok_or(r#"#[derive(AsVariant)] on an enum requires a `#[dbus(repr = "...")]` attribute"#).spanning(&tokens)?;
+			if variants.is_empty() {
+				return Err("#[derive(AsVariant)] cannot be used on enums with no variants").spanning(&tokens);
+			}
+
+			let variants = super::attrs::enum_variants(&repr, variants)?;
+
+			for super::attrs::EnumVariant { fields, .. } in &variants {
+				predicates.extend(fields.iter().filter_map(field_predicate));
+				checks.extend(fields_signature_checks(fields, &input.generics)?);
+			}
+
+			enum_bodies(ident, &repr, &variants)
+		},
+
+		syn::Data::Union(_) =>
+			return Err("#[derive(AsVariant)] can only be used on structs and enums").spanning(&tokens),
+	};
+
+	let mut generics = input.generics.clone();
+	generics.make_where_clause().predicates.extend(predicates);
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	let result = quote::quote! {
+		impl #impl_generics dbus_pure_proto::AsVariant for #ident #ty_generics #where_clause {
+			const SIGNATURE_STR: Option<&'static str> = #signature_str;
+
+			fn signature() -> dbus_pure_proto::Signature {
+				#signature_body
+			}
+
+			fn as_variant<'__a>(&'__a self) -> dbus_pure_proto::Variant<'__a> {
+				#as_variant_body
+			}
+
+			fn try_as_variant<'__a>(&'__a self) -> Result<dbus_pure_proto::Variant<'__a>, dbus_pure_proto::ToVariantError> {
+				let variant = #try_as_variant_body;
+				Ok(variant)
+			}
+		}
+
+		#(#checks)*
+	};
+
+	Ok(result)
+}
+
+fn enum_bodies(
+	ident: &syn::Ident,
+	repr: &super::attrs::Repr,
+	variants: &[super::attrs::EnumVariant<'_>],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	match repr {
+		super::attrs::Repr::Integer { name, ty } => {
+			// Variant::U32 etc, using the discriminant of each variant
+
+			let arms =
+				variants.iter()
+				.map(|super::attrs::EnumVariant { ident: variant_ident, .. }| quote::quote! { #ident::#variant_ident => #ident::#variant_ident as #ty });
+
+			let as_variant = quote::quote! {
+				dbus_pure_proto::Variant::#name(match self {
+					#(#arms ,)*
+				})
+			};
 
 			(
 				quote::quote! {
-					dbus_pure_proto::Signature::Struct {
-						fields: vec![#(#fields_signature ,)*],
-					}
+					<#ty as dbus_pure_proto::AsVariant>::SIGNATURE_STR
 				},
 				quote::quote! {
-					dbus_pure_proto::Variant::Struct {
-						fields: vec![#(#fields_as_variant ,)*].into(),
-					}
+					dbus_pure_proto::Signature::#name
 				},
+				as_variant.clone(),
+				as_variant,
 			)
 		},
 
-		syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Unnamed(syn::FieldsUnnamed { unnamed: fields, .. }), .. }) if fields.len() == 1 => {
-			// Delegate to the wrapped type's impl
+		super::attrs::Repr::String => {
+			// Variant::String, using the name of each variant
 
-			let syn::Field { ty, .. } = fields.into_iter().next().unwrap();
+			let arms =
+				variants.iter()
+				.map(|super::attrs::EnumVariant { ident: variant_ident, name, .. }| quote::quote! { #ident::#variant_ident => #name });
+
+			let as_variant = quote::quote! {
+				dbus_pure_proto::Variant::String(std::borrow::Cow::Borrowed(match self {
+					#(#arms ,)*
+				}))
+			};
 
 			(
 				quote::quote! {
-					<#ty as dbus_pure_proto::AsVariant>::signature()
+					Some("s")
 				},
 				quote::quote! {
-					<dbus_pure_proto::AsVariant>::as_variant(&self.0)
+					dbus_pure_proto::Signature::String
 				},
+				as_variant.clone(),
+				as_variant,
 			)
 		},
 
-		syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Unnamed(..), .. }) =>
-			return Err("#[derive(AsVariant)] cannot be used on tuple structs with one field").spanning(&tokens),
+		super::attrs::Repr::Tagged => {
+			// Variant::Struct of the name of the variant and a Variant::Variant of its fields, ie `(sv)`
 
-		syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Unit, .. }) =>
-			return Err("#[derive(AsVariant)] cannot be used on unit structs").spanning(&tokens),
+			let arms = |fallible: bool| variants.iter().map(move |super::attrs::EnumVariant { ident: variant_ident, name, fields, is_newtype }| {
+				let bindings: Vec<_> = (0..fields.len()).map(|i| quote::format_ident!("__field{}", i)).collect();
 
-		syn::Data::Enum(_) | syn::Data::Union(_) =>
-			return Err("#[derive(AsVariant)] can only be used on structs").spanning(&tokens),
-	};
+				let pattern_fields =
+					fields.iter().zip(&bindings)
+					.filter(|(super::attrs::Field { conversion, .. }, _)| !matches!(conversion, super::attrs::Conversion::Skip))
+					.map(|(super::attrs::Field { member, .. }, binding)| quote::quote! { #member: #binding });
+				let pattern = quote::quote! { #ident::#variant_ident { #(#pattern_fields ,)* .. } };
 
-	let result = quote::quote! {
-		impl #impl_generics dbus_pure_proto::AsVariant for #ident #ty_generics #where_clause {
-			fn signature() -> dbus_pure_proto::Signature {
-				#signature_body
+				let (_, _, value, try_value) = fields_bodies(fields, *is_newtype, |i, _| {
+					let binding = &bindings[i];
+					quote::quote! { #binding }
+				});
+				let value = if fallible { try_value } else { value };
+
+				quote::quote! {
+					#pattern => dbus_pure_proto::Variant::Struct {
+						fields: vec![
+							dbus_pure_proto::Variant::String(std::borrow::Cow::Borrowed(#name)),
+							dbus_pure_proto::Variant::Variant(Box::new(#value).into()),
+						].into(),
+					}
+				}
+			});
+
+			let as_variant_arms = arms(false);
+			let try_as_variant_arms = arms(true);
+
+			(
+				quote::quote! {
+					Some("(sv)")
+				},
+				quote::quote! {
+					dbus_pure_proto::Signature::Struct {
+						fields: vec![dbus_pure_proto::Signature::String, dbus_pure_proto::Signature::Variant],
+					}
+				},
+				quote::quote! {
+					match self {
+						#(#as_variant_arms ,)*
+					}
+				},
+				quote::quote! {
+					match self {
+						#(#try_as_variant_arms ,)*
+					}
+				},
+			)
+		},
+	}
+}
+
+/// The value of `SIGNATURE_STR`, the bodies of `signature()` and `as_variant()`, and the value that `try_as_variant()` returns in `Ok`,
+/// for the given fields of a struct or enum variant.
+///
+/// `value` returns an expression of a reference to the field with the given index and member.
+fn fields_bodies(
+	fields: &[super::attrs::Field<'_>],
+	is_newtype: bool,
+	value: impl Fn(usize, &syn::Member) -> proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	let fields: Vec<_> =
+		fields.iter().enumerate()
+		.filter(|(_, super::attrs::Field { conversion, .. })| !matches!(conversion, super::attrs::Conversion::Skip))
+		.collect();
+
+	if is_newtype {
+		// Delegate to the wrapped type's impl
+
+		let (i, field) = fields[0];
+		let value = value(i, &field.member);
+		return (field_signature_str(field), field_signature(field), field_as_variant(field, &value, false), field_as_variant(field, &value, true));
+	}
+
+	if fields.is_empty() {
+		// Variant::Tuple with no elements, ie `()`
+
+		return (
+			quote::quote! {
+				<() as dbus_pure_proto::AsVariant>::SIGNATURE_STR
+			},
+			quote::quote! {
+				<() as dbus_pure_proto::AsVariant>::signature()
+			},
+			quote::quote! {
+				<() as dbus_pure_proto::AsVariant>::as_variant(&())
+			},
+			quote::quote! {
+				<() as dbus_pure_proto::AsVariant>::as_variant(&())
+			},
+		);
+	}
+
+	// Variant::Struct
+
+	let fields_signature_str = fields.iter().map(|(_, field)| field_signature_str(field));
+
+	let fields_signature = fields.iter().map(|(_, field)| field_signature(field));
+
+	let fields_as_variant = fields.iter().map(|&(i, field)| field_as_variant(field, &value(i, &field.member), false));
+
+	let fields_try_as_variant = fields.iter().map(|&(i, field)| field_as_variant(field, &value(i, &field.member), true));
+
+	(
+		quote::quote! {
+			dbus_pure_proto::SignatureStr::get(&dbus_pure_proto::SignatureStr::concat(&[Some("("), #(#fields_signature_str ,)* Some(")")]))
+		},
+		quote::quote! {
+			dbus_pure_proto::Signature::Struct {
+				fields: vec![#(#fields_signature ,)*],
+			}
+		},
+		quote::quote! {
+			dbus_pure_proto::Variant::Struct {
+				fields: vec![#(#fields_as_variant ,)*].into(),
+			}
+		},
+		quote::quote! {
+			dbus_pure_proto::Variant::Struct {
+				fields: vec![#(#fields_try_as_variant ,)*].into(),
 			}
+		},
+	)
+}
 
-			fn as_variant<'__a>(&'__a self) -> dbus_pure_proto::Variant<'__a> {
-				#as_variant_body
+/// The value of `SIGNATURE_STR`, the bodies of `signature()` and `as_variant()`, and the value that `try_as_variant()` returns in `Ok`,
+/// for the given fields of a `#[dbus(dict)]` struct.
+fn dict_bodies(fields: &[super::attrs::Field<'_>]) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	// Variant::Array of Variant::DictEntry of the key of each field that is `Some`, and a Variant::Variant of its value
+
+	let entries = |fallible: bool| fields.iter().filter_map(move |field| {
+		let super::attrs::Field { member, ty, conversion, key } = field;
+		match conversion {
+			super::attrs::Conversion::Skip => None,
+
+			// Spanned on the type of the field so that a type that isn't a map of strings to variants is reported there
+			super::attrs::Conversion::Flatten => Some(quote::quote_spanned! { syn::spanned::Spanned::span(ty) =>
+				std::iter::IntoIterator::into_iter(&self.#member)
+				.map(|(key, value)| (
+					std::borrow::Cow::Borrowed(std::convert::AsRef::<str>::as_ref(key)),
+					dbus_pure_proto::Variant::borrowed(value),
+				))
+			}),
+
+			// A `match` rather than `Option::map` so that `try_as_variant()` can use `?` in the value
+			_ => {
+				let value = field_as_variant(field, &quote::quote! { value }, fallible);
+				Some(quote::quote! {
+					match &self.#member {
+						Some(value) => Some((std::borrow::Cow::Borrowed(#key), #value)),
+						None => None,
+					}
+				})
+			},
+		}
+	});
+
+	(
+		quote::quote! {
+			Some("a{sv}")
+		},
+		quote::quote! {
+			dbus_pure_proto::Signature::Array {
+				element: Box::new(dbus_pure_proto::Signature::DictEntry {
+					key: Box::new(dbus_pure_proto::Signature::String),
+					value: Box::new(dbus_pure_proto::Signature::Variant),
+				}),
+			}
+		},
+		dict_as_variant(entries(false)),
+		dict_as_variant(entries(true)),
+	)
+}
+
+fn dict_as_variant(entries: impl Iterator<Item = proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+	quote::quote! {
+		dbus_pure_proto::dict_as_variant(
+			std::iter::empty()
+			#(.chain(#entries))*
+		)
+	}
+}
+
+fn field_signature_str(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::AsVariant>::SIGNATURE_STR },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields have no signature"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are only used in dicts"),
+		super::attrs::Conversion::With(_) => quote::quote! { None },
+		super::attrs::Conversion::Signature(_, signature) => {
+			let signature = signature.to_string();
+			quote::quote! { Some(#signature) }
+		},
+	}
+}
+
+fn field_signature(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::AsVariant>::signature() },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields have no signature"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are only used in dicts"),
+		super::attrs::Conversion::With(module) => quote::quote! { #module::signature() },
+		super::attrs::Conversion::Signature(_, signature) => super::signature::expr(signature),
+	}
+}
+
+/// An expression of the variant of the field. If `fallible`, it's an expression for `try_as_variant()` that returns errors with `?`.
+fn field_as_variant(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>, value: &proc_macro2::TokenStream, fallible: bool) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default if fallible => quote::quote! { <#ty as dbus_pure_proto::AsVariant>::try_as_variant(#value)? },
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::AsVariant>::as_variant(#value) },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields are not converted"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are converted by dict_bodies"),
+		super::attrs::Conversion::With(module) => quote::quote! { #module::as_variant(#value) },
+		super::attrs::Conversion::Signature(_, signature) => {
+			let signature = super::signature::expr(signature);
+			if fallible {
+				quote::quote! {
+					<#ty as dbus_pure_proto::AsVariant>::try_as_variant(#value)?.with_signature(&#signature)?
+				}
+			}
+			else {
+				// The type of the field is checked against the signature at compile time, so this only fails if the field has a string
+				// that isn't a valid object path or signature. `as_variant()` can't return the error, so it sends the field as its own type instead.
+				quote::quote! {
+					match <#ty as dbus_pure_proto::AsVariant>::as_variant(#value).with_signature(&#signature) {
+						Ok(variant) => variant,
+						Err(_) => <#ty as dbus_pure_proto::AsVariant>::as_variant(#value),
+					}
+				}
 			}
+		},
+	}
+}
+
+/// Compile-time assertions that the types of the given fields can be converted to the signatures of their `#[dbus(signature = "...")]` attributes.
+fn fields_signature_checks(fields: &[super::attrs::Field<'_>], generics: &syn::Generics) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
+	// The assertions are `const` items outside the impl, which can't use the type's generic parameters.
+	// Lifetimes are replaced with `'static`, which doesn't change the signature, but types and consts would.
+	let params: Vec<_> =
+		generics.params.iter()
+		.filter_map(|param| match param {
+			syn::GenericParam::Type(syn::TypeParam { ident, .. }) |
+			syn::GenericParam::Const(syn::ConstParam { ident, .. }) => Some(ident),
+			syn::GenericParam::Lifetime(_) => None,
+		})
+		.collect();
+
+	let mut result = vec![];
+
+	for super::attrs::Field { member, ty, conversion, .. } in fields {
+		let lit = match conversion {
+			super::attrs::Conversion::Signature(lit, _) => lit,
+			_ => continue,
+		};
+
+		let ty = static_lifetimes(quote::quote! { #ty });
+		if mentions_any(&ty, &params) {
+			return Err(r#"`#[dbus(signature = "...")]` cannot be used on fields whose type depends on type or const parameters, since the type is checked against the signature at compile time"#).spanning(lit);
 		}
-	};
+
+		let message = format!(r#"the type of field `{}` cannot be converted to the signature in its `#[dbus(signature = "...")]` attribute"#, quote::quote!(#member));
+		result.push(quote::quote_spanned! { lit.span() =>
+			const _: () = assert!(dbus_pure_proto::SignatureStr::converts_to(<#ty as dbus_pure_proto::AsVariant>::SIGNATURE_STR, #lit), #message);
+		});
+	}
 
 	Ok(result)
 }
+
+/// Replaces every lifetime in the given tokens with `'static`.
+fn static_lifetimes(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	let mut result = vec![];
+
+	let mut tokens = tokens.into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match token {
+			proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+				if let Some(proc_macro2::TokenTree::Ident(ident)) = tokens.next() {
+					result.push(proc_macro2::TokenTree::Punct(punct));
+					result.push(proc_macro2::TokenTree::Ident(syn::Ident::new("static", ident.span())));
+				}
+			},
+
+			proc_macro2::TokenTree::Group(group) => {
+				let mut new_group = proc_macro2::Group::new(group.delimiter(), static_lifetimes(group.stream()));
+				new_group.set_span(group.span());
+				result.push(proc_macro2::TokenTree::Group(new_group));
+			},
+
+			token => result.push(token),
+		}
+	}
+
+	result.into_iter().collect()
+}
+
+/// Whether any of the given identifiers appears in the given tokens.
+fn mentions_any(tokens: &proc_macro2::TokenStream, idents: &[&syn::Ident]) -> bool {
+	tokens.clone().into_iter().any(|token| match token {
+		proc_macro2::TokenTree::Ident(ident) => idents.iter().any(|&param| ident == *param),
+		proc_macro2::TokenTree::Group(group) => mentions_any(&group.stream(), idents),
+		proc_macro2::TokenTree::Punct(_) |
+		proc_macro2::TokenTree::Literal(_) => false,
+	})
+}
+
+/// The bound required by the impl on the type of the field.
+fn field_predicate(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> Option<syn::WherePredicate> {
+	match conversion {
+		super::attrs::Conversion::Default |
+		super::attrs::Conversion::Signature(..) => Some(syn::parse_quote! { #ty: dbus_pure_proto::AsVariant }),
+		super::attrs::Conversion::Skip |
+		super::attrs::Conversion::Flatten |
+		super::attrs::Conversion::With(_) => None,
+	}
+}
@@ -0,0 +1,316 @@
+use super::ResultExt;
+
+/// How an enum is represented in D-Bus, set by the `#[dbus(repr = "...")]` attribute on the enum.
+pub(super) enum Repr {
+	/// The discriminant of the variant as an integer type `ty`, such as `u32`.
+	/// `name` is the name of the `Signature` and `Variant` variants for that type, such as `U32`.
+	Integer { name: syn::Ident, ty: syn::Ident },
+
+	/// The name of the variant as a string.
+	String,
+
+	/// A struct of signature `(sv)` of the name of the variant and a variant of its fields.
+	Tagged,
+}
+
+impl Repr {
+	fn parse(value: &syn::LitStr) -> Result<Self, syn::Error> {
+		let (name, ty) = match &*value.value() {
+			"u8" => ("U8", "u8"),
+			"i16" => ("I16", "i16"),
+			"u16" => ("U16", "u16"),
+			"i32" => ("I32", "i32"),
+			"u32" => ("U32", "u32"),
+			"i64" => ("I64", "i64"),
+			"u64" => ("U64", "u64"),
+			"string" => return Ok(Repr::String),
+			"tagged" => return Ok(Repr::Tagged),
+			_ => return Err(r#"unexpected repr, expected one of "u8", "i16", "u16", "i32", "u32", "i64", "u64", "string" or "tagged""#).spanning(value),
+		};
+
+		Ok(Repr::Integer {
+			name: syn::Ident::new(name, value.span()),
+			ty: syn::Ident::new(ty, value.span()),
+		})
+	}
+}
+
+/// The `#[dbus(...)]` attributes on the type.
+pub(super) struct ContainerAttrs {
+	pub(super) repr: Option<Repr>,
+
+	/// `#[dbus(dict)]`: The struct is sent as a dictionary of signature `a{sv}` of its fields.
+	pub(super) dict: Option<syn::Path>,
+}
+
+impl ContainerAttrs {
+	pub(super) fn parse(attrs: &[syn::Attribute]) -> Result<Self, syn::Error> {
+		let mut repr = None;
+		let mut dict = None;
+
+		for_each_dbus_attr(attrs, |nested| match nested {
+			syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(value), .. })) if path.is_ident("repr") =>
+				set_once(&mut repr, Repr::parse(&value)?, &path),
+
+			syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("dict") =>
+				set_once(&mut dict, path.clone(), &path),
+
+			nested => Err(r#"unexpected attribute, expected one of `#[dbus(repr = "...")]` or `#[dbus(dict)]`"#).spanning(nested),
+		})?;
+
+		Ok(ContainerAttrs {
+			repr,
+			dict,
+		})
+	}
+}
+
+/// An enum variant that has been checked to be allowed by the repr of the enum.
+pub(super) struct EnumVariant<'a> {
+	pub(super) ident: &'a syn::Ident,
+
+	/// The name of the variant as sent over D-Bus, which is the variant's identifier unless renamed with `#[dbus(rename = "...")]`
+	pub(super) name: syn::LitStr,
+
+	pub(super) fields: Vec<Field<'a>>,
+
+	pub(super) is_newtype: bool,
+}
+
+pub(super) fn enum_variants<'a>(
+	repr: &Repr,
+	variants: &'a syn::punctuated::Punctuated<syn::Variant, syn::Token![,]>,
+) -> Result<Vec<EnumVariant<'a>>, syn::Error> {
+	let mut result = vec![];
+
+	for variant in variants {
+		let VariantAttrs { rename } = VariantAttrs::parse(&variant.attrs)?;
+
+		match (repr, &variant.fields) {
+			(Repr::Integer { .. }, syn::Fields::Unit) => if let Some(rename) = &rename {
+				return Err(r#"`#[dbus(rename = "...")]` cannot be used with an integer repr"#).spanning(rename);
+			},
+
+			(Repr::Integer { .. }, _) =>
+				return Err("enums with an integer repr can only have unit variants").spanning(variant),
+
+			(Repr::String, syn::Fields::Unit) => (),
+
+			(Repr::String, _) =>
+				return Err(r#"enums with `#[dbus(repr = "string")]` can only have unit variants"#).spanning(variant),
+
+			(Repr::Tagged, _) => (),
+		}
+
+		let variant_fields = fields(&variant.fields, false)?;
+
+		if let Repr::Tagged = repr {
+			if variant_fields.iter().all(|Field { conversion, .. }| matches!(conversion, Conversion::Skip)) {
+				return Err(r#"variants of enums with `#[dbus(repr = "tagged")]` must have at least one field that isn't skipped, since D-Bus has no unit type to hold in the variant"#).spanning(variant);
+			}
+		}
+
+		result.push(EnumVariant {
+			ident: &variant.ident,
+			name: rename.unwrap_or_else(|| syn::LitStr::new(&variant.ident.to_string(), variant.ident.span())),
+			is_newtype: is_newtype(&variant.fields, &variant_fields),
+			fields: variant_fields,
+		});
+	}
+
+	Ok(result)
+}
+
+/// A field of a struct or enum variant.
+pub(super) struct Field<'a> {
+	/// The name of the field, or its index for tuple structs and variants.
+	pub(super) member: syn::Member,
+
+	/// The type of the field. For fields of `#[dbus(dict)]` structs other than skipped and flattened fields, this is the `T` of the field's `Option<T>`.
+	pub(super) ty: &'a syn::Type,
+
+	pub(super) conversion: Conversion,
+
+	/// The key of the field in a `#[dbus(dict)]` struct, which is the field's identifier unless renamed with `#[dbus(rename = "...")]`
+	pub(super) key: Option<syn::LitStr>,
+}
+
+/// How a field is converted to and from a variant, set by the `#[dbus(...)]` attributes on the field.
+pub(super) enum Conversion {
+	/// The field type's `AsVariant` and `FromVariant` impls are used.
+	Default,
+
+	/// `#[dbus(skip)]`: The field is not sent, and is set to its `Default` value when received.
+	Skip,
+
+	/// `#[dbus(with = "module")]`: The `signature`, `as_variant` and `from_variant` functions in the given module are used.
+	With(syn::Path),
+
+	/// `#[dbus(signature = "...")]`: The field type's impls are used, but the value is sent with the given signature.
+	/// The signature is validated when the attribute is parsed. Whether the field type can be converted to it is checked at compile time.
+	Signature(syn::LitStr, dbus_pure_proto::Signature),
+
+	/// `#[dbus(flatten)]`: The entries of the field, which is a map of strings to variants, are sent as entries of the `#[dbus(dict)]` struct.
+	/// Entries with keys that don't match any other field are collected into it when received.
+	Flatten,
+}
+
+/// Parses the fields of a struct or enum variant. `dict` is whether the fields are those of a `#[dbus(dict)]` struct.
+pub(super) fn fields(fields: &syn::Fields, dict: bool) -> Result<Vec<Field<'_>>, syn::Error> {
+	let mut result: Vec<Field<'_>> = vec![];
+
+	for (i, field) in fields.iter().enumerate() {
+		let member = match &field.ident {
+			Some(ident) => syn::Member::Named(ident.clone()),
+			None => syn::Member::Unnamed(syn::Index { index: std::convert::TryInto::try_into(i).spanning(field)?, span: proc_macro2::Span::call_site() }),
+		};
+
+		let mut conversion = None;
+		let mut rename = None;
+
+		for_each_dbus_attr(&field.attrs, |nested| match nested {
+			syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") =>
+				set_once(&mut conversion, Conversion::Skip, &path),
+
+			syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(value), .. })) if path.is_ident("with") =>
+				set_once(&mut conversion, Conversion::With(value.parse()?), &path),
+
+			syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(value), .. })) if path.is_ident("signature") => {
+				let signature = super::signature::parse(&value)?;
+				set_once(&mut conversion, Conversion::Signature(value, signature), &path)
+			},
+
+			syn::NestedMeta::Meta(syn::Meta::Path(path)) if dict && path.is_ident("flatten") =>
+				set_once(&mut conversion, Conversion::Flatten, &path),
+
+			syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(value), .. })) if dict && path.is_ident("rename") =>
+				set_once(&mut rename, value, &path),
+
+			nested if dict => Err(r#"unexpected attribute, expected one of `#[dbus(skip)]`, `#[dbus(with = "...")]`, `#[dbus(signature = "...")]`, `#[dbus(rename = "...")]` or `#[dbus(flatten)]`"#).spanning(nested),
+
+			nested => Err(r#"unexpected attribute, expected one of `#[dbus(skip)]`, `#[dbus(with = "...")]` or `#[dbus(signature = "...")]`"#).spanning(nested),
+		})?;
+
+		let conversion = conversion.unwrap_or(Conversion::Default);
+
+		if let Conversion::Flatten = conversion {
+			if result.iter().any(|Field { conversion, .. }| matches!(conversion, Conversion::Flatten)) {
+				return Err("only one field can have `#[dbus(flatten)]`").spanning(field);
+			}
+		}
+
+		let (ty, key) = match (dict, &conversion, &field.ident) {
+			(false, _, _) => (&field.ty, None),
+
+			(true, Conversion::Skip, _) |
+			(true, Conversion::Flatten, _) => {
+				if let Some(rename) = &rename {
+					return Err(r#"`#[dbus(rename = "...")]` cannot be used on skipped or flattened fields"#).spanning(rename);
+				}
+
+				(&field.ty, None)
+			},
+
+			(true, _, Some(ident)) => {
+				let ty = option_inner_type(&field.ty).ok_or("fields of `#[dbus(dict)]` structs must be `Option`s").spanning(&field.ty)?;
+				let key = rename.unwrap_or_else(|| syn::LitStr::new(&syn::ext::IdentExt::unraw(ident).to_string(), ident.span()));
+				if result.iter().filter_map(|Field { key, .. }| key.as_ref()).any(|other| other.value() == key.value()) {
+					return Err(format!("duplicate key {:?} in `#[dbus(dict)]` struct", key.value())).spanning(&key);
+				}
+				(ty, Some(key))
+			},
+
+			(true, _, None) => return Err("`#[dbus(dict)]` can only be used on structs with named fields").spanning(field),
+		};
+
+		result.push(Field {
+			member,
+			ty,
+			conversion,
+			key,
+		});
+	}
+
+	Ok(result)
+}
+
+/// The `T` of the given type if it's `Option<T>`
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+	let path = match ty {
+		syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+		_ => return None,
+	};
+
+	let segment = path.segments.last()?;
+	if segment.ident != "Option" {
+		return None;
+	}
+
+	match &segment.arguments {
+		syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments { args, .. }) if args.len() == 1 => match &args[0] {
+			syn::GenericArgument::Type(ty) => Some(ty),
+			_ => None,
+		},
+
+		_ => None,
+	}
+}
+
+/// Whether the fields are those of a tuple struct or variant with a single field that isn't skipped. Such a type is converted as that field.
+pub(super) fn is_newtype(fields: &syn::Fields, parsed: &[Field<'_>]) -> bool {
+	matches!(fields, syn::Fields::Unnamed(_)) && matches!(parsed, [Field { conversion, .. }] if !matches!(conversion, Conversion::Skip))
+}
+
+/// The `#[dbus(...)]` attributes on an enum variant.
+struct VariantAttrs {
+	rename: Option<syn::LitStr>,
+}
+
+impl VariantAttrs {
+	fn parse(attrs: &[syn::Attribute]) -> Result<Self, syn::Error> {
+		let mut rename = None;
+
+		for_each_dbus_attr(attrs, |nested| match nested {
+			syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(value), .. })) if path.is_ident("rename") =>
+				set_once(&mut rename, value, &path),
+
+			nested => Err(r#"unexpected attribute, expected `#[dbus(rename = "...")]`"#).spanning(nested),
+		})?;
+
+		Ok(VariantAttrs {
+			rename,
+		})
+	}
+}
+
+fn for_each_dbus_attr(
+	attrs: &[syn::Attribute],
+	mut f: impl FnMut(syn::NestedMeta) -> Result<(), syn::Error>,
+) -> Result<(), syn::Error> {
+	for attr in attrs {
+		if !attr.path.is_ident("dbus") {
+			continue;
+		}
+
+		let meta = attr.parse_meta()?;
+		let nested = match meta {
+			syn::Meta::List(syn::MetaList { nested, .. }) => nested,
+			meta => return Err("unexpected attribute, expected `#[dbus(...)]`").spanning(meta),
+		};
+
+		for nested in nested {
+			let () = f(nested)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, path: &syn::Path) -> Result<(), syn::Error> {
+	if slot.is_some() {
+		return Err("duplicate or conflicting attribute").spanning(path);
+	}
+
+	*slot = Some(value);
+	Ok(())
+}
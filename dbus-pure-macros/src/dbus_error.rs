@@ -0,0 +1,99 @@
+use super::ResultExt;
+
+pub(super) fn run(input: proc_macro::TokenStream) -> Result<proc_macro2::TokenStream, syn::Error> {
+	let input: proc_macro2::TokenStream = input.into();
+
+	let tokens = input.clone();
+
+	let input: syn::DeriveInput = syn::parse2(input)?;
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let variants = match &input.data {
+		syn::Data::Enum(syn::DataEnum { variants, .. }) => variants,
+		syn::Data::Struct(_) | syn::Data::Union(_) =>
+			return Err("#[derive(DBusError)] can only be used on enums").spanning(&tokens),
+	};
+
+	let mut name_arms = vec![];
+	let mut message_arms = vec![];
+	let mut from_error_arms = vec![];
+
+	for variant in variants {
+		let variant_ident = &variant.ident;
+		let name = error_name(variant)?;
+
+		match &variant.fields {
+			syn::Fields::Unit => {
+				name_arms.push(quote::quote! { #ident::#variant_ident => #name });
+				message_arms.push(quote::quote! { #ident::#variant_ident => None });
+				from_error_arms.push(quote::quote! { #name => Some(#ident::#variant_ident) });
+			},
+
+			syn::Fields::Unnamed(syn::FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
+				// The field holds the message
+
+				name_arms.push(quote::quote! { #ident::#variant_ident(_) => #name });
+				message_arms.push(quote::quote! { #ident::#variant_ident(message) => Some(std::convert::AsRef::<str>::as_ref(message)) });
+				from_error_arms.push(quote::quote! { #name => Some(#ident::#variant_ident(message.unwrap_or_default().into())) });
+			},
+
+			_ => return Err("#[derive(DBusError)] can only be used on enums whose variants are unit variants, or tuple variants with one field for the message").spanning(variant),
+		}
+	}
+
+	let result = quote::quote! {
+		impl #impl_generics dbus_pure::DBusError for #ident #ty_generics #where_clause {
+			fn name(&self) -> &str {
+				match self {
+					#(#name_arms ,)*
+				}
+			}
+
+			fn message(&self) -> Option<&str> {
+				match self {
+					#(#message_arms ,)*
+				}
+			}
+
+			fn from_error(name: &dbus_pure::ErrorKind, message: Option<&str>) -> Option<Self> {
+				match name.as_str() {
+					#(#from_error_arms ,)*
+					_ => None,
+				}
+			}
+		}
+	};
+
+	Ok(result)
+}
+
+fn error_name(variant: &syn::Variant) -> Result<syn::LitStr, syn::Error> {
+	for attr in &variant.attrs {
+		if !attr.path.is_ident("dbus_error") {
+			continue;
+		}
+
+		let meta = attr.parse_meta()?;
+		let nested = match meta {
+			syn::Meta::List(syn::MetaList { nested, .. }) => nested,
+			meta => return Err(r#"unexpected attribute, expected `#[dbus_error(name = "...")]`"#).spanning(meta),
+		};
+
+		let mut nested = nested.into_iter();
+		match (nested.next(), nested.next()) {
+			(Some(syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(name), .. }))), None) if path.is_ident("name") => {
+				if let Err(err) = dbus_pure_proto::ErrorName::new(name.value()) {
+					return Err(format!("invalid error name {:?}: {}", name.value(), err)).spanning(&name);
+				}
+
+				return Ok(name);
+			},
+
+			_ => return Err(r#"unexpected attribute, expected `#[dbus_error(name = "...")]`"#).spanning(attr),
+		}
+	}
+
+	Err(r#"variant is missing a `#[dbus_error(name = "...")]` attribute to set the D-Bus error name"#).spanning(variant)
+}
@@ -0,0 +1,406 @@
+use super::ResultExt;
+
+pub(super) fn run(input: proc_macro::TokenStream) -> Result<proc_macro2::TokenStream, syn::Error> {
+	let input: proc_macro2::TokenStream = input.into();
+
+	let tokens = input.clone();
+
+	let input: syn::DeriveInput = syn::parse2(input)?;
+
+	let ident = &input.ident;
+
+	let super::attrs::ContainerAttrs { repr, dict } = super::attrs::ContainerAttrs::parse(&input.attrs)?;
+
+	// The bounds required by the impl on the types of the fields
+	let mut predicates = vec![];
+
+	let (signature_str, signature_body, from_variant_body) = match &input.data {
+		syn::Data::Struct(_) if repr.is_some() =>
+			return Err(r#"`#[dbus(repr = "...")]` can only be used on enums"#).spanning(&tokens),
+
+		syn::Data::Struct(syn::DataStruct { fields, .. }) if dict.is_some() => {
+			let parsed = super::attrs::fields(fields, true)?;
+			predicates.extend(parsed.iter().filter_map(field_predicate));
+			dict_bodies(ident, &parsed)
+		},
+
+		syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+			let parsed = super::attrs::fields(fields, false)?;
+			let is_newtype = super::attrs::is_newtype(fields, &parsed);
+			predicates.extend(parsed.iter().filter_map(field_predicate));
+			let (signature_str, signature_body, value) = fields_bodies(&quote::quote! { #ident }, &parsed, is_newtype, &quote::quote! { variant });
+			(signature_str, signature_body, quote::quote! { Ok(#value) })
+		},
+
+		syn::Data::Enum(_) if dict.is_some() =>
+			return Err("`#[dbus(dict)]` can only be used on structs").spanning(&tokens),
+
+		syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+			let repr = repr.ok_or(r#"#[derive(FromVariant)] on an enum requires a `#[dbus(repr = "...")]` attribute"#).spanning(&tokens)?;
+			if variants.is_empty() {
+				return Err("#[derive(FromVariant)] cannot be used on enums with no variants").spanning(&tokens);
+			}
+
+			let variants = super::attrs::enum_variants(&repr, variants)?;
+
+			for super::attrs::EnumVariant { fields, .. } in &variants {
+				predicates.extend(fields.iter().filter_map(field_predicate));
+			}
+
+			enum_bodies(ident, &repr, &variants)
+		},
+
+		syn::Data::Union(_) =>
+			return Err("#[derive(FromVariant)] can only be used on structs and enums").spanning(&tokens),
+	};
+
+	let mut generics = input.generics.clone();
+	generics.params.insert(0, syn::parse_quote! { '__a });
+	generics.make_where_clause().predicates.extend(predicates);
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, ty_generics, _) = input.generics.split_for_impl();
+
+	let result = quote::quote! {
+		impl #impl_generics dbus_pure_proto::FromVariant<'__a> for #ident #ty_generics #where_clause {
+			const SIGNATURE_STR: Option<&'static str> = #signature_str;
+
+			fn signature() -> dbus_pure_proto::Signature {
+				#signature_body
+			}
+
+			fn from_variant(variant: dbus_pure_proto::Variant<'__a>) -> Result<Self, dbus_pure_proto::FromVariantError> {
+				#from_variant_body
+			}
+		}
+	};
+
+	Ok(result)
+}
+
+fn enum_bodies(
+	ident: &syn::Ident,
+	repr: &super::attrs::Repr,
+	variants: &[super::attrs::EnumVariant<'_>],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	match repr {
+		super::attrs::Repr::Integer { name, ty } => {
+			// Variant::U32 etc, using the discriminant of each variant
+
+			let checks =
+				variants.iter()
+				.map(|super::attrs::EnumVariant { ident: variant_ident, .. }| quote::quote! {
+					if value == #ident::#variant_ident as #ty {
+						return Ok(#ident::#variant_ident);
+					}
+				});
+
+			(
+				quote::quote! {
+					<#ty as dbus_pure_proto::FromVariant<'__a>>::SIGNATURE_STR
+				},
+				quote::quote! {
+					dbus_pure_proto::Signature::#name
+				},
+				quote::quote! {
+					let value = <#ty as dbus_pure_proto::FromVariant<'__a>>::from_variant(variant)?;
+					#(#checks)*
+					Err(dbus_pure_proto::FromVariantError::UnknownEnumVariant(value.to_string()))
+				},
+			)
+		},
+
+		super::attrs::Repr::String => {
+			// Variant::String, using the name of each variant
+
+			let arms =
+				variants.iter()
+				.map(|super::attrs::EnumVariant { ident: variant_ident, name, .. }| quote::quote! { #name => Ok(#ident::#variant_ident) });
+
+			(
+				quote::quote! {
+					Some("s")
+				},
+				quote::quote! {
+					dbus_pure_proto::Signature::String
+				},
+				quote::quote! {
+					let value = <std::borrow::Cow<'__a, str> as dbus_pure_proto::FromVariant<'__a>>::from_variant(variant)?;
+					match &*value {
+						#(#arms ,)*
+						value => Err(dbus_pure_proto::FromVariantError::UnknownEnumVariant(format!("{:?}", value))),
+					}
+				},
+			)
+		},
+
+		super::attrs::Repr::Tagged => {
+			// Variant::Struct of the name of the variant and a Variant::Variant of its fields, ie `(sv)`
+
+			let arms = variants.iter().map(|super::attrs::EnumVariant { ident: variant_ident, name, fields, is_newtype }| {
+				let (_, _, value) = fields_bodies(&quote::quote! { #ident::#variant_ident }, fields, *is_newtype, &quote::quote! { value });
+				quote::quote! { #name => Ok(#value) }
+			});
+
+			(
+				quote::quote! {
+					Some("(sv)")
+				},
+				quote::quote! {
+					dbus_pure_proto::Signature::Struct {
+						fields: vec![dbus_pure_proto::Signature::String, dbus_pure_proto::Signature::Variant],
+					}
+				},
+				quote::quote! {
+					let (tag, value) =
+						<(std::borrow::Cow<'__a, str>, dbus_pure_proto::Variant<'__a>) as dbus_pure_proto::FromVariant<'__a>>::from_variant(variant)?;
+					match &*tag {
+						#(#arms ,)*
+						tag => Err(dbus_pure_proto::FromVariantError::UnknownEnumVariant(format!("{:?}", tag))),
+					}
+				},
+			)
+		},
+	}
+}
+
+/// The value of `SIGNATURE_STR` and the bodies of `signature()` and `from_variant()` for the given fields of a struct or enum variant.
+///
+/// `path` is the path of the struct or enum variant, and `variant` is the expression of the variant to convert from.
+/// The `from_variant()` body evaluates to the struct or enum variant, and uses `?` to return errors.
+fn fields_bodies(
+	path: &proc_macro2::TokenStream,
+	fields: &[super::attrs::Field<'_>],
+	is_newtype: bool,
+	variant: &proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	if is_newtype {
+		// Delegate to the wrapped type's impl
+
+		let field = &fields[0];
+		let member = &field.member;
+		let value = field_from_variant(field, variant);
+		return (
+			field_signature_str(field),
+			field_signature(field),
+			quote::quote! {
+				#path { #member: #value }
+			},
+		);
+	}
+
+	let bindings: Vec<_> =
+		fields.iter().enumerate()
+		.filter(|(_, super::attrs::Field { conversion, .. })| !matches!(conversion, super::attrs::Conversion::Skip))
+		.map(|(i, _)| quote::format_ident!("__field{}", i))
+		.collect();
+
+	let fields_from_variant = fields.iter().enumerate().map(|(i, field)| {
+		let member = &field.member;
+		let value = match field.conversion {
+			super::attrs::Conversion::Skip => quote::quote! { Default::default() },
+			_ => {
+				let binding = quote::format_ident!("__field{}", i);
+				field_from_variant(field, &quote::quote! { #binding })
+			},
+		};
+		quote::quote! { #member: #value }
+	});
+
+	if bindings.is_empty() {
+		// Variant::Tuple with no elements, ie `()`
+
+		return (
+			quote::quote! {
+				<() as dbus_pure_proto::FromVariant<'__a>>::SIGNATURE_STR
+			},
+			quote::quote! {
+				<() as dbus_pure_proto::FromVariant<'__a>>::signature()
+			},
+			quote::quote! {
+				{
+					let () = <() as dbus_pure_proto::FromVariant<'__a>>::from_variant(#variant)?;
+					#path { #(#fields_from_variant ,)* }
+				}
+			},
+		);
+	}
+
+	// Variant::Struct
+
+	let fields_signature_str =
+		fields.iter()
+		.filter(|super::attrs::Field { conversion, .. }| !matches!(conversion, super::attrs::Conversion::Skip))
+		.map(field_signature_str);
+
+	let fields_signature =
+		fields.iter()
+		.filter(|super::attrs::Field { conversion, .. }| !matches!(conversion, super::attrs::Conversion::Skip))
+		.map(field_signature);
+	let fields_signature = quote::quote! {
+		dbus_pure_proto::Signature::Struct {
+			fields: vec![#(#fields_signature ,)*],
+		}
+	};
+
+	(
+		quote::quote! {
+			dbus_pure_proto::SignatureStr::get(&dbus_pure_proto::SignatureStr::concat(&[Some("("), #(#fields_signature_str ,)* Some(")")]))
+		},
+		fields_signature.clone(),
+		quote::quote! {
+			{
+				let [#(#bindings ,)*] = dbus_pure_proto::struct_fields_from_variant(#variant, || #fields_signature)?;
+				#path { #(#fields_from_variant ,)* }
+			}
+		},
+	)
+}
+
+/// The value of `SIGNATURE_STR` and the bodies of `signature()` and `from_variant()` for the given fields of a `#[dbus(dict)]` struct.
+fn dict_bodies(
+	ident: &syn::Ident,
+	fields: &[super::attrs::Field<'_>],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	// Variant::Array of Variant::DictEntry of the key of each field that is `Some`, and a Variant::Variant of its value
+
+	let bindings: Vec<_> = (0..fields.len()).map(|i| quote::format_ident!("__field{}", i)).collect();
+
+	let mut keyed_bindings = vec![];
+	let mut arms = vec![];
+	let mut fields_from_variant = vec![];
+	let mut rest = None;
+
+	for (field, binding) in fields.iter().zip(&bindings) {
+		let super::attrs::Field { member, ty, conversion, key } = field;
+		let value = match conversion {
+			super::attrs::Conversion::Skip => quote::quote! { Default::default() },
+
+			super::attrs::Conversion::Flatten => {
+				rest = Some(binding);
+				quote::quote! { <#ty as dbus_pure_proto::FromVariant<'__a>>::from_variant(dbus_pure_proto::dict_as_variant(#binding))? }
+			},
+
+			_ => {
+				keyed_bindings.push(binding);
+				let value = field_from_variant(field, &quote::quote! { value });
+				arms.push(quote::quote! { #key => #binding = Some(#value) });
+				quote::quote! { #binding }
+			},
+		};
+		fields_from_variant.push(quote::quote! { #member: #value });
+	}
+
+	let entries = match (arms.is_empty(), rest) {
+		(false, Some(rest)) => quote::quote! {
+			let mut #rest = vec![];
+			for (key, value) in dbus_pure_proto::dict_from_variant(variant)? {
+				match &*key {
+					#(#arms ,)*
+					_ => #rest.push((key, value)),
+				}
+			}
+		},
+
+		(false, None) => quote::quote! {
+			for (key, value) in dbus_pure_proto::dict_from_variant(variant)? {
+				match &*key {
+					#(#arms ,)*
+					_ => (),
+				}
+			}
+		},
+
+		(true, Some(rest)) => quote::quote! {
+			let #rest = dbus_pure_proto::dict_from_variant(variant)?;
+		},
+
+		(true, None) => quote::quote! {
+			let _ = dbus_pure_proto::dict_from_variant(variant)?;
+		},
+	};
+
+	(
+		quote::quote! {
+			Some("a{sv}")
+		},
+		quote::quote! {
+			dbus_pure_proto::Signature::Array {
+				element: Box::new(dbus_pure_proto::Signature::DictEntry {
+					key: Box::new(dbus_pure_proto::Signature::String),
+					value: Box::new(dbus_pure_proto::Signature::Variant),
+				}),
+			}
+		},
+		quote::quote! {
+			#(let mut #keyed_bindings = None;)*
+			#entries
+			Ok(#ident {
+				#(#fields_from_variant ,)*
+			})
+		},
+	)
+}
+
+fn field_signature_str(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::FromVariant<'__a>>::SIGNATURE_STR },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields have no signature"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are only used in dicts"),
+		super::attrs::Conversion::With(_) => quote::quote! { None },
+		super::attrs::Conversion::Signature(_, signature) => {
+			let signature = signature.to_string();
+			quote::quote! { Some(#signature) }
+		},
+	}
+}
+
+fn field_signature(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::FromVariant<'__a>>::signature() },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields have no signature"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are only used in dicts"),
+		super::attrs::Conversion::With(module) => quote::quote! { #module::signature() },
+		super::attrs::Conversion::Signature(_, signature) => super::signature::expr(signature),
+	}
+}
+
+fn field_from_variant(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>, variant: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	match conversion {
+		super::attrs::Conversion::Default => quote::quote! { <#ty as dbus_pure_proto::FromVariant<'__a>>::from_variant(#variant)? },
+		super::attrs::Conversion::Skip => unreachable!("skipped fields are not converted"),
+		super::attrs::Conversion::Flatten => unreachable!("flattened fields are converted by dict_bodies"),
+		super::attrs::Conversion::With(module) => quote::quote! { #module::from_variant(#variant)? },
+		super::attrs::Conversion::Signature(lit, signature) => {
+			let signature = super::signature::expr(signature);
+			quote::quote! {
+				{
+					let variant: dbus_pure_proto::Variant<'__a> = #variant;
+					let actual = variant.inner_signature();
+					if actual != #lit {
+						return Err(dbus_pure_proto::FromVariantError::UnexpectedSignature { expected: #signature, actual });
+					}
+					// The conversion can be skipped when the field type's signature is already known to be the same
+					let variant =
+						if dbus_pure_proto::SignatureStr::matches(<#ty as dbus_pure_proto::FromVariant<'__a>>::SIGNATURE_STR, #lit) {
+							variant
+						}
+						else {
+							variant.with_signature(&<#ty as dbus_pure_proto::FromVariant<'__a>>::signature())?
+						};
+					<#ty as dbus_pure_proto::FromVariant<'__a>>::from_variant(variant)?
+				}
+			}
+		},
+	}
+}
+
+/// The bound required by the impl on the type of the field.
+fn field_predicate(super::attrs::Field { ty, conversion, .. }: &super::attrs::Field<'_>) -> Option<syn::WherePredicate> {
+	match conversion {
+		super::attrs::Conversion::Default |
+		super::attrs::Conversion::Signature(..) |
+		super::attrs::Conversion::Flatten => Some(syn::parse_quote! { #ty: dbus_pure_proto::FromVariant<'__a> }),
+		super::attrs::Conversion::Skip => Some(syn::parse_quote! { #ty: Default }),
+		super::attrs::Conversion::With(_) => None,
+	}
+}
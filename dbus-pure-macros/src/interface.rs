@@ -1,7 +1,10 @@
 use super::ResultExt;
 
 pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> Result<proc_macro2::TokenStream, syn::Error> {
-	let interface_name: syn::Expr = syn::parse(attr)?;
+	let interface_name: syn::LitStr = syn::parse(attr)?;
+	if let Err(err) = dbus_pure_proto::InterfaceName::new(interface_name.value()) {
+		return Err(format!("invalid interface name {:?}: {}", interface_name.value(), err)).spanning(&interface_name);
+	}
 
 	let input: proc_macro2::TokenStream = item.into();
 	let input: syn::ItemTrait = syn::parse2(input)?;
@@ -20,14 +23,20 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 
 		let dbus_fn_name_attr =
 			attrs.iter()
-			.next()
-			.ok_or_else(|| r#"item is missing a `#[name = "..."]` attribute to set the D-Bus function name"#)
+			.find(|attr| attr.path.is_ident("name"))
+			.ok_or(r#"item is missing a `#[name = "..."]` attribute to set the D-Bus function name"#)
 			.spanning(item)?;
 		let dbus_fn_name_meta = dbus_fn_name_attr.parse_meta()?;
 		let dbus_fn_name = match dbus_fn_name_meta {
-			syn::Meta::NameValue(syn::MetaNameValue { path, lit, .. }) if path.is_ident("name") => lit,
+			syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(lit), .. }) if path.is_ident("name") => lit,
 			meta => return Err(r#"unexpected attribute, expected `#[name = "..."]`"#).spanning(meta),
 		};
+		if let Err(err) = dbus_pure_proto::MemberName::new(dbus_fn_name.value()) {
+			return Err(format!("invalid member name {:?}: {}", dbus_fn_name.value(), err)).spanning(&dbus_fn_name);
+		}
+
+		// Other attributes, such as doc comments, are copied to the emitted fn
+		let other_attrs = attrs.iter().filter(|attr| !attr.path.is_ident("name"));
 
 		let fn_name = &sig.ident;
 
@@ -50,29 +59,52 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 					arg_variants.push(arg);
 				}
 
+				// Parameters are borrowed so that they can be passed by value, like `u32`, or by reference, like `&str`.
+				// A parameter that can't be converted, such as an invalid object path in a string sent as one, fails the call before it's sent.
 				quote::quote! {
 					Some(&dbus_pure::proto::Variant::Tuple {
 						elements: (&[
-							#(<_ as dbus_pure::proto::AsVariant>::as_variant(#arg_variants),)*
+							#(
+								<_ as dbus_pure::proto::AsVariant>::try_as_variant(&#arg_variants)
+								.map_err(|err| dbus_pure::MethodCallError::SendRequest(dbus_pure::SendError::Serialize(dbus_pure::proto::SerializeError::Convert(err))))?,
+							)*
 						][..]).into(),
 					})
 				}
 			};
 
-		let (return_ty, return_expr) = match &sig.output {
-			syn::ReturnType::Default => (
+		// If return type is `Result<T, E>`, `E` is an error that the method can return, and `T` is the type of the response
+		let (return_ty, error_ty) = match &sig.output {
+			syn::ReturnType::Default => (None, None),
+
+			syn::ReturnType::Type(_, ty) => match result_ty(ty) {
+				Some((ok_ty, error_ty)) => (Some(ok_ty), Some(error_ty)),
+				None => (Some(&**ty), None),
+			},
+		};
+
+		let (return_ty, return_expr) = match return_ty {
+			None => (
+				quote::quote! { () },
+				quote::quote! {
+					let _ = body;
+					let body = ();
+				},
+			),
+
+			Some(syn::Type::Tuple(syn::TypeTuple { elems, .. })) if elems.is_empty() => (
 				quote::quote! { () },
 				quote::quote! {
 					let _ = body;
-					Ok(())
+					let body = ();
 				},
 			),
 
-			syn::ReturnType::Type(_, ty) => {
+			Some(ty) => {
 				let return_ty = quote::quote! { #ty };
 
 				// If return type is `dbus_pure::proto::Variant`, return it as-is
-				let is_variant = match &**ty {
+				let is_variant = match ty {
 					syn::Type::Path(syn::TypePath { path, .. }) => {
 						let segments: Vec<_> =
 							path.segments.iter()
@@ -95,7 +127,6 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 							let body =
 								body
 								.ok_or_else(|| dbus_pure::MethodCallError::UnexpectedResponse(None))?;
-							Ok(body)
 						}
 					}
 					else {
@@ -106,7 +137,6 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 							let body =
 								serde::Deserialize::deserialize(body)
 								.map_err(|err| dbus_pure::MethodCallError::UnexpectedResponse(Some(err)))?;
-							Ok(body)
 						}
 					};
 
@@ -114,28 +144,85 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 			}
 		};
 
-		impl_body.push(quote::quote! {
-			fn #fn_name(
-				&self,
-				client: &mut dbus_pure::Client,
-				#args
-			) -> Result<#return_ty, dbus_pure::MethodCallError> {
-				let body =
-					client.method_call(
-						self.name(),
-						self.path(),
-						#interface_name,
-						#dbus_fn_name,
-						#args_variant,
-					)?;
-				#return_expr
-			}
-		});
+		// The interface and member names were validated above, so they can't fail to be validated again here.
+		let method_call = quote::quote! {
+			client.method_call(
+				self.name(),
+				self.path(),
+				dbus_pure::proto::InterfaceName::new(#interface_name).expect("interface name is valid"),
+				dbus_pure::proto::MemberName::new(#dbus_fn_name).expect("member name is valid"),
+				#args_variant,
+			)
+		};
+
+		if let Some(error_ty) = error_ty {
+			impl_body.push(quote::quote! {
+				#(#other_attrs)*
+				fn #fn_name(
+					&self,
+					client: &mut dbus_pure::Client,
+					#args
+				) -> Result<Result<#return_ty, #error_ty>, dbus_pure::MethodCallError> {
+					let body = match #method_call {
+						Ok(body) => body,
+						Err(err) => return <#error_ty as dbus_pure::DBusError>::from_method_call_error(err).map(Err),
+					};
+					#return_expr
+					Ok(Ok(body))
+				}
+			});
+		}
+		else {
+			impl_body.push(quote::quote! {
+				#(#other_attrs)*
+				fn #fn_name(
+					&self,
+					client: &mut dbus_pure::Client,
+					#args
+				) -> Result<#return_ty, dbus_pure::MethodCallError> {
+					let body = #method_call?;
+					#return_expr
+					Ok(body)
+				}
+			});
+		}
 	}
 
+	let attrs = &input.attrs;
+
 	Ok(quote::quote! {
+		#(#attrs)*
 		#vis trait #struct_name: dbus_pure::proto::Object {
 			#(#impl_body)*
 		}
 	})
 }
+
+/// Returns the `T` and `E` of the given type if it is `Result<T, E>`, `std::result::Result<T, E>` or `core::result::Result<T, E>`
+fn result_ty(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+	let path = match ty {
+		syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+		_ => return None,
+	};
+
+	let segments: Vec<_> = path.segments.iter().map(|path_segment| path_segment.ident.to_string()).collect();
+	let is_result = match &segments[..] {
+		[result] => path.leading_colon.is_none() && result == "Result",
+		[krate, module, result] => (krate == "std" || krate == "core") && module == "result" && result == "Result",
+		_ => false,
+	};
+	if !is_result {
+		return None;
+	}
+
+	let args = match &path.segments.last()?.arguments {
+		syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments { args, .. }) => args,
+		_ => return None,
+	};
+
+	let mut args = args.iter();
+	match (args.next(), args.next(), args.next()) {
+		(Some(syn::GenericArgument::Type(ok_ty)), Some(syn::GenericArgument::Type(error_ty)), None) => Some((ok_ty, error_ty)),
+		_ => None,
+	}
+}
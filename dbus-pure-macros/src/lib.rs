@@ -11,10 +11,18 @@ extern crate proc_macro;
 
 mod as_variant;
 
+mod attrs;
+
+mod dbus_error;
+
+mod from_variant;
+
 mod interface;
 
 mod object;
 
+mod signature;
+
 fn run(result: Result<proc_macro2::TokenStream, syn::Error>) -> proc_macro::TokenStream {
 	let token_stream = match result {
 		Ok(token_stream) => token_stream,
@@ -35,6 +43,15 @@ impl<T, E> ResultExt<T> for Result<T, E> where E: std::fmt::Display {
 
 /// Derives `dbus_pure_proto::AsVariant` on the type.
 ///
+/// Structs with named fields and tuple structs are sent as D-Bus structs of their fields. Tuple structs with a single field are sent as that field,
+/// and unit structs are sent as `()`.
+///
+/// The impl requires the type of every field to impl `dbus_pure_proto::AsVariant`, except for fields with `#[dbus(with = "...")]`,
+/// `#[dbus(skip)]` or `#[dbus(flatten)]`.
+///
+/// The impl sets `AsVariant::SIGNATURE_STR` when the signature is known at compile time, which is the case for enums and dicts,
+/// and for structs unless a field has `#[dbus(with = "...")]` or is of a type that doesn't set it.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -43,12 +60,175 @@ impl<T, E> ResultExt<T> for Result<T, E> where E: std::fmt::Display {
 ///     foo: u32,
 ///     bar: std::borrow::Cow<'a, str>,
 /// }
+///
+/// #[derive(Debug, dbus_pure_macros::AsVariant, serde_derive::Deserialize)]
+/// struct Point(i32, i32);
+/// ```
+///
+/// # Fields
+///
+/// Fields of structs and enum variants support these attributes:
+///
+/// - `#[dbus(skip)]` does not send the field. `#[derive(FromVariant)]` sets it to its `Default` value.
+///
+/// - `#[dbus(with = "module")]` converts the field with the `signature`, `as_variant` and `from_variant` functions in the given module,
+///   which have the same signatures as the functions of the `AsVariant` and `FromVariant` traits.
+///
+/// - `#[dbus(signature = "...")]` sends the field with the given signature instead of that of its type. This can send strings as
+///   object paths and signatures and the other way around, and convert between `u32`s and unix fds, including inside arrays and structs.
+///   An invalid signature is a compile error, and so is a signature that the field's type can't be converted to, as checked with its
+///   `AsVariant::SIGNATURE_STR`, so the type can't depend on the type parameters of the struct or enum. A string that isn't a valid object path or signature makes `AsVariant::try_as_variant` fail,
+///   which is what the methods of `#[interface]` traits use, while `AsVariant::as_variant` sends it as a string instead.
+///
+/// ```rust,ignore
+/// #[derive(Debug, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+/// struct Device {
+///     #[dbus(signature = "as")]
+///     paths: Vec<dbus_pure_proto::ObjectPath<'static>>,
+///
+///     #[dbus(signature = "o")]
+///     parent: String,
+///
+///     #[dbus(skip)]
+///     cached_name: Option<String>,
+///
+///     #[dbus(with = "mac_address")]
+///     address: [u8; 6],
+/// }
+/// ```
+///
+/// # Dicts
+///
+/// Structs with a `#[dbus(dict)]` attribute are sent as dictionaries of signature `a{sv}`, as is common for properties and options.
+/// Every field must be an `Option<T>`. Fields that are `Some` are sent as an entry whose key is the name of the field, and whose value
+/// is a variant that holds the `T`. Fields that are `None` are not sent.
+///
+/// The key of a field can be changed with `#[dbus(rename = "...")]`. Two fields with the same key are a compile error. The other field attributes apply to the `T` of the field.
+///
+/// One field can have `#[dbus(flatten)]` instead. This field must be a map of strings to variants, such as
+/// `std::collections::HashMap<String, dbus_pure_proto::Variant<'a>>`. Its entries are sent along with those of the other fields, and
+/// `#[derive(FromVariant)]` collects the entries whose keys don't match any other field into it. Without such a field, those entries are ignored.
+///
+/// These structs can also be deserialized with `serde_derive::Deserialize` if the fields are renamed the same way with `#[serde(rename = "...")]`.
+/// Unknown keys are ignored unless the struct has `#[serde(deny_unknown_fields)]`.
+///
+/// ```rust,ignore
+/// #[derive(Debug, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+/// #[dbus(dict)]
+/// struct Hints<'a> {
+///     urgency: Option<u8>,
+///
+///     #[dbus(rename = "desktop-entry")]
+///     desktop_entry: Option<String>,
+///
+///     #[dbus(flatten)]
+///     other: std::collections::HashMap<String, dbus_pure_proto::Variant<'a>>,
+/// }
+/// ```
+///
+/// # Enums
+///
+/// Enums must have a `#[dbus(repr = "...")]` attribute to choose how they're represented:
+///
+/// - `#[dbus(repr = "u32")]`, or any other of `"u8"`, `"i16"`, `"u16"`, `"i32"`, `"i64"` and `"u64"`, sends the discriminant of the variant
+///   as that integer type. All variants must be unit variants.
+///
+/// - `#[dbus(repr = "string")]` sends the name of the variant as a string. All variants must be unit variants.
+///
+/// - `#[dbus(repr = "tagged")]` sends a struct of signature `(sv)` containing the name of the variant, and a variant that holds the variant's field
+///   if it's a newtype variant, else a struct of its fields. Variants must have at least one field that isn't skipped.
+///
+/// The name of a variant can be changed with `#[dbus(rename = "...")]`. Use `#[derive(FromVariant)]` to convert enums back from variants.
+/// Enums with the `"string"` and `"tagged"` reprs can also be deserialized with `serde_derive::Deserialize` if the variants are renamed
+/// the same way with `#[serde(rename = "...")]`.
+///
+/// ```rust,ignore
+/// #[derive(Debug, dbus_pure_macros::AsVariant, serde_derive::Deserialize)]
+/// #[dbus(repr = "string")]
+/// enum PlaybackStatus {
+///     Playing,
+///     Paused,
+///     Stopped,
+/// }
+///
+/// #[derive(Debug, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+/// #[dbus(repr = "u32")]
+/// #[repr(u32)]
+/// enum State {
+///     Asleep = 10,
+///     Connected = 70,
+/// }
+///
+/// #[derive(Debug, dbus_pure_macros::AsVariant, serde_derive::Deserialize)]
+/// #[dbus(repr = "tagged")]
+/// enum Address {
+///     #[dbus(rename = "ipv4")]
+///     #[serde(rename = "ipv4")]
+///     Ipv4(u32),
+///
+///     #[dbus(rename = "ipv6")]
+///     #[serde(rename = "ipv6")]
+///     Ipv6(Vec<u8>, u32),
+/// }
 /// ```
-#[proc_macro_derive(AsVariant)]
+#[proc_macro_derive(AsVariant, attributes(dbus))]
 pub fn as_variant(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	run(as_variant::run(input))
 }
 
+/// Derives `dbus_pure::DBusError` on the type.
+///
+/// The type must be an enum. Every variant must have a `#[dbus_error(name = "...")]` attribute to set its D-Bus error name.
+/// An invalid error name is a compile error.
+/// Variants can either be unit variants, or tuple variants with one field that holds the error message.
+/// The field must impl `AsRef<str>` and `From<&str>`, such as `String`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Debug, dbus_pure_macros::DBusError)]
+/// enum FooError {
+///     #[dbus_error(name = "com.example.Foo.NotFound")]
+///     NotFound(String),
+///
+///     #[dbus_error(name = "com.example.Foo.Busy")]
+///     Busy,
+/// }
+/// ```
+#[proc_macro_derive(DBusError, attributes(dbus_error))]
+pub fn dbus_error(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	run(dbus_error::run(input))
+}
+
+/// Derives `dbus_pure_proto::FromVariant` on the type.
+///
+/// The type is converted from the same representation that `#[derive(AsVariant)]` converts it to, and supports the same `#[dbus(...)]` attributes.
+/// The impl requires the type of every field to impl `dbus_pure_proto::FromVariant`, except for fields with `#[dbus(with = "...")]`,
+/// and fields with `#[dbus(skip)]` which must impl `Default` instead.
+///
+/// The impl sets `FromVariant::SIGNATURE_STR` in the same cases as `#[derive(AsVariant)]` sets `AsVariant::SIGNATURE_STR`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Debug, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+/// struct Response<'a> {
+///     foo: u32,
+///     bar: std::borrow::Cow<'a, str>,
+/// }
+///
+/// #[derive(Debug, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+/// #[dbus(repr = "u32")]
+/// enum State {
+///     Asleep = 10,
+///     Connected = 70,
+/// }
+/// ```
+#[proc_macro_derive(FromVariant, attributes(dbus))]
+pub fn from_variant(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	run(from_variant::run(input))
+}
+
 /// Takes a trait representing a D-Bus interface as input, and emits a trait that can be used to invoke methods using D-Bus.
 ///
 /// ```rust,ignore
@@ -62,6 +242,9 @@ pub fn as_variant(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// }
 /// ```
 ///
+/// Every `fn` must have a `#[name = "..."]` attribute to set the D-Bus method name. Any other attributes, such as doc comments,
+/// are kept as-is.
+///
 /// The macro modifies the trait definition in these ways:
 ///
 /// - The trait is modified to inherit from `dbus_pure::proto::Object`
@@ -70,6 +253,10 @@ pub fn as_variant(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///
 /// - Every `fn` in the trait is modified to return `Result<TheOriginalReturnType, dbus_pure::MethodCallError>`.
 ///
+///   If the original return type is `Result<T, E>` where `E` impls `dbus_pure::DBusError`, the `fn` returns
+///   `Result<Result<T, E>, dbus_pure::MethodCallError>` instead. Errors returned by the method that `E` recognizes are returned as `Ok(Err(E))`,
+///   and other errors are returned as `Err(dbus_pure::MethodCallError)` as usual.
+///
 /// - Every `fn` in the trait is modified to have a default implementation. This default implementation uses the client
 ///   to invoke the method and parse its response.
 ///
@@ -112,17 +299,17 @@ pub fn interface(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -
 ///
 /// ```rust,ignore
 /// struct OrgFreeDesktopDbusObject<'a> {
-///     name: std::borrow::Cow<'a, str>,
+///     name: dbus_pure::proto::BusName<'a>,
 ///     path: dbus_pure::proto::ObjectPath<'a>,
 /// }
 ///
 /// impl dbus_pure::proto::Object for OrgFreeDesktopDbusObject<'_> {
-///     fn name(&self) -> &str {
-///         &*self.name
+///     fn name(&self) -> dbus_pure::proto::BusName<'_> {
+///         self.name.borrowed()
 ///     }
 ///
 ///     fn path(&self) -> dbus_pure::proto::ObjectPath<'_> {
-///         dbus_pure::proto::ObjectPath((&*self.path.0).into())
+///         self.path.borrowed()
 ///     }
 /// }
 ///
@@ -132,3 +319,23 @@ pub fn interface(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -
 pub fn object(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	run(object::run(attr, item))
 }
+
+/// Parses a D-Bus signature string at compile time, and expands to an expression of type `dbus_pure_proto::Signature`.
+///
+/// An invalid signature is a compile error.
+///
+/// ```rust,ignore
+/// let signature = dbus_pure_macros::signature!("a{sv}");
+/// assert_eq!(signature, dbus_pure_proto::Signature::Array {
+///     element: Box::new(dbus_pure_proto::Signature::DictEntry {
+///         key: Box::new(dbus_pure_proto::Signature::String),
+///         value: Box::new(dbus_pure_proto::Signature::Variant),
+///     }),
+/// });
+/// ```
+///
+/// The string follows the same rules as parsing a signature with `std::str::FromStr`, so it can also be empty or contain more than one complete type.
+#[proc_macro]
+pub fn signature(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	run(signature::run(input))
+}
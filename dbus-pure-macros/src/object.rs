@@ -4,6 +4,8 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 	let input: proc_macro2::TokenStream = item.into();
 	let input: syn::ItemStruct = syn::parse2(input)?;
 
+	let attrs = &input.attrs;
+
 	let vis = &input.vis;
 
 	let struct_name = &input.ident;
@@ -15,18 +17,19 @@ pub(super) fn run(attr: proc_macro::TokenStream, item: proc_macro::TokenStream)
 		});
 
 	Ok(quote::quote! {
+		#(#attrs)*
 		#vis struct #struct_name<'a> {
-			#vis name: std::borrow::Cow<'a, str>,
+			#vis name: dbus_pure::proto::BusName<'a>,
 			#vis path: dbus_pure::proto::ObjectPath<'a>,
 		}
 
 		impl dbus_pure::proto::Object for #struct_name<'_> {
-			fn name(&self) -> &str {
-				&*self.name
+			fn name(&self) -> dbus_pure::proto::BusName<'_> {
+				self.name.borrowed()
 			}
 
 			fn path(&self) -> dbus_pure::proto::ObjectPath<'_> {
-				dbus_pure::proto::ObjectPath((&*self.path.0).into())
+				self.path.borrowed()
 			}
 		}
 
@@ -0,0 +1,72 @@
+use super::ResultExt;
+
+pub(super) fn run(input: proc_macro::TokenStream) -> Result<proc_macro2::TokenStream, syn::Error> {
+	let signature: syn::LitStr = syn::parse(input)?;
+	let parsed = parse(&signature)?;
+	Ok(expr(&parsed))
+}
+
+/// Parses and validates the given signature string literal.
+pub(super) fn parse(signature: &syn::LitStr) -> Result<dbus_pure_proto::Signature, syn::Error> {
+	let value = signature.value();
+	value.parse().map_err(|err| format!("invalid signature {:?}: {}", value, err)).spanning(signature)
+}
+
+/// An expression that evaluates to the given signature.
+pub(super) fn expr(signature: &dbus_pure_proto::Signature) -> proc_macro2::TokenStream {
+	match signature {
+		dbus_pure_proto::Signature::Array { element } => {
+			let element = expr(element);
+			quote::quote! { dbus_pure_proto::Signature::Array { element: Box::new(#element) } }
+		},
+
+		dbus_pure_proto::Signature::Bool => quote::quote! { dbus_pure_proto::Signature::Bool },
+
+		dbus_pure_proto::Signature::DictEntry { key, value } => {
+			let key = expr(key);
+			let value = expr(value);
+			quote::quote! { dbus_pure_proto::Signature::DictEntry { key: Box::new(#key), value: Box::new(#value) } }
+		},
+
+		dbus_pure_proto::Signature::F64 => quote::quote! { dbus_pure_proto::Signature::F64 },
+
+		dbus_pure_proto::Signature::I16 => quote::quote! { dbus_pure_proto::Signature::I16 },
+
+		dbus_pure_proto::Signature::I32 => quote::quote! { dbus_pure_proto::Signature::I32 },
+
+		dbus_pure_proto::Signature::I64 => quote::quote! { dbus_pure_proto::Signature::I64 },
+
+		dbus_pure_proto::Signature::Maybe { element } => {
+			let element = expr(element);
+			quote::quote! { dbus_pure_proto::Signature::Maybe { element: Box::new(#element) } }
+		},
+
+		dbus_pure_proto::Signature::ObjectPath => quote::quote! { dbus_pure_proto::Signature::ObjectPath },
+
+		dbus_pure_proto::Signature::Signature => quote::quote! { dbus_pure_proto::Signature::Signature },
+
+		dbus_pure_proto::Signature::String => quote::quote! { dbus_pure_proto::Signature::String },
+
+		dbus_pure_proto::Signature::Struct { fields } => {
+			let fields = fields.iter().map(expr);
+			quote::quote! { dbus_pure_proto::Signature::Struct { fields: vec![#(#fields ,)*] } }
+		},
+
+		dbus_pure_proto::Signature::Tuple { elements } => {
+			let elements = elements.iter().map(expr);
+			quote::quote! { dbus_pure_proto::Signature::Tuple { elements: vec![#(#elements ,)*] } }
+		},
+
+		dbus_pure_proto::Signature::U8 => quote::quote! { dbus_pure_proto::Signature::U8 },
+
+		dbus_pure_proto::Signature::U16 => quote::quote! { dbus_pure_proto::Signature::U16 },
+
+		dbus_pure_proto::Signature::U32 => quote::quote! { dbus_pure_proto::Signature::U32 },
+
+		dbus_pure_proto::Signature::U64 => quote::quote! { dbus_pure_proto::Signature::U64 },
+
+		dbus_pure_proto::Signature::UnixFd => quote::quote! { dbus_pure_proto::Signature::UnixFd },
+
+		dbus_pure_proto::Signature::Variant => quote::quote! { dbus_pure_proto::Signature::Variant },
+	}
+}
@@ -0,0 +1,7 @@
+#![deny(rust_2018_idioms, warnings)]
+
+#[test]
+fn compile_fail() {
+	let t = trybuild::TestCases::new();
+	t.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, dbus_pure_macros::DBusError)]
+enum FooError {
+	#[dbus_error(name = "com.example.Foo.NotFound")]
+	NotFound(String),
+
+	#[dbus_error(name = "Busy")]
+	Busy,
+}
+
+fn main() {
+}
@@ -0,0 +1,10 @@
+#[derive(Debug, dbus_pure_macros::DBusError)]
+enum FooError {
+	#[dbus_error(name = "com.example.Foo.NotFound")]
+	NotFound(String),
+
+	Busy,
+}
+
+fn main() {
+}
@@ -0,0 +1,5 @@
+#[derive(Debug, dbus_pure_macros::DBusError)]
+struct FooError;
+
+fn main() {
+}
@@ -0,0 +1,11 @@
+#[derive(dbus_pure_macros::AsVariant)]
+#[dbus(dict)]
+struct Options {
+	r#type: Option<u32>,
+
+	#[dbus(rename = "type")]
+	kind: Option<u32>,
+}
+
+fn main() {
+}
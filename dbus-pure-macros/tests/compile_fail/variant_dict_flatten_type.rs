@@ -0,0 +1,11 @@
+#[derive(dbus_pure_macros::AsVariant)]
+#[dbus(dict)]
+struct Hints {
+	urgency: Option<u8>,
+
+	#[dbus(flatten)]
+	other: Vec<u8>,
+}
+
+fn main() {
+}
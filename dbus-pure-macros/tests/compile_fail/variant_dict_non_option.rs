@@ -0,0 +1,8 @@
+#[derive(dbus_pure_macros::AsVariant)]
+#[dbus(dict)]
+struct Options {
+	timeout: u32,
+}
+
+fn main() {
+}
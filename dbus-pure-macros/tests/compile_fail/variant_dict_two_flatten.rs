@@ -0,0 +1,14 @@
+#[derive(dbus_pure_macros::FromVariant)]
+#[dbus(dict)]
+struct Hints {
+	urgency: Option<u8>,
+
+	#[dbus(flatten)]
+	other: std::collections::HashMap<String, dbus_pure_proto::Variant<'static>>,
+
+	#[dbus(flatten)]
+	more: std::collections::HashMap<String, dbus_pure_proto::Variant<'static>>,
+}
+
+fn main() {
+}
@@ -0,0 +1,9 @@
+#[derive(dbus_pure_macros::AsVariant)]
+#[dbus(repr = "u32")]
+enum State {
+	Asleep,
+	Connected(String),
+}
+
+fn main() {
+}
@@ -0,0 +1,8 @@
+#[derive(dbus_pure_macros::AsVariant)]
+struct Device<T> {
+	#[dbus(signature = "as")]
+	paths: Vec<T>,
+}
+
+fn main() {
+}
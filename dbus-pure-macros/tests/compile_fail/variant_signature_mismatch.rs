@@ -0,0 +1,8 @@
+#[derive(dbus_pure_macros::AsVariant)]
+struct Device {
+	#[dbus(signature = "u")]
+	name: String,
+}
+
+fn main() {
+}
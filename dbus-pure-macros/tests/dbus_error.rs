@@ -0,0 +1,78 @@
+#![deny(rust_2018_idioms, warnings)]
+
+#[derive(Debug, PartialEq, dbus_pure_macros::DBusError)]
+enum FooError {
+	#[dbus_error(name = "com.example.Foo.NotFound")]
+	NotFound(String),
+
+	#[dbus_error(name = "com.example.Foo.Busy")]
+	Busy,
+}
+
+#[test]
+fn name_and_message() {
+	let err = FooError::NotFound("no such thing".to_owned());
+	assert_eq!(dbus_pure::DBusError::name(&err), "com.example.Foo.NotFound");
+	assert_eq!(dbus_pure::DBusError::message(&err), Some("no such thing"));
+
+	let err = FooError::Busy;
+	assert_eq!(dbus_pure::DBusError::name(&err), "com.example.Foo.Busy");
+	assert_eq!(dbus_pure::DBusError::message(&err), None);
+}
+
+#[test]
+fn from_error() {
+	assert_eq!(
+		<FooError as dbus_pure::DBusError>::from_error(&"com.example.Foo.NotFound".into(), Some("no such thing")),
+		Some(FooError::NotFound("no such thing".to_owned())),
+	);
+	assert_eq!(
+		<FooError as dbus_pure::DBusError>::from_error(&"com.example.Foo.NotFound".into(), None),
+		Some(FooError::NotFound(String::new())),
+	);
+	assert_eq!(
+		<FooError as dbus_pure::DBusError>::from_error(&"com.example.Foo.Busy".into(), Some("ignored")),
+		Some(FooError::Busy),
+	);
+	assert_eq!(
+		<FooError as dbus_pure::DBusError>::from_error(&dbus_pure::ErrorKind::Failed, None),
+		None,
+	);
+}
+
+#[test]
+fn error_reply_round_trip() {
+	let method_call =
+		dbus_pure::proto::Message::method_call(
+			dbus_pure::proto::BusName::new("com.example.Foo").unwrap(),
+			dbus_pure::proto::ObjectPath::new("/com/example/Foo").unwrap(),
+			dbus_pure::proto::InterfaceName::new("com.example.Foo").unwrap(),
+			dbus_pure::proto::MemberName::new("Bar").unwrap(),
+		)
+		.with_field(dbus_pure::proto::MessageHeaderField::Sender(":1.5".into()));
+
+	for err in [FooError::NotFound("no such thing".to_owned()), FooError::Busy] {
+		let reply = dbus_pure::DBusError::error_reply(&err, &method_call);
+		assert_eq!(reply.error_name().map(dbus_pure::proto::ErrorName::as_str), Some(dbus_pure::DBusError::name(&err)));
+		assert_eq!(reply.destination(), Some(":1.5"));
+
+		let method_call_error = dbus_pure::MethodCallError::Error(reply.error_name().unwrap().as_str().into(), reply.body);
+		let converted = <FooError as dbus_pure::DBusError>::from_method_call_error(method_call_error).unwrap();
+		assert_eq!(converted, err);
+	}
+}
+
+#[test]
+fn from_method_call_error_unrecognized() {
+	let err = dbus_pure::MethodCallError::Error(dbus_pure::ErrorKind::Failed, None);
+	match <FooError as dbus_pure::DBusError>::from_method_call_error(err) {
+		Err(dbus_pure::MethodCallError::Error(dbus_pure::ErrorKind::Failed, None)) => (),
+		result => panic!("{:?}", result),
+	}
+
+	let err = dbus_pure::MethodCallError::UnexpectedResponse(None);
+	match <FooError as dbus_pure::DBusError>::from_method_call_error(err) {
+		Err(dbus_pure::MethodCallError::UnexpectedResponse(None)) => (),
+		result => panic!("{:?}", result),
+	}
+}
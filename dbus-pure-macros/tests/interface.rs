@@ -0,0 +1,104 @@
+#![deny(rust_2018_idioms, warnings)]
+
+#[derive(Debug, PartialEq, dbus_pure_macros::DBusError)]
+enum FooError {
+	#[dbus_error(name = "com.example.Foo.NotFound")]
+	NotFound(String),
+}
+
+#[dbus_pure_macros::interface("com.example.Foo")]
+trait ComExampleFooInterface {
+	#[name = "Get"]
+	fn get(key: &str) -> Result<u32, FooError>;
+
+	#[name = "Clear"]
+	fn clear() -> std::result::Result<(), FooError>;
+}
+
+#[dbus_pure_macros::object(ComExampleFooInterface)]
+struct ComExampleFooObject;
+
+#[dbus_pure_macros::interface("org.freedesktop.DBus")]
+trait OrgFreeDesktopDbusInterface {
+	#[name = "RequestName"]
+	fn request_name(name: &str, flags: u32) -> u32;
+}
+
+#[dbus_pure_macros::object(OrgFreeDesktopDbusInterface)]
+struct OrgFreeDesktopDbusObject;
+
+fn connect() -> dbus_pure::Client {
+	let connection = dbus_pure::Connection::new(dbus_pure::BusPath::Session, dbus_pure::SaslAuthType::Uid).unwrap();
+	dbus_pure::Client::new(connection).unwrap()
+}
+
+/// Serves `com.example.Foo` on the session bus until it has replied to `num_calls` method calls.
+///
+/// `Get("foo")` returns 5, and `Get` of any other key returns a `com.example.Foo.NotFound` error.
+/// `Clear` returns an `org.freedesktop.DBus.Error.AccessDenied` error, which `FooError` does not recognize.
+fn serve(name: &'static str, num_calls: usize) -> std::thread::JoinHandle<()> {
+	let (ready_send, ready_recv) = std::sync::mpsc::channel();
+
+	let server = std::thread::spawn(move || {
+		let mut client = connect();
+
+		let obj = OrgFreeDesktopDbusObject {
+			name: dbus_pure::proto::BusName::new("org.freedesktop.DBus").unwrap(),
+			path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus").unwrap(),
+		};
+		// DBUS_NAME_FLAG_DO_NOT_QUEUE, and DBUS_REQUEST_NAME_REPLY_PRIMARY_OWNER
+		assert_eq!(obj.request_name(&mut client, name, 0x4).unwrap(), 1);
+		ready_send.send(()).unwrap();
+
+		let mut num_replies = 0;
+		while num_replies < num_calls {
+			let method_call = client.recv().unwrap();
+			if !matches!(method_call.header.r#type, dbus_pure::proto::MessageType::MethodCall { .. }) {
+				continue;
+			}
+
+			let mut reply = match (method_call.member(), &method_call.body) {
+				(Some("Get"), Some(dbus_pure::proto::Variant::String(key))) if key == "foo" =>
+					dbus_pure::proto::Message::method_return(&method_call).with_body(dbus_pure::proto::Variant::U32(5)),
+
+				(Some("Get"), Some(dbus_pure::proto::Variant::String(key))) =>
+					dbus_pure::DBusError::error_reply(&FooError::NotFound(format!("no such key {:?}", key)), &method_call),
+
+				(Some("Clear"), None) =>
+					dbus_pure::proto::Message::error(&method_call, dbus_pure::proto::ErrorName::new(dbus_pure::ErrorKind::AccessDenied.as_str().to_owned()).unwrap()),
+
+				(member, body) => panic!("unexpected method call {:?} {:?}", member, body),
+			};
+			let _ = client.send(&mut reply).unwrap();
+			num_replies += 1;
+		}
+	});
+
+	ready_recv.recv().unwrap();
+	server
+}
+
+#[test]
+fn result_return_type() {
+	let server = serve("com.example.MacrosInterfaceTest", 3);
+
+	let mut client = connect();
+	let obj = ComExampleFooObject {
+		name: dbus_pure::proto::BusName::new("com.example.MacrosInterfaceTest").unwrap(),
+		path: dbus_pure::proto::ObjectPath::new("/com/example/Foo").unwrap(),
+	};
+
+	// A successful response is returned as `Ok(Ok(_))`
+	assert_eq!(obj.get(&mut client, "foo").unwrap(), Ok(5));
+
+	// An error that `FooError` recognizes is returned as `Ok(Err(_))`
+	assert_eq!(obj.get(&mut client, "bar").unwrap(), Err(FooError::NotFound(r#"no such key "bar""#.to_owned())));
+
+	// Any other error is returned as `Err(_)`
+	match obj.clear(&mut client) {
+		Err(dbus_pure::MethodCallError::Error(dbus_pure::ErrorKind::AccessDenied, None)) => (),
+		result => panic!("{:?}", result),
+	}
+
+	server.join().unwrap();
+}
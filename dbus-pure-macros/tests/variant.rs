@@ -0,0 +1,315 @@
+#![deny(rust_2018_idioms, warnings)]
+
+/// Converts the value to a variant and checks that it's the expected one, that its signature matches that of the type, and that it converts back.
+fn round_trip<'a, T>(value: &'a T, expected: &dbus_pure_proto::Variant<'a>)
+where
+	T: dbus_pure_proto::AsVariant + dbus_pure_proto::FromVariant<'a> + std::fmt::Debug + PartialEq,
+{
+	let variant = dbus_pure_proto::AsVariant::as_variant(value);
+	assert_eq!(variant, *expected);
+
+	let signature = <T as dbus_pure_proto::AsVariant>::signature();
+	assert_eq!(variant.inner_signature(), signature);
+	assert_eq!(<T as dbus_pure_proto::FromVariant<'_>>::signature(), signature);
+	assert_eq!(<T as dbus_pure_proto::FromVariant<'_>>::SIGNATURE_STR, <T as dbus_pure_proto::AsVariant>::SIGNATURE_STR);
+	if let Some(signature_str) = <T as dbus_pure_proto::AsVariant>::SIGNATURE_STR {
+		assert_eq!(signature_str, signature.to_string());
+	}
+
+	let actual: T = dbus_pure_proto::FromVariant::from_variant(variant).unwrap();
+	assert_eq!(actual, *value);
+}
+
+fn string(value: &str) -> dbus_pure_proto::Variant<'_> {
+	dbus_pure_proto::Variant::String(value.into())
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+#[dbus(repr = "u32")]
+#[repr(u32)]
+enum State {
+	Asleep = 10,
+	Connected = 70,
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+#[dbus(repr = "string")]
+enum PlaybackStatus {
+	Playing,
+
+	#[dbus(rename = "paused")]
+	Paused,
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+#[dbus(repr = "tagged")]
+enum Address {
+	#[dbus(rename = "ipv4")]
+	Ipv4(u32),
+
+	#[dbus(rename = "ipv6")]
+	Ipv6(Vec<u8>, u32),
+
+	Named { host: String },
+}
+
+#[test]
+fn integer_repr() {
+	round_trip(&State::Asleep, &dbus_pure_proto::Variant::U32(10));
+	round_trip(&State::Connected, &dbus_pure_proto::Variant::U32(70));
+
+	assert!(matches!(
+		<State as dbus_pure_proto::FromVariant<'_>>::from_variant(dbus_pure_proto::Variant::U32(20)),
+		Err(dbus_pure_proto::FromVariantError::UnknownEnumVariant(value)) if value == "20",
+	));
+}
+
+#[test]
+fn string_repr() {
+	round_trip(&PlaybackStatus::Playing, &string("Playing"));
+	round_trip(&PlaybackStatus::Paused, &string("paused"));
+
+	assert!(matches!(
+		<PlaybackStatus as dbus_pure_proto::FromVariant<'_>>::from_variant(string("Paused")),
+		Err(dbus_pure_proto::FromVariantError::UnknownEnumVariant(value)) if value == r#""Paused""#,
+	));
+}
+
+#[test]
+fn tagged_repr() {
+	fn tagged<'a>(tag: &'a str, value: dbus_pure_proto::Variant<'a>) -> dbus_pure_proto::Variant<'a> {
+		dbus_pure_proto::Variant::Struct {
+			fields: vec![string(tag), dbus_pure_proto::Variant::Variant(Box::new(value).into())].into(),
+		}
+	}
+
+	round_trip(&Address::Ipv4(0x7f00_0001), &tagged("ipv4", dbus_pure_proto::Variant::U32(0x7f00_0001)));
+	round_trip(
+		&Address::Ipv6(vec![0; 16], 64),
+		&tagged("ipv6", dbus_pure_proto::Variant::Struct {
+			fields: vec![dbus_pure_proto::AsVariant::as_variant(&vec![0_u8; 16]), dbus_pure_proto::Variant::U32(64)].into(),
+		}),
+	);
+	round_trip(
+		&Address::Named { host: "localhost".to_owned() },
+		&tagged("Named", dbus_pure_proto::Variant::Struct { fields: vec![string("localhost")].into() }),
+	);
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Response<'a> {
+	foo: u32,
+	bar: std::borrow::Cow<'a, str>,
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Point(i32, i32);
+
+#[test]
+fn structs() {
+	round_trip(
+		&Response { foo: 5, bar: "baz".into() },
+		&dbus_pure_proto::Variant::Struct { fields: vec![dbus_pure_proto::Variant::U32(5), string("baz")].into() },
+	);
+	round_trip(
+		&Point(-1, 2),
+		&dbus_pure_proto::Variant::Struct { fields: vec![dbus_pure_proto::Variant::I32(-1), dbus_pure_proto::Variant::I32(2)].into() },
+	);
+
+	assert_eq!(<Response<'_> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("(us)"));
+	assert_eq!(<Point as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("(ii)"));
+
+	assert!(matches!(
+		<Point as dbus_pure_proto::FromVariant<'_>>::from_variant(dbus_pure_proto::Variant::Struct { fields: vec![dbus_pure_proto::Variant::I32(-1)].into() }),
+		Err(dbus_pure_proto::FromVariantError::UnexpectedSignature { expected, actual }) if expected == *"(ii)" && actual == *"(i)",
+	));
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Device<'a> {
+	#[dbus(signature = "as")]
+	paths: Vec<dbus_pure_proto::ObjectPath<'a>>,
+
+	#[dbus(signature = "h")]
+	fd: u32,
+
+	#[dbus(skip)]
+	cached_name: Option<String>,
+
+	#[dbus(with = "mac_address")]
+	address: [u8; 6],
+}
+
+/// Sends a MAC address as a string like `"00:11:22:33:44:55"`
+mod mac_address {
+	pub(super) fn signature() -> dbus_pure_proto::Signature {
+		dbus_pure_proto::Signature::String
+	}
+
+	pub(super) fn as_variant(value: &[u8; 6]) -> dbus_pure_proto::Variant<'_> {
+		let value: Vec<_> = value.iter().map(|b| format!("{:02x}", b)).collect();
+		dbus_pure_proto::Variant::String(value.join(":").into())
+	}
+
+	pub(super) fn from_variant(variant: dbus_pure_proto::Variant<'_>) -> Result<[u8; 6], dbus_pure_proto::FromVariantError> {
+		let value: std::borrow::Cow<'_, str> = dbus_pure_proto::FromVariant::from_variant(variant)?;
+		let value: Vec<_> = value.split(':').filter_map(|b| u8::from_str_radix(b, 16).ok()).collect();
+		let len = value.len();
+		let result = std::convert::TryInto::try_into(value).map_err(|_| dbus_pure_proto::FromVariantError::InvalidLength { expected: 6, actual: len })?;
+		Ok(result)
+	}
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Child {
+	#[dbus(signature = "o")]
+	parent: String,
+
+	#[dbus(signature = "ag")]
+	types: Vec<String>,
+}
+
+#[test]
+fn validated_string_fields() {
+	round_trip(
+		&Child { parent: "/a".to_owned(), types: vec!["u".to_owned()] },
+		&dbus_pure_proto::Variant::Struct {
+			fields: vec![
+				dbus_pure_proto::Variant::ObjectPath(dbus_pure_proto::ObjectPath::new("/a").unwrap()),
+				dbus_pure_proto::Variant::Array {
+					element_signature: dbus_pure_proto::Signature::Signature,
+					elements: vec![dbus_pure_proto::Variant::Signature(dbus_pure_proto::Signature::U32)].into(),
+				},
+			].into(),
+		},
+	);
+	assert_eq!(<Child as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("(oag)"));
+
+	let child = Child { parent: "/a".to_owned(), types: vec![] };
+	assert_eq!(dbus_pure_proto::AsVariant::try_as_variant(&child).unwrap(), dbus_pure_proto::AsVariant::as_variant(&child));
+
+	// Strings that aren't valid object paths or signatures fail `try_as_variant`, including inside containers,
+	// and are sent as strings by `as_variant`.
+	let child = Child { parent: "a".to_owned(), types: vec![] };
+	assert!(matches!(dbus_pure_proto::AsVariant::try_as_variant(&child), Err(dbus_pure_proto::ToVariantError::InvalidObjectPath(_))));
+	assert!(matches!(dbus_pure_proto::AsVariant::try_as_variant(&vec![child]), Err(dbus_pure_proto::ToVariantError::InvalidObjectPath(_))));
+
+	let child = Child { parent: "/a".to_owned(), types: vec!["a{".to_owned()] };
+	assert!(matches!(dbus_pure_proto::AsVariant::try_as_variant(&child), Err(dbus_pure_proto::ToVariantError::InvalidSignature(signature)) if signature == "a{"));
+	assert_eq!(dbus_pure_proto::AsVariant::as_variant(&child), dbus_pure_proto::Variant::Struct {
+		fields: vec![
+			dbus_pure_proto::Variant::ObjectPath(dbus_pure_proto::ObjectPath::new("/a").unwrap()),
+			dbus_pure_proto::Variant::Array { element_signature: dbus_pure_proto::Signature::String, elements: vec![string("a{")].into() },
+		].into(),
+	});
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Id(u64);
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+struct Empty;
+
+#[test]
+fn field_attrs() {
+	round_trip(
+		&Device {
+			paths: vec![dbus_pure_proto::ObjectPath::new("/a").unwrap()],
+			fd: 3,
+			cached_name: None,
+			address: [0x00, 0x11, 0x22, 0x33, 0x44, 0xff],
+		},
+		&dbus_pure_proto::Variant::Struct {
+			fields: vec![
+				dbus_pure_proto::Variant::Array { element_signature: dbus_pure_proto::Signature::String, elements: vec![string("/a")].into() },
+				dbus_pure_proto::Variant::UnixFd(dbus_pure_proto::UnixFd(3)),
+				string("00:11:22:33:44:ff"),
+			].into(),
+		},
+	);
+
+	// Skipped fields are not sent, and are set to their default value when received.
+	let device = Device {
+		paths: vec![],
+		fd: 3,
+		cached_name: Some("foo".to_owned()),
+		address: [0; 6],
+	};
+	let actual: Device<'_> = dbus_pure_proto::FromVariant::from_variant(dbus_pure_proto::AsVariant::as_variant(&device)).unwrap();
+	assert_eq!(actual, Device {
+		paths: vec![],
+		fd: 3,
+		cached_name: None,
+		address: [0; 6],
+	});
+
+	// Strings received for fields with object paths are validated.
+	assert!(matches!(
+		<Device<'_> as dbus_pure_proto::FromVariant<'_>>::from_variant(dbus_pure_proto::Variant::Struct {
+			fields: vec![
+				dbus_pure_proto::Variant::Array { element_signature: dbus_pure_proto::Signature::String, elements: vec![string("a")].into() },
+				dbus_pure_proto::Variant::UnixFd(dbus_pure_proto::UnixFd(3)),
+				string("00:11:22:33:44:ff"),
+			].into(),
+		}),
+		Err(dbus_pure_proto::FromVariantError::InvalidObjectPath(..)),
+	));
+
+	// The signature isn't known at compile time because of the field with `#[dbus(with = "...")]`
+	assert_eq!(<Device<'_> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, None);
+}
+
+#[test]
+fn newtype_and_unit_structs() {
+	round_trip(&Id(5), &dbus_pure_proto::Variant::U64(5));
+	assert_eq!(<Id as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("t"));
+
+	round_trip(&Empty, &dbus_pure_proto::AsVariant::as_variant(&()));
+	assert_eq!(<Empty as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some(""));
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+#[dbus(dict)]
+struct Hints<'a> {
+	urgency: Option<u8>,
+
+	#[dbus(rename = "desktop-entry")]
+	desktop_entry: Option<String>,
+
+	#[dbus(flatten)]
+	other: std::collections::BTreeMap<String, dbus_pure_proto::Variant<'a>>,
+}
+
+#[derive(Debug, PartialEq, dbus_pure_macros::AsVariant, dbus_pure_macros::FromVariant)]
+#[dbus(dict)]
+struct Options {
+	timeout: Option<u32>,
+}
+
+#[test]
+fn dicts() {
+	round_trip(
+		&Hints {
+			urgency: Some(2),
+			desktop_entry: Some("foo".to_owned()),
+			other: vec![("x-bar".to_owned(), dbus_pure_proto::Variant::Bool(true))].into_iter().collect(),
+		},
+		&dbus_pure_proto::dict_as_variant(vec![
+			("urgency".into(), dbus_pure_proto::Variant::U8(2)),
+			("desktop-entry".into(), string("foo")),
+			("x-bar".into(), dbus_pure_proto::Variant::Bool(true)),
+		]),
+	);
+	round_trip(
+		&Hints { urgency: None, desktop_entry: None, other: Default::default() },
+		&dbus_pure_proto::dict_as_variant(vec![]),
+	);
+	assert_eq!(<Hints<'_> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("a{sv}"));
+
+	// Keys that don't match any field are ignored without a flattened field.
+	let actual: Options = dbus_pure_proto::FromVariant::from_variant(dbus_pure_proto::dict_as_variant(vec![
+		("timeout".into(), dbus_pure_proto::Variant::U32(5)),
+		("x-bar".into(), dbus_pure_proto::Variant::Bool(true)),
+	])).unwrap();
+	assert_eq!(actual, Options { timeout: Some(5) });
+}
@@ -0,0 +1,283 @@
+/// Parse the given arguments into a value of the given signature.
+///
+/// The syntax is the same as that of `busctl call`, where every basic value is one argument:
+///
+/// - Basic types are a single argument each, such as `hello` for `s` and `5` for `u`. Booleans can be `true`/`false`, `yes`/`no`, `on`/`off` or `1`/`0`.
+///
+/// - Arrays are the number of elements followed by the elements, such as `2 a b` for `as`.
+///
+/// - Dict entries are the key followed by the value, such as `1 key value` for `a{ss}`.
+///
+/// - Structs are their fields in order, such as `hello 5` for `(su)`.
+///
+/// - Maybe values are parsed like arrays of at most one element, such as `1 hello` or `0` for `ms`.
+///
+/// - Variants are the signature of the inner value followed by the inner value, such as `s hello` for `v`.
+///
+/// Unix fds cannot be parsed, since an argument cannot hold a file descriptor.
+///
+/// A signature of more than one type, such as `sa{sv}`, is parsed into a `Variant::Tuple` of the values, which can be used as a message body.
+/// All the arguments must be consumed by the signature.
+///
+/// # Example
+///
+/// ```rust
+/// let signature: dbus_pure_proto::Signature = "sa{sv}".parse().unwrap();
+/// let body = dbus_pure_proto::parse_args(&signature, &["hello", "1", "key", "u", "5"]).unwrap();
+/// assert_eq!(body.inner_signature(), signature);
+///
+/// let err = dbus_pure_proto::parse_args(&signature, &["hello", "1", "key", "u", "five"]).unwrap_err();
+/// assert_eq!(err.to_string(), r#"invalid argument "five" at index 4: expected a value of type "u""#);
+/// ```
+pub fn parse_args<'a, S>(signature: &crate::Signature, args: &[S]) -> Result<crate::Variant<'a>, ParseArgsError> where S: AsRef<str> {
+	let mut parser = Parser { args, index: 0 };
+	let value = parser.parse(signature)?;
+
+	if let Some(arg) = args.get(parser.index) {
+		return Err(ParseArgsError::TooManyArguments { index: parser.index, arg: arg.as_ref().to_owned() });
+	}
+
+	Ok(value)
+}
+
+struct Parser<'a, S> {
+	args: &'a [S],
+	index: usize,
+}
+
+impl<S> Parser<'_, S> where S: AsRef<str> {
+	fn parse<'b>(&mut self, signature: &crate::Signature) -> Result<crate::Variant<'b>, ParseArgsError> {
+		Ok(match signature {
+			crate::Signature::Array { element } => {
+				let len: usize = self.next_parse(signature)?;
+				let elements: Result<Vec<_>, _> = (0..len).map(|_| self.parse(element)).collect();
+				crate::Variant::Array {
+					element_signature: (**element).clone(),
+					elements: elements?.into(),
+				}
+			},
+
+			crate::Signature::Bool => {
+				let (index, arg) = self.next(signature)?;
+				let value = match arg {
+					"true" | "yes" | "on" | "1" => true,
+					"false" | "no" | "off" | "0" => false,
+					_ => return Err(ParseArgsError::InvalidArgument { index, arg: arg.to_owned(), expected: signature.clone() }),
+				};
+				crate::Variant::Bool(value)
+			},
+
+			crate::Signature::DictEntry { key, value } => {
+				let key = self.parse(key)?;
+				let value = self.parse(value)?;
+				crate::Variant::DictEntry {
+					key: Box::new(key).into(),
+					value: Box::new(value).into(),
+				}
+			},
+
+			crate::Signature::F64 => crate::Variant::F64(self.next_parse(signature)?),
+
+			crate::Signature::I16 => crate::Variant::I16(self.next_parse(signature)?),
+
+			crate::Signature::I32 => crate::Variant::I32(self.next_parse(signature)?),
+
+			crate::Signature::I64 => crate::Variant::I64(self.next_parse(signature)?),
+
+			crate::Signature::Maybe { element } => {
+				let (index, arg) = self.next(signature)?;
+				let value = match arg {
+					"0" => None,
+					"1" => Some(Box::new(self.parse(element)?).into()),
+					_ => return Err(ParseArgsError::InvalidArgument { index, arg: arg.to_owned(), expected: signature.clone() }),
+				};
+				crate::Variant::Maybe { element_signature: (**element).clone(), value }
+			},
+
+			crate::Signature::ObjectPath => {
+				let (index, arg) = self.next(signature)?;
+				let path =
+					crate::ObjectPath::new(arg.to_owned())
+					.map_err(|_| ParseArgsError::InvalidArgument { index, arg: arg.to_owned(), expected: signature.clone() })?;
+				crate::Variant::ObjectPath(path)
+			},
+
+			crate::Signature::Signature => crate::Variant::Signature(self.next_signature(signature)?),
+
+			crate::Signature::String => {
+				let (_, arg) = self.next(signature)?;
+				crate::Variant::String(arg.to_owned().into())
+			},
+
+			crate::Signature::Struct { fields } => {
+				let fields: Result<Vec<_>, _> = fields.iter().map(|field| self.parse(field)).collect();
+				crate::Variant::Struct { fields: fields?.into() }
+			},
+
+			crate::Signature::Tuple { elements } => {
+				let elements: Result<Vec<_>, _> = elements.iter().map(|element| self.parse(element)).collect();
+				crate::Variant::Tuple { elements: elements?.into() }
+			},
+
+			crate::Signature::U8 => crate::Variant::U8(self.next_parse(signature)?),
+
+			crate::Signature::U16 => crate::Variant::U16(self.next_parse(signature)?),
+
+			crate::Signature::U32 => crate::Variant::U32(self.next_parse(signature)?),
+
+			crate::Signature::U64 => crate::Variant::U64(self.next_parse(signature)?),
+
+			crate::Signature::UnixFd => {
+				let (index, arg) = self.next(signature)?;
+				return Err(ParseArgsError::UnixFd { index, arg: arg.to_owned() });
+			},
+
+			crate::Signature::Variant => {
+				let inner_signature = self.next_signature(signature)?;
+				let inner = self.parse(&inner_signature)?;
+				crate::Variant::Variant(Box::new(inner).into())
+			},
+		})
+	}
+
+	fn next(&mut self, expected: &crate::Signature) -> Result<(usize, &str), ParseArgsError> {
+		let index = self.index;
+		let arg = self.args.get(index).ok_or_else(|| ParseArgsError::MissingArgument { index, expected: expected.clone() })?;
+		self.index += 1;
+		Ok((index, arg.as_ref()))
+	}
+
+	fn next_parse<T>(&mut self, expected: &crate::Signature) -> Result<T, ParseArgsError> where T: std::str::FromStr {
+		let (index, arg) = self.next(expected)?;
+		let value = arg.parse().map_err(|_| ParseArgsError::InvalidArgument { index, arg: arg.to_owned(), expected: expected.clone() })?;
+		Ok(value)
+	}
+
+	fn next_signature(&mut self, expected: &crate::Signature) -> Result<crate::Signature, ParseArgsError> {
+		let (index, arg) = self.next(expected)?;
+		let signature = arg.parse().map_err(|_| ParseArgsError::InvalidArgument { index, arg: arg.to_owned(), expected: expected.clone() })?;
+		Ok(signature)
+	}
+}
+
+/// An error from parsing arguments with [`parse_args`].
+///
+/// Indices are 0-based positions in the arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseArgsError {
+	/// The argument at the given index is not a valid value of the expected type.
+	///
+	/// For an array, the expected type is the array type itself if the argument is its number of elements.
+	/// For a variant, the expected type is `v` if the argument is the signature of its inner value.
+	InvalidArgument { index: usize, arg: String, expected: crate::Signature },
+
+	/// The arguments ran out at the given index while a value of the expected type was still needed.
+	MissingArgument { index: usize, expected: crate::Signature },
+
+	/// The signature was satisfied before the argument at the given index.
+	TooManyArguments { index: usize, arg: String },
+
+	/// The argument at the given index is a unix fd, which cannot be parsed.
+	UnixFd { index: usize, arg: String },
+}
+
+impl std::fmt::Display for ParseArgsError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseArgsError::InvalidArgument { index, arg, expected } =>
+				write!(f, "invalid argument {:?} at index {}: expected a value of type {:?}", arg, index, expected.to_string()),
+			ParseArgsError::MissingArgument { index, expected } =>
+				write!(f, "missing argument at index {}: expected a value of type {:?}", index, expected.to_string()),
+			ParseArgsError::TooManyArguments { index, arg } =>
+				write!(f, "unexpected argument {:?} at index {}: the signature is already complete", arg, index),
+			ParseArgsError::UnixFd { index, arg } =>
+				write!(f, "invalid argument {:?} at index {}: unix fds cannot be parsed from arguments", arg, index),
+		}
+	}
+}
+
+impl std::error::Error for ParseArgsError {
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse_args() {
+		fn parse(signature: &str, args: &str) -> Result<crate::Variant<'static>, super::ParseArgsError> {
+			let signature: crate::Signature = signature.parse().unwrap();
+			let args: Vec<&str> = args.split(' ').filter(|arg| !arg.is_empty()).collect();
+			super::parse_args(&signature, &args)
+		}
+
+		assert_eq!(parse("", ""), Ok(crate::Variant::Tuple { elements: vec![].into() }));
+
+		assert_eq!(parse("u", "5"), Ok(crate::Variant::U32(5)));
+
+		assert_eq!(
+			parse("sa{sv}", "hello 2 key1 s value key2 b yes"),
+			Ok(crate::Variant::Tuple {
+				elements: vec![
+					crate::Variant::String("hello".into()),
+					crate::Variant::Array {
+						element_signature: crate::Signature::DictEntry { key: Box::new(crate::Signature::String), value: Box::new(crate::Signature::Variant) },
+						elements: vec![
+							crate::Variant::DictEntry {
+								key: Box::new(crate::Variant::String("key1".into())).into(),
+								value: Box::new(crate::Variant::Variant(Box::new(crate::Variant::String("value".into())).into())).into(),
+							},
+							crate::Variant::DictEntry {
+								key: Box::new(crate::Variant::String("key2".into())).into(),
+								value: Box::new(crate::Variant::Variant(Box::new(crate::Variant::Bool(true)).into())).into(),
+							},
+						].into(),
+					},
+				].into(),
+			}),
+		);
+
+		assert_eq!(
+			parse("(oag)", "/foo 0"),
+			Ok(crate::Variant::Struct {
+				fields: vec![
+					crate::Variant::ObjectPath(crate::ObjectPath("/foo".into())),
+					crate::Variant::Array { element_signature: crate::Signature::Signature, elements: vec![].into() },
+				].into(),
+			}),
+		);
+
+		assert_eq!(
+			super::parse_args(&crate::Signature::from_gvariant_type("(msms)").unwrap(), &["1", "hello", "0"]),
+			Ok(crate::Variant::Struct {
+				fields: vec![
+					crate::Variant::Maybe { element_signature: crate::Signature::String, value: Some(Box::new(crate::Variant::String("hello".into())).into()) },
+					crate::Variant::Maybe { element_signature: crate::Signature::String, value: None },
+				].into(),
+			}),
+		);
+
+		assert_eq!(
+			parse("su", "hello five"),
+			Err(super::ParseArgsError::InvalidArgument { index: 1, arg: "five".to_owned(), expected: crate::Signature::U32 }),
+		);
+		assert_eq!(
+			parse("as", "x a"),
+			Err(super::ParseArgsError::InvalidArgument { index: 0, arg: "x".to_owned(), expected: "as".parse().unwrap() }),
+		);
+		assert_eq!(
+			parse("v", "(s a"),
+			Err(super::ParseArgsError::InvalidArgument { index: 0, arg: "(s".to_owned(), expected: crate::Signature::Variant }),
+		);
+		assert_eq!(
+			parse("as", "2 a"),
+			Err(super::ParseArgsError::MissingArgument { index: 2, expected: crate::Signature::String }),
+		);
+		assert_eq!(
+			parse("s", "a b"),
+			Err(super::ParseArgsError::TooManyArguments { index: 1, arg: "b".to_owned() }),
+		);
+		assert_eq!(
+			parse("h", "3"),
+			Err(super::ParseArgsError::UnixFd { index: 0, arg: "3".to_owned() }),
+		);
+	}
+}
@@ -3,16 +3,90 @@
 /// This is useful to allow a value of this type to be serialized into a message body.
 ///
 /// Consider using `#[derive(dbus_pure_macros::AsVariant)]` to implement this trait for your custom struct types,
-/// along with `#[derive(serde_derive::Deserialize)]` to be able to deserialize a message body into this type.
+/// along with `#[derive(dbus_pure_macros::FromVariant)]` or `#[derive(serde_derive::Deserialize)]` to be able to convert
+/// a message body back into this type.
 pub trait AsVariant {
+	/// The D-Bus signature of a value of this type as a string, if it can be known at compile time.
+	///
+	/// If this is `Some`, it's the string form of [`AsVariant::signature`]. It can be compared with a [`crate::Signature`] without allocating.
+	/// Impls for container types like `Vec<T>` build it from the `SIGNATURE_STR` of their element types with [`SignatureStr`],
+	/// and leave it as `None` if any of those is `None`.
+	const SIGNATURE_STR: Option<&'static str> = None;
+
 	/// Get the D-Bus signature of a value of this type.
 	fn signature() -> crate::Signature;
 
 	/// Convert this value to a variant.
 	fn as_variant<'a>(&'a self) -> crate::Variant<'a>;
+
+	/// Convert this value to a variant, or fail if the value cannot be sent with the signature of its type.
+	///
+	/// This is the same as [`AsVariant::as_variant`] for most types. It fails for `#[derive(dbus_pure_macros::AsVariant)]` types
+	/// with a string field sent as an object path or signature with `#[dbus(signature = "...")]`, if the string is not a valid one.
+	/// Impls for container types like `Vec<T>` call it on their elements.
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		Ok(self.as_variant())
+	}
+}
+
+/// A marker trait for types whose signature is a basic type, which are the only types that can be the keys of dict entries.
+///
+/// This is the bound on the keys of the [`AsVariant`] and [`crate::FromVariant`] impls for `HashMap` and `BTreeMap`.
+pub trait DictKey {}
+
+impl<T> DictKey for &'_ T where T: DictKey + ?Sized {}
+impl<T> DictKey for Box<T> where T: DictKey + ?Sized {}
+impl DictKey for bool {}
+impl DictKey for f64 {}
+impl DictKey for i16 {}
+impl DictKey for i32 {}
+impl DictKey for i64 {}
+impl DictKey for crate::ObjectPath<'_> {}
+impl DictKey for crate::Signature {}
+impl DictKey for str {}
+impl DictKey for String {}
+impl DictKey for std::borrow::Cow<'_, str> {}
+impl DictKey for u8 {}
+impl DictKey for u16 {}
+impl DictKey for u32 {}
+impl DictKey for u64 {}
+impl DictKey for crate::UnixFd {}
+
+impl<T> AsVariant for &'_ T where T: AsVariant + ?Sized {
+	const SIGNATURE_STR: Option<&'static str> = T::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		T::signature()
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		(**self).as_variant()
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		(**self).try_as_variant()
+	}
+}
+
+impl<T> AsVariant for Box<T> where T: AsVariant + ?Sized {
+	const SIGNATURE_STR: Option<&'static str> = T::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		T::signature()
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		(**self).as_variant()
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		(**self).try_as_variant()
+	}
 }
 
 impl AsVariant for bool {
+	const SIGNATURE_STR: Option<&'static str> = Some("b");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::Bool
 	}
@@ -23,6 +97,8 @@ impl AsVariant for bool {
 }
 
 impl AsVariant for f64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("d");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::F64
 	}
@@ -33,6 +109,8 @@ impl AsVariant for f64 {
 }
 
 impl AsVariant for i16 {
+	const SIGNATURE_STR: Option<&'static str> = Some("n");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::I16
 	}
@@ -43,6 +121,8 @@ impl AsVariant for i16 {
 }
 
 impl AsVariant for i32 {
+	const SIGNATURE_STR: Option<&'static str> = Some("i");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::I32
 	}
@@ -53,6 +133,8 @@ impl AsVariant for i32 {
 }
 
 impl AsVariant for i64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("x");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::I64
 	}
@@ -62,17 +144,46 @@ impl AsVariant for i64 {
 	}
 }
 
+/// `Option<T>` is the `GVariant` maybe type `m`, which cannot be used in D-Bus messages.
+impl<T> AsVariant for Option<T> where T: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("m"), T::SIGNATURE_STR]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Maybe {
+			element: Box::new(<T as AsVariant>::signature()),
+		}
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		crate::Variant::Maybe {
+			element_signature: <T as AsVariant>::signature(),
+			value: self.as_ref().map(|value| Box::new(value.as_variant()).into()),
+		}
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		Ok(crate::Variant::Maybe {
+			element_signature: <T as AsVariant>::signature(),
+			value: self.as_ref().map(|value| value.try_as_variant().map(|value| Box::new(value).into())).transpose()?,
+		})
+	}
+}
+
 impl AsVariant for crate::ObjectPath<'_> {
+	const SIGNATURE_STR: Option<&'static str> = Some("o");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::ObjectPath
 	}
 
 	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
-		crate::Variant::ObjectPath(crate::ObjectPath((&*self.0).into()))
+		crate::Variant::ObjectPath(self.borrowed())
 	}
 }
 
 impl AsVariant for crate::Signature {
+	const SIGNATURE_STR: Option<&'static str> = Some("g");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::Signature
 	}
@@ -83,6 +194,8 @@ impl AsVariant for crate::Signature {
 }
 
 impl AsVariant for str {
+	const SIGNATURE_STR: Option<&'static str> = Some("s");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::String
 	}
@@ -93,6 +206,8 @@ impl AsVariant for str {
 }
 
 impl AsVariant for String {
+	const SIGNATURE_STR: Option<&'static str> = Some("s");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::String
 	}
@@ -103,6 +218,8 @@ impl AsVariant for String {
 }
 
 impl AsVariant for std::borrow::Cow<'_, str> {
+	const SIGNATURE_STR: Option<&'static str> = Some("s");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::String
 	}
@@ -113,6 +230,8 @@ impl AsVariant for std::borrow::Cow<'_, str> {
 }
 
 impl AsVariant for u8 {
+	const SIGNATURE_STR: Option<&'static str> = Some("y");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::U8
 	}
@@ -123,6 +242,8 @@ impl AsVariant for u8 {
 }
 
 impl AsVariant for u16 {
+	const SIGNATURE_STR: Option<&'static str> = Some("q");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::U16
 	}
@@ -133,6 +254,8 @@ impl AsVariant for u16 {
 }
 
 impl AsVariant for u32 {
+	const SIGNATURE_STR: Option<&'static str> = Some("u");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::U32
 	}
@@ -143,6 +266,8 @@ impl AsVariant for u32 {
 }
 
 impl AsVariant for u64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("t");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::U64
 	}
@@ -152,7 +277,64 @@ impl AsVariant for u64 {
 	}
 }
 
+impl AsVariant for () {
+	const SIGNATURE_STR: Option<&'static str> = Some("");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Tuple {
+			elements: vec![],
+		}
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		crate::Variant::Tuple {
+			elements: (&[][..]).into(),
+		}
+	}
+}
+
+macro_rules! tuple_as_variant {
+	($($ty:ident $field:tt,)*) => {
+		impl<$($ty,)*> AsVariant for ($($ty,)*) where $($ty: AsVariant,)* {
+			const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("("), $($ty::SIGNATURE_STR,)* Some(")")]));
+
+			fn signature() -> crate::Signature {
+				crate::Signature::Struct {
+					fields: vec![$(<$ty as AsVariant>::signature(),)*],
+				}
+			}
+
+			fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+				crate::Variant::Struct {
+					fields: vec![$(self.$field.as_variant(),)*].into(),
+				}
+			}
+
+			fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+				Ok(crate::Variant::Struct {
+					fields: vec![$(self.$field.try_as_variant()?,)*].into(),
+				})
+			}
+		}
+	};
+}
+
+tuple_as_variant! { T0 0, }
+tuple_as_variant! { T0 0, T1 1, }
+tuple_as_variant! { T0 0, T1 1, T2 2, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, }
+tuple_as_variant! { T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11, }
+
 impl AsVariant for crate::UnixFd {
+	const SIGNATURE_STR: Option<&'static str> = Some("h");
+
 	fn signature() -> crate::Signature {
 		crate::Signature::UnixFd
 	}
@@ -162,8 +344,22 @@ impl AsVariant for crate::UnixFd {
 	}
 }
 
+impl AsVariant for crate::Variant<'_> {
+	const SIGNATURE_STR: Option<&'static str> = Some("v");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Variant
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		crate::Variant::Variant(Box::new(self.borrowed()).into())
+	}
+}
+
 // Lack of specialization means we can't impl this different for `[u8]` etc to use the more efficient `Variant::ArrayU8` etc
 impl<T> AsVariant for [T] where T: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("a"), T::SIGNATURE_STR]));
+
 	fn signature() -> crate::Signature {
 		crate::Signature::Array {
 			element: Box::new(<T as AsVariant>::signature()),
@@ -176,10 +372,36 @@ impl<T> AsVariant for [T] where T: AsVariant {
 			elements: self.iter().map(AsVariant::as_variant).collect::<Vec<_>>().into(),
 		}
 	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		Ok(crate::Variant::Array {
+			element_signature: <T as AsVariant>::signature(),
+			elements: self.iter().map(AsVariant::try_as_variant).collect::<Result<Vec<_>, _>>()?.into(),
+		})
+	}
+}
+
+// Lack of specialization means we can't impl this different for `[u8; N]` etc to use the more efficient `Variant::ArrayU8` etc
+impl<T, const N: usize> AsVariant for [T; N] where T: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = <[T] as AsVariant>::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		<[T] as AsVariant>::signature()
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		self[..].as_variant()
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		self[..].try_as_variant()
+	}
 }
 
 // Lack of specialization means we can't impl this different for `Cow<'_, [u8]>` etc to use the more efficient `Variant::ArrayU8` etc
 impl<T> AsVariant for std::borrow::Cow<'_, [T]> where T: AsVariant, [T]: std::borrow::ToOwned {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("a"), T::SIGNATURE_STR]));
+
 	fn signature() -> crate::Signature {
 		crate::Signature::Array {
 			element: Box::new(<T as AsVariant>::signature()),
@@ -192,10 +414,19 @@ impl<T> AsVariant for std::borrow::Cow<'_, [T]> where T: AsVariant, [T]: std::bo
 			elements: self.iter().map(AsVariant::as_variant).collect::<Vec<_>>().into(),
 		}
 	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		Ok(crate::Variant::Array {
+			element_signature: <T as AsVariant>::signature(),
+			elements: self.iter().map(AsVariant::try_as_variant).collect::<Result<Vec<_>, _>>()?.into(),
+		})
+	}
 }
 
 // Lack of specialization means we can't impl this different for `Vec<u8>` etc to use the more efficient `Variant::ArrayU8` etc
 impl<T> AsVariant for Vec<T> where T: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("a"), T::SIGNATURE_STR]));
+
 	fn signature() -> crate::Signature {
 		crate::Signature::Array {
 			element: Box::new(<T as AsVariant>::signature()),
@@ -208,4 +439,279 @@ impl<T> AsVariant for Vec<T> where T: AsVariant {
 			elements: self.iter().map(AsVariant::as_variant).collect::<Vec<_>>().into(),
 		}
 	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		Ok(crate::Variant::Array {
+			element_signature: <T as AsVariant>::signature(),
+			elements: self.iter().map(AsVariant::try_as_variant).collect::<Result<Vec<_>, _>>()?.into(),
+		})
+	}
+}
+
+/// Maps are arrays of dict entries `a{kv}`.
+impl<K, V, S> AsVariant for std::collections::HashMap<K, V, S> where K: AsVariant + DictKey, V: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("a{"), K::SIGNATURE_STR, V::SIGNATURE_STR, Some("}")]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Array {
+			element: Box::new(dict_entry_signature(<K as AsVariant>::signature(), <V as AsVariant>::signature())),
+		}
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		map_as_variant(self)
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		try_map_as_variant(self)
+	}
+}
+
+/// Maps are arrays of dict entries `a{kv}`.
+impl<K, V> AsVariant for std::collections::BTreeMap<K, V> where K: AsVariant + DictKey, V: AsVariant {
+	const SIGNATURE_STR: Option<&'static str> = SignatureStr::get(&SignatureStr::concat(&[Some("a{"), K::SIGNATURE_STR, V::SIGNATURE_STR, Some("}")]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Array {
+			element: Box::new(dict_entry_signature(<K as AsVariant>::signature(), <V as AsVariant>::signature())),
+		}
+	}
+
+	fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+		map_as_variant(self)
+	}
+
+	fn try_as_variant<'a>(&'a self) -> Result<crate::Variant<'a>, crate::ToVariantError> {
+		try_map_as_variant(self)
+	}
+}
+
+/// Converts the given keys and values to a variant of signature `a{sv}`, with each value wrapped in a [`crate::Variant::Variant`].
+///
+/// This is useful for implementing [`AsVariant`] for types that are sent as dictionaries of properties or options.
+/// [`crate::dict_from_variant`] does the reverse.
+///
+/// # Example
+///
+/// ```rust
+/// struct Hints {
+///     urgency: Option<u8>,
+///     category: Option<String>,
+/// }
+///
+/// impl dbus_pure_proto::AsVariant for Hints {
+///     fn signature() -> dbus_pure_proto::Signature {
+///         "a{sv}".parse().unwrap()
+///     }
+///
+///     fn as_variant<'a>(&'a self) -> dbus_pure_proto::Variant<'a> {
+///         let mut entries = vec![];
+///         if let Some(urgency) = &self.urgency {
+///             entries.push(("urgency".into(), urgency.as_variant()));
+///         }
+///         if let Some(category) = &self.category {
+///             entries.push(("category".into(), category.as_variant()));
+///         }
+///         dbus_pure_proto::dict_as_variant(entries)
+///     }
+/// }
+/// ```
+pub fn dict_as_variant<'a>(entries: impl IntoIterator<Item = (std::borrow::Cow<'a, str>, crate::Variant<'a>)>) -> crate::Variant<'a> {
+	crate::Variant::Array {
+		element_signature: dict_entry_signature(crate::Signature::String, crate::Signature::Variant),
+		elements:
+			entries.into_iter()
+			.map(|(key, value)| crate::Variant::DictEntry {
+				key: Box::new(crate::Variant::String(key)).into(),
+				value: Box::new(crate::Variant::Variant(Box::new(value).into())).into(),
+			})
+			.collect::<Vec<_>>()
+			.into(),
+	}
+}
+
+pub(crate) fn dict_entry_signature(key: crate::Signature, value: crate::Signature) -> crate::Signature {
+	assert!(key.is_basic(), "dict entry key must be of a basic type, not {}", key);
+
+	crate::Signature::DictEntry {
+		key: Box::new(key),
+		value: Box::new(value),
+	}
+}
+
+/// A signature string concatenated at compile time, for the [`AsVariant::SIGNATURE_STR`] and [`crate::FromVariant::SIGNATURE_STR`] of container types.
+///
+/// A `&'static str` can't be built from other `&'static str`s in a `const` directly. Instead, build a `SignatureStr` with
+/// [`SignatureStr::concat`], and get the string from a reference to it with [`SignatureStr::get`]. The reference must be taken
+/// in the same `const` so that it's `'static`.
+///
+/// # Example
+///
+/// ```rust
+/// struct Pair<T>(T, T);
+///
+/// impl<T> dbus_pure_proto::AsVariant for Pair<T> where T: dbus_pure_proto::AsVariant {
+///     const SIGNATURE_STR: Option<&'static str> = dbus_pure_proto::SignatureStr::get(&dbus_pure_proto::SignatureStr::concat(&[
+///         Some("("), T::SIGNATURE_STR, T::SIGNATURE_STR, Some(")"),
+///     ]));
+///
+///     fn signature() -> dbus_pure_proto::Signature {
+///         dbus_pure_proto::Signature::Struct { fields: vec![T::signature(), T::signature()] }
+///     }
+///
+///     fn as_variant<'a>(&'a self) -> dbus_pure_proto::Variant<'a> {
+///         dbus_pure_proto::Variant::Struct { fields: vec![self.0.as_variant(), self.1.as_variant()].into() }
+///     }
+/// }
+///
+/// assert_eq!(<Pair<u32> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("(uu)"));
+/// assert_eq!(<Pair<Vec<String>> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, Some("(asas)"));
+///
+/// const _: () = assert!(dbus_pure_proto::SignatureStr::matches(<Pair<u8> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, "(yy)"));
+/// const _: () = assert!(dbus_pure_proto::SignatureStr::converts_to(<Pair<String> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, "(og)"));
+/// const _: () = assert!(!dbus_pure_proto::SignatureStr::converts_to(<Pair<String> as dbus_pure_proto::AsVariant>::SIGNATURE_STR, "(su)"));
+/// ```
+pub struct SignatureStr {
+	buf: [u8; crate::SIGNATURE_MAX_LEN],
+
+	/// The length of the string in `buf`, or `None` if any of the parts was `None` or the string would be too long to be a signature.
+	len: Option<usize>,
+}
+
+impl SignatureStr {
+	/// Concatenates the given parts of a signature.
+	pub const fn concat(parts: &[Option<&str>]) -> Self {
+		let mut buf = [0_u8; crate::SIGNATURE_MAX_LEN];
+		let mut len = 0;
+
+		let mut i = 0;
+		while i < parts.len() {
+			let part = match parts[i] {
+				Some(part) => part.as_bytes(),
+				None => return SignatureStr { buf, len: None },
+			};
+
+			if part.len() > buf.len() - len {
+				return SignatureStr { buf, len: None };
+			}
+
+			let mut j = 0;
+			while j < part.len() {
+				buf[len] = part[j];
+				len += 1;
+				j += 1;
+			}
+
+			i += 1;
+		}
+
+		SignatureStr { buf, len: Some(len) }
+	}
+
+	/// The concatenated signature, or `None` if any of the parts was `None` or the signature would be longer than 255 bytes.
+	///
+	/// # Panics
+	///
+	/// Does not panic in practice, since the parts are whole `str`s and so their concatenation is valid UTF-8.
+	pub const fn get(&'static self) -> Option<&'static str> {
+		let len = match self.len {
+			Some(len) => len,
+			None => return None,
+		};
+
+		let (s, _) = self.buf.split_at(len);
+		match std::str::from_utf8(s) {
+			Ok(s) => Some(s),
+			Err(_) => panic!("signature parts are not split in the middle of a character"),
+		}
+	}
+
+	/// Whether the given `SIGNATURE_STR` is known and equal to the given signature.
+	///
+	/// This can be used to check the signature of a type at compile time, such as with `const _: () = assert!(...);`
+	pub const fn matches(signature_str: Option<&str>, signature: &str) -> bool {
+		let signature_str = match signature_str {
+			Some(signature_str) => signature_str.as_bytes(),
+			None => return false,
+		};
+		let signature = signature.as_bytes();
+
+		if signature_str.len() != signature.len() {
+			return false;
+		}
+
+		let mut i = 0;
+		while i < signature.len() {
+			if signature_str[i] != signature[i] {
+				return false;
+			}
+
+			i += 1;
+		}
+
+		true
+	}
+
+	/// Whether the given `SIGNATURE_STR` is known and a value of it can be converted to the given signature with [`crate::Variant::with_signature`].
+	///
+	/// This is the case if the two signatures are the same except that strings, object paths and signatures (`s`, `o` and `g`)
+	/// can be in place of each other, and so can `u32`s and unix fds (`u` and `h`).
+	pub const fn converts_to(signature_str: Option<&str>, signature: &str) -> bool {
+		const fn kind(b: u8) -> u8 {
+			match b {
+				b'o' | b'g' => b's',
+				b'h' => b'u',
+				b => b,
+			}
+		}
+
+		let signature_str = match signature_str {
+			Some(signature_str) => signature_str.as_bytes(),
+			None => return false,
+		};
+		let signature = signature.as_bytes();
+
+		if signature_str.len() != signature.len() {
+			return false;
+		}
+
+		let mut i = 0;
+		while i < signature.len() {
+			if kind(signature_str[i]) != kind(signature[i]) {
+				return false;
+			}
+
+			i += 1;
+		}
+
+		true
+	}
+}
+
+fn map_as_variant<'a, K, V>(map: impl IntoIterator<Item = (&'a K, &'a V)>) -> crate::Variant<'a> where K: AsVariant + 'a, V: AsVariant + 'a {
+	crate::Variant::Array {
+		element_signature: dict_entry_signature(<K as AsVariant>::signature(), <V as AsVariant>::signature()),
+		elements:
+			map.into_iter()
+			.map(|(key, value)| crate::Variant::DictEntry {
+				key: Box::new(key.as_variant()).into(),
+				value: Box::new(value.as_variant()).into(),
+			})
+			.collect::<Vec<_>>()
+			.into(),
+	}
+}
+
+fn try_map_as_variant<'a, K, V>(map: impl IntoIterator<Item = (&'a K, &'a V)>) -> Result<crate::Variant<'a>, crate::ToVariantError> where K: AsVariant + 'a, V: AsVariant + 'a {
+	Ok(crate::Variant::Array {
+		element_signature: dict_entry_signature(<K as AsVariant>::signature(), <V as AsVariant>::signature()),
+		elements:
+			map.into_iter()
+			.map(|(key, value)| Ok(crate::Variant::DictEntry {
+				key: Box::new(key.try_as_variant()?).into(),
+				value: Box::new(value.try_as_variant()?).into(),
+			}))
+			.collect::<Result<Vec<_>, crate::ToVariantError>>()?
+			.into(),
+	})
 }
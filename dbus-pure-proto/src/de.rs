@@ -1,8 +1,16 @@
+/// The maximum length of an array, in bytes.
+pub const MAX_ARRAY_LEN: usize = 64 * 1024 * 1024;
+
+/// The maximum number of arrays, structs, dict entries and variants that a value can be nested in.
+pub const MAX_DEPTH: usize = 64;
+
 #[derive(Debug)]
 pub(crate) struct Deserializer<'de> {
 	buf: &'de [u8],
 	pos: usize,
 	endianness: crate::Endianness,
+	/// The number of containers that the value being deserialized is nested in.
+	depth: usize,
 }
 
 impl<'de> Deserializer<'de> {
@@ -11,7 +19,44 @@ impl<'de> Deserializer<'de> {
 			buf,
 			pos,
 			endianness,
+			depth: 0,
+		}
+	}
+
+	/// Deserializes the contents of a container with `f`, failing if the container is itself nested in [`MAX_DEPTH`] containers.
+	fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, DeserializeError>) -> Result<T, DeserializeError> {
+		if self.depth >= MAX_DEPTH {
+			return Err(DeserializeError::NestingTooDeep);
+		}
+
+		self.depth += 1;
+		let result = f(self);
+		self.depth -= 1;
+		result
+	}
+
+	/// Reads the length of an array, failing if it exceeds [`MAX_ARRAY_LEN`].
+	fn array_len(&mut self) -> Result<usize, DeserializeError> {
+		let len: u32 = serde::Deserialize::deserialize(&mut *self)?;
+		let len: usize = std::convert::TryInto::try_into(len).map_err(DeserializeError::ExceedsNumericLimits)?;
+		if len > MAX_ARRAY_LEN {
+			return Err(DeserializeError::ArrayTooLong { len });
 		}
+		Ok(len)
+	}
+
+	/// A deserializer for the array of the given length that starts at the current position, which ends at the end of the array.
+	fn array(&self, len: usize) -> Result<Deserializer<'de>, DeserializeError> {
+		if self.buf.len() - self.pos < len {
+			return Err(DeserializeError::EndOfInput);
+		}
+
+		Ok(Deserializer {
+			buf: &self.buf[..(self.pos + len)],
+			pos: self.pos,
+			endianness: self.endianness,
+			depth: self.depth,
+		})
 	}
 
 	pub(crate) fn pad_to(&mut self, alignment: usize) -> Result<(), DeserializeError> {
@@ -194,15 +239,15 @@ impl<'de> serde::Deserializer<'de> for &'_ mut Deserializer<'de> {
 		unimplemented!();
 	}
 
-	fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
-		unimplemented!();
+	fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		self.deserialize_string(visitor)
 	}
 
 	fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
 		let len: u32 = serde::Deserialize::deserialize(&mut *self)?;
 		let len: usize = std::convert::TryInto::try_into(len).map_err(DeserializeError::ExceedsNumericLimits)?;
 
-		if self.buf.len() < self.pos + len + 1 {
+		if self.buf.len() - self.pos <= len {
 			return Err(DeserializeError::EndOfInput);
 		}
 		if self.buf[self.pos + len] != b'\0' {
@@ -216,8 +261,18 @@ impl<'de> serde::Deserializer<'de> for &'_ mut Deserializer<'de> {
 		visitor.visit_borrowed_str(s)
 	}
 
-	fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
-		unimplemented!();
+	// This deserializes a byte array (`ay`), borrowed from the input.
+	fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let len = self.array_len()?;
+
+		if self.buf.len() - self.pos < len {
+			return Err(DeserializeError::EndOfInput);
+		}
+
+		let data = &self.buf[self.pos..(self.pos + len)];
+		self.pos += len;
+
+		visitor.visit_borrowed_bytes(data)
 	}
 
 	fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
@@ -245,25 +300,27 @@ impl<'de> serde::Deserializer<'de> for &'_ mut Deserializer<'de> {
 		unimplemented!();
 	}
 
+	// Every value that `crate::VariantDeserializeSeed` deserializes is a tuple, including basic values, so a tuple is not necessarily a container.
+	// It only fails if it's nested in more than `MAX_DEPTH` containers, so that a basic value can be nested in exactly that many.
 	fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
-		visitor.visit_seq(TupleDeserializer(self))
+		if self.depth > MAX_DEPTH {
+			return Err(DeserializeError::NestingTooDeep);
+		}
+
+		self.depth += 1;
+		let result = visitor.visit_seq(TupleDeserializer(&mut *self));
+		self.depth -= 1;
+		result
 	}
 
 	// HACK: This deserializes a sequence, not a tuple struct. We use this instead of deserialize_seq because we need the Deserialize impl to pass in
 	// the alignment of the value it's deserializing, so that we can skip padding for empty arrays. So we (ab)use the `len` parameter to pass that in.
 	fn deserialize_tuple_struct<V>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
-		let data_len: u32 = serde::de::Deserialize::deserialize(&mut *self)?;
-		let data_len: usize = std::convert::TryInto::try_into(data_len).map_err(serde::de::Error::custom)?;
+		let data_len = self.array_len()?;
 
 		self.pad_to(len)?;
 
-		let data_end_pos = self.pos + data_len;
-
-		let mut inner = Deserializer {
-			buf: &self.buf.get(..data_end_pos).ok_or(DeserializeError::EndOfInput)?,
-			pos: self.pos,
-			endianness: self.endianness,
-		};
+		let mut inner = self.array(data_len)?;
 
 		let result = visitor.visit_seq(SeqDeserializer(&mut inner))?;
 
@@ -338,15 +395,353 @@ impl<'de, 'a> serde::de::MapAccess<'de> for StructDeserializer<'de, 'a> {
 	}
 }
 
+/// Deserializes a value of the given signature with the given deserializer.
+///
+/// Unlike deserializing the value with [`Deserializer`] directly, this does not require the value's `Deserialize` impl to follow the conventions
+/// of `Deserializer`, such as passing the alignment of array elements to `deserialize_tuple_struct`, so it works for any `Deserialize` type.
+/// The value is deserialized the same way as it would be from a [`crate::Variant`] of the same signature.
+pub(crate) struct SignatureDeserializer<'de, 'a, 's> {
+	inner: &'a mut Deserializer<'de>,
+	signature: &'s crate::Signature,
+}
+
+impl<'de, 'a, 's> SignatureDeserializer<'de, 'a, 's> {
+	pub(crate) fn new(inner: &'a mut Deserializer<'de>, signature: &'s crate::Signature) -> Self {
+		SignatureDeserializer {
+			inner,
+			signature,
+		}
+	}
+}
+
+impl<'de> serde::Deserializer<'de> for SignatureDeserializer<'de, '_, '_> {
+	type Error = DeserializeError;
+
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let SignatureDeserializer { inner, signature } = self;
+
+		match signature {
+			crate::Signature::Array { element } => inner.nested(|inner| {
+				let data_len = inner.array_len()?;
+
+				inner.pad_to(element.alignment())?;
+
+				let mut array_inner = inner.array(data_len)?;
+
+				// Special-case `Array { element: DictEntry { ... } }` to call visit_map,
+				// since that's what serde's Deserialize impls for `std::collections::*Map` want.
+				let result =
+					if let crate::Signature::DictEntry { key, value } = &**element {
+						visitor.visit_map(SignatureMapAccess { inner: &mut array_inner, key, value })?
+					}
+					else {
+						visitor.visit_seq(SignatureSeqAccess { inner: &mut array_inner, element })?
+					};
+
+				inner.pos = array_inner.buf.len();
+
+				Ok(result)
+			}),
+
+			crate::Signature::Bool => inner.deserialize_bool(visitor),
+
+			crate::Signature::DictEntry { key, value } => inner.nested(|inner| {
+				inner.pad_to(8)?;
+				let mut elements = vec![&**key, &**value].into_iter();
+				let result = visitor.visit_seq(SignatureTupleAccess { inner: &mut *inner, elements: &mut elements })?;
+				let () = ignore_remaining(inner, elements)?;
+				Ok(result)
+			}),
+
+			crate::Signature::F64 => inner.deserialize_f64(visitor),
+
+			crate::Signature::I16 => inner.deserialize_i16(visitor),
+
+			crate::Signature::I32 => inner.deserialize_i32(visitor),
+
+			crate::Signature::I64 => inner.deserialize_i64(visitor),
+
+			crate::Signature::Maybe { .. } => Err(serde::de::Error::custom("maybe values cannot be deserialized from the D-Bus format")),
+
+			crate::Signature::ObjectPath |
+			crate::Signature::String => inner.deserialize_string(visitor),
+
+			crate::Signature::Signature => {
+				let value: crate::Signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+				visitor.visit_string(value.to_string())
+			},
+
+			crate::Signature::Struct { fields: elements } => inner.nested(|inner| {
+				inner.pad_to(8)?;
+				let mut elements = elements.iter();
+				let result = visitor.visit_seq(SignatureTupleAccess { inner: &mut *inner, elements: &mut elements })?;
+				let () = ignore_remaining(inner, elements)?;
+				Ok(result)
+			}),
+
+			crate::Signature::Tuple { elements } if elements.is_empty() => visitor.visit_unit(),
+
+			crate::Signature::Tuple { elements } => {
+				let mut elements = elements.iter();
+				let result = visitor.visit_seq(SignatureTupleAccess { inner: &mut *inner, elements: &mut elements })?;
+				let () = ignore_remaining(inner, elements)?;
+				Ok(result)
+			},
+
+			crate::Signature::U8 => inner.deserialize_u8(visitor),
+
+			crate::Signature::U16 => inner.deserialize_u16(visitor),
+
+			crate::Signature::U32 |
+			crate::Signature::UnixFd => inner.deserialize_u32(visitor),
+
+			crate::Signature::U64 => inner.deserialize_u64(visitor),
+
+			crate::Signature::Variant => inner.nested(|inner| {
+				let signature: crate::Signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+				serde::Deserializer::deserialize_any(SignatureDeserializer { inner, signature: &signature }, visitor)
+			}),
+		}
+	}
+
+	fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		if name == crate::variant::NEWTYPE_NAME {
+			let SignatureDeserializer { inner, signature } = self;
+
+			return match signature {
+				crate::Signature::Variant => inner.nested(|inner| {
+					let signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+					visitor.visit_seq(VariantSeqAccess { inner, signature, next: VariantSeqAccessNext::Signature })
+				}),
+				signature => visitor.visit_seq(VariantSeqAccess { inner, signature: signature.clone(), next: VariantSeqAccessNext::Signature }),
+			};
+		}
+
+		self.deserialize_any(visitor)
+	}
+
+	// Same behavior as the `Deserializer` impl of `crate::Variant`
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let SignatureDeserializer { inner, signature } = self;
+
+		match signature {
+			crate::Signature::Maybe { .. } => SignatureDeserializer { inner, signature }.deserialize_any(visitor),
+
+			crate::Signature::Variant => inner.nested(|inner| {
+				let signature: crate::Signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+				SignatureDeserializer { inner, signature: &signature }.deserialize_option(visitor)
+			}),
+
+			signature => visitor.visit_some(SignatureDeserializer { inner, signature }),
+		}
+	}
+
+	// Same representation as the `Deserializer` impl of `crate::Variant`
+	fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let SignatureDeserializer { inner, signature } = self;
+
+		match signature {
+			crate::Signature::ObjectPath |
+			crate::Signature::String => {
+				let value: String = serde::de::Deserialize::deserialize(&mut *inner)?;
+				visitor.visit_enum(serde::de::IntoDeserializer::into_deserializer(value))
+			},
+
+			crate::Signature::Struct { fields } if matches!(&fields[..], [crate::Signature::String, crate::Signature::Variant]) => inner.nested(|inner| {
+				inner.pad_to(8)?;
+				visitor.visit_enum(SignatureEnumAccess { inner })
+			}),
+
+			crate::Signature::Variant => inner.nested(|inner| {
+				let signature: crate::Signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+				SignatureDeserializer { inner, signature: &signature }.deserialize_enum(name, variants, visitor)
+			}),
+
+			signature => SignatureDeserializer { inner, signature }.deserialize_any(visitor),
+		}
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool
+		i8 i16 i32 i64 i128
+		u8 u16 u32 u64 u128
+		f32 f64
+		char
+		str string
+		bytes byte_buf
+		unit unit_struct
+		seq tuple tuple_struct
+		map
+		struct
+		identifier
+		ignored_any
+	}
+
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+/// Skips the elements of a struct or tuple that the visitor did not deserialize.
+fn ignore_remaining<'s>(inner: &mut Deserializer<'_>, elements: impl Iterator<Item = &'s crate::Signature>) -> Result<(), DeserializeError> {
+	for signature in elements {
+		let serde::de::IgnoredAny = serde::de::Deserialize::deserialize(SignatureDeserializer { inner: &mut *inner, signature })?;
+	}
+
+	Ok(())
+}
+
+/// Visits the signature of a value as a string followed by the value itself, for the `serde::Deserialize` impl of `crate::Variant`.
+struct VariantSeqAccess<'de, 'a> {
+	inner: &'a mut Deserializer<'de>,
+	signature: crate::Signature,
+	next: VariantSeqAccessNext,
+}
+
+enum VariantSeqAccessNext {
+	Signature,
+	Value,
+	End,
+}
+
+impl<'de> serde::de::SeqAccess<'de> for VariantSeqAccess<'de, '_> {
+	type Error = DeserializeError;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> where T: serde::de::DeserializeSeed<'de> {
+		match self.next {
+			VariantSeqAccessNext::Signature => {
+				self.next = VariantSeqAccessNext::Value;
+				let signature = serde::de::IntoDeserializer::into_deserializer(self.signature.to_string());
+				seed.deserialize(signature).map(Some)
+			},
+
+			VariantSeqAccessNext::Value => {
+				self.next = VariantSeqAccessNext::End;
+				seed.deserialize(SignatureDeserializer { inner: &mut *self.inner, signature: &self.signature }).map(Some)
+			},
+
+			VariantSeqAccessNext::End => Ok(None),
+		}
+	}
+}
+
+/// Visits the name of an enum variant followed by its fields, from a struct of signature `(sv)`.
+struct SignatureEnumAccess<'de, 'a> {
+	inner: &'a mut Deserializer<'de>,
+}
+
+impl<'de, 'a> serde::de::EnumAccess<'de> for SignatureEnumAccess<'de, 'a> {
+	type Error = DeserializeError;
+	type Variant = SignatureVariantAccess<'de, 'a>;
+
+	fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> where V: serde::de::DeserializeSeed<'de> {
+		let SignatureEnumAccess { inner } = self;
+		let tag = seed.deserialize(SignatureDeserializer { inner: &mut *inner, signature: &crate::Signature::String })?;
+		let signature = serde::de::Deserialize::deserialize(&mut *inner)?;
+		Ok((tag, SignatureVariantAccess { inner, signature }))
+	}
+}
+
+struct SignatureVariantAccess<'de, 'a> {
+	inner: &'a mut Deserializer<'de>,
+	signature: crate::Signature,
+}
+
+impl<'de> serde::de::VariantAccess<'de> for SignatureVariantAccess<'de, '_> {
+	type Error = DeserializeError;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		let SignatureVariantAccess { inner, signature } = self;
+		let serde::de::IgnoredAny = serde::de::Deserialize::deserialize(SignatureDeserializer { inner, signature: &signature })?;
+		Ok(())
+	}
+
+	fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error> where T: serde::de::DeserializeSeed<'de> {
+		let SignatureVariantAccess { inner, signature } = self;
+		seed.deserialize(SignatureDeserializer { inner, signature: &signature })
+	}
+
+	fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let SignatureVariantAccess { inner, signature } = self;
+		serde::Deserializer::deserialize_any(SignatureDeserializer { inner, signature: &signature }, visitor)
+	}
+
+	fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		let SignatureVariantAccess { inner, signature } = self;
+		serde::Deserializer::deserialize_any(SignatureDeserializer { inner, signature: &signature }, visitor)
+	}
+}
+
+struct SignatureSeqAccess<'de, 'a, 's> {
+	inner: &'a mut Deserializer<'de>,
+	element: &'s crate::Signature,
+}
+
+impl<'de> serde::de::SeqAccess<'de> for SignatureSeqAccess<'de, '_, '_> {
+	type Error = DeserializeError;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> where T: serde::de::DeserializeSeed<'de> {
+		if self.inner.pos == self.inner.buf.len() {
+			Ok(None)
+		}
+		else {
+			seed.deserialize(SignatureDeserializer { inner: &mut *self.inner, signature: self.element }).map(Some)
+		}
+	}
+}
+
+struct SignatureMapAccess<'de, 'a, 's> {
+	inner: &'a mut Deserializer<'de>,
+	key: &'s crate::Signature,
+	value: &'s crate::Signature,
+}
+
+impl<'de> serde::de::MapAccess<'de> for SignatureMapAccess<'de, '_, '_> {
+	type Error = DeserializeError;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> where K: serde::de::DeserializeSeed<'de> {
+		if self.inner.pos == self.inner.buf.len() {
+			Ok(None)
+		}
+		else {
+			self.inner.pad_to(8)?;
+			seed.deserialize(SignatureDeserializer { inner: &mut *self.inner, signature: self.key }).map(Some)
+		}
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error> where V: serde::de::DeserializeSeed<'de> {
+		seed.deserialize(SignatureDeserializer { inner: &mut *self.inner, signature: self.value })
+	}
+}
+
+struct SignatureTupleAccess<'de, 'a, 'i, I> {
+	inner: &'a mut Deserializer<'de>,
+	elements: &'i mut I,
+}
+
+impl<'de, 's, I> serde::de::SeqAccess<'de> for SignatureTupleAccess<'de, '_, '_, I> where I: Iterator<Item = &'s crate::Signature> {
+	type Error = DeserializeError;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> where T: serde::de::DeserializeSeed<'de> {
+		self.elements.next()
+			.map(|signature| seed.deserialize(SignatureDeserializer { inner: &mut *self.inner, signature }))
+			.transpose()
+	}
+}
+
 /// An error from deserializing a value using the D-Bus binary protocol.
 #[derive(Debug)]
 pub enum DeserializeError {
 	ArrayElementDoesntMatchSignature { expected: crate::Signature, actual: crate::Signature },
+	ArrayTooLong { len: usize },
 	Custom(String),
 	DeserializeAnyNotSupported,
 	EndOfInput,
 	ExceedsNumericLimits(std::num::TryFromIntError),
+	InvalidHeaderField { field: &'static str, value: String, err: crate::NameError },
 	InvalidUtf8(std::str::Utf8Error),
+	MessageTooLong { len: usize },
+	NestingTooDeep,
 	NonZeroPadding { start: usize, end: usize },
 	StringMissingNulTerminator,
 	Unexpected(String),
@@ -360,11 +755,15 @@ impl std::fmt::Display for DeserializeError {
 				"array has element signature {} but it contains an element with signature {}",
 				expected, actual,
 			),
+			DeserializeError::ArrayTooLong { len } => write!(f, "array is {} bytes long, which exceeds the maximum of {} bytes", len, MAX_ARRAY_LEN),
 			DeserializeError::Custom(message) => f.write_str(message),
 			DeserializeError::DeserializeAnyNotSupported => f.write_str("deserialize_any is not supported"),
 			DeserializeError::EndOfInput => f.write_str("end of input"),
 			DeserializeError::ExceedsNumericLimits(_) => f.write_str("value exceeds numeric limits"),
+			DeserializeError::InvalidHeaderField { field, value, err: _ } => write!(f, "{} header field {:?} is invalid", field, value),
 			DeserializeError::InvalidUtf8(_) => f.write_str("deserialized string is not valid UTF-8"),
+			DeserializeError::MessageTooLong { len } => write!(f, "message is {} bytes long, which exceeds the maximum of {} bytes", len, crate::message::MAX_MESSAGE_LEN),
+			DeserializeError::NestingTooDeep => write!(f, "value is nested in more than {} containers", MAX_DEPTH),
 			DeserializeError::NonZeroPadding { start, end } => write!(f, "padding contains a byte other than 0x00 between positions {} and {}", start, end),
 			DeserializeError::StringMissingNulTerminator => f.write_str("deserialized string is not nul-terminated"),
 			DeserializeError::Unexpected(message) => f.write_str(message),
@@ -377,11 +776,15 @@ impl std::error::Error for DeserializeError {
 		#[allow(clippy::match_same_arms)]
 		match self {
 			DeserializeError::ArrayElementDoesntMatchSignature { expected: _, actual: _ } => None,
+			DeserializeError::ArrayTooLong { len: _ } => None,
 			DeserializeError::Custom(_) => None,
 			DeserializeError::DeserializeAnyNotSupported => None,
 			DeserializeError::EndOfInput => None,
 			DeserializeError::ExceedsNumericLimits(err) => Some(err),
+			DeserializeError::InvalidHeaderField { field: _, value: _, err } => Some(err),
 			DeserializeError::InvalidUtf8(err) => Some(err),
+			DeserializeError::MessageTooLong { len: _ } => None,
+			DeserializeError::NestingTooDeep => None,
 			DeserializeError::NonZeroPadding { start: _, end: _ } => None,
 			DeserializeError::StringMissingNulTerminator => None,
 			DeserializeError::Unexpected(_) => None,
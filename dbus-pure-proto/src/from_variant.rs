@@ -0,0 +1,762 @@
+/// A trait to convert a [`crate::Variant`] into a Rust value. It is the inverse of [`crate::AsVariant`].
+///
+/// Unlike deserializing the variant with `serde::Deserialize`, the signature of the variant is checked against the signature of the type,
+/// so a string is not accepted where an object path is expected, and a struct must have exactly as many fields as the type.
+///
+/// Consider using `#[derive(dbus_pure_macros::FromVariant)]` to implement this trait for your custom types.
+pub trait FromVariant<'a>: Sized {
+	/// The D-Bus signature of a value of this type as a string, if it can be known at compile time.
+	///
+	/// This is the same as [`crate::AsVariant::SIGNATURE_STR`] for types that impl both traits, and is used to check the signature of the variant
+	/// without building the signature of the type.
+	const SIGNATURE_STR: Option<&'static str> = None;
+
+	/// Get the D-Bus signature of a value of this type.
+	fn signature() -> crate::Signature;
+
+	/// Convert the variant to a value of this type.
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError>;
+}
+
+impl<'a, T> FromVariant<'a> for Box<T> where T: FromVariant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = T::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		T::signature()
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		T::from_variant(variant).map(Box::new)
+	}
+}
+
+impl<'a> FromVariant<'a> for bool {
+	const SIGNATURE_STR: Option<&'static str> = Some("b");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Bool
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::Bool(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for f64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("d");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::F64
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::F64(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for i16 {
+	const SIGNATURE_STR: Option<&'static str> = Some("n");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::I16
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::I16(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for i32 {
+	const SIGNATURE_STR: Option<&'static str> = Some("i");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::I32
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::I32(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for i64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("x");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::I64
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::I64(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+/// `Option<T>` is the `GVariant` maybe type `m`, which cannot be used in D-Bus messages.
+impl<'a, T> FromVariant<'a> for Option<T> where T: FromVariant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = crate::SignatureStr::get(&crate::SignatureStr::concat(&[Some("m"), T::SIGNATURE_STR]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Maybe {
+			element: Box::new(T::signature()),
+		}
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::Maybe { element_signature, value } if element_signature == T::signature() =>
+				value.map(|value| T::from_variant(ref_into_variant(value))).transpose(),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a: 'b, 'b> FromVariant<'a> for crate::ObjectPath<'b> {
+	const SIGNATURE_STR: Option<&'static str> = Some("o");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::ObjectPath
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::ObjectPath(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for crate::Signature {
+	const SIGNATURE_STR: Option<&'static str> = Some("g");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Signature
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::Signature(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for String {
+	const SIGNATURE_STR: Option<&'static str> = Some("s");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::String
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::String(value) => Ok(value.into_owned()),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a: 'b, 'b> FromVariant<'a> for std::borrow::Cow<'b, str> {
+	const SIGNATURE_STR: Option<&'static str> = Some("s");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::String
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::String(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for u8 {
+	const SIGNATURE_STR: Option<&'static str> = Some("y");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::U8
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::U8(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for u16 {
+	const SIGNATURE_STR: Option<&'static str> = Some("q");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::U16
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::U16(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for u32 {
+	const SIGNATURE_STR: Option<&'static str> = Some("u");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::U32
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::U32(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for u64 {
+	const SIGNATURE_STR: Option<&'static str> = Some("t");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::U64
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::U64(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for () {
+	const SIGNATURE_STR: Option<&'static str> = Some("");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Tuple {
+			elements: vec![],
+		}
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::Tuple { elements } if elements.is_empty() => Ok(()),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+macro_rules! tuple_from_variant {
+	($($ty:ident $field:ident,)*) => {
+		impl<'a, $($ty,)*> FromVariant<'a> for ($($ty,)*) where $($ty: FromVariant<'a>,)* {
+			const SIGNATURE_STR: Option<&'static str> = crate::SignatureStr::get(&crate::SignatureStr::concat(&[Some("("), $(<$ty as FromVariant<'a>>::SIGNATURE_STR,)* Some(")")]));
+
+			fn signature() -> crate::Signature {
+				crate::Signature::Struct {
+					fields: vec![$(<$ty as FromVariant<'a>>::signature(),)*],
+				}
+			}
+
+			fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+				let [$($field,)*] = struct_fields_from_variant(variant, Self::signature)?;
+				Ok(($(<$ty as FromVariant<'a>>::from_variant($field)?,)*))
+			}
+		}
+	};
+}
+
+tuple_from_variant! { T0 f0, }
+tuple_from_variant! { T0 f0, T1 f1, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, T7 f7, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, T7 f7, T8 f8, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, T7 f7, T8 f8, T9 f9, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, T7 f7, T8 f8, T9 f9, T10 f10, }
+tuple_from_variant! { T0 f0, T1 f1, T2 f2, T3 f3, T4 f4, T5 f5, T6 f6, T7 f7, T8 f8, T9 f9, T10 f10, T11 f11, }
+
+impl<'a> FromVariant<'a> for crate::UnixFd {
+	const SIGNATURE_STR: Option<&'static str> = Some("h");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::UnixFd
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::UnixFd(value) => Ok(value),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a> FromVariant<'a> for crate::Variant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = Some("v");
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Variant
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		match variant {
+			crate::Variant::Variant(value) => Ok(ref_into_variant(value)),
+			variant => Err(unexpected_signature::<Self>(&variant)),
+		}
+	}
+}
+
+impl<'a, T, const N: usize> FromVariant<'a> for [T; N] where T: FromVariant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = <Vec<T> as FromVariant<'a>>::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		<Vec<T> as FromVariant<'a>>::signature()
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		let elements = <Vec<T> as FromVariant<'a>>::from_variant(variant)?;
+		let len = elements.len();
+		std::convert::TryInto::try_into(elements).map_err(|_| FromVariantError::InvalidLength { expected: N, actual: len })
+	}
+}
+
+impl<'a, T> FromVariant<'a> for std::borrow::Cow<'_, [T]> where T: FromVariant<'a> + Clone {
+	const SIGNATURE_STR: Option<&'static str> = <Vec<T> as FromVariant<'a>>::SIGNATURE_STR;
+
+	fn signature() -> crate::Signature {
+		<Vec<T> as FromVariant<'a>>::signature()
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		<Vec<T> as FromVariant<'a>>::from_variant(variant).map(std::borrow::Cow::Owned)
+	}
+}
+
+impl<'a, T> FromVariant<'a> for Vec<T> where T: FromVariant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = crate::SignatureStr::get(&crate::SignatureStr::concat(&[Some("a"), T::SIGNATURE_STR]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Array {
+			element: Box::new(T::signature()),
+		}
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		array_elements(variant, Self::SIGNATURE_STR, Self::signature)?
+			.into_iter()
+			.map(T::from_variant)
+			.collect()
+	}
+}
+
+/// Maps are arrays of dict entries `a{kv}`. If a key appears more than once, the last value wins.
+impl<'a, K, V, S> FromVariant<'a> for std::collections::HashMap<K, V, S>
+where
+	K: FromVariant<'a> + crate::DictKey + Eq + std::hash::Hash,
+	V: FromVariant<'a>,
+	S: std::hash::BuildHasher + Default,
+{
+	const SIGNATURE_STR: Option<&'static str> = crate::SignatureStr::get(&crate::SignatureStr::concat(&[Some("a{"), K::SIGNATURE_STR, V::SIGNATURE_STR, Some("}")]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Array {
+			element: Box::new(crate::as_variant::dict_entry_signature(K::signature(), V::signature())),
+		}
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		array_elements(variant, Self::SIGNATURE_STR, Self::signature)?
+			.into_iter()
+			.map(map_entry)
+			.collect()
+	}
+}
+
+/// Maps are arrays of dict entries `a{kv}`. If a key appears more than once, the last value wins.
+impl<'a, K, V> FromVariant<'a> for std::collections::BTreeMap<K, V> where K: FromVariant<'a> + crate::DictKey + Ord, V: FromVariant<'a> {
+	const SIGNATURE_STR: Option<&'static str> = crate::SignatureStr::get(&crate::SignatureStr::concat(&[Some("a{"), K::SIGNATURE_STR, V::SIGNATURE_STR, Some("}")]));
+
+	fn signature() -> crate::Signature {
+		crate::Signature::Array {
+			element: Box::new(crate::as_variant::dict_entry_signature(K::signature(), V::signature())),
+		}
+	}
+
+	fn from_variant(variant: crate::Variant<'a>) -> Result<Self, FromVariantError> {
+		array_elements(variant, Self::SIGNATURE_STR, Self::signature)?
+			.into_iter()
+			.map(map_entry)
+			.collect()
+	}
+}
+
+/// Converts a variant of a struct with `N` fields into those fields.
+///
+/// This is useful for implementing [`FromVariant`] for struct types. A [`crate::Variant::Tuple`] with `N` elements, such as a message body,
+/// is also accepted. `signature` returns the signature of the struct type, and is used for the error if the variant is neither of these.
+///
+/// # Example
+///
+/// ```rust
+/// struct Item {
+///     name: String,
+///     id: u32,
+/// }
+///
+/// impl<'a> dbus_pure_proto::FromVariant<'a> for Item {
+///     fn signature() -> dbus_pure_proto::Signature {
+///         "(su)".parse().unwrap()
+///     }
+///
+///     fn from_variant(variant: dbus_pure_proto::Variant<'a>) -> Result<Self, dbus_pure_proto::FromVariantError> {
+///         let [name, id] = dbus_pure_proto::struct_fields_from_variant(variant, Self::signature)?;
+///         Ok(Item {
+///             name: dbus_pure_proto::FromVariant::from_variant(name)?,
+///             id: dbus_pure_proto::FromVariant::from_variant(id)?,
+///         })
+///     }
+/// }
+/// ```
+pub fn struct_fields_from_variant<'a, const N: usize>(
+	variant: crate::Variant<'a>,
+	signature: impl FnOnce() -> crate::Signature,
+) -> Result<[crate::Variant<'a>; N], FromVariantError> {
+	match variant {
+		crate::Variant::Struct { fields } |
+		crate::Variant::Tuple { elements: fields } if fields.len() == N => {
+			let fields = slice_into_variants(fields);
+			Ok(std::convert::TryInto::try_into(fields).unwrap_or_else(|_| unreachable!()))
+		},
+
+		variant => Err(FromVariantError::UnexpectedSignature { expected: signature(), actual: variant.inner_signature() }),
+	}
+}
+
+/// Converts a variant of signature `a{sv}` to its keys and values, with each value unwrapped from its [`crate::Variant::Variant`].
+///
+/// This is useful for implementing [`FromVariant`] for types that are sent as dictionaries of properties or options.
+/// The entries are returned in the order they appear in the variant, including any duplicate keys.
+/// [`crate::dict_as_variant`] does the reverse.
+///
+/// # Example
+///
+/// ```rust
+/// struct Hints {
+///     urgency: Option<u8>,
+///     category: Option<String>,
+/// }
+///
+/// impl<'a> dbus_pure_proto::FromVariant<'a> for Hints {
+///     fn signature() -> dbus_pure_proto::Signature {
+///         "a{sv}".parse().unwrap()
+///     }
+///
+///     fn from_variant(variant: dbus_pure_proto::Variant<'a>) -> Result<Self, dbus_pure_proto::FromVariantError> {
+///         let mut result = Hints { urgency: None, category: None };
+///         for (key, value) in dbus_pure_proto::dict_from_variant(variant)? {
+///             match &*key {
+///                 "urgency" => result.urgency = Some(dbus_pure_proto::FromVariant::from_variant(value)?),
+///                 "category" => result.category = Some(dbus_pure_proto::FromVariant::from_variant(value)?),
+///                 _ => (),
+///             }
+///         }
+///         Ok(result)
+///     }
+/// }
+/// ```
+pub fn dict_from_variant(variant: crate::Variant<'_>) -> Result<Vec<(std::borrow::Cow<'_, str>, crate::Variant<'_>)>, FromVariantError> {
+	array_elements(variant, Some("a{sv}"), || crate::Signature::Array {
+		element: Box::new(crate::as_variant::dict_entry_signature(crate::Signature::String, crate::Signature::Variant)),
+	})?
+		.into_iter()
+		.map(map_entry)
+		.collect()
+}
+
+/// The elements of an array variant of the given signature, as individual variants.
+///
+/// If `signature_str` is known, the signature of the variant is compared with it, and `signature` is only called for the error.
+fn array_elements<'a>(
+	variant: crate::Variant<'a>,
+	signature_str: Option<&str>,
+	signature: impl FnOnce() -> crate::Signature,
+) -> Result<Vec<crate::Variant<'a>>, FromVariantError> {
+	// The signature of an array is cheap to compute, unlike that of a struct, so only compute it once the variant is known to be an array.
+	if !is_array(&variant) {
+		return Err(FromVariantError::UnexpectedSignature { expected: signature(), actual: variant.inner_signature() });
+	}
+
+	let actual = variant.inner_signature();
+	let expected = match signature_str {
+		Some(expected) if actual == *expected => return Ok(into_elements(variant)),
+		Some(_) => signature(),
+		None => {
+			let expected = signature();
+			if actual == expected {
+				return Ok(into_elements(variant));
+			}
+			expected
+		},
+	};
+	Err(FromVariantError::UnexpectedSignature { expected, actual })
+}
+
+pub(crate) fn is_array(variant: &crate::Variant<'_>) -> bool {
+	matches!(
+		variant,
+		crate::Variant::Array { .. } |
+		crate::Variant::ArrayBool(_) |
+		crate::Variant::ArrayF64(_) |
+		crate::Variant::ArrayI16(_) |
+		crate::Variant::ArrayI32(_) |
+		crate::Variant::ArrayI64(_) |
+		crate::Variant::ArrayString(_) |
+		crate::Variant::ArrayU8(_) |
+		crate::Variant::ArrayU16(_) |
+		crate::Variant::ArrayU32(_) |
+		crate::Variant::ArrayU64(_) |
+		crate::Variant::ArrayUnixFd(_)
+	)
+}
+
+/// The elements of an array variant as individual variants.
+///
+/// # Panics
+///
+/// Panics if the variant is not an array.
+pub(crate) fn into_elements(variant: crate::Variant<'_>) -> Vec<crate::Variant<'_>> {
+	match variant {
+		crate::Variant::Array { element_signature: _, elements } => slice_into_variants(elements),
+		crate::Variant::ArrayBool(elements) => elements.iter().copied().map(crate::Variant::Bool).collect(),
+		crate::Variant::ArrayF64(elements) => elements.iter().copied().map(crate::Variant::F64).collect(),
+		crate::Variant::ArrayI16(elements) => elements.iter().copied().map(crate::Variant::I16).collect(),
+		crate::Variant::ArrayI32(elements) => elements.iter().copied().map(crate::Variant::I32).collect(),
+		crate::Variant::ArrayI64(elements) => elements.iter().copied().map(crate::Variant::I64).collect(),
+		crate::Variant::ArrayString(std::borrow::Cow::Borrowed(elements)) =>
+			elements.iter().map(|element| crate::Variant::String((&**element).into())).collect(),
+		crate::Variant::ArrayString(std::borrow::Cow::Owned(elements)) => elements.into_iter().map(crate::Variant::String).collect(),
+		crate::Variant::ArrayU8(elements) => elements.iter().copied().map(crate::Variant::U8).collect(),
+		crate::Variant::ArrayU16(elements) => elements.iter().copied().map(crate::Variant::U16).collect(),
+		crate::Variant::ArrayU32(elements) => elements.iter().copied().map(crate::Variant::U32).collect(),
+		crate::Variant::ArrayU64(elements) => elements.iter().copied().map(crate::Variant::U64).collect(),
+		crate::Variant::ArrayUnixFd(elements) => elements.iter().copied().map(crate::Variant::UnixFd).collect(),
+		variant => panic!("expected an array, found a value of type {}", variant.inner_signature()),
+	}
+}
+
+fn map_entry<'a, K, V>(entry: crate::Variant<'a>) -> Result<(K, V), FromVariantError> where K: FromVariant<'a>, V: FromVariant<'a> {
+	match entry {
+		crate::Variant::DictEntry { key, value } => Ok((K::from_variant(ref_into_variant(key))?, V::from_variant(ref_into_variant(value))?)),
+		entry => Err(FromVariantError::UnexpectedSignature {
+			expected: crate::as_variant::dict_entry_signature(K::signature(), V::signature()),
+			actual: entry.inner_signature(),
+		}),
+	}
+}
+
+pub(crate) fn ref_into_variant<'a>(value: crate::std2::CowRef<'a, crate::Variant<'a>>) -> crate::Variant<'a> {
+	match value {
+		crate::std2::CowRef::Borrowed(value) => value.borrowed(),
+		crate::std2::CowRef::Owned(value) => *value,
+	}
+}
+
+pub(crate) fn slice_into_variants<'a>(elements: crate::std2::CowSlice<'a, crate::Variant<'a>>) -> Vec<crate::Variant<'a>> {
+	match elements {
+		crate::std2::CowSlice::Borrowed(elements) => elements.iter().map(crate::Variant::borrowed).collect(),
+		crate::std2::CowSlice::Owned(elements) => elements,
+	}
+}
+
+fn unexpected_signature<'a, T>(variant: &crate::Variant<'_>) -> FromVariantError where T: FromVariant<'a> {
+	FromVariantError::UnexpectedSignature { expected: T::signature(), actual: variant.inner_signature() }
+}
+
+/// An error from converting a [`crate::Variant`] into a Rust value with [`FromVariant`].
+#[derive(Debug)]
+pub enum FromVariantError {
+	/// The array has a different number of elements than the fixed-size array type.
+	InvalidLength { expected: usize, actual: usize },
+
+	/// The string could not be converted to an object path.
+	InvalidObjectPath(String, crate::NameError),
+
+	/// The string could not be converted to a signature.
+	InvalidSignature(String),
+
+	/// The variant does not have the signature of the type.
+	UnexpectedSignature { expected: crate::Signature, actual: crate::Signature },
+
+	/// The value is not the value of any variant of the enum type.
+	UnknownEnumVariant(String),
+}
+
+impl std::fmt::Display for FromVariantError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FromVariantError::InvalidLength { expected, actual } =>
+				write!(f, "expected an array of {} elements, found {} elements", expected, actual),
+			FromVariantError::InvalidObjectPath(path, err) => write!(f, "invalid object path {:?}: {}", path, err),
+			FromVariantError::InvalidSignature(signature) => write!(f, "invalid signature {:?}", signature),
+			FromVariantError::UnexpectedSignature { expected, actual } =>
+				write!(f, "expected a value of type {:?}, found a value of type {:?}", expected.to_string(), actual.to_string()),
+			FromVariantError::UnknownEnumVariant(value) => write!(f, "unknown enum variant {}", value),
+		}
+	}
+}
+
+impl std::error::Error for FromVariantError {
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_from_variant() {
+		use crate::{AsVariant, FromVariant};
+
+		#[allow(clippy::needless_pass_by_value)]
+		fn test<T>(value: T) where T: AsVariant + for<'a> FromVariant<'a> + std::fmt::Debug + PartialEq {
+			assert_eq!(<T as AsVariant>::signature(), <T as FromVariant<'_>>::signature());
+			assert_eq!(<T as AsVariant>::SIGNATURE_STR, <T as FromVariant<'_>>::SIGNATURE_STR);
+			let actual: T = FromVariant::from_variant(value.as_variant()).unwrap();
+			assert_eq!(value, actual);
+		}
+
+		test(true);
+		test(3.5_f64);
+		test(-5_i16);
+		test(0x01020304_u32);
+		test("foo".to_owned());
+		test(crate::Signature::Variant);
+		test(());
+		test((5_u8, "bar".to_owned(), (true,)));
+		test([1_u16, 2, 3]);
+		test(vec![("a".to_owned(), 1_u64)].into_iter().collect::<std::collections::BTreeMap<_, _>>());
+		test(vec![(1_u8, vec!["a".to_owned()])].into_iter().collect::<std::collections::HashMap<_, _>>());
+		test(Some(5_u32));
+		test(None::<String>);
+		test(Some(Some(vec![true])));
+
+		// The simpler `Array*` variants are accepted for arrays.
+		let actual: Vec<String> = FromVariant::from_variant(crate::Variant::ArrayString(vec!["a".into(), "b".into()].into())).unwrap();
+		assert_eq!(actual, ["a", "b"]);
+		let actual: [u32; 2] = FromVariant::from_variant(crate::Variant::ArrayU32((&[1, 2][..]).into())).unwrap();
+		assert_eq!(actual, [1, 2]);
+
+		// `Option<T>` only converts from the `GVariant` maybe type.
+		let actual: Option<u8> = FromVariant::from_variant(crate::Variant::Maybe { element_signature: crate::Signature::U8, value: Some(Box::new(crate::Variant::U8(3)).into()) }).unwrap();
+		assert_eq!(actual, Some(3));
+		let actual: Option<u8> = FromVariant::from_variant(crate::Variant::Maybe { element_signature: crate::Signature::U8, value: None }).unwrap();
+		assert_eq!(actual, None);
+
+		let actual: crate::ObjectPath<'_> = FromVariant::from_variant(crate::Variant::ObjectPath(crate::ObjectPath("/org/example/Foo".into()))).unwrap();
+		assert_eq!(actual.0, "/org/example/Foo");
+
+		// Borrowed strings and variants are not copied.
+		let variant = crate::Variant::Struct {
+			fields: vec![
+				crate::Variant::String("foo".into()),
+				crate::Variant::Variant(Box::new(crate::Variant::U32(3)).into()),
+			].into(),
+		};
+		let (name, value): (std::borrow::Cow<'_, str>, crate::Variant<'_>) = FromVariant::from_variant(variant.borrowed()).unwrap();
+		assert!(matches!(name, std::borrow::Cow::Borrowed("foo")));
+		assert_eq!(value.into_owned(), crate::Variant::U32(3));
+
+		// Message bodies are accepted as structs.
+		let (name, id): (String, u32) = FromVariant::from_variant(crate::Variant::Tuple {
+			elements: vec![crate::Variant::String("foo".into()), crate::Variant::U32(3)].into(),
+		}).unwrap();
+		assert_eq!((&*name, id), ("foo", 3));
+
+		// Mismatches report the expected and actual signatures.
+		let err = <String as FromVariant<'_>>::from_variant(crate::Variant::ObjectPath(crate::ObjectPath("/".into()))).unwrap_err();
+		assert!(matches!(err, crate::FromVariantError::UnexpectedSignature { expected: crate::Signature::String, actual: crate::Signature::ObjectPath }));
+
+		let err = <(String, u32) as FromVariant<'_>>::from_variant(("foo", 3_u32, true).as_variant()).unwrap_err();
+		assert_eq!(err.to_string(), r#"expected a value of type "(su)", found a value of type "(sub)""#);
+
+		let err = <Vec<u32> as FromVariant<'_>>::from_variant(vec![3_u8].as_variant()).unwrap_err();
+		assert_eq!(err.to_string(), r#"expected a value of type "au", found a value of type "ay""#);
+
+		let err = <Vec<(String, u32)> as FromVariant<'_>>::from_variant(vec![("foo", true)].as_variant()).unwrap_err();
+		assert_eq!(err.to_string(), r#"expected a value of type "a(su)", found a value of type "a(sb)""#);
+
+		let err = <[u8; 3] as FromVariant<'_>>::from_variant(vec![3_u8].as_variant()).unwrap_err();
+		assert!(matches!(err, crate::FromVariantError::InvalidLength { expected: 3, actual: 1 }));
+	}
+
+	#[test]
+	fn test_with_signature() {
+		use crate::AsVariant;
+
+		let signature: crate::Signature = "a{oao}".parse().unwrap();
+		let value: std::collections::BTreeMap<_, _> = vec![("/a", vec!["/b", "/c"])].into_iter().collect();
+		let variant = value.as_variant().with_signature(&signature).unwrap();
+		assert_eq!(variant.inner_signature(), signature);
+		assert_eq!(variant.to_string(), "{objectpath '/a': [objectpath '/b', '/c']}");
+
+		let signature: crate::Signature = "(gh)".parse().unwrap();
+		let variant = ("a{sv}", 3_u32).as_variant().with_signature(&signature).unwrap();
+		assert_eq!(variant.into_owned(), crate::Variant::Struct {
+			fields: vec![
+				crate::Variant::Signature("a{sv}".parse().unwrap()),
+				crate::Variant::UnixFd(crate::UnixFd(3)),
+			].into(),
+		});
+
+		// Already matching values are returned unchanged.
+		let value = vec![1_u8, 2];
+		let variant = value.as_variant().with_signature(&crate::Signature::Array { element: Box::new(crate::Signature::U8) }).unwrap();
+		assert_eq!(variant, value.as_variant());
+
+		let err = "a{".as_variant().with_signature(&crate::Signature::Signature).unwrap_err();
+		assert_eq!(err.to_string(), r#"invalid signature "a{""#);
+
+		let err = "a/".as_variant().with_signature(&crate::Signature::ObjectPath).unwrap_err();
+		assert_eq!(err.to_string(), r#"invalid object path "a/": object path does not start with '/'"#);
+
+		let err = "/a/".as_variant().with_signature(&crate::Signature::ObjectPath).unwrap_err();
+		assert!(matches!(err, crate::FromVariantError::InvalidObjectPath(path, crate::NameError::TrailingSlash) if path == "/a/"));
+
+		let err = 3_u8.as_variant().with_signature(&crate::Signature::U32).unwrap_err();
+		assert!(matches!(err, crate::FromVariantError::UnexpectedSignature { expected: crate::Signature::U32, actual: crate::Signature::U8 }));
+	}
+
+	#[test]
+	fn test_dict() {
+		use crate::AsVariant;
+
+		let variant = crate::dict_as_variant(vec![
+			("urgency".into(), 2_u8.as_variant()),
+			("category".into(), "im".as_variant()),
+		]);
+		assert_eq!(variant.to_string(), "{'urgency': <byte 0x02>, 'category': <'im'>}");
+
+		let entries = crate::dict_from_variant(variant).unwrap();
+		assert_eq!(entries, [
+			("urgency".into(), crate::Variant::U8(2)),
+			("category".into(), crate::Variant::String("im".into())),
+		]);
+
+		let err = crate::dict_from_variant(vec![("urgency", 2_u8)].as_variant()).unwrap_err();
+		assert_eq!(err.to_string(), r#"expected a value of type "a{sv}", found a value of type "a(sy)""#);
+	}
+}
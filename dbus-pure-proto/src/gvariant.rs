@@ -0,0 +1,798 @@
+//! The `GVariant` binary format, as used by `GSettings`, dconf databases and GVDB files.
+//!
+//! Unlike the D-Bus wire format, values are not prefixed with their lengths. Instead the length of every value is known from its container,
+//! using framing offsets at the end of the container for values that are not of a fixed size.
+
+/// Serialize the given value in the `GVariant` format into the given buffer.
+///
+/// The value is aligned relative to the start of its serialized data, so `buf` does not need to be empty.
+///
+/// A `Variant::Tuple`, ie a message body, is serialized like a struct. So a `Variant::Variant` of a `Variant::Tuple`
+/// is deserialized back as a `Variant::Variant` of a `Variant::Struct`.
+///
+/// # Example
+///
+/// ```rust
+/// let value = dbus_pure_proto::parse_gvariant_text("('foo', -1)", None).unwrap();
+///
+/// let mut buf = vec![];
+/// dbus_pure_proto::serialize_gvariant(&value, &mut buf, dbus_pure_proto::Endianness::Little);
+/// assert_eq!(buf, b"foo\0\xff\xff\xff\xff\x04");
+///
+/// let deserialized = dbus_pure_proto::deserialize_gvariant(&buf, &value.inner_signature(), dbus_pure_proto::Endianness::Little).unwrap();
+/// assert_eq!(deserialized, value);
+/// ```
+pub fn serialize_gvariant(value: &crate::Variant<'_>, buf: &mut Vec<u8>, endianness: crate::Endianness) {
+	let start = buf.len();
+	let mut serializer = Serializer { buf, start, endianness };
+	serializer.serialize(value);
+}
+
+/// Deserialize a value of the given signature from the given `GVariant`-format buffer. The whole buffer is the value.
+///
+/// Strings, object paths and byte arrays in the returned value borrow from `buf`.
+pub fn deserialize_gvariant<'a>(
+	buf: &'a [u8],
+	signature: &crate::Signature,
+	endianness: crate::Endianness,
+) -> Result<crate::Variant<'a>, DeserializeGVariantError> {
+	let mut deserializer = Deserializer { endianness, depth: 0 };
+	deserializer.deserialize(buf, signature)
+}
+
+/// The alignment of a type, and its size if it's a fixed-size type.
+#[derive(Clone, Copy)]
+struct Layout {
+	alignment: usize,
+	fixed_size: Option<usize>,
+}
+
+impl Layout {
+	const STRING: Self = Layout { alignment: 1, fixed_size: None };
+
+	fn fixed(size: usize) -> Self {
+		Layout { alignment: size, fixed_size: Some(size) }
+	}
+
+	fn of_signature(signature: &crate::Signature) -> Self {
+		match signature {
+			crate::Signature::Array { element } |
+			crate::Signature::Maybe { element } =>
+				Layout { alignment: Layout::of_signature(element).alignment, fixed_size: None },
+
+			crate::Signature::Bool |
+			crate::Signature::U8 =>
+				Layout::fixed(1),
+
+			crate::Signature::DictEntry { key, value } =>
+				Layout::of_struct(vec![Layout::of_signature(key), Layout::of_signature(value)]),
+
+			crate::Signature::F64 |
+			crate::Signature::I64 |
+			crate::Signature::U64 =>
+				Layout::fixed(8),
+
+			crate::Signature::I16 |
+			crate::Signature::U16 =>
+				Layout::fixed(2),
+
+			crate::Signature::I32 |
+			crate::Signature::U32 |
+			crate::Signature::UnixFd =>
+				Layout::fixed(4),
+
+			crate::Signature::ObjectPath |
+			crate::Signature::Signature |
+			crate::Signature::String =>
+				Layout::STRING,
+
+			crate::Signature::Struct { fields: elements } |
+			crate::Signature::Tuple { elements } =>
+				Layout::of_struct(elements.iter().map(Layout::of_signature)),
+
+			crate::Signature::Variant =>
+				Layout { alignment: 8, fixed_size: None },
+		}
+	}
+
+	fn of_value(value: &crate::Variant<'_>) -> Self {
+		match value {
+			crate::Variant::Array { element_signature, elements: _ } |
+			crate::Variant::Maybe { element_signature, value: _ } =>
+				Layout { alignment: Layout::of_signature(element_signature).alignment, fixed_size: None },
+
+			crate::Variant::ArrayBool(_) |
+			crate::Variant::ArrayString(_) |
+			crate::Variant::ArrayU8(_) |
+			crate::Variant::ObjectPath(_) |
+			crate::Variant::Signature(_) |
+			crate::Variant::String(_) =>
+				Layout::STRING,
+
+			crate::Variant::ArrayF64(_) |
+			crate::Variant::ArrayI64(_) |
+			crate::Variant::ArrayU64(_) |
+			crate::Variant::Variant(_) =>
+				Layout { alignment: 8, fixed_size: None },
+
+			crate::Variant::ArrayI16(_) |
+			crate::Variant::ArrayU16(_) =>
+				Layout { alignment: 2, fixed_size: None },
+
+			crate::Variant::ArrayI32(_) |
+			crate::Variant::ArrayU32(_) |
+			crate::Variant::ArrayUnixFd(_) =>
+				Layout { alignment: 4, fixed_size: None },
+
+			crate::Variant::Bool(_) |
+			crate::Variant::U8(_) =>
+				Layout::fixed(1),
+
+			crate::Variant::DictEntry { key, value } =>
+				Layout::of_struct(vec![Layout::of_value(key), Layout::of_value(value)]),
+
+			crate::Variant::F64(_) |
+			crate::Variant::I64(_) |
+			crate::Variant::U64(_) =>
+				Layout::fixed(8),
+
+			crate::Variant::I16(_) |
+			crate::Variant::U16(_) =>
+				Layout::fixed(2),
+
+			crate::Variant::I32(_) |
+			crate::Variant::U32(_) |
+			crate::Variant::UnixFd(_) =>
+				Layout::fixed(4),
+
+			crate::Variant::Struct { fields: elements } |
+			crate::Variant::Tuple { elements } =>
+				Layout::of_struct(elements.iter().map(Layout::of_value)),
+		}
+	}
+
+	/// A struct is aligned to its most-aligned field. It's fixed-size if all its fields are, in which case its size includes
+	/// padding at the end up to its alignment. The empty struct is a single byte.
+	fn of_struct(fields: impl IntoIterator<Item = Layout>) -> Self {
+		let mut alignment = 1;
+		let mut fixed_size = Some(0);
+		let mut is_empty = true;
+
+		for field in fields {
+			alignment = std::cmp::max(alignment, field.alignment);
+			fixed_size = match (fixed_size, field.fixed_size) {
+				(Some(size), Some(field_size)) => Some(align(size, field.alignment) + field_size),
+				_ => None,
+			};
+			is_empty = false;
+		}
+
+		let fixed_size = if is_empty { Some(1) } else { fixed_size.map(|size| align(size, alignment)) };
+
+		Layout { alignment, fixed_size }
+	}
+}
+
+fn align(pos: usize, alignment: usize) -> usize {
+	(pos + alignment - 1) / alignment * alignment
+}
+
+/// The size of each framing offset in a container of the given total length.
+fn offset_size(container_len: usize) -> usize {
+	if container_len == 0 {
+		0
+	}
+	else if container_len <= 0xff {
+		1
+	}
+	else if container_len <= 0xffff {
+		2
+	}
+	else if container_len <= 0xffff_ffff {
+		4
+	}
+	else {
+		8
+	}
+}
+
+struct Serializer<'a> {
+	buf: &'a mut Vec<u8>,
+
+	/// The position in `buf` that the value starts at. Alignment is relative to this.
+	start: usize,
+
+	endianness: crate::Endianness,
+}
+
+impl Serializer<'_> {
+	fn serialize(&mut self, value: &crate::Variant<'_>) {
+		match value {
+			crate::Variant::Array { element_signature, elements } =>
+				self.array(elements, Layout::of_signature(element_signature), |serializer, element| serializer.serialize(element)),
+
+			crate::Variant::ArrayBool(elements) =>
+				self.buf.extend(elements.iter().map(|&element| u8::from(element))),
+
+			crate::Variant::ArrayF64(elements) =>
+				self.array(elements, Layout::fixed(8), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.f64_to_bytes(element))),
+
+			crate::Variant::ArrayI16(elements) =>
+				self.array(elements, Layout::fixed(2), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.i16_to_bytes(element))),
+
+			crate::Variant::ArrayI32(elements) =>
+				self.array(elements, Layout::fixed(4), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.i32_to_bytes(element))),
+
+			crate::Variant::ArrayI64(elements) =>
+				self.array(elements, Layout::fixed(8), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.i64_to_bytes(element))),
+
+			crate::Variant::ArrayString(elements) =>
+				self.array(elements, Layout::STRING, |serializer, element| serializer.string(element)),
+
+			crate::Variant::ArrayU8(elements) =>
+				self.buf.extend_from_slice(elements),
+
+			crate::Variant::ArrayU16(elements) =>
+				self.array(elements, Layout::fixed(2), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.u16_to_bytes(element))),
+
+			crate::Variant::ArrayU32(elements) =>
+				self.array(elements, Layout::fixed(4), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.u32_to_bytes(element))),
+
+			crate::Variant::ArrayU64(elements) =>
+				self.array(elements, Layout::fixed(8), |serializer, &element| serializer.buf.extend_from_slice(&serializer.endianness.u64_to_bytes(element))),
+
+			crate::Variant::ArrayUnixFd(elements) =>
+				self.array(elements, Layout::fixed(4), |serializer, element| serializer.buf.extend_from_slice(&serializer.endianness.u32_to_bytes(element.0))),
+
+			crate::Variant::Bool(value) =>
+				self.buf.push(u8::from(*value)),
+
+			crate::Variant::DictEntry { key, value } =>
+				self.structure(&[&**key, &**value]),
+
+			crate::Variant::F64(value) =>
+				self.buf.extend_from_slice(&self.endianness.f64_to_bytes(*value)),
+
+			crate::Variant::I16(value) =>
+				self.buf.extend_from_slice(&self.endianness.i16_to_bytes(*value)),
+
+			crate::Variant::I32(value) =>
+				self.buf.extend_from_slice(&self.endianness.i32_to_bytes(*value)),
+
+			crate::Variant::I64(value) =>
+				self.buf.extend_from_slice(&self.endianness.i64_to_bytes(*value)),
+
+			crate::Variant::Maybe { element_signature, value } =>
+				if let Some(value) = value {
+					self.serialize(value);
+
+					// A variable-size value is followed by a nul byte, so that `just` of an empty value is distinguishable from `nothing`.
+					if Layout::of_signature(element_signature).fixed_size.is_none() {
+						self.buf.push(0);
+					}
+				},
+
+			crate::Variant::ObjectPath(value) =>
+				self.string(&value.0),
+
+			crate::Variant::Signature(value) =>
+				self.string(&value.to_string()),
+
+			crate::Variant::String(value) =>
+				self.string(value),
+
+			crate::Variant::Struct { fields: elements } |
+			crate::Variant::Tuple { elements } => {
+				let elements: Vec<_> = elements.iter().collect();
+				self.structure(&elements);
+			},
+
+			crate::Variant::U8(value) =>
+				self.buf.push(*value),
+
+			crate::Variant::U16(value) =>
+				self.buf.extend_from_slice(&self.endianness.u16_to_bytes(*value)),
+
+			crate::Variant::U32(value) =>
+				self.buf.extend_from_slice(&self.endianness.u32_to_bytes(*value)),
+
+			crate::Variant::U64(value) =>
+				self.buf.extend_from_slice(&self.endianness.u64_to_bytes(*value)),
+
+			crate::Variant::UnixFd(value) =>
+				self.buf.extend_from_slice(&self.endianness.u32_to_bytes(value.0)),
+
+			crate::Variant::Variant(value) => {
+				self.serialize(value);
+				self.buf.push(0);
+				let signature = match value.inner_signature() {
+					signature @ crate::Signature::Tuple { .. } => format!("({})", signature),
+					signature => signature.to_string(),
+				};
+				self.buf.extend_from_slice(signature.as_bytes());
+			},
+		}
+	}
+
+	fn string(&mut self, value: &str) {
+		self.buf.extend_from_slice(value.as_bytes());
+		self.buf.push(0);
+	}
+
+	/// Arrays of fixed-size elements are just the elements. Arrays of variable-size elements are followed by the offset of the end of each element.
+	fn array<T>(&mut self, elements: &[T], element_layout: Layout, mut serialize_element: impl FnMut(&mut Self, &T)) {
+		let container_start = self.pos();
+		let mut offsets = vec![];
+
+		for element in elements {
+			self.pad_to(element_layout.alignment);
+			serialize_element(self, element);
+			if element_layout.fixed_size.is_none() {
+				offsets.push(self.pos() - container_start);
+			}
+		}
+
+		self.write_offsets(container_start, &offsets);
+	}
+
+	/// Structs are followed by the offset of the end of each variable-size field, except the last field, in reverse order.
+	fn structure(&mut self, fields: &[&crate::Variant<'_>]) {
+		if fields.is_empty() {
+			self.buf.push(0);
+			return;
+		}
+
+		let container_start = self.pos();
+		let mut offsets = vec![];
+		let mut alignment = 1;
+		let mut is_fixed_size = true;
+
+		for (i, field) in fields.iter().enumerate() {
+			let layout = Layout::of_value(field);
+			alignment = std::cmp::max(alignment, layout.alignment);
+
+			self.pad_to(layout.alignment);
+			self.serialize(field);
+
+			if layout.fixed_size.is_none() {
+				is_fixed_size = false;
+				if i + 1 < fields.len() {
+					offsets.push(self.pos() - container_start);
+				}
+			}
+		}
+
+		if is_fixed_size {
+			self.pad_to(alignment);
+		}
+
+		offsets.reverse();
+		self.write_offsets(container_start, &offsets);
+	}
+
+	/// Framing offsets are always little-endian, and are as small as possible for the total length of the container including the offsets.
+	fn write_offsets(&mut self, container_start: usize, offsets: &[usize]) {
+		let body_len = self.pos() - container_start;
+		let offset_size =
+			[1, 2, 4, 8].iter().copied()
+			.find(|&size| offset_size(body_len + offsets.len() * size) <= size)
+			.expect("8-byte offsets fit any length");
+
+		for offset in offsets {
+			self.buf.extend_from_slice(&offset.to_le_bytes()[..offset_size]);
+		}
+	}
+
+	fn pad_to(&mut self, alignment: usize) {
+		let new_len = self.start + align(self.pos(), alignment);
+		self.buf.resize(new_len, 0);
+	}
+
+	fn pos(&self) -> usize {
+		self.buf.len() - self.start
+	}
+}
+
+/// The maximum number of variants that a value can be nested in.
+///
+/// The nesting of other containers is already limited by the signature, but variants can be nested without limit.
+const MAX_DEPTH: usize = 128;
+
+struct Deserializer {
+	endianness: crate::Endianness,
+
+	/// The number of variants that the value being deserialized is nested in.
+	depth: usize,
+}
+
+impl Deserializer {
+	fn deserialize<'a>(&mut self, buf: &'a [u8], signature: &crate::Signature) -> Result<crate::Variant<'a>, DeserializeGVariantError> {
+		let invalid_length = || DeserializeGVariantError::InvalidLength { signature: signature.clone(), len: buf.len() };
+
+		Ok(match signature {
+			crate::Signature::Array { element } => match &**element {
+				crate::Signature::Bool => crate::Variant::ArrayBool(fixed_size_array(buf, signature, 1, |bytes| bool_from_bytes(bytes[0]))?.into()),
+				crate::Signature::F64 => crate::Variant::ArrayF64(fixed_size_array(buf, signature, 8, |bytes| Ok(self.endianness.f64_from_bytes(array(bytes))))?.into()),
+				crate::Signature::I16 => crate::Variant::ArrayI16(fixed_size_array(buf, signature, 2, |bytes| Ok(self.endianness.i16_from_bytes(array(bytes))))?.into()),
+				crate::Signature::I32 => crate::Variant::ArrayI32(fixed_size_array(buf, signature, 4, |bytes| Ok(self.endianness.i32_from_bytes(array(bytes))))?.into()),
+				crate::Signature::I64 => crate::Variant::ArrayI64(fixed_size_array(buf, signature, 8, |bytes| Ok(self.endianness.i64_from_bytes(array(bytes))))?.into()),
+				crate::Signature::String => {
+					let elements: Result<Vec<_>, _> = variable_size_array(buf, 1)?.into_iter().map(string_from_bytes).collect();
+					crate::Variant::ArrayString(elements?.into())
+				},
+				crate::Signature::U8 => crate::Variant::ArrayU8(buf.into()),
+				crate::Signature::U16 => crate::Variant::ArrayU16(fixed_size_array(buf, signature, 2, |bytes| Ok(self.endianness.u16_from_bytes(array(bytes))))?.into()),
+				crate::Signature::U32 => crate::Variant::ArrayU32(fixed_size_array(buf, signature, 4, |bytes| Ok(self.endianness.u32_from_bytes(array(bytes))))?.into()),
+				crate::Signature::U64 => crate::Variant::ArrayU64(fixed_size_array(buf, signature, 8, |bytes| Ok(self.endianness.u64_from_bytes(array(bytes))))?.into()),
+				crate::Signature::UnixFd =>
+					crate::Variant::ArrayUnixFd(fixed_size_array(buf, signature, 4, |bytes| Ok(crate::UnixFd(self.endianness.u32_from_bytes(array(bytes)))))?.into()),
+				element => {
+					let layout = Layout::of_signature(element);
+					let elements: Result<Vec<_>, _> = match layout.fixed_size {
+						Some(size) => {
+							if buf.len() % size != 0 {
+								return Err(invalid_length());
+							}
+							buf.chunks_exact(size).map(|element_buf| self.deserialize(element_buf, element)).collect()
+						},
+						None => variable_size_array(buf, layout.alignment)?.into_iter().map(|element_buf| self.deserialize(element_buf, element)).collect(),
+					};
+					crate::Variant::Array { element_signature: element.clone(), elements: elements?.into() }
+				},
+			},
+
+			crate::Signature::Bool => match buf {
+				&[value] => crate::Variant::Bool(bool_from_bytes(value)?),
+				_ => return Err(invalid_length()),
+			},
+
+			crate::Signature::DictEntry { key, value } => {
+				let mut fields = self.structure(buf, signature, &[&**key, &**value])?.into_iter();
+				let key = fields.next().expect("two fields");
+				let value = fields.next().expect("two fields");
+				crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() }
+			},
+
+			crate::Signature::F64 => crate::Variant::F64(self.endianness.f64_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::I16 => crate::Variant::I16(self.endianness.i16_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::I32 => crate::Variant::I32(self.endianness.i32_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::I64 => crate::Variant::I64(self.endianness.i64_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::Maybe { element } => {
+				let value = match (buf, Layout::of_signature(element).fixed_size) {
+					([], _) => None,
+					(buf, Some(_)) => Some(self.deserialize(buf, element)?),
+					([value_buf @ .., 0], None) => Some(self.deserialize(value_buf, element)?),
+					(_, None) => return Err(DeserializeGVariantError::MissingNulTerminator),
+				};
+				crate::Variant::Maybe { element_signature: (**element).clone(), value: value.map(|value| Box::new(value).into()) }
+			},
+
+			crate::Signature::ObjectPath => {
+				let value = string_from_bytes(buf)?;
+				let () = crate::names::validate_object_path(&value).map_err(DeserializeGVariantError::InvalidObjectPath)?;
+				crate::Variant::ObjectPath(crate::ObjectPath(value))
+			},
+
+			crate::Signature::Signature => {
+				let value = string_from_bytes(buf)?;
+				let signature = value.parse().map_err(|_| DeserializeGVariantError::InvalidSignature(value.into_owned()))?;
+				crate::Variant::Signature(signature)
+			},
+
+			crate::Signature::String => crate::Variant::String(string_from_bytes(buf)?),
+
+			crate::Signature::Struct { fields } => {
+				let fields: Vec<_> = fields.iter().collect();
+				crate::Variant::Struct { fields: self.structure(buf, signature, &fields)?.into() }
+			},
+
+			crate::Signature::Tuple { elements } => {
+				let elements: Vec<_> = elements.iter().collect();
+				crate::Variant::Tuple { elements: self.structure(buf, signature, &elements)?.into() }
+			},
+
+			crate::Signature::U8 => match buf {
+				&[value] => crate::Variant::U8(value),
+				_ => return Err(invalid_length()),
+			},
+
+			crate::Signature::U16 => crate::Variant::U16(self.endianness.u16_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::U32 => crate::Variant::U32(self.endianness.u32_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::U64 => crate::Variant::U64(self.endianness.u64_from_bytes(fixed_size(buf, signature)?)),
+
+			crate::Signature::UnixFd => crate::Variant::UnixFd(crate::UnixFd(self.endianness.u32_from_bytes(fixed_size(buf, signature)?))),
+
+			crate::Signature::Variant => {
+				// The value is followed by a nul byte and the signature of the value. The value itself can contain nul bytes, so search from the end.
+				let separator_pos = buf.iter().rposition(|&b| b == 0).ok_or(DeserializeGVariantError::MissingNulTerminator)?;
+				let inner_signature = std::str::from_utf8(&buf[(separator_pos + 1)..]).map_err(DeserializeGVariantError::InvalidUtf8)?;
+				let inner_signature = match crate::Signature::from_gvariant_type(inner_signature) {
+					Ok(crate::Signature::Tuple { .. }) | Err(_) => return Err(DeserializeGVariantError::InvalidSignature(inner_signature.to_owned())),
+					Ok(inner_signature) => inner_signature,
+				};
+
+				if self.depth >= MAX_DEPTH {
+					return Err(DeserializeGVariantError::NestingTooDeep);
+				}
+
+				self.depth += 1;
+				let value = self.deserialize(&buf[..separator_pos], &inner_signature);
+				self.depth -= 1;
+				crate::Variant::Variant(Box::new(value?).into())
+			},
+		})
+	}
+
+	fn structure<'a>(
+		&mut self,
+		buf: &'a [u8],
+		signature: &crate::Signature,
+		fields: &[&crate::Signature],
+	) -> Result<Vec<crate::Variant<'a>>, DeserializeGVariantError> {
+		if let Some(size) = Layout::of_signature(signature).fixed_size {
+			if buf.len() != size {
+				return Err(DeserializeGVariantError::InvalidLength { signature: signature.clone(), len: buf.len() });
+			}
+		}
+
+		let offset_size = offset_size(buf.len());
+
+		// Framing offsets are read backwards from the end of the struct.
+		let mut offsets_start = buf.len();
+		let mut pos = 0;
+		let mut result = Vec::with_capacity(fields.len());
+
+		for (i, field) in fields.iter().enumerate() {
+			let layout = Layout::of_signature(field);
+
+			pos = align(pos, layout.alignment);
+
+			let end = match layout.fixed_size {
+				Some(size) => pos + size,
+				None if i + 1 == fields.len() => offsets_start,
+				None => {
+					offsets_start = offsets_start.checked_sub(offset_size).ok_or(DeserializeGVariantError::InvalidFramingOffset)?;
+					read_offset(&buf[offsets_start..(offsets_start + offset_size)])?
+				},
+			};
+			if pos > end || end > offsets_start {
+				return Err(DeserializeGVariantError::InvalidFramingOffset);
+			}
+
+			result.push(self.deserialize(&buf[pos..end], field)?);
+			pos = end;
+		}
+
+		Ok(result)
+	}
+}
+
+fn fixed_size_array<T>(
+	buf: &[u8],
+	signature: &crate::Signature,
+	element_size: usize,
+	deserialize_element: impl FnMut(&[u8]) -> Result<T, DeserializeGVariantError>,
+) -> Result<Vec<T>, DeserializeGVariantError> {
+	if buf.len() % element_size != 0 {
+		return Err(DeserializeGVariantError::InvalidLength { signature: signature.clone(), len: buf.len() });
+	}
+
+	buf.chunks_exact(element_size).map(deserialize_element).collect()
+}
+
+/// Splits an array of variable-size elements into the elements. The last framing offset is the end of the last element,
+/// which is also the start of the framing offsets.
+fn variable_size_array(buf: &[u8], alignment: usize) -> Result<Vec<&[u8]>, DeserializeGVariantError> {
+	if buf.is_empty() {
+		return Ok(vec![]);
+	}
+
+	let offset_size = offset_size(buf.len());
+	let offsets_start = read_offset(&buf[(buf.len() - offset_size)..])?;
+	let offsets = buf.get(offsets_start..).ok_or(DeserializeGVariantError::InvalidFramingOffset)?;
+	if offsets.is_empty() || offsets.len() % offset_size != 0 {
+		return Err(DeserializeGVariantError::InvalidFramingOffset);
+	}
+
+	let mut result = Vec::with_capacity(offsets.len() / offset_size);
+	let mut pos = 0;
+	for offset in offsets.chunks_exact(offset_size) {
+		let start = align(pos, alignment);
+		let end = read_offset(offset)?;
+		if start > end || end > offsets_start {
+			return Err(DeserializeGVariantError::InvalidFramingOffset);
+		}
+
+		result.push(&buf[start..end]);
+		pos = end;
+	}
+
+	Ok(result)
+}
+
+fn read_offset(buf: &[u8]) -> Result<usize, DeserializeGVariantError> {
+	let mut bytes = [0_u8; 8];
+	bytes[..buf.len()].copy_from_slice(buf);
+	std::convert::TryInto::try_into(u64::from_le_bytes(bytes)).map_err(|_| DeserializeGVariantError::InvalidFramingOffset)
+}
+
+fn fixed_size<const N: usize>(buf: &[u8], signature: &crate::Signature) -> Result<[u8; N], DeserializeGVariantError> {
+	std::convert::TryInto::try_into(buf).map_err(|_| DeserializeGVariantError::InvalidLength { signature: signature.clone(), len: buf.len() })
+}
+
+/// Converts a chunk of a fixed-size array into an array. The chunk is always of the right size.
+fn array<const N: usize>(buf: &[u8]) -> [u8; N] {
+	std::convert::TryInto::try_into(buf).expect("chunk has the size of the element")
+}
+
+fn bool_from_bytes(value: u8) -> Result<bool, DeserializeGVariantError> {
+	match value {
+		0 => Ok(false),
+		1 => Ok(true),
+		value => Err(DeserializeGVariantError::InvalidBool(value)),
+	}
+}
+
+fn string_from_bytes(buf: &[u8]) -> Result<std::borrow::Cow<'_, str>, DeserializeGVariantError> {
+	match buf {
+		[value @ .., 0] => Ok(std::str::from_utf8(value).map_err(DeserializeGVariantError::InvalidUtf8)?.into()),
+		_ => Err(DeserializeGVariantError::MissingNulTerminator),
+	}
+}
+
+/// An error from deserializing a value in the `GVariant` format with [`deserialize_gvariant`].
+#[derive(Debug)]
+pub enum DeserializeGVariantError {
+	InvalidBool(u8),
+
+	/// A framing offset points outside its container, or before the previous one.
+	InvalidFramingOffset,
+
+	/// A fixed-size value, or an array of fixed-size values, has the wrong length.
+	InvalidLength { signature: crate::Signature, len: usize },
+
+	InvalidObjectPath(crate::NameError),
+
+	InvalidSignature(String),
+
+	InvalidUtf8(std::str::Utf8Error),
+
+	/// A string, or the value of a variant, is not followed by a nul byte.
+	MissingNulTerminator,
+
+	/// A value is nested in more than 128 variants.
+	NestingTooDeep,
+}
+
+impl std::fmt::Display for DeserializeGVariantError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DeserializeGVariantError::InvalidBool(value) => write!(f, "invalid boolean value 0x{:02x}", value),
+			DeserializeGVariantError::InvalidFramingOffset => f.write_str("framing offset is out of bounds"),
+			DeserializeGVariantError::InvalidLength { signature, len } => write!(f, "a value of type {:?} cannot be {} bytes long", signature.to_string(), len),
+			DeserializeGVariantError::InvalidObjectPath(_) => f.write_str("invalid object path"),
+			DeserializeGVariantError::InvalidSignature(signature) => write!(f, "invalid signature {:?}", signature),
+			DeserializeGVariantError::InvalidUtf8(_) => f.write_str("deserialized string is not valid UTF-8"),
+			DeserializeGVariantError::MissingNulTerminator => f.write_str("deserialized string is not nul-terminated"),
+			DeserializeGVariantError::NestingTooDeep => write!(f, "value is nested in more than {} variants", MAX_DEPTH),
+		}
+	}
+}
+
+impl std::error::Error for DeserializeGVariantError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		#[allow(clippy::match_same_arms)]
+		match self {
+			DeserializeGVariantError::InvalidBool(_) => None,
+			DeserializeGVariantError::InvalidFramingOffset => None,
+			DeserializeGVariantError::InvalidLength { signature: _, len: _ } => None,
+			DeserializeGVariantError::InvalidObjectPath(err) => Some(err),
+			DeserializeGVariantError::InvalidSignature(_) => None,
+			DeserializeGVariantError::InvalidUtf8(err) => Some(err),
+			DeserializeGVariantError::MissingNulTerminator => None,
+			DeserializeGVariantError::NestingTooDeep => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_gvariant() {
+		fn test(s: &str, signature: &str, endianness: crate::Endianness, expected_serialized: &[u8]) {
+			let signature = crate::Signature::from_gvariant_type(signature).unwrap();
+			let value = crate::parse_gvariant_text(s, Some(&signature)).unwrap();
+
+			let mut actual_serialized = vec![];
+			super::serialize_gvariant(&value, &mut actual_serialized, endianness);
+			assert_eq!(expected_serialized, &actual_serialized[..], "{}", s);
+
+			let actual_deserialized = super::deserialize_gvariant(&actual_serialized, &signature, endianness).unwrap();
+			assert_eq!(value, actual_deserialized, "{}", s);
+			assert_eq!(s, actual_deserialized.to_string());
+		}
+
+		// Examples from the GVariant specification
+		test("'hello world'", "s", crate::Endianness::Little, b"hello world\0");
+		test("just 'hello world'", "ms", crate::Endianness::Little, b"hello world\0\0");
+		test("[true, false]", "ab", crate::Endianness::Little, b"\x01\x00");
+		test("('foo', -1)", "(si)", crate::Endianness::Little, b"foo\0\xff\xff\xff\xff\x04");
+		test("{'a': -1}", "a{si}", crate::Endianness::Little, b"a\0\0\0\xff\xff\xff\xff\x02\x09");
+		test("['i', 'can', 'has', 'strings?']", "as", crate::Endianness::Little, b"i\0can\0has\0strings?\0\x02\x06\x0a\x13");
+		test("[('hi', -2), ('bye', -1)]", "a(si)", crate::Endianness::Little, b"hi\0\0\xfe\xff\xff\xff\x03\0\0\0bye\0\xff\xff\xff\xff\x04\x09\x15");
+		test("[['hello', 'world'], ['i', 'am', 'here']]", "aas", crate::Endianness::Little, b"hello\0world\0\x06\x0ci\0am\0here\0\x02\x05\x0a\x0e\x1b");
+		test("[(1, byte 0x02), (3, 0x04)]", "a(iy)", crate::Endianness::Little, b"\x01\0\0\0\x02\0\0\0\x03\0\0\0\x04\0\0\0");
+		test("(int16 -1, true, 'x', <uint32 5>)", "(nbsv)", crate::Endianness::Little, b"\xff\xff\x01x\0\0\0\0\x05\0\0\0\0u\x05");
+
+		test("@mi nothing", "mi", crate::Endianness::Little, b"");
+		test("just 5", "mi", crate::Endianness::Big, b"\0\0\0\x05");
+		test("just just ''", "mms", crate::Endianness::Little, b"\0\0\0");
+		test("just @ms nothing", "mms", crate::Endianness::Little, b"\0");
+		test("()", "()", crate::Endianness::Little, b"\0");
+		test("@a{sv} {}", "a{sv}", crate::Endianness::Little, b"");
+		test("(uint64 1, byte 0x02)", "(ty)", crate::Endianness::Big, b"\0\0\0\0\0\0\0\x01\x02\0\0\0\0\0\0\0");
+		test("(objectpath '/foo', signature 'a{sv}')", "(og)", crate::Endianness::Little, b"/foo\0a{sv}\0\x05");
+		test("('hello', {'k': <[1.5]>})", "sa{sv}", crate::Endianness::Little, b"hello\0\0\0k\0\0\0\0\0\0\0\0\0\0\0\0\0\xf8\x3f\0ad\x02\x14\x06");
+
+		// 2-byte framing offsets
+		let value = crate::Variant::ArrayString(vec!["a".repeat(200).into(), "b".repeat(100).into()].into());
+		let mut buf = vec![];
+		super::serialize_gvariant(&value, &mut buf, crate::Endianness::Little);
+		assert_eq!(buf.len(), 302 + 4);
+		assert_eq!(&buf[302..], b"\xc9\0\x2e\x01");
+		assert_eq!(super::deserialize_gvariant(&buf, &"as".parse().unwrap(), crate::Endianness::Little).unwrap(), value);
+
+		assert!(matches!(
+			super::deserialize_gvariant(b"\x02", &crate::Signature::Bool, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::InvalidBool(2)),
+		));
+		assert!(matches!(
+			super::deserialize_gvariant(b"\x01\0\0", &crate::Signature::U32, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::InvalidLength { len: 3, .. }),
+		));
+		assert!(matches!(
+			super::deserialize_gvariant(b"foo", &crate::Signature::String, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::MissingNulTerminator),
+		));
+		assert!(matches!(
+			super::deserialize_gvariant(b"foo\0\x07", &"as".parse().unwrap(), crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::InvalidFramingOffset),
+		));
+		assert!(matches!(
+			super::deserialize_gvariant(b"\x05\0\0\0\0su", &crate::Signature::Variant, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::InvalidSignature(signature)) if signature == "su",
+		));
+	}
+
+	#[test]
+	fn test_gvariant_nesting_too_deep() {
+		fn nested_variant<'a>(depth: usize) -> crate::Variant<'a> {
+			(0..depth).fold(crate::Variant::U8(5), |value, _| crate::Variant::Variant(Box::new(value).into()))
+		}
+
+		let value = nested_variant(super::MAX_DEPTH);
+		let mut buf = vec![];
+		super::serialize_gvariant(&value, &mut buf, crate::Endianness::Little);
+		assert_eq!(super::deserialize_gvariant(&buf, &crate::Signature::Variant, crate::Endianness::Little).unwrap(), value);
+
+		let value = nested_variant(super::MAX_DEPTH + 1);
+		let mut buf = vec![];
+		super::serialize_gvariant(&value, &mut buf, crate::Endianness::Little);
+		assert!(matches!(
+			super::deserialize_gvariant(&buf, &crate::Signature::Variant, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::NestingTooDeep),
+		));
+
+		// Every `\0v` suffix makes the rest of the buffer the value of another variant.
+		assert!(matches!(
+			super::deserialize_gvariant(&b"\0v".repeat(100_000), &crate::Signature::Variant, crate::Endianness::Little),
+			Err(super::DeserializeGVariantError::NestingTooDeep),
+		));
+	}
+}
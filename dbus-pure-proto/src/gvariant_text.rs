@@ -0,0 +1,977 @@
+//! The `GVariant` text format, as used by `gdbus call`, `gsettings` and `g_variant_print`.
+//!
+//! For example, a `(sv)` struct is written as `('foo', <uint32 5>)` and an `a{sv}` dict as `{'k': <true>}`.
+
+/// Formats this value in the `GVariant` text format, such as `('foo', <uint32 5>, {'k': <true>})`.
+///
+/// Types that cannot be inferred from the value itself have a type annotation, such as `uint32 5`, `objectpath '/foo'` and `@as []`.
+/// Elements of an array after the first, and keys and values of a dict after the first entry, are not annotated
+/// since their type is the same as that of the first.
+///
+/// A `Variant::Tuple`, ie a message body, is formatted like a struct. The output can be parsed back with [`parse_gvariant_text`].
+impl std::fmt::Display for crate::Variant<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write_value(f, self, true)
+	}
+}
+
+fn write_value(f: &mut std::fmt::Formatter<'_>, value: &crate::Variant<'_>, annotate: bool) -> std::fmt::Result {
+	match value {
+		crate::Variant::Array { element_signature, elements } => {
+			if let crate::Signature::DictEntry { .. } = element_signature {
+				if elements.is_empty() {
+					if annotate {
+						write!(f, "@a{} ", element_signature)?;
+					}
+					return f.write_str("{}");
+				}
+
+				f.write_str("{")?;
+				for (i, element) in elements.iter().enumerate() {
+					if i > 0 {
+						f.write_str(", ")?;
+					}
+					match element {
+						crate::Variant::DictEntry { key, value } => {
+							write_value(f, key, annotate && i == 0)?;
+							f.write_str(": ")?;
+							write_value(f, value, annotate && i == 0)?;
+						},
+						element => write_value(f, element, annotate && i == 0)?,
+					}
+				}
+				f.write_str("}")
+			}
+			else {
+				write_array(f, elements, element_signature, annotate, |f, element, annotate| write_value(f, element, annotate))
+			}
+		},
+
+		crate::Variant::ArrayBool(elements) => write_array(f, elements, &crate::Signature::Bool, annotate, |f, element, _| write!(f, "{}", element)),
+		crate::Variant::ArrayF64(elements) => write_array(f, elements, &crate::Signature::F64, annotate, |f, element, _| write_f64(f, *element)),
+		crate::Variant::ArrayI16(elements) => write_array(f, elements, &crate::Signature::I16, annotate, |f, element, annotate| write_number(f, "int16", element, annotate)),
+		crate::Variant::ArrayI32(elements) => write_array(f, elements, &crate::Signature::I32, annotate, |f, element, _| write!(f, "{}", element)),
+		crate::Variant::ArrayI64(elements) => write_array(f, elements, &crate::Signature::I64, annotate, |f, element, annotate| write_number(f, "int64", element, annotate)),
+		crate::Variant::ArrayString(elements) => write_array(f, elements, &crate::Signature::String, annotate, |f, element, _| write_string(f, element)),
+		crate::Variant::ArrayU8(elements) => write_array(f, elements, &crate::Signature::U8, annotate, |f, element, annotate| write_u8(f, *element, annotate)),
+		crate::Variant::ArrayU16(elements) => write_array(f, elements, &crate::Signature::U16, annotate, |f, element, annotate| write_number(f, "uint16", element, annotate)),
+		crate::Variant::ArrayU32(elements) => write_array(f, elements, &crate::Signature::U32, annotate, |f, element, annotate| write_number(f, "uint32", element, annotate)),
+		crate::Variant::ArrayU64(elements) => write_array(f, elements, &crate::Signature::U64, annotate, |f, element, annotate| write_number(f, "uint64", element, annotate)),
+		crate::Variant::ArrayUnixFd(elements) => write_array(f, elements, &crate::Signature::UnixFd, annotate, |f, element, annotate| write_number(f, "handle", &element.0, annotate)),
+
+		crate::Variant::Bool(value) => write!(f, "{}", value),
+
+		crate::Variant::DictEntry { key, value } => {
+			f.write_str("{")?;
+			write_value(f, key, annotate)?;
+			f.write_str(", ")?;
+			write_value(f, value, annotate)?;
+			f.write_str("}")
+		},
+
+		crate::Variant::F64(value) => write_f64(f, *value),
+
+		crate::Variant::I16(value) => write_number(f, "int16", value, annotate),
+
+		crate::Variant::I32(value) => write!(f, "{}", value),
+
+		crate::Variant::I64(value) => write_number(f, "int64", value, annotate),
+
+		crate::Variant::Maybe { element_signature, value: None } => {
+			if annotate {
+				write!(f, "@m{} ", element_signature)?;
+			}
+			f.write_str("nothing")
+		},
+
+		crate::Variant::Maybe { element_signature: _, value: Some(value) } => {
+			f.write_str("just ")?;
+			write_value(f, value, annotate)
+		},
+
+		crate::Variant::ObjectPath(value) => {
+			if annotate {
+				f.write_str("objectpath ")?;
+			}
+			write_string(f, &value.0)
+		},
+
+		crate::Variant::Signature(value) => {
+			if annotate {
+				f.write_str("signature ")?;
+			}
+			write_string(f, &value.to_string())
+		},
+
+		crate::Variant::String(value) => write_string(f, value),
+
+		crate::Variant::Struct { fields: elements } |
+		crate::Variant::Tuple { elements } => {
+			f.write_str("(")?;
+			for (i, element) in elements.iter().enumerate() {
+				if i > 0 {
+					f.write_str(", ")?;
+				}
+				write_value(f, element, annotate)?;
+			}
+			if elements.len() == 1 {
+				f.write_str(",")?;
+			}
+			f.write_str(")")
+		},
+
+		crate::Variant::U8(value) => write_u8(f, *value, annotate),
+
+		crate::Variant::U16(value) => write_number(f, "uint16", value, annotate),
+
+		crate::Variant::U32(value) => write_number(f, "uint32", value, annotate),
+
+		crate::Variant::U64(value) => write_number(f, "uint64", value, annotate),
+
+		crate::Variant::UnixFd(value) => write_number(f, "handle", &value.0, annotate),
+
+		crate::Variant::Variant(value) => {
+			// The type of the inner value is not known from the outside, so it's always annotated.
+			f.write_str("<")?;
+			write_value(f, value, true)?;
+			f.write_str(">")
+		},
+	}
+}
+
+fn write_array<T>(
+	f: &mut std::fmt::Formatter<'_>,
+	elements: &[T],
+	element_signature: &crate::Signature,
+	annotate: bool,
+	mut write_element: impl FnMut(&mut std::fmt::Formatter<'_>, &T, bool) -> std::fmt::Result,
+) -> std::fmt::Result {
+	if elements.is_empty() {
+		if annotate {
+			write!(f, "@a{} ", element_signature)?;
+		}
+		return f.write_str("[]");
+	}
+
+	f.write_str("[")?;
+	for (i, element) in elements.iter().enumerate() {
+		if i > 0 {
+			f.write_str(", ")?;
+		}
+		write_element(f, element, annotate && i == 0)?;
+	}
+	f.write_str("]")
+}
+
+fn write_number(f: &mut std::fmt::Formatter<'_>, keyword: &str, value: &impl std::fmt::Display, annotate: bool) -> std::fmt::Result {
+	if annotate {
+		write!(f, "{} ", keyword)?;
+	}
+	write!(f, "{}", value)
+}
+
+fn write_u8(f: &mut std::fmt::Formatter<'_>, value: u8, annotate: bool) -> std::fmt::Result {
+	if annotate {
+		f.write_str("byte ")?;
+	}
+	write!(f, "0x{:02x}", value)
+}
+
+fn write_f64(f: &mut std::fmt::Formatter<'_>, value: f64) -> std::fmt::Result {
+	if value.is_nan() {
+		f.write_str("nan")
+	}
+	else if value.is_infinite() {
+		f.write_str(if value.is_sign_positive() { "inf" } else { "-inf" })
+	}
+	else {
+		// Debug always includes a decimal point or exponent, so that the value is not parsed back as an int32.
+		write!(f, "{:?}", value)
+	}
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, value: &str) -> std::fmt::Result {
+	use std::fmt::Write;
+
+	let quote = if value.contains('\'') && !value.contains('"') { '"' } else { '\'' };
+
+	f.write_char(quote)?;
+	for c in value.chars() {
+		match c {
+			'\\' => f.write_str("\\\\")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			'\u{07}' => f.write_str("\\a")?,
+			'\u{08}' => f.write_str("\\b")?,
+			'\u{0b}' => f.write_str("\\v")?,
+			'\u{0c}' => f.write_str("\\f")?,
+			c if c == quote => write!(f, "\\{}", c)?,
+			c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+			c => f.write_char(c)?,
+		}
+	}
+	f.write_char(quote)
+}
+
+/// Parse a value in the `GVariant` text format.
+///
+/// If `signature` is set, the value must be of that type, and it's used to interpret values whose type is ambiguous,
+/// such as `5` for any number type and `'/foo'` for a string or object path. A `Signature::Tuple` parses a struct into a `Variant::Tuple`,
+/// ie a message body.
+///
+/// Otherwise the type is inferred from the value, using type annotations such as `uint32 5` and `@as []` where present.
+/// Unannotated integers are `i`, numbers with a decimal point or exponent are `d`, and strings are `s`.
+/// Elements of an array, and keys and values of a dict, after the first are parsed with the type of the first.
+///
+/// Integers can be written in decimal or in hexadecimal with a `0x` prefix. `b'...'` can be used for byte strings.
+/// Maybe values are written as `just 5` and `nothing`. `just` can be left out if `signature` or a type annotation says the value is a maybe.
+///
+/// Values nested in more than 128 values, counting type annotations and keywords, fail with [`ParseGVariantTextError::NestingTooDeep`].
+///
+/// # Example
+///
+/// ```rust
+/// let value = dbus_pure_proto::parse_gvariant_text("('foo', <uint32 5>, {'k': <true>})", None).unwrap();
+/// assert_eq!(value.inner_signature().to_string(), "(sva{sv})");
+/// assert_eq!(value.to_string(), "('foo', <uint32 5>, {'k': <true>})");
+///
+/// let signature = "(ou)".parse().unwrap();
+/// let value = dbus_pure_proto::parse_gvariant_text("('/foo', 5)", Some(&signature)).unwrap();
+/// assert_eq!(value.to_string(), "(objectpath '/foo', uint32 5)");
+/// ```
+pub fn parse_gvariant_text<'a>(s: &str, signature: Option<&crate::Signature>) -> Result<crate::Variant<'a>, ParseGVariantTextError> {
+	let mut parser = Parser { s, pos: 0, depth: 0 };
+
+	let value = parser.parse_value(signature)?;
+
+	parser.skip_whitespace();
+	if parser.pos < s.len() {
+		return Err(ParseGVariantTextError::TrailingCharacters { pos: parser.pos });
+	}
+
+	Ok(value)
+}
+
+/// The maximum number of values that a value can be nested in, including type annotations and keywords like `just` that wrap it.
+///
+/// This bounds the recursion of the parser so that deeply nested input fails instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+	s: &'a str,
+	pos: usize,
+
+	/// The number of values that the value being parsed is nested in.
+	depth: usize,
+}
+
+impl Parser<'_> {
+	fn parse_value<'b>(&mut self, expected: Option<&crate::Signature>) -> Result<crate::Variant<'b>, ParseGVariantTextError> {
+		if self.depth >= MAX_DEPTH {
+			return Err(ParseGVariantTextError::NestingTooDeep { pos: self.pos });
+		}
+
+		self.depth += 1;
+		let result = self.parse_value_inner(expected);
+		self.depth -= 1;
+		result
+	}
+
+	fn parse_value_inner<'b>(&mut self, expected: Option<&crate::Signature>) -> Result<crate::Variant<'b>, ParseGVariantTextError> {
+		self.skip_whitespace();
+
+		let start = self.pos;
+		let type_mismatch = |expected: &crate::Signature| ParseGVariantTextError::TypeMismatch { pos: start, expected: expected.clone() };
+
+		// `g_variant_print` leaves out `just` where it's unambiguous, such as `@mi 5`.
+		if let Some(crate::Signature::Maybe { element }) = expected {
+			let rest = &self.s[self.pos..];
+			if !rest.starts_with('@') && !matches!(leading_word(rest), "just" | "nothing") {
+				let value = self.parse_value(Some(element))?;
+				return Ok(crate::Variant::Maybe { element_signature: (**element).clone(), value: Some(Box::new(value).into()) });
+			}
+		}
+
+		match self.peek().ok_or(ParseGVariantTextError::UnexpectedEnd)? {
+			'@' => {
+				self.pos += 1;
+				let rest = &self.s[self.pos..];
+				let len = single_complete_type_len(rest).ok_or(ParseGVariantTextError::InvalidSignature { pos: self.pos })?;
+				let signature = crate::Signature::from_gvariant_type(&rest[..len]).map_err(|_| ParseGVariantTextError::InvalidSignature { pos: self.pos })?;
+				self.pos += len;
+				match expected {
+					Some(expected) if *expected != signature => Err(type_mismatch(expected)),
+					_ => self.parse_value(Some(&signature)),
+				}
+			},
+
+			'<' => {
+				match expected {
+					None | Some(crate::Signature::Variant) => (),
+					Some(expected) => return Err(type_mismatch(expected)),
+				}
+
+				self.pos += 1;
+				let inner = self.parse_value(None)?;
+				self.expect('>')?;
+				Ok(crate::Variant::Variant(Box::new(inner).into()))
+			},
+
+			'[' => {
+				let element_signature = match expected {
+					None => None,
+					Some(crate::Signature::Array { element }) => Some(&**element),
+					Some(expected) => return Err(type_mismatch(expected)),
+				};
+
+				self.pos += 1;
+				let (element_signature, elements) = self.parse_elements(']', element_signature, start, |parser, element_signature| {
+					let element = parser.parse_value(element_signature)?;
+					Ok(element)
+				})?;
+				Ok(array(element_signature, elements))
+			},
+
+			'{' => self.parse_dict_or_dict_entry(expected),
+
+			'(' => {
+				let field_signatures = match expected {
+					None => None,
+					Some(crate::Signature::Struct { fields }) => Some(&fields[..]),
+					Some(crate::Signature::Tuple { elements }) => Some(&elements[..]),
+					Some(expected) => return Err(type_mismatch(expected)),
+				};
+
+				self.pos += 1;
+				let mut fields = vec![];
+				loop {
+					self.skip_whitespace();
+					if self.peek() == Some(')') {
+						self.pos += 1;
+						break;
+					}
+
+					let field_signature = match (expected, field_signatures) {
+						(Some(expected), Some(field_signatures)) => Some(field_signatures.get(fields.len()).ok_or_else(|| type_mismatch(expected))?),
+						_ => None,
+					};
+					fields.push(self.parse_value(field_signature)?);
+
+					self.skip_whitespace();
+					match self.next() {
+						Some(',') => (),
+						Some(')') => break,
+						Some(c) => return Err(ParseGVariantTextError::UnexpectedCharacter { pos: self.pos - c.len_utf8(), c }),
+						None => return Err(ParseGVariantTextError::UnexpectedEnd),
+					}
+				}
+
+				// `()` is inferred as an empty message body, since D-Bus does not have empty structs.
+				match expected {
+					Some(crate::Signature::Struct { fields: field_signatures }) if field_signatures.len() == fields.len() =>
+						Ok(crate::Variant::Struct { fields: fields.into() }),
+					Some(crate::Signature::Tuple { elements: field_signatures }) if field_signatures.len() == fields.len() =>
+						Ok(crate::Variant::Tuple { elements: fields.into() }),
+					Some(expected) =>
+						Err(type_mismatch(expected)),
+					None if fields.is_empty() =>
+						Ok(crate::Variant::Tuple { elements: fields.into() }),
+					None =>
+						Ok(crate::Variant::Struct { fields: fields.into() }),
+				}
+			},
+
+			'\'' | '"' => {
+				let value = self.parse_string()?;
+				match expected {
+					None | Some(crate::Signature::String) => Ok(crate::Variant::String(value.into())),
+					Some(crate::Signature::ObjectPath) => {
+						let path = crate::ObjectPath::new(value).map_err(|err| ParseGVariantTextError::InvalidObjectPath { pos: start, err })?;
+						Ok(crate::Variant::ObjectPath(path))
+					},
+					Some(crate::Signature::Signature) => {
+						let signature = value.parse().map_err(|_| ParseGVariantTextError::InvalidSignature { pos: start })?;
+						Ok(crate::Variant::Signature(signature))
+					},
+					Some(expected) => Err(type_mismatch(expected)),
+				}
+			},
+
+			c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => self.parse_number(expected),
+
+			c if c.is_ascii_alphabetic() => {
+				let word = leading_word(&self.s[self.pos..]);
+				let word_len = word.len();
+
+				if word == "b" && matches!(self.s[(self.pos + 1)..].chars().next(), Some('\'') | Some('"')) {
+					match expected {
+						None => (),
+						Some(crate::Signature::Array { element }) if **element == crate::Signature::U8 => (),
+						Some(expected) => return Err(type_mismatch(expected)),
+					}
+
+					self.pos += 1;
+					let value = self.parse_string()?;
+					return Ok(crate::Variant::ArrayU8(value.into_bytes().into()));
+				}
+
+				let keyword_signature = match word {
+					"true" | "false" => {
+						match expected {
+							None | Some(crate::Signature::Bool) => (),
+							Some(expected) => return Err(type_mismatch(expected)),
+						}
+
+						self.pos += word_len;
+						return Ok(crate::Variant::Bool(word == "true"));
+					},
+
+					"inf" | "nan" => return self.parse_number(expected),
+
+					"just" => {
+						let element_signature = match expected {
+							None => None,
+							Some(crate::Signature::Maybe { element }) => Some(&**element),
+							Some(expected) => return Err(type_mismatch(expected)),
+						};
+
+						self.pos += word_len;
+						let value = self.parse_value(element_signature)?;
+						let element_signature = match element_signature {
+							Some(element_signature) => element_signature.clone(),
+							None => value.inner_signature(),
+						};
+						return Ok(crate::Variant::Maybe { element_signature, value: Some(Box::new(value).into()) });
+					},
+
+					"nothing" => {
+						let element_signature = match expected {
+							None => return Err(ParseGVariantTextError::CannotInferType { pos: start }),
+							Some(crate::Signature::Maybe { element }) => (**element).clone(),
+							Some(expected) => return Err(type_mismatch(expected)),
+						};
+
+						self.pos += word_len;
+						return Ok(crate::Variant::Maybe { element_signature, value: None });
+					},
+
+					"boolean" => crate::Signature::Bool,
+					"byte" => crate::Signature::U8,
+					"double" => crate::Signature::F64,
+					"handle" => crate::Signature::UnixFd,
+					"int16" => crate::Signature::I16,
+					"int32" => crate::Signature::I32,
+					"int64" => crate::Signature::I64,
+					"objectpath" => crate::Signature::ObjectPath,
+					"signature" => crate::Signature::Signature,
+					"string" => crate::Signature::String,
+					"uint16" => crate::Signature::U16,
+					"uint32" => crate::Signature::U32,
+					"uint64" => crate::Signature::U64,
+
+					_ => return Err(ParseGVariantTextError::UnknownKeyword { pos: start, keyword: word.to_owned() }),
+				};
+
+				match expected {
+					Some(expected) if *expected != keyword_signature => Err(type_mismatch(expected)),
+					_ => {
+						self.pos += word_len;
+						self.parse_value(Some(&keyword_signature))
+					},
+				}
+			},
+
+			c => Err(ParseGVariantTextError::UnexpectedCharacter { pos: start, c }),
+		}
+	}
+
+	/// Parses `{k: v, ...}` into an array of dict entries, or `{k, v}` into a single dict entry.
+	fn parse_dict_or_dict_entry<'b>(&mut self, expected: Option<&crate::Signature>) -> Result<crate::Variant<'b>, ParseGVariantTextError> {
+		let start = self.pos;
+		let type_mismatch = |expected: &crate::Signature| ParseGVariantTextError::TypeMismatch { pos: start, expected: expected.clone() };
+
+		let (is_dict, key_signature, value_signature) = match expected {
+			None => (None, None, None),
+			Some(crate::Signature::Array { element }) => match &**element {
+				crate::Signature::DictEntry { key, value } => (Some(true), Some(&**key), Some(&**value)),
+				_ => return Err(type_mismatch(element)),
+			},
+			Some(crate::Signature::DictEntry { key, value }) => (Some(false), Some(&**key), Some(&**value)),
+			Some(expected) => return Err(type_mismatch(expected)),
+		};
+
+		self.pos += 1;
+		self.skip_whitespace();
+
+		if self.peek() == Some('}') {
+			return match expected {
+				Some(crate::Signature::Array { element }) => {
+					self.pos += 1;
+					Ok(crate::Variant::Array { element_signature: (**element).clone(), elements: vec![].into() })
+				},
+				Some(expected) => Err(type_mismatch(expected)),
+				None => Err(ParseGVariantTextError::CannotInferType { pos: start }),
+			};
+		}
+
+		let key = self.parse_value(key_signature)?;
+		self.skip_whitespace();
+
+		match (self.next(), is_dict) {
+			(Some(','), Some(false)) | (Some(','), None) => {
+				let value = self.parse_value(value_signature)?;
+				self.expect('}')?;
+				Ok(crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
+			},
+
+			(Some(':'), Some(true)) | (Some(':'), None) => {
+				let key_signature = match key_signature {
+					Some(key_signature) => key_signature.clone(),
+					None => key.inner_signature(),
+				};
+
+				let value = self.parse_value(value_signature)?;
+				let value_signature = match value_signature {
+					Some(value_signature) => value_signature.clone(),
+					None => value.inner_signature(),
+				};
+
+				let mut elements = vec![crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() }];
+				loop {
+					self.skip_whitespace();
+					match self.next() {
+						Some(',') => (),
+						Some('}') => break,
+						Some(c) => return Err(ParseGVariantTextError::UnexpectedCharacter { pos: self.pos - c.len_utf8(), c }),
+						None => return Err(ParseGVariantTextError::UnexpectedEnd),
+					}
+
+					let key = self.parse_value(Some(&key_signature))?;
+					self.expect(':')?;
+					let value = self.parse_value(Some(&value_signature))?;
+					elements.push(crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() });
+				}
+
+				Ok(crate::Variant::Array {
+					element_signature: crate::Signature::DictEntry { key: Box::new(key_signature), value: Box::new(value_signature) },
+					elements: elements.into(),
+				})
+			},
+
+			(Some(c), _) => Err(ParseGVariantTextError::UnexpectedCharacter { pos: self.pos - c.len_utf8(), c }),
+
+			(None, _) => Err(ParseGVariantTextError::UnexpectedEnd),
+		}
+	}
+
+	/// Parses comma-separated elements up to the given closing character, which has to be consumed as well.
+	///
+	/// If `element_signature` is not set, it's inferred from the first element.
+	fn parse_elements<'b>(
+		&mut self,
+		end: char,
+		element_signature: Option<&crate::Signature>,
+		start: usize,
+		mut parse_element: impl FnMut(&mut Self, Option<&crate::Signature>) -> Result<crate::Variant<'b>, ParseGVariantTextError>,
+	) -> Result<(crate::Signature, Vec<crate::Variant<'b>>), ParseGVariantTextError> {
+		let mut element_signature = element_signature.cloned();
+		let mut elements = vec![];
+
+		self.skip_whitespace();
+		if self.peek() == Some(end) {
+			self.pos += 1;
+			let element_signature = element_signature.ok_or(ParseGVariantTextError::CannotInferType { pos: start })?;
+			return Ok((element_signature, elements));
+		}
+
+		loop {
+			let element = parse_element(self, element_signature.as_ref())?;
+			if element_signature.is_none() {
+				element_signature = Some(element.inner_signature());
+			}
+			elements.push(element);
+
+			self.skip_whitespace();
+			match self.next() {
+				Some(',') => (),
+				Some(c) if c == end => break,
+				Some(c) => return Err(ParseGVariantTextError::UnexpectedCharacter { pos: self.pos - c.len_utf8(), c }),
+				None => return Err(ParseGVariantTextError::UnexpectedEnd),
+			}
+		}
+
+		Ok((element_signature.expect("set by the first element"), elements))
+	}
+
+	fn parse_number<'b>(&mut self, expected: Option<&crate::Signature>) -> Result<crate::Variant<'b>, ParseGVariantTextError> {
+		let start = self.pos;
+		let len = self.s[start..].find(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '+' && c != '-').unwrap_or(self.s.len() - start);
+		let number = &self.s[start..(start + len)];
+		self.pos += len;
+
+		let expected = expected.unwrap_or_else(|| {
+			let is_hex = number.trim_start_matches(&['-', '+'][..]).starts_with("0x");
+			if number.contains('.') || number.ends_with("inf") || number.ends_with("nan") || (!is_hex && number.contains(&['e', 'E'][..])) {
+				&crate::Signature::F64
+			}
+			else {
+				&crate::Signature::I32
+			}
+		});
+
+		let invalid_number = || ParseGVariantTextError::InvalidNumber { pos: start, expected: expected.clone() };
+
+		Ok(match expected {
+			crate::Signature::F64 => crate::Variant::F64(number.parse().map_err(|_| invalid_number())?),
+			crate::Signature::I16 => crate::Variant::I16(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::I32 => crate::Variant::I32(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::I64 => crate::Variant::I64(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::U8 => crate::Variant::U8(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::U16 => crate::Variant::U16(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::U32 => crate::Variant::U32(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::U64 => crate::Variant::U64(parse_integer(number).ok_or_else(invalid_number)?),
+			crate::Signature::UnixFd => crate::Variant::UnixFd(crate::UnixFd(parse_integer(number).ok_or_else(invalid_number)?)),
+			expected => return Err(ParseGVariantTextError::TypeMismatch { pos: start, expected: expected.clone() }),
+		})
+	}
+
+	fn parse_string(&mut self) -> Result<String, ParseGVariantTextError> {
+		let quote = self.next().ok_or(ParseGVariantTextError::UnexpectedEnd)?;
+
+		let mut result = String::new();
+		loop {
+			let escape_pos = self.pos;
+			match self.next().ok_or(ParseGVariantTextError::UnexpectedEnd)? {
+				'\\' => {
+					let c = match self.next().ok_or(ParseGVariantTextError::UnexpectedEnd)? {
+						'a' => '\u{07}',
+						'b' => '\u{08}',
+						'f' => '\u{0c}',
+						'n' => '\n',
+						'r' => '\r',
+						't' => '\t',
+						'v' => '\u{0b}',
+						'u' => self.parse_unicode_escape(4, escape_pos)?,
+						'U' => self.parse_unicode_escape(8, escape_pos)?,
+						c => c,
+					};
+					result.push(c);
+				},
+
+				c if c == quote => break,
+
+				c => result.push(c),
+			}
+		}
+
+		Ok(result)
+	}
+
+	fn parse_unicode_escape(&mut self, len: usize, escape_pos: usize) -> Result<char, ParseGVariantTextError> {
+		let digits = self.s.get(self.pos..(self.pos + len)).ok_or(ParseGVariantTextError::InvalidEscape { pos: escape_pos })?;
+		let c =
+			u32::from_str_radix(digits, 16).ok()
+			.and_then(std::char::from_u32)
+			.ok_or(ParseGVariantTextError::InvalidEscape { pos: escape_pos })?;
+		self.pos += len;
+		Ok(c)
+	}
+
+	fn expect(&mut self, expected: char) -> Result<(), ParseGVariantTextError> {
+		self.skip_whitespace();
+		match self.next() {
+			Some(c) if c == expected => Ok(()),
+			Some(c) => Err(ParseGVariantTextError::UnexpectedCharacter { pos: self.pos - c.len_utf8(), c }),
+			None => Err(ParseGVariantTextError::UnexpectedEnd),
+		}
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.pos += c.len_utf8();
+		Some(c)
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.s[self.pos..].chars().next()
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = &self.s[self.pos..];
+		self.pos += rest.len() - rest.trim_start().len();
+	}
+}
+
+/// The word of ASCII alphanumeric characters and underscores at the start of the given string.
+fn leading_word(s: &str) -> &str {
+	let len = s.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(s.len());
+	&s[..len]
+}
+
+/// The length of the single complete type at the start of the given string, if there is one.
+fn single_complete_type_len(s: &str) -> Option<usize> {
+	let mut depth = 0_usize;
+
+	for (i, c) in s.char_indices() {
+		match c {
+			'a' | 'm' => (),
+			'(' | '{' => depth += 1,
+			')' | '}' => {
+				depth = depth.checked_sub(1)?;
+				if depth == 0 {
+					return Some(i + 1);
+				}
+			},
+			'b' | 'd' | 'g' | 'h' | 'i' | 'n' | 'o' | 'q' | 's' | 't' | 'u' | 'v' | 'x' | 'y' =>
+				if depth == 0 {
+					return Some(i + 1);
+				},
+			_ => return None,
+		}
+	}
+
+	None
+}
+
+/// An array of the given elements.
+///
+/// Arrays of basic types are the specialized variants like `Variant::ArrayBool` rather than `Variant::Array`,
+/// the same as when they're deserialized with [`crate::VariantDeserializeSeed`] or [`crate::deserialize_gvariant`].
+fn array(element_signature: crate::Signature, elements: Vec<crate::Variant<'_>>) -> crate::Variant<'_> {
+	macro_rules! specialized {
+		($array_variant:ident, $element_variant:ident) => {{
+			let elements =
+				elements.into_iter()
+				.map(|element| match element {
+					crate::Variant::$element_variant(element) => element,
+					_ => unreachable!("element was parsed with the array's element signature"),
+				})
+				.collect::<Vec<_>>();
+			crate::Variant::$array_variant(elements.into())
+		}};
+	}
+
+	match element_signature {
+		crate::Signature::Bool => specialized!(ArrayBool, Bool),
+		crate::Signature::F64 => specialized!(ArrayF64, F64),
+		crate::Signature::I16 => specialized!(ArrayI16, I16),
+		crate::Signature::I32 => specialized!(ArrayI32, I32),
+		crate::Signature::I64 => specialized!(ArrayI64, I64),
+		crate::Signature::String => specialized!(ArrayString, String),
+		crate::Signature::U8 => specialized!(ArrayU8, U8),
+		crate::Signature::U16 => specialized!(ArrayU16, U16),
+		crate::Signature::U32 => specialized!(ArrayU32, U32),
+		crate::Signature::U64 => specialized!(ArrayU64, U64),
+		crate::Signature::UnixFd => specialized!(ArrayUnixFd, UnixFd),
+		element_signature => crate::Variant::Array { element_signature, elements: elements.into() },
+	}
+}
+
+fn parse_integer<T>(s: &str) -> Option<T> where T: std::convert::TryFrom<i128> {
+	let (negative, unsigned) = match s.as_bytes().first()? {
+		b'-' => (true, &s[1..]),
+		b'+' => (false, &s[1..]),
+		_ => (false, s),
+	};
+
+	let value =
+		if let Some(hex) = unsigned.strip_prefix("0x") {
+			i128::from_str_radix(hex, 16).ok()?
+		}
+		else if unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+			unsigned.parse().ok()?
+		}
+		else {
+			return None;
+		};
+	let value = if negative { -value } else { value };
+
+	std::convert::TryInto::try_into(value).ok()
+}
+
+/// An error from parsing a value in the `GVariant` text format with [`parse_gvariant_text`].
+///
+/// Positions are byte offsets into the string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseGVariantTextError {
+	/// The type of the value at the given position cannot be inferred, such as an empty array without a type annotation.
+	CannotInferType { pos: usize },
+
+	InvalidEscape { pos: usize },
+
+	InvalidNumber { pos: usize, expected: crate::Signature },
+
+	InvalidObjectPath { pos: usize, err: crate::NameError },
+
+	InvalidSignature { pos: usize },
+
+	/// The value at the given position is nested too deeply.
+	NestingTooDeep { pos: usize },
+
+	TrailingCharacters { pos: usize },
+
+	/// The value at the given position is not of the expected type.
+	TypeMismatch { pos: usize, expected: crate::Signature },
+
+	UnexpectedCharacter { pos: usize, c: char },
+
+	UnexpectedEnd,
+
+	UnknownKeyword { pos: usize, keyword: String },
+}
+
+impl std::fmt::Display for ParseGVariantTextError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseGVariantTextError::CannotInferType { pos } => write!(f, "the type of the value at position {} cannot be inferred", pos),
+			ParseGVariantTextError::InvalidEscape { pos } => write!(f, "invalid escape sequence at position {}", pos),
+			ParseGVariantTextError::InvalidNumber { pos, expected } => write!(f, "invalid number at position {}: expected a value of type {:?}", pos, expected.to_string()),
+			ParseGVariantTextError::InvalidObjectPath { pos, err: _ } => write!(f, "invalid object path at position {}", pos),
+			ParseGVariantTextError::InvalidSignature { pos } => write!(f, "invalid signature at position {}", pos),
+			ParseGVariantTextError::NestingTooDeep { pos } => write!(f, "the value at position {} is nested in more than {} values", pos, MAX_DEPTH),
+			ParseGVariantTextError::TrailingCharacters { pos } => write!(f, "unexpected characters after the value at position {}", pos),
+			ParseGVariantTextError::TypeMismatch { pos, expected } => write!(f, "the value at position {} is not of the expected type {:?}", pos, expected.to_string()),
+			ParseGVariantTextError::UnexpectedCharacter { pos, c } => write!(f, "unexpected character {:?} at position {}", c, pos),
+			ParseGVariantTextError::UnexpectedEnd => f.write_str("unexpected end of input"),
+			ParseGVariantTextError::UnknownKeyword { pos, keyword } => write!(f, "unknown keyword {:?} at position {}", keyword, pos),
+		}
+	}
+}
+
+impl std::error::Error for ParseGVariantTextError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			ParseGVariantTextError::InvalidObjectPath { pos: _, err } => Some(err),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_gvariant_text() {
+		fn roundtrip(s: &str, expected_signature: &str) {
+			let value = super::parse_gvariant_text(s, None).unwrap();
+			assert_eq!(value.inner_signature().to_string(), expected_signature);
+			assert_eq!(value.to_string(), s);
+		}
+
+		roundtrip("('foo', <uint32 5>, {'k': <true>})", "(sva{sv})");
+		roundtrip("[int64 1, 2, 3]", "ax");
+		roundtrip("@a{sv} {}", "a{sv}");
+		roundtrip("[@as [], ['a']]", "aas");
+		roundtrip("(objectpath '/foo', signature 'a{sv}', byte 0xff, handle 3)", "(ogyh)");
+		roundtrip("{'a', [1.5, -inf]}", "{sad}");
+		roundtrip("(\"it's\", 'say \"hi\"\\n', 'both \\' \"')", "(sss)");
+		roundtrip("(<<-5>>,)", "(v)");
+		roundtrip("[just uint32 5, nothing]", "amu");
+		roundtrip("(@ms nothing, just @mi nothing)", "(msmmi)");
+
+		assert_eq!(
+			super::parse_gvariant_text("@mi 5", None),
+			Ok(crate::Variant::Maybe { element_signature: crate::Signature::I32, value: Some(Box::new(crate::Variant::I32(5)).into()) }),
+		);
+
+		assert_eq!(
+			super::parse_gvariant_text("b'hi'", None),
+			Ok(crate::Variant::ArrayU8(b"hi"[..].into())),
+		);
+
+		let signature = "sa{sv}".parse().unwrap();
+		let body = super::parse_gvariant_text(" ( 'hello' , { 'k' : < @u 0x10 > } ) ", Some(&signature)).unwrap();
+		assert_eq!(body.inner_signature(), signature);
+		assert_eq!(body.to_string(), "('hello', {'k': <uint32 16>})");
+
+		assert_eq!(super::parse_gvariant_text("[]", None), Err(super::ParseGVariantTextError::CannotInferType { pos: 0 }));
+		assert_eq!(super::parse_gvariant_text("[1, 'a']", None), Err(super::ParseGVariantTextError::TypeMismatch { pos: 4, expected: crate::Signature::I32 }));
+		assert_eq!(super::parse_gvariant_text("(1, 2", None), Err(super::ParseGVariantTextError::UnexpectedEnd));
+		assert_eq!(super::parse_gvariant_text("1 2", None), Err(super::ParseGVariantTextError::TrailingCharacters { pos: 2 }));
+		assert_eq!(super::parse_gvariant_text("byte 256", None), Err(super::ParseGVariantTextError::InvalidNumber { pos: 5, expected: crate::Signature::U8 }));
+		assert_eq!(super::parse_gvariant_text("uint8 1", None), Err(super::ParseGVariantTextError::UnknownKeyword { pos: 0, keyword: "uint8".to_owned() }));
+		assert_eq!(super::parse_gvariant_text("nothing", None), Err(super::ParseGVariantTextError::CannotInferType { pos: 0 }));
+		assert_eq!(
+			super::parse_gvariant_text("'foo'", Some(&crate::Signature::ObjectPath)),
+			Err(super::ParseGVariantTextError::InvalidObjectPath { pos: 0, err: crate::NameError::MissingLeadingSlash }),
+		);
+	}
+
+	#[test]
+	fn test_decoded_round_trip() {
+		// Arrays of basic types are decoded as the specialized variants like `Variant::ArrayU8`, so they must also be parsed as those.
+		const VALUES: &[(&str, &str)] = &[
+			("[true, false]", "ab"),
+			("[1.5, -inf]", "ad"),
+			("[int16 -1, 2]", "an"),
+			("[-1, 2]", "ai"),
+			("[int64 -1, 2]", "ax"),
+			("['a', 'bc']", "as"),
+			("@as []", "as"),
+			("b'hi'", "ay"),
+			("[byte 0x01, 0x02]", "ay"),
+			("[uint16 1, 2]", "aq"),
+			("[uint32 1, 2]", "au"),
+			("[uint64 1, 2]", "at"),
+			("[handle 1, 2]", "ah"),
+			("[objectpath '/a', '/b']", "ao"),
+			("[[1, 2], @ai []]", "aai"),
+			("{'k': <[byte 0x01]>, 'l': <['a']>}", "a{sv}"),
+			("('a', [true], [(['b'], [uint32 1])])", "(saba(asau))"),
+		];
+
+		const GVARIANT_VALUES: &[(&str, &str)] = &[
+			("[just [true], nothing]", "amab"),
+			("just ['a']", "mas"),
+		];
+
+		for &(s, signature) in VALUES.iter().chain(GVARIANT_VALUES) {
+			let signature = crate::Signature::from_gvariant_type(signature).unwrap();
+			let value = super::parse_gvariant_text(s, Some(&signature)).unwrap();
+
+			let mut serialized = vec![];
+			crate::serialize_gvariant(&value, &mut serialized, crate::Endianness::Little);
+			let decoded = crate::deserialize_gvariant(&serialized, &signature, crate::Endianness::Little).unwrap();
+			assert_eq!(decoded, value, "{}", s);
+			assert_eq!(super::parse_gvariant_text(&decoded.to_string(), Some(&signature)).unwrap(), decoded, "{}", s);
+		}
+
+		for &(s, signature) in VALUES {
+			let signature = crate::Signature::from_gvariant_type(signature).unwrap();
+			let value = super::parse_gvariant_text(s, Some(&signature)).unwrap();
+
+			let mut serialized = vec![];
+			let mut serializer = crate::ser::Serializer::new(&mut serialized, crate::Endianness::Little);
+			serde::Serialize::serialize(&value, &mut serializer).unwrap();
+			let mut deserializer = crate::de::Deserializer::new(&serialized, 0, crate::Endianness::Little);
+			let decoded: crate::Variant<'_> =
+				serde::de::DeserializeSeed::deserialize(crate::VariantDeserializeSeed::new(&signature), &mut deserializer).unwrap();
+			assert_eq!(decoded, value, "{}", s);
+			assert_eq!(super::parse_gvariant_text(&decoded.to_string(), Some(&signature)).unwrap(), decoded, "{}", s);
+		}
+	}
+
+	#[test]
+	fn test_nesting_too_deep() {
+		// The innermost value is nested in 127 values.
+		let s = format!("{}1{}", "<".repeat(super::MAX_DEPTH - 1), ">".repeat(super::MAX_DEPTH - 1));
+		let value = super::parse_gvariant_text(&s, None).unwrap();
+		assert_eq!(value.to_string(), s);
+
+		let s = format!("{}1{}", "<".repeat(super::MAX_DEPTH), ">".repeat(super::MAX_DEPTH));
+		assert_eq!(super::parse_gvariant_text(&s, None), Err(super::ParseGVariantTextError::NestingTooDeep { pos: super::MAX_DEPTH }));
+
+		assert_eq!(
+			super::parse_gvariant_text(&"[".repeat(100_000), None),
+			Err(super::ParseGVariantTextError::NestingTooDeep { pos: super::MAX_DEPTH }),
+		);
+		assert_eq!(
+			super::parse_gvariant_text(&"<[".repeat(100_000), None),
+			Err(super::ParseGVariantTextError::NestingTooDeep { pos: super::MAX_DEPTH }),
+		);
+		assert_eq!(
+			super::parse_gvariant_text(&"@i ".repeat(100_000), None),
+			Err(super::ParseGVariantTextError::NestingTooDeep { pos: super::MAX_DEPTH * 3 - 1 }),
+		);
+	}
+}
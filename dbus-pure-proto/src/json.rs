@@ -0,0 +1,395 @@
+//! Conversions between [`crate::Variant`] and [`serde_json::Value`]. Requires the `serde_json` feature.
+
+impl crate::Variant<'_> {
+	/// Convert the given JSON value into a `Variant` of the given signature.
+	///
+	/// - `b` is a JSON boolean.
+	///
+	/// - `y`, `n`, `q`, `i`, `u`, `x`, `t` and `h` are JSON integers that are in range of the type. `d` is any JSON number.
+	///
+	/// - `s` and `o` are JSON strings. `g` is a JSON string that is a valid signature.
+	///
+	/// - `a{..}` is a JSON object. The keys are parsed according to the key type, such as `"5"` for `u`.
+	///   So the same JSON object becomes an `a{sv}` or an `a{ss}` depending on the signature.
+	///
+	/// - Other arrays are JSON arrays.
+	///
+	/// - Structs are JSON arrays of the fields, and dict entries outside an array are two-element JSON arrays of the key and value.
+	///
+	/// - `m..` is `null` for `nothing`, or else the JSON value of the inner value.
+	///
+	/// - A signature of more than one type, ie a message body, is a JSON array of the values.
+	///   The empty signature is an empty JSON array or `null`.
+	///
+	/// - `v` is any JSON value other than `null`, with the inner signature inferred from it: `b` for a boolean,
+	///   `x` for an integer (or `t` if it does not fit in an `x`), `d` for other numbers, `s` for a string,
+	///   `av` for an array, and `a{sv}` for an object.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// let json = serde_json::json!({ "key": "value" });
+	///
+	/// let value = dbus_pure_proto::Variant::from_json(&json, &"a{sv}".parse().unwrap()).unwrap();
+	/// assert_eq!(value.to_string(), "{'key': <'value'>}");
+	///
+	/// let value = dbus_pure_proto::Variant::from_json(&json, &"a{ss}".parse().unwrap()).unwrap();
+	/// assert_eq!(value.to_string(), "{'key': 'value'}");
+	/// ```
+	pub fn from_json(value: &serde_json::Value, signature: &crate::Signature) -> Result<Self, FromJsonError> {
+		from_json(value, signature, &mut String::new())
+	}
+
+	/// Convert this `Variant` into a JSON value.
+	///
+	/// - Booleans, numbers and strings become the corresponding JSON values. NaN and infinite doubles become `null`,
+	///   since JSON cannot represent them.
+	///
+	/// - Object paths and signatures become JSON strings.
+	///
+	/// - Unix fds become their index into the message's unix fds, as a JSON number.
+	///
+	/// - Arrays of dict entries become JSON objects. Keys that are not strings become strings, such as `"5"` for a `u` key.
+	///
+	/// - Other arrays, structs and message bodies become JSON arrays. A dict entry outside an array becomes a two-element JSON array
+	///   of the key and value.
+	///
+	/// - Maybe values become `null` for `nothing`, or else the JSON value of their inner value.
+	///
+	/// - Variants become the JSON value of their inner value. The inner signature is not preserved.
+	///
+	/// This is the inverse of [`Variant::from_json`](crate::Variant::from_json) given the original signature,
+	/// except for non-finite doubles, the inner signatures of variants, and nested maybe values.
+	pub fn to_json(&self) -> serde_json::Value {
+		match self {
+			crate::Variant::Array { element_signature: crate::Signature::DictEntry { .. }, elements } =>
+				serde_json::Value::Object(
+					elements.iter()
+					.filter_map(|element| match element {
+						crate::Variant::DictEntry { key, value } => Some((key_to_json(key), value.to_json())),
+						_ => None,
+					})
+					.collect(),
+				),
+
+			crate::Variant::Array { element_signature: _, elements } |
+			crate::Variant::Struct { fields: elements } |
+			crate::Variant::Tuple { elements } =>
+				elements.iter().map(crate::Variant::to_json).collect(),
+
+			crate::Variant::ArrayBool(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayF64(elements) => elements.iter().copied().map(f64_to_json).collect(),
+			crate::Variant::ArrayI16(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayI32(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayI64(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayString(elements) => elements.iter().map(|element| serde_json::Value::from(&**element)).collect(),
+			crate::Variant::ArrayU8(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayU16(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayU32(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayU64(elements) => elements.iter().copied().map(serde_json::Value::from).collect(),
+			crate::Variant::ArrayUnixFd(elements) => elements.iter().map(|element| serde_json::Value::from(element.0)).collect(),
+
+			crate::Variant::Bool(value) => (*value).into(),
+
+			crate::Variant::DictEntry { key, value } => vec![key.to_json(), value.to_json()].into(),
+
+			crate::Variant::F64(value) => f64_to_json(*value),
+
+			crate::Variant::I16(value) => (*value).into(),
+
+			crate::Variant::I32(value) => (*value).into(),
+
+			crate::Variant::I64(value) => (*value).into(),
+
+			crate::Variant::Maybe { element_signature: _, value } => value.as_ref().map_or(serde_json::Value::Null, |value| value.to_json()),
+
+			crate::Variant::ObjectPath(value) => (&*value.0).into(),
+
+			crate::Variant::Signature(value) => value.to_string().into(),
+
+			crate::Variant::String(value) => (&**value).into(),
+
+			crate::Variant::U8(value) => (*value).into(),
+
+			crate::Variant::U16(value) => (*value).into(),
+
+			crate::Variant::U32(value) => (*value).into(),
+
+			crate::Variant::U64(value) => (*value).into(),
+
+			crate::Variant::UnixFd(value) => value.0.into(),
+
+			crate::Variant::Variant(value) => value.to_json(),
+		}
+	}
+}
+
+fn f64_to_json(value: f64) -> serde_json::Value {
+	serde_json::Number::from_f64(value).map_or(serde_json::Value::Null, serde_json::Value::Number)
+}
+
+fn key_to_json(key: &crate::Variant<'_>) -> String {
+	match key.to_json() {
+		serde_json::Value::String(key) => key,
+		key => key.to_string(),
+	}
+}
+
+/// `pointer` is the JSON pointer of `value` within the original value, and is used for errors.
+fn from_json<'a>(value: &serde_json::Value, signature: &crate::Signature, pointer: &mut String) -> Result<crate::Variant<'a>, FromJsonError> {
+	let invalid_value = |pointer: &str| FromJsonError::InvalidValue { pointer: pointer.to_owned(), expected: signature.clone() };
+
+	Ok(match signature {
+		crate::Signature::Array { element } => match (value, &**element) {
+			(serde_json::Value::Object(entries), crate::Signature::DictEntry { key: key_signature, value: value_signature }) => {
+				let mut elements = Vec::with_capacity(entries.len());
+				for (key, value) in entries {
+					let pointer_len = push_pointer(pointer, key);
+
+					let key =
+						key_from_json(key, key_signature)
+						.ok_or_else(|| FromJsonError::InvalidKey { pointer: pointer.clone(), expected: (**key_signature).clone() })?;
+					let value = from_json(value, value_signature, pointer)?;
+					elements.push(crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() });
+
+					pointer.truncate(pointer_len);
+				}
+
+				crate::Variant::Array { element_signature: (**element).clone(), elements: elements.into() }
+			},
+
+			(serde_json::Value::Array(_), crate::Signature::DictEntry { .. }) |
+			(serde_json::Value::Object(_), _) =>
+				return Err(invalid_value(pointer)),
+
+			(serde_json::Value::Array(values), _) => {
+				let elements = elements_from_json(values, values.iter().map(|_| &**element), pointer)?;
+				crate::Variant::Array { element_signature: (**element).clone(), elements: elements.into() }
+			},
+
+			_ => return Err(invalid_value(pointer)),
+		},
+
+		crate::Signature::Bool => crate::Variant::Bool(value.as_bool().ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::DictEntry { key, value: value_signature } => match value {
+			serde_json::Value::Array(values) if values.len() == 2 => {
+				let mut elements = elements_from_json(values, vec![&**key, &**value_signature], pointer)?.into_iter();
+				let key = elements.next().expect("two elements");
+				let value = elements.next().expect("two elements");
+				crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() }
+			},
+
+			_ => return Err(invalid_value(pointer)),
+		},
+
+		crate::Signature::F64 => crate::Variant::F64(value.as_f64().ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::I16 => crate::Variant::I16(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::I32 => crate::Variant::I32(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::I64 => crate::Variant::I64(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::Maybe { element } => {
+			let value = match value {
+				serde_json::Value::Null => None,
+				value => Some(Box::new(from_json(value, element, pointer)?).into()),
+			};
+			crate::Variant::Maybe { element_signature: (**element).clone(), value }
+		},
+
+		crate::Signature::ObjectPath => {
+			let value = value.as_str().ok_or_else(|| invalid_value(pointer))?;
+			let path = crate::ObjectPath::new(value.to_owned()).map_err(|err| FromJsonError::InvalidObjectPath { pointer: pointer.clone(), err })?;
+			crate::Variant::ObjectPath(path)
+		},
+
+		crate::Signature::Signature => {
+			let value = value.as_str().ok_or_else(|| invalid_value(pointer))?;
+			crate::Variant::Signature(value.parse().map_err(|_| invalid_value(pointer))?)
+		},
+
+		crate::Signature::String => crate::Variant::String(value.as_str().ok_or_else(|| invalid_value(pointer))?.to_owned().into()),
+
+		crate::Signature::Struct { fields } => match value {
+			serde_json::Value::Array(values) if values.len() == fields.len() =>
+				crate::Variant::Struct { fields: elements_from_json(values, fields, pointer)?.into() },
+
+			_ => return Err(invalid_value(pointer)),
+		},
+
+		crate::Signature::Tuple { elements } => match value {
+			serde_json::Value::Null if elements.is_empty() =>
+				crate::Variant::Tuple { elements: vec![].into() },
+
+			serde_json::Value::Array(values) if values.len() == elements.len() =>
+				crate::Variant::Tuple { elements: elements_from_json(values, elements, pointer)?.into() },
+
+			_ => return Err(invalid_value(pointer)),
+		},
+
+		crate::Signature::U8 => crate::Variant::U8(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::U16 => crate::Variant::U16(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::U32 => crate::Variant::U32(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::U64 => crate::Variant::U64(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?),
+
+		crate::Signature::UnixFd => crate::Variant::UnixFd(crate::UnixFd(integer_from_json(value).ok_or_else(|| invalid_value(pointer))?)),
+
+		crate::Signature::Variant => {
+			let inner_signature = match value {
+				serde_json::Value::Null => return Err(invalid_value(pointer)),
+				serde_json::Value::Bool(_) => crate::Signature::Bool,
+				serde_json::Value::Number(number) if number.is_i64() => crate::Signature::I64,
+				serde_json::Value::Number(number) if number.is_u64() => crate::Signature::U64,
+				serde_json::Value::Number(_) => crate::Signature::F64,
+				serde_json::Value::String(_) => crate::Signature::String,
+				serde_json::Value::Array(_) => crate::Signature::Array { element: Box::new(crate::Signature::Variant) },
+				serde_json::Value::Object(_) => crate::Signature::Array {
+					element: Box::new(crate::Signature::DictEntry {
+						key: Box::new(crate::Signature::String),
+						value: Box::new(crate::Signature::Variant),
+					}),
+				},
+			};
+
+			let inner = from_json(value, &inner_signature, pointer)?;
+			crate::Variant::Variant(Box::new(inner).into())
+		},
+	})
+}
+
+fn elements_from_json<'a, 'b>(
+	values: &[serde_json::Value],
+	signatures: impl IntoIterator<Item = &'b crate::Signature>,
+	pointer: &mut String,
+) -> Result<Vec<crate::Variant<'a>>, FromJsonError> {
+	let mut elements = Vec::with_capacity(values.len());
+
+	for (i, (value, signature)) in values.iter().zip(signatures).enumerate() {
+		let pointer_len = push_pointer(pointer, &i.to_string());
+		elements.push(from_json(value, signature, pointer)?);
+		pointer.truncate(pointer_len);
+	}
+
+	Ok(elements)
+}
+
+fn key_from_json<'a>(key: &str, signature: &crate::Signature) -> Option<crate::Variant<'a>> {
+	Some(match signature {
+		crate::Signature::Bool => crate::Variant::Bool(key.parse().ok()?),
+		crate::Signature::F64 => crate::Variant::F64(key.parse().ok()?),
+		crate::Signature::I16 => crate::Variant::I16(key.parse().ok()?),
+		crate::Signature::I32 => crate::Variant::I32(key.parse().ok()?),
+		crate::Signature::I64 => crate::Variant::I64(key.parse().ok()?),
+		crate::Signature::ObjectPath => crate::Variant::ObjectPath(crate::ObjectPath::new(key.to_owned()).ok()?),
+		crate::Signature::Signature => crate::Variant::Signature(key.parse().ok()?),
+		crate::Signature::String => crate::Variant::String(key.to_owned().into()),
+		crate::Signature::U8 => crate::Variant::U8(key.parse().ok()?),
+		crate::Signature::U16 => crate::Variant::U16(key.parse().ok()?),
+		crate::Signature::U32 => crate::Variant::U32(key.parse().ok()?),
+		crate::Signature::U64 => crate::Variant::U64(key.parse().ok()?),
+		crate::Signature::UnixFd => crate::Variant::UnixFd(crate::UnixFd(key.parse().ok()?)),
+		_ => return None,
+	})
+}
+
+fn integer_from_json<T>(value: &serde_json::Value) -> Option<T> where T: std::convert::TryFrom<i64> + std::convert::TryFrom<u64> {
+	if let Some(value) = value.as_i64() {
+		std::convert::TryInto::try_into(value).ok()
+	}
+	else {
+		std::convert::TryInto::try_into(value.as_u64()?).ok()
+	}
+}
+
+/// Appends the given reference token to the given JSON pointer, and returns the original length of the pointer
+/// so that the token can be removed afterwards.
+fn push_pointer(pointer: &mut String, token: &str) -> usize {
+	let pointer_len = pointer.len();
+	pointer.push('/');
+	pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+	pointer_len
+}
+
+/// An error from converting a JSON value into a [`crate::Variant`] with [`crate::Variant::from_json`].
+///
+/// `pointer` is the JSON pointer of the invalid value within the original JSON value, such as `/foo/0`. It's empty for the original value itself.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FromJsonError {
+	/// The JSON object key at the given pointer could not be parsed as the expected key type.
+	InvalidKey { pointer: String, expected: crate::Signature },
+
+	InvalidObjectPath { pointer: String, err: crate::NameError },
+
+	/// The JSON value at the given pointer cannot be converted to the expected type.
+	InvalidValue { pointer: String, expected: crate::Signature },
+}
+
+impl std::fmt::Display for FromJsonError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FromJsonError::InvalidKey { pointer, expected } =>
+				write!(f, "object key at {:?} is not a valid value of type {:?}", pointer, expected.to_string()),
+			FromJsonError::InvalidObjectPath { pointer, err: _ } =>
+				write!(f, "value at {:?} is not a valid object path", pointer),
+			FromJsonError::InvalidValue { pointer, expected } =>
+				write!(f, "value at {:?} is not a valid value of type {:?}", pointer, expected.to_string()),
+		}
+	}
+}
+
+impl std::error::Error for FromJsonError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			FromJsonError::InvalidObjectPath { pointer: _, err } => Some(err),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_json() {
+		let json = serde_json::json!(["hello", { "a": 1, "b": [true, "x"], "c/d": { "e": null } }, { "5": "/foo" }, [1.5, -2]]);
+
+		let signature = "sa{sv}a{uo}(dx)".parse().unwrap();
+		assert_eq!(
+			crate::Variant::from_json(&json, &signature),
+			Err(super::FromJsonError::InvalidValue { pointer: "/1/c~1d/e".to_owned(), expected: crate::Signature::Variant }),
+		);
+
+		let mut json = json;
+		json[1]["c/d"]["e"] = 0.5.into();
+
+		let value = crate::Variant::from_json(&json, &signature).unwrap();
+		assert_eq!(value.inner_signature(), signature);
+		assert_eq!(value.to_string(), "('hello', {'a': <int64 1>, 'b': <[<true>, <'x'>]>, 'c/d': <{'e': <0.5>}>}, {uint32 5: objectpath '/foo'}, (1.5, int64 -2))");
+		assert_eq!(value.to_json(), json);
+
+		assert_eq!(
+			crate::Variant::from_json(&serde_json::json!({ "five": "/foo" }), &"a{uo}".parse().unwrap()),
+			Err(super::FromJsonError::InvalidKey { pointer: "/five".to_owned(), expected: crate::Signature::U32 }),
+		);
+		assert_eq!(
+			crate::Variant::from_json(&serde_json::json!([256]), &"ay".parse().unwrap()),
+			Err(super::FromJsonError::InvalidValue { pointer: "/0".to_owned(), expected: crate::Signature::U8 }),
+		);
+		assert_eq!(
+			crate::Variant::from_json(&serde_json::json!([1, 2]), &"(u)".parse().unwrap()),
+			Err(super::FromJsonError::InvalidValue { pointer: String::new(), expected: "(u)".parse().unwrap() }),
+		);
+
+		let value = crate::Variant::from_json(&serde_json::json!([null, 5]), &crate::Signature::from_gvariant_type("msmu").unwrap()).unwrap();
+		assert_eq!(value.to_string(), "(@ms nothing, just uint32 5)");
+		assert_eq!(value.to_json(), serde_json::json!([null, 5]));
+
+		assert_eq!(crate::Variant::F64(f64::NAN).to_json(), serde_json::Value::Null);
+		assert_eq!(crate::Variant::ArrayU8(vec![1, 2].into()).to_json(), serde_json::json!([1, 2]));
+		assert_eq!(crate::Variant::UnixFd(crate::UnixFd(3)).to_json(), serde_json::json!(3));
+	}
+}
@@ -2,37 +2,101 @@
 #![deny(clippy::all, clippy::pedantic)]
 #![allow(
 	clippy::default_trait_access,
+	clippy::elidable_lifetime_names,
 	clippy::let_unit_value,
+	clippy::manual_div_ceil,
+	clippy::manual_is_multiple_of,
+	clippy::manual_let_else,
 	clippy::missing_errors_doc,
 	clippy::module_name_repetitions,
 	clippy::must_use_candidate,
 	clippy::needless_lifetimes,
+	clippy::redundant_else,
 	clippy::similar_names,
 	clippy::too_many_lines,
+	clippy::uninlined_format_args,
 	clippy::unneeded_field_pattern,
+	clippy::unnested_or_patterns,
+	clippy::unreadable_literal,
 )]
 
 //! This is a pure Rust implementation of the D-Bus binary protocol.
 
+mod args;
+pub use args::{
+	parse_args,
+	ParseArgsError,
+};
+
 mod as_variant;
 pub use as_variant::{
 	AsVariant,
+	DictKey,
+	dict_as_variant,
+	SignatureStr,
 };
 
 pub(crate) mod de;
 pub use de::{
 	DeserializeError,
+	MAX_ARRAY_LEN,
+	MAX_DEPTH,
+};
+
+mod from_variant;
+pub use from_variant::{
+	dict_from_variant,
+	FromVariant,
+	FromVariantError,
+	struct_fields_from_variant,
+};
+
+mod gvariant;
+pub use gvariant::{
+	deserialize_gvariant,
+	DeserializeGVariantError,
+	serialize_gvariant,
+};
+
+mod gvariant_text;
+pub use gvariant_text::{
+	parse_gvariant_text,
+	ParseGVariantTextError,
+};
+
+#[cfg(feature = "serde_json")]
+mod json;
+#[cfg(feature = "serde_json")]
+pub use json::{
+	FromJsonError,
 };
 
 pub(crate) mod message;
 pub use message::{
 	deserialize_message,
+	deserialize_message_borrowed,
+	deserialize_message_with_body,
 	flags as message_flags,
+	MAX_MESSAGE_LEN,
+	Message,
+	message_len,
 	MessageFlags,
 	MessageHeader,
 	MessageHeaderField,
 	MessageType,
 	serialize_message,
+	serialize_message_with_body,
+};
+
+mod names;
+pub use names::{
+	BusName,
+	ErrorName,
+	InterfaceName,
+	MAX_NAME_LEN,
+	MemberName,
+	NameError,
+	ObjectPath,
 };
 
 pub(crate) mod ser;
@@ -44,12 +108,19 @@ pub mod std2;
 
 mod variant;
 pub use variant::{
+	BorrowedVariantDeserializeSeed,
 	Variant,
 	VariantDeserializeSeed,
 };
 
 mod variant_deserializer;
 
+mod variant_serializer;
+pub use variant_serializer::{
+	to_variant,
+	ToVariantError,
+};
+
 #[derive(Clone, Copy, Debug)]
 pub enum Endianness {
 	Big,
@@ -111,22 +182,6 @@ endianness_to_bytes! {
 	f64_to_bytes -> f64,
 }
 
-/// An object path.
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct ObjectPath<'a>(pub std::borrow::Cow<'a, str>);
-
-impl serde::Serialize for ObjectPath<'_> {
-	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
-		self.0.serialize(serializer)
-	}
-}
-
-impl<'de, 'a> serde::Deserialize<'de> for ObjectPath<'a> {
-	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
-		Ok(ObjectPath(serde::de::Deserialize::deserialize(deserializer)?))
-	}
-}
-
 /// A signature.
 ///
 /// Use `.to_string()` to get the string representation of the signature.
@@ -139,6 +194,8 @@ pub enum Signature {
 	I16,
 	I32,
 	I64,
+	/// The `GVariant` maybe type `m`. It is not supported by the D-Bus wire format.
+	Maybe { element: Box<Signature> },
 	ObjectPath,
 	Signature,
 	String,
@@ -163,6 +220,7 @@ impl Signature {
 			Signature::I16 => 2,
 			Signature::I32 => 4,
 			Signature::I64 => 8,
+			Signature::Maybe { element } => element.alignment(),
 			Signature::ObjectPath => 4,
 			Signature::Signature => 1,
 			Signature::String => 4,
@@ -176,6 +234,26 @@ impl Signature {
 			Signature::Variant => 1,
 		}
 	}
+
+	/// Whether this is a basic type, ie one that can be the key of a dict entry.
+	pub(crate) fn is_basic(&self) -> bool {
+		matches!(
+			self,
+			Signature::Bool |
+			Signature::F64 |
+			Signature::I16 |
+			Signature::I32 |
+			Signature::I64 |
+			Signature::ObjectPath |
+			Signature::Signature |
+			Signature::String |
+			Signature::U8 |
+			Signature::U16 |
+			Signature::U32 |
+			Signature::U64 |
+			Signature::UnixFd
+		)
+	}
 }
 
 impl std::fmt::Display for Signature {
@@ -206,6 +284,9 @@ impl std::fmt::Display for Signature {
 			Signature::I64 =>
 				f.write_str("x")?,
 
+			Signature::Maybe { element } =>
+				write!(f, "m{}", element)?,
+
 			Signature::ObjectPath =>
 				f.write_str("o")?,
 
@@ -251,99 +332,286 @@ impl std::fmt::Display for Signature {
 	}
 }
 
+/// Compares a signature with its string form without allocating.
+impl PartialEq<str> for Signature {
+	fn eq(&self, other: &str) -> bool {
+		/// Strips each written piece from the front of the remaining string, and fails as soon as a piece doesn't match.
+		struct Comparer<'a>(&'a str);
+
+		impl std::fmt::Write for Comparer<'_> {
+			fn write_str(&mut self, s: &str) -> std::fmt::Result {
+				self.0 = self.0.strip_prefix(s).ok_or(std::fmt::Error)?;
+				Ok(())
+			}
+		}
+
+		let mut comparer = Comparer(other);
+		std::fmt::write(&mut comparer, format_args!("{}", self)).is_ok() && comparer.0.is_empty()
+	}
+}
+
+impl PartialEq<&'_ str> for Signature {
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+/// Parses a signature, validating it according to the D-Bus specification.
+///
+/// An empty string is parsed as an empty [`Signature::Tuple`], and a string of more than one complete type is parsed as a [`Signature::Tuple`]
+/// of those types. The `GVariant` maybe type `m` is not accepted, since it does not exist in D-Bus. Use [`Signature::from_gvariant_type`] to parse it.
 impl std::str::FromStr for Signature {
-	type Err = ();
+	type Err = SignatureError;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		fn from_inner(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Result<Signature, ()> {
-			match chars.next().ok_or(())? {
-				'a' => {
-					let element = from_inner(chars)?;
-					Ok(Signature::Array { element: Box::new(element) })
-				},
+		SignatureParser::parse(s, false)
+	}
+}
 
-				'b' => Ok(Signature::Bool),
+impl Signature {
+	/// Parses a `GVariant` type string.
+	///
+	/// Unlike parsing with [`std::str::FromStr`], this allows the maybe type `m`, the unit type `()` and dict entries outside arrays.
+	/// The type string is also not limited to 255 bytes.
+	pub fn from_gvariant_type(s: &str) -> Result<Self, SignatureError> {
+		SignatureParser::parse(s, true)
+	}
+}
 
-				'd' => Ok(Signature::F64),
+/// The maximum length of a signature in bytes.
+const SIGNATURE_MAX_LEN: usize = 255;
 
-				'g' => Ok(Signature::Signature),
+/// The maximum number of arrays that a type can be nested in. Maybes count as arrays.
+const SIGNATURE_MAX_ARRAY_DEPTH: usize = 32;
 
-				'h' => Ok(Signature::UnixFd),
+/// The maximum number of structs that a type can be nested in. Dict entries count as structs.
+const SIGNATURE_MAX_STRUCT_DEPTH: usize = 32;
 
-				'i' => Ok(Signature::I32),
+struct SignatureParser<'a> {
+	s: &'a str,
+	pos: usize,
+	gvariant: bool,
+	array_depth: usize,
+	struct_depth: usize,
+}
 
-				'n' => Ok(Signature::I16),
+impl SignatureParser<'_> {
+	fn parse(s: &str, gvariant: bool) -> Result<Signature, SignatureError> {
+		if !gvariant && s.len() > SIGNATURE_MAX_LEN {
+			return Err(SignatureError::TooLong { len: s.len() });
+		}
 
-				'o' => Ok(Signature::ObjectPath),
+		let mut parser = SignatureParser {
+			s,
+			pos: 0,
+			gvariant,
+			array_depth: 0,
+			struct_depth: 0,
+		};
+
+		let mut elements = vec![];
+		while parser.pos < s.len() {
+			elements.push(parser.single_complete_type(false)?);
+		}
 
-				'q' => Ok(Signature::U16),
+		if elements.len() == 1 {
+			Ok(elements.pop().expect("one element"))
+		}
+		else {
+			Ok(Signature::Tuple { elements })
+		}
+	}
+
+	/// Parses a single complete type. `in_array` is whether the type is the element type of an array, which is the only place a dict entry can be.
+	fn single_complete_type(&mut self, in_array: bool) -> Result<Signature, SignatureError> {
+		let start = self.pos;
+		let c = self.next()?;
 
-				's' => Ok(Signature::String),
+		match c {
+			'a' => {
+				let element = self.nested(start, true, |parser| parser.single_complete_type(true))?;
+				Ok(Signature::Array { element: Box::new(element) })
+			},
 
-				't' => Ok(Signature::U64),
+			'b' => Ok(Signature::Bool),
 
-				'u' => Ok(Signature::U32),
+			'd' => Ok(Signature::F64),
 
-				'v' => Ok(Signature::Variant),
+			'g' => Ok(Signature::Signature),
 
-				'x' => Ok(Signature::I64),
+			'h' => Ok(Signature::UnixFd),
 
-				'y' => Ok(Signature::U8),
+			'i' => Ok(Signature::I32),
 
-				'(' => {
-					let mut fields = vec![];
+			'm' => {
+				if !self.gvariant {
+					return Err(SignatureError::UnexpectedCharacter { c: 'm', pos: start });
+				}
+
+				let element = self.nested(start, true, |parser| parser.single_complete_type(false))?;
+				Ok(Signature::Maybe { element: Box::new(element) })
+			},
+
+			'n' => Ok(Signature::I16),
+
+			'o' => Ok(Signature::ObjectPath),
+
+			'q' => Ok(Signature::U16),
+
+			's' => Ok(Signature::String),
 
-					loop {
-						let next = chars.peek().copied();
-						if next == Some(')') {
-							let _ = chars.next();
-							break;
-						}
-						else {
-							let field = from_inner(chars)?;
-							fields.push(field);
-						}
+			't' => Ok(Signature::U64),
+
+			'u' => Ok(Signature::U32),
+
+			'v' => Ok(Signature::Variant),
+
+			'x' => Ok(Signature::I64),
+
+			'y' => Ok(Signature::U8),
+
+			'(' => {
+				let fields = self.nested(start, false, |parser| {
+					let mut fields = vec![];
+					while parser.peek() != Some(')') {
+						fields.push(parser.single_complete_type(false)?);
 					}
+					parser.pos += 1;
+					Ok(fields)
+				})?;
 
-					Ok(Signature::Struct { fields })
-				},
+				if fields.is_empty() && !self.gvariant {
+					return Err(SignatureError::EmptyStruct { pos: start });
+				}
 
-				'{' => {
-					let key = from_inner(chars)?;
+				Ok(Signature::Struct { fields })
+			},
 
-					let value = from_inner(chars)?;
+			'{' => {
+				if !in_array && !self.gvariant {
+					return Err(SignatureError::DictEntryOutsideArray { pos: start });
+				}
 
-					let next = chars.next();
-					if next != Some('}') {
-						return Err(());
+				let (key, value) = self.nested(start, false, |parser| {
+					let key_pos = parser.pos;
+					if parser.peek() == Some('}') {
+						return Err(SignatureError::DictEntryNotTwoTypes { pos: start });
+					}
+					let key = parser.single_complete_type(false)?;
+					if !key.is_basic() {
+						return Err(SignatureError::DictEntryKeyNotBasic { pos: key_pos });
 					}
 
-					Ok(Signature::DictEntry { key: Box::new(key), value: Box::new(value) })
-				},
+					if parser.peek() == Some('}') {
+						return Err(SignatureError::DictEntryNotTwoTypes { pos: start });
+					}
+					let value = parser.single_complete_type(false)?;
+
+					match parser.peek() {
+						Some('}') => {
+							parser.pos += 1;
+							Ok((key, value))
+						},
+						Some(_) => Err(SignatureError::DictEntryNotTwoTypes { pos: start }),
+						None => Err(SignatureError::UnexpectedEnd),
+					}
+				})?;
 
-				_ => Err(()),
-			}
-		}
+				Ok(Signature::DictEntry { key: Box::new(key), value: Box::new(value) })
+			},
 
-		let mut chars = s.chars().peekable();
-		if chars.peek().is_none() {
-			return Ok(Signature::Tuple { elements: vec![] });
+			c => Err(SignatureError::UnexpectedCharacter { c, pos: start }),
 		}
+	}
 
-		let first = from_inner(&mut chars)?;
-		if chars.peek().is_some() {
-			let mut elements = vec![first];
-			while chars.peek().is_some() {
-				elements.push(from_inner(&mut chars)?);
+	/// Runs `f` one level deeper in arrays or structs, failing if that exceeds the maximum depth.
+	fn nested<T>(&mut self, start: usize, array: bool, f: impl FnOnce(&mut Self) -> Result<T, SignatureError>) -> Result<T, SignatureError> {
+		let (depth, max_depth, err) =
+			if array {
+				(&mut self.array_depth, SIGNATURE_MAX_ARRAY_DEPTH, SignatureError::ArrayNestingTooDeep { pos: start })
 			}
-			Ok(Signature::Tuple { elements })
+			else {
+				(&mut self.struct_depth, SIGNATURE_MAX_STRUCT_DEPTH, SignatureError::StructNestingTooDeep { pos: start })
+			};
+		if *depth == max_depth {
+			return Err(err);
+		}
+		*depth += 1;
+
+		let result = f(self);
+
+		if array {
+			self.array_depth -= 1;
 		}
 		else {
-			Ok(first)
+			self.struct_depth -= 1;
+		}
+
+		result
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.s[self.pos..].chars().next()
+	}
+
+	fn next(&mut self) -> Result<char, SignatureError> {
+		let c = self.peek().ok_or(SignatureError::UnexpectedEnd)?;
+		self.pos += c.len_utf8();
+		Ok(c)
+	}
+}
+
+/// An error from parsing a signature.
+///
+/// Positions are byte offsets into the signature string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureError {
+	/// The type at the given position is nested in more than 32 arrays.
+	ArrayNestingTooDeep { pos: usize },
+
+	/// The key of the dict entry at the given position is not a basic type.
+	DictEntryKeyNotBasic { pos: usize },
+
+	/// The dict entry at the given position does not have exactly two types, a key and a value.
+	DictEntryNotTwoTypes { pos: usize },
+
+	/// The dict entry at the given position is not the element type of an array.
+	DictEntryOutsideArray { pos: usize },
+
+	/// The struct at the given position has no fields.
+	EmptyStruct { pos: usize },
+
+	/// The type at the given position is nested in more than 32 structs and dict entries.
+	StructNestingTooDeep { pos: usize },
+
+	/// The signature is longer than 255 bytes.
+	TooLong { len: usize },
+
+	/// The character at the given position is not a valid type code, or is a `)` or `}` that does not close a struct or dict entry.
+	UnexpectedCharacter { c: char, pos: usize },
+
+	/// The signature ended in the middle of a type, such as after an `a` or before the `)` of a struct.
+	UnexpectedEnd,
+}
+
+impl std::fmt::Display for SignatureError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SignatureError::ArrayNestingTooDeep { pos } => write!(f, "type at position {} is nested in more than {} arrays", pos, SIGNATURE_MAX_ARRAY_DEPTH),
+			SignatureError::DictEntryKeyNotBasic { pos } => write!(f, "dict entry key at position {} is not a basic type", pos),
+			SignatureError::DictEntryNotTwoTypes { pos } => write!(f, "dict entry at position {} does not have exactly two types", pos),
+			SignatureError::DictEntryOutsideArray { pos } => write!(f, "dict entry at position {} is not the element type of an array", pos),
+			SignatureError::EmptyStruct { pos } => write!(f, "struct at position {} has no fields", pos),
+			SignatureError::StructNestingTooDeep { pos } => write!(f, "type at position {} is nested in more than {} structs", pos, SIGNATURE_MAX_STRUCT_DEPTH),
+			SignatureError::TooLong { len } => write!(f, "signature is {} bytes long, which exceeds the limit of {} bytes", len, SIGNATURE_MAX_LEN),
+			SignatureError::UnexpectedCharacter { c, pos } => write!(f, "unexpected {:?} at position {}", c, pos),
+			SignatureError::UnexpectedEnd => f.write_str("unexpected end of signature"),
 		}
 	}
 }
 
+impl std::error::Error for SignatureError {}
+
 impl<'de> serde::Deserialize<'de> for Signature {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
 		struct Visitor;
@@ -371,7 +639,7 @@ impl<'de> serde::Deserialize<'de> for Signature {
 
 				let signature =
 					signature.parse()
-					.map_err(|()| serde::de::Error::invalid_value(serde::de::Unexpected::Str(&signature), &self))?;
+					.map_err(|err| serde::de::Error::custom(format_args!("invalid signature {:?}: {}", signature, err)))?;
 				Ok(signature)
 			}
 		}
@@ -417,7 +685,7 @@ impl<'de> serde::Deserialize<'de> for UnixFd {
 
 /// A trait representing a generic object on a message bus.
 pub trait Object {
-	fn name(&self) -> &str;
+	fn name(&self) -> BusName<'_>;
 	fn path(&self) -> ObjectPath<'_>;
 }
 
@@ -447,3 +715,99 @@ impl<T> serde::Serialize for Slice<'_, T> where T: serde::Serialize {
 		serializer.end()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse_signature() {
+		fn parse(s: &str) -> Result<String, super::SignatureError> {
+			s.parse::<super::Signature>().map(|signature| signature.to_string())
+		}
+
+		assert_eq!(parse(""), Ok(String::new()));
+		assert_eq!(parse("a{sv}"), Ok("a{sv}".to_owned()));
+		assert_eq!(parse("ya(ib)a{oa{sv}}h"), Ok("ya(ib)a{oa{sv}}h".to_owned()));
+		assert_eq!(parse(&("a".repeat(32) + "y")), Ok("a".repeat(32) + "y"));
+		assert_eq!(parse(&"y".repeat(255)), Ok("y".repeat(255)));
+
+		assert_eq!(parse(&"y".repeat(256)), Err(super::SignatureError::TooLong { len: 256 }));
+		assert_eq!(parse("{sv}"), Err(super::SignatureError::DictEntryOutsideArray { pos: 0 }));
+		assert_eq!(parse("a{vs}"), Err(super::SignatureError::DictEntryKeyNotBasic { pos: 2 }));
+		assert_eq!(parse("a{(i)s}"), Err(super::SignatureError::DictEntryKeyNotBasic { pos: 2 }));
+		assert_eq!(parse("a{s}"), Err(super::SignatureError::DictEntryNotTwoTypes { pos: 1 }));
+		assert_eq!(parse("a{sii}"), Err(super::SignatureError::DictEntryNotTwoTypes { pos: 1 }));
+		assert_eq!(parse("i()"), Err(super::SignatureError::EmptyStruct { pos: 1 }));
+		assert_eq!(parse("(i"), Err(super::SignatureError::UnexpectedEnd));
+		assert_eq!(parse("a"), Err(super::SignatureError::UnexpectedEnd));
+		assert_eq!(parse("i)"), Err(super::SignatureError::UnexpectedCharacter { c: ')', pos: 1 }));
+		assert_eq!(parse("iz"), Err(super::SignatureError::UnexpectedCharacter { c: 'z', pos: 1 }));
+		assert_eq!(parse("ims"), Err(super::SignatureError::UnexpectedCharacter { c: 'm', pos: 1 }));
+		assert_eq!(parse("a{sms}"), Err(super::SignatureError::UnexpectedCharacter { c: 'm', pos: 3 }));
+		assert_eq!(parse(&("a".repeat(33) + "y")), Err(super::SignatureError::ArrayNestingTooDeep { pos: 32 }));
+		assert_eq!(parse(&format!("{}y{}", "(".repeat(33), ")".repeat(33))), Err(super::SignatureError::StructNestingTooDeep { pos: 32 }));
+
+		assert_eq!(super::Signature::from_gvariant_type("()").map(|signature| signature.to_string()), Ok("()".to_owned()));
+		assert_eq!(super::Signature::from_gvariant_type("{sv}").map(|signature| signature.to_string()), Ok("{sv}".to_owned()));
+		assert_eq!(super::Signature::from_gvariant_type("ms").map(|signature| signature.to_string()), Ok("ms".to_owned()));
+		assert_eq!(super::Signature::from_gvariant_type("{vs}"), Err(super::SignatureError::DictEntryKeyNotBasic { pos: 1 }));
+		assert!(super::Signature::from_gvariant_type(&"y".repeat(256)).is_ok());
+	}
+
+	#[test]
+	fn test_signature_eq_str() {
+		/// A type whose signature can't be known at compile time
+		struct Unknown;
+
+		/// A type whose signature is 170 bytes long
+		type Long = (Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve, Twelve);
+		type Twelve = (String, String, String, String, String, String, String, String, String, String, String, String);
+
+		impl crate::AsVariant for Unknown {
+			fn signature() -> crate::Signature {
+				crate::Signature::U8
+			}
+
+			fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+				crate::Variant::U8(0)
+			}
+		}
+
+		fn check<T>() where T: crate::AsVariant + ?Sized {
+			let signature = T::signature();
+			assert_eq!(T::SIGNATURE_STR, Some(&*signature.to_string()));
+			assert_eq!(signature, T::SIGNATURE_STR.unwrap());
+		}
+
+		let signature: super::Signature = "a{sv}(ias)".parse().unwrap();
+		assert_eq!(signature, "a{sv}(ias)");
+		assert_ne!(signature, "a{sv}(ia");
+		assert_ne!(signature, "a{sv}(ias)u");
+		assert_ne!(signature, "a{sv}(iau)");
+		assert_ne!(signature, "");
+		assert_eq!(super::Signature::Tuple { elements: vec![] }, "");
+
+		check::<bool>();
+		check::<f64>();
+		check::<&str>();
+		check::<Box<crate::ObjectPath<'_>>>();
+		check::<crate::Signature>();
+		check::<crate::UnixFd>();
+		check::<crate::Variant<'_>>();
+		check::<()>();
+		check::<u64>();
+		check::<Vec<u8>>();
+		check::<[crate::ObjectPath<'_>]>();
+		check::<[(u8, String); 2]>();
+		check::<std::borrow::Cow<'_, [Vec<bool>]>>();
+		check::<(i16, (crate::Variant<'_>,), ())>();
+		check::<std::collections::HashMap<String, crate::Variant<'_>>>();
+		check::<std::collections::BTreeMap<u32, Vec<(String, u8)>>>();
+		check::<Long>();
+
+		// The signature of a container is unknown if that of an element is unknown, or if it's too long.
+		assert_eq!(<Vec<crate::Variant<'_>> as crate::AsVariant>::SIGNATURE_STR, Some("av"));
+		assert_eq!(<Vec<Unknown> as crate::AsVariant>::SIGNATURE_STR, None);
+		assert_eq!(<(u8, Unknown) as crate::AsVariant>::SIGNATURE_STR, None);
+		assert_eq!(<(Long, Long) as crate::AsVariant>::SIGNATURE_STR, None);
+	}
+}
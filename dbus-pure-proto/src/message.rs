@@ -1,5 +1,8 @@
+/// The maximum length of a message, including its header and body, in bytes.
+pub const MAX_MESSAGE_LEN: usize = 128 * 1024 * 1024;
+
 /// Message header.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MessageHeader<'a> {
 	/// The message type.
 	pub r#type: MessageType<'a>,
@@ -17,39 +20,514 @@ pub struct MessageHeader<'a> {
 	pub fields: std::borrow::Cow<'a, [MessageHeaderField<'a>]>,
 }
 
-pub fn deserialize_message(buf: &[u8]) -> Result<(MessageHeader<'static>, Option<crate::Variant<'static>>, usize), crate::DeserializeError> {
+impl MessageHeader<'_> {
+	/// Convert this header into one that owns all its data, copying any data that it borrows.
+	pub fn into_owned(self) -> MessageHeader<'static> {
+		MessageHeader {
+			r#type: self.r#type.into_owned(),
+			flags: self.flags,
+			body_len: self.body_len,
+			serial: self.serial,
+			fields: match self.fields {
+				std::borrow::Cow::Borrowed(fields) => fields.iter().map(|field| field.borrowed().into_owned()).collect::<Vec<_>>().into(),
+				std::borrow::Cow::Owned(fields) => fields.into_iter().map(MessageHeaderField::into_owned).collect::<Vec<_>>().into(),
+			},
+		}
+	}
+
+	/// A header that borrows all its data from this one.
+	pub fn borrowed(&self) -> MessageHeader<'_> {
+		MessageHeader {
+			r#type: self.r#type.borrowed(),
+			flags: self.flags,
+			body_len: self.body_len,
+			serial: self.serial,
+			fields: self.fields.iter().map(MessageHeaderField::borrowed).collect::<Vec<_>>().into(),
+		}
+	}
+
+	/// Validate the names and object paths in this header, both those of its message type and those in its header fields.
+	///
+	/// Returns the name of the first invalid header field, along with its value and the reason it's invalid.
+	fn validate(&self) -> Result<(), (&'static str, &str, crate::NameError)> {
+		fn check<'a>(field: &'static str, value: &'a str, validate: fn(&str) -> Result<(), crate::NameError>) -> Result<(), (&'static str, &'a str, crate::NameError)> {
+			validate(value).map_err(|err| (field, value, err))
+		}
+
+		match &self.r#type {
+			MessageType::Error { name, reply_serial: _ } =>
+				check("ERROR_NAME", &name.0, crate::names::validate_interface_name)?,
+
+			MessageType::MethodCall { member, path } => {
+				check("MEMBER", member, crate::names::validate_member_name)?;
+				check("PATH", &path.0, crate::names::validate_object_path)?;
+			},
+
+			MessageType::MethodReturn { reply_serial: _ } => (),
+
+			MessageType::Signal { interface, member, path } => {
+				check("INTERFACE", interface, crate::names::validate_interface_name)?;
+				check("MEMBER", member, crate::names::validate_member_name)?;
+				check("PATH", &path.0, crate::names::validate_object_path)?;
+			},
+		}
+
+		for field in &*self.fields {
+			match field {
+				MessageHeaderField::Destination(name) => check("DESTINATION", name, crate::names::validate_bus_name)?,
+				MessageHeaderField::ErrorName(name) => check("ERROR_NAME", name, crate::names::validate_interface_name)?,
+				MessageHeaderField::Interface(name) => check("INTERFACE", name, crate::names::validate_interface_name)?,
+				MessageHeaderField::Member(name) => check("MEMBER", name, crate::names::validate_member_name)?,
+				MessageHeaderField::Path(path) => check("PATH", &path.0, crate::names::validate_object_path)?,
+				MessageHeaderField::Sender(name) => check("SENDER", name, crate::names::validate_bus_name)?,
+				MessageHeaderField::ReplySerial(_) |
+				MessageHeaderField::Signature(_) |
+				MessageHeaderField::UnixFds(_) |
+				MessageHeaderField::Unknown { .. } => (),
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// A message, consisting of a header and an optional body.
+///
+/// Use the [`Message::method_call`], [`Message::signal`], [`Message::method_return`] and [`Message::error`] functions to create a message.
+#[derive(Clone, Debug)]
+pub struct Message<'a> {
+	/// The message header.
+	///
+	/// Header fields corresponding to the required properties of the message type, and the `MessageHeaderField::Signature` field,
+	/// are not stored in `header.fields`. They are generated automatically when the message is serialized.
+	///
+	/// The exception is a message whose body was deserialized separately, such as with [`deserialize_message_with_body`].
+	/// Its `body` is `None`, so it keeps the `MessageHeaderField::Signature` field of the body.
+	pub header: MessageHeader<'a>,
+
+	/// The message body.
+	///
+	/// If the message has more than one parameter, the body is a `Variant::Tuple` of the parameters.
+	pub body: Option<crate::Variant<'a>>,
+}
+
+impl<'a> Message<'a> {
+	/// Create a `METHOD_CALL` message to call the given method on the given object at the given destination.
+	pub fn method_call(
+		destination: crate::BusName<'a>,
+		path: crate::ObjectPath<'a>,
+		interface: crate::InterfaceName<'a>,
+		member: crate::MemberName<'a>,
+	) -> Self {
+		Message::new(
+			MessageType::MethodCall {
+				member: member.into(),
+				path,
+			},
+			vec![
+				MessageHeaderField::Destination(destination.into()),
+				MessageHeaderField::Interface(interface.into()),
+			],
+		)
+	}
+
+	/// Create a `SIGNAL` message to emit the given signal from the given object.
+	pub fn signal(
+		path: crate::ObjectPath<'a>,
+		interface: crate::InterfaceName<'a>,
+		member: crate::MemberName<'a>,
+	) -> Self {
+		Message::new(
+			MessageType::Signal {
+				interface: interface.into(),
+				member: member.into(),
+				path,
+			},
+			vec![],
+		)
+	}
+
+	/// Create a `METHOD_RETURN` message in reply to the given `METHOD_CALL` message.
+	///
+	/// The reply is addressed to the sender of the method call, if it has one.
+	pub fn method_return(method_call: &Message<'_>) -> Self {
+		Message::new(
+			MessageType::MethodReturn {
+				reply_serial: method_call.header.serial,
+			},
+			reply_fields(method_call),
+		)
+	}
+
+	/// Create an `ERROR` message with the given error name in reply to the given `METHOD_CALL` message.
+	///
+	/// The reply is addressed to the sender of the method call, if it has one.
+	pub fn error(method_call: &Message<'_>, name: crate::ErrorName<'a>) -> Self {
+		Message::new(
+			MessageType::Error {
+				name,
+				reply_serial: method_call.header.serial,
+			},
+			reply_fields(method_call),
+		)
+	}
+
+	fn new(r#type: MessageType<'a>, fields: Vec<MessageHeaderField<'a>>) -> Self {
+		Message {
+			header: MessageHeader {
+				r#type,
+				flags: flags::NONE,
+				body_len: 0,
+				serial: 0,
+				fields: fields.into(),
+			},
+			body: None,
+		}
+	}
+
+	/// Set the body of this message.
+	///
+	/// If the message has more than one parameter, the body should be a `Variant::Tuple` of the parameters.
+	#[must_use]
+	pub fn with_body(mut self, body: crate::Variant<'a>) -> Self {
+		self.body = Some(body);
+		self
+	}
+
+	/// Set the flags of this message.
+	#[must_use]
+	pub fn with_flags(mut self, flags: MessageFlags) -> Self {
+		self.header.flags = flags;
+		self
+	}
+
+	/// Add the given header field to this message.
+	///
+	/// This must not be a header field that is generated automatically, ie one corresponding to the required properties of the message type,
+	/// or the `MessageHeaderField::Signature` field.
+	#[must_use]
+	pub fn with_field(mut self, field: MessageHeaderField<'a>) -> Self {
+		self.header.fields.to_mut().push(field);
+		self
+	}
+
+	/// Convert this message into one that owns all its data, copying any data that it borrows.
+	///
+	/// This is useful to keep a message that was deserialized with [`deserialize_message_borrowed`] after its buffer is reused.
+	pub fn into_owned(self) -> Message<'static> {
+		Message {
+			header: self.header.into_owned(),
+			body: self.body.map(crate::Variant::into_owned),
+		}
+	}
+
+	/// A message that borrows all its data from this one.
+	///
+	/// See [`crate::Variant::borrowed`] for details.
+	pub fn borrowed(&self) -> Message<'_> {
+		Message {
+			header: self.header.borrowed(),
+			body: self.body.as_ref().map(crate::Variant::borrowed),
+		}
+	}
+
+	/// The value of the `MessageHeaderField::Sender` header field, if any.
+	pub fn sender(&self) -> Option<&str> {
+		self.header.fields.iter().find_map(|field| match field {
+			MessageHeaderField::Sender(sender) => Some(&**sender),
+			_ => None,
+		})
+	}
+
+	/// The value of the `MessageHeaderField::Destination` header field, if any.
+	pub fn destination(&self) -> Option<&str> {
+		self.header.fields.iter().find_map(|field| match field {
+			MessageHeaderField::Destination(destination) => Some(&**destination),
+			_ => None,
+		})
+	}
+
+	/// The interface of the method call or signal, if any.
+	pub fn interface(&self) -> Option<&str> {
+		match &self.header.r#type {
+			MessageType::Signal { interface, .. } => Some(interface),
+			_ => self.header.fields.iter().find_map(|field| match field {
+				MessageHeaderField::Interface(interface) => Some(&**interface),
+				_ => None,
+			}),
+		}
+	}
+
+	/// The member name of the method call or signal, if this message is one.
+	pub fn member(&self) -> Option<&str> {
+		match &self.header.r#type {
+			MessageType::MethodCall { member, .. } |
+			MessageType::Signal { member, .. } => Some(member),
+			MessageType::Error { .. } |
+			MessageType::MethodReturn { .. } => None,
+		}
+	}
+
+	/// The object path of the method call or signal, if this message is one.
+	pub fn path(&self) -> Option<&crate::ObjectPath<'a>> {
+		match &self.header.r#type {
+			MessageType::MethodCall { path, .. } |
+			MessageType::Signal { path, .. } => Some(path),
+			MessageType::Error { .. } |
+			MessageType::MethodReturn { .. } => None,
+		}
+	}
+
+	/// The error name of the error, if this message is one.
+	pub fn error_name(&self) -> Option<&crate::ErrorName<'a>> {
+		match &self.header.r#type {
+			MessageType::Error { name, .. } => Some(name),
+			_ => None,
+		}
+	}
+
+	/// The serial of the message that this message is a reply to, if this message is a reply.
+	pub fn reply_serial(&self) -> Option<u32> {
+		match &self.header.r#type {
+			MessageType::Error { reply_serial, .. } |
+			MessageType::MethodReturn { reply_serial } => Some(*reply_serial),
+			MessageType::MethodCall { .. } |
+			MessageType::Signal { .. } => None,
+		}
+	}
+
+	/// The signature of the message body. This is an empty `Signature::Tuple` if the message does not have a body.
+	///
+	/// If the body was deserialized separately, such as with [`deserialize_message_with_body`], this is the signature of that body.
+	pub fn signature(&self) -> crate::Signature {
+		match &self.body {
+			Some(body) => body.inner_signature(),
+			None =>
+				self.header.fields.iter().find_map(|field| match field {
+					MessageHeaderField::Signature(signature) => Some(signature.clone()),
+					_ => None,
+				})
+				.unwrap_or(crate::Signature::Tuple { elements: vec![] }),
+		}
+	}
+
+	/// The number of unix file descriptors that accompany this message, from the `MessageHeaderField::UnixFds` header field.
+	pub fn unix_fds(&self) -> u32 {
+		self.header.fields.iter().find_map(|field| match field {
+			MessageHeaderField::UnixFds(num_unix_fds) => Some(*num_unix_fds),
+			_ => None,
+		})
+		.unwrap_or_default()
+	}
+}
+
+fn reply_fields<'a>(method_call: &Message<'_>) -> Vec<MessageHeaderField<'a>> {
+	method_call.sender()
+		.map(|sender| MessageHeaderField::Destination(sender.to_owned().into()))
+		.into_iter()
+		.collect()
+}
+
+/// Deserialize a message from the given buffer.
+///
+/// Returns the message and the number of bytes of the buffer that it used.
+///
+/// Fails with `DeserializeError::InvalidHeaderField` if any name or object path in the header is invalid.
+///
+/// Fails with `DeserializeError::MessageTooLong`, `DeserializeError::ArrayTooLong` or `DeserializeError::NestingTooDeep` if the message
+/// exceeds [`MAX_MESSAGE_LEN`], [`crate::MAX_ARRAY_LEN`] or [`crate::MAX_DEPTH`] respectively, so it's safe to deserialize messages from untrusted peers.
+pub fn deserialize_message(buf: &[u8]) -> Result<(Message<'static>, usize), crate::DeserializeError> {
+	let (message_header, message_body, read) = deserialize_message_inner(buf, |deserializer, signature| {
+		let deserialize_seed = crate::VariantDeserializeSeed::new(signature);
+		let message_body: crate::Variant<'static> = serde::de::DeserializeSeed::deserialize(deserialize_seed, deserializer)?;
+		Ok(message_body)
+	})?;
+
+	Ok((Message { header: message_header.into_owned(), body: message_body }, read))
+}
+
+/// Deserialize a message from the given buffer, borrowing its data from the buffer.
+///
+/// This is the same as [`deserialize_message`], except that the strings, object paths and byte arrays of the header and body
+/// borrow from the buffer instead of being copied out of it. Use [`Message::into_owned`] to detach the message from the buffer.
+///
+/// # Example
+///
+/// ```rust
+/// let message =
+///     dbus_pure_proto::Message::signal(
+///         dbus_pure_proto::ObjectPath::new("/org/example/Foo")?,
+///         dbus_pure_proto::InterfaceName::new("org.example.Foo")?,
+///         dbus_pure_proto::MemberName::new("Changed")?,
+///     )
+///     .with_body(dbus_pure_proto::Variant::ArrayU8((&b"contents"[..]).into()));
+///
+/// let mut buf = vec![];
+/// dbus_pure_proto::serialize_message(&message, &mut buf, dbus_pure_proto::Endianness::Little)?;
+///
+/// let (message, read) = dbus_pure_proto::deserialize_message_borrowed(&buf)?;
+/// assert!(matches!(message.body, Some(dbus_pure_proto::Variant::ArrayU8(std::borrow::Cow::Borrowed(b"contents")))));
+/// assert_eq!(read, buf.len());
+///
+/// let message: dbus_pure_proto::Message<'static> = message.into_owned();
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn deserialize_message_borrowed(buf: &[u8]) -> Result<(Message<'_>, usize), crate::DeserializeError> {
+	let (message_header, message_body, read) = deserialize_message_inner(buf, |deserializer, signature| {
+		let deserialize_seed = crate::BorrowedVariantDeserializeSeed::new(signature);
+		let message_body = serde::de::DeserializeSeed::deserialize(deserialize_seed, deserializer)?;
+		Ok(message_body)
+	})?;
+
+	Ok((Message { header: message_header, body: message_body }, read))
+}
+
+/// Deserialize a message from the given buffer, deserializing its body directly into a value of type `T`.
+///
+/// This is faster than [`deserialize_message`] followed by deserializing `T` from the body `Variant`, since the body is not converted to a `Variant` first.
+/// The body is deserialized the same way that it would be from the body `Variant`, so `T` can be any type that `serde::Deserialize` can be
+/// deserialized from a `Variant` of the message's signature. A message without a body is deserialized as `()`.
+///
+/// Returns the message, the body, and the number of bytes of the buffer that the message used. The `body` of the returned message is always `None`,
+/// but its header keeps the `MessageHeaderField::Signature` field, so [`Message::signature`] is still the signature of the body.
+///
+/// Fails with `DeserializeError::InvalidHeaderField` if any name or object path in the header is invalid,
+/// and with the same errors as [`deserialize_message`] if the message exceeds the limits of the D-Bus specification.
+///
+/// # Example
+///
+/// ```rust
+/// let message =
+///     dbus_pure_proto::Message::signal(
+///         dbus_pure_proto::ObjectPath::new("/org/example/Foo")?,
+///         dbus_pure_proto::InterfaceName::new("org.example.Foo")?,
+///         dbus_pure_proto::MemberName::new("Changed")?,
+///     )
+///     .with_body(dbus_pure_proto::Variant::Tuple {
+///         elements: vec![
+///             dbus_pure_proto::Variant::String("bar".into()),
+///             dbus_pure_proto::Variant::U32(5),
+///         ].into(),
+///     });
+///
+/// let mut buf = vec![];
+/// dbus_pure_proto::serialize_message(&message, &mut buf, dbus_pure_proto::Endianness::Little)?;
+///
+/// let (message, (name, value), read): (_, (&str, u32), _) = dbus_pure_proto::deserialize_message_with_body(&buf)?;
+/// assert_eq!(message.member(), Some("Changed"));
+/// assert_eq!((name, value), ("bar", 5));
+/// assert_eq!(read, buf.len());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn deserialize_message_with_body<'de, T>(buf: &'de [u8]) -> Result<(Message<'static>, T, usize), crate::DeserializeError> where T: serde::Deserialize<'de> {
+	let (mut message_header, message_body, read) = deserialize_message_inner(buf, |deserializer, signature| {
+		let body_deserializer = crate::de::SignatureDeserializer::new(deserializer, signature);
+		let message_body: T = serde::Deserialize::deserialize(body_deserializer)?;
+		Ok((message_body, signature.clone()))
+	})?;
+
+	let message_body = match message_body {
+		Some((message_body, signature)) => {
+			// The message does not have the body to imply its signature, so keep the signature in the header fields.
+			message_header.fields.to_mut().push(MessageHeaderField::Signature(signature));
+			message_body
+		},
+		None => serde::Deserialize::deserialize(serde::de::IntoDeserializer::<crate::DeserializeError>::into_deserializer(()))?,
+	};
+
+	Ok((Message { header: message_header.into_owned(), body: None }, message_body, read))
+}
+
+/// Get the length of the message at the start of the given buffer, without deserializing it.
+///
+/// The length is computed from the fixed-size part of the message header, so only the first 16 bytes of the message need to be in the buffer.
+/// This can be used to find out how many bytes of a message still need to be read before it can be deserialized.
+///
+/// Fails with `DeserializeError::EndOfInput` if the buffer is too short to contain the fixed-size part of the header.
+/// Fails with `DeserializeError::ArrayTooLong` or `DeserializeError::MessageTooLong` if the lengths in the header exceed
+/// [`crate::MAX_ARRAY_LEN`] or [`MAX_MESSAGE_LEN`], so the caller can reject the message before reading the rest of it.
+pub fn message_len(buf: &[u8]) -> Result<usize, crate::DeserializeError> {
+	// Arbitrarily pick `Endianness::Little` to initialize the deserializer. It'll be overridden as soon as the endianness marker is parsed.
+	let mut deserializer = crate::de::Deserializer::new(buf, 0, crate::Endianness::Little);
+
+	let EndiannessMarker(endianness) = serde::Deserialize::deserialize(&mut deserializer)?;
+	deserializer.set_endianness(endianness);
+
+	// Message type, flags and protocol version
+	for _ in 0..3 {
+		let _: u8 = serde::Deserialize::deserialize(&mut deserializer)?;
+	}
+
+	let body_len: u32 = serde::Deserialize::deserialize(&mut deserializer)?;
+	let body_len: usize = std::convert::TryInto::try_into(body_len).map_err(crate::DeserializeError::ExceedsNumericLimits)?;
+
+	let _serial: u32 = serde::Deserialize::deserialize(&mut deserializer)?;
+
+	let fields_len: u32 = serde::Deserialize::deserialize(&mut deserializer)?;
+	let fields_len: usize = std::convert::TryInto::try_into(fields_len).map_err(crate::DeserializeError::ExceedsNumericLimits)?;
+	if fields_len > crate::MAX_ARRAY_LEN {
+		return Err(crate::DeserializeError::ArrayTooLong { len: fields_len });
+	}
+
+	// The header is padded to 8 bytes, and the header fields array has no padding before its first element since its elements are 8-byte aligned structs
+	// that start at offset 16.
+	let header_len = ((deserializer.pos() + fields_len + 7) / 8) * 8;
+
+	let len = header_len.saturating_add(body_len);
+	if len > MAX_MESSAGE_LEN {
+		return Err(crate::DeserializeError::MessageTooLong { len });
+	}
+
+	Ok(len)
+}
+
+/// Deserializes a message header from the given buffer, and its body with the given function if the message has one.
+fn deserialize_message_inner<'de, T>(
+	buf: &'de [u8],
+	deserialize_body: impl FnOnce(&mut crate::de::Deserializer<'de>, &crate::Signature) -> Result<T, crate::DeserializeError>,
+) -> Result<(MessageHeader<'de>, Option<T>, usize), crate::DeserializeError> {
 	// Arbitrarily pick `Endianness::Little` to initialize the deserializer. It'll be overridden as soon as the endianness marker is parsed.
 	let mut deserializer = crate::de::Deserializer::new(buf, 0, crate::Endianness::Little);
 
 	let EndiannessMarker(endianness) = serde::Deserialize::deserialize(&mut deserializer)?;
 	deserializer.set_endianness(endianness);
 
-	let message_header: MessageHeader<'static> = serde::Deserialize::deserialize(&mut deserializer)?;
+	let BorrowedMessageHeader(mut message_header) = serde::Deserialize::deserialize(&mut deserializer)?;
+
+	let () =
+		message_header.validate()
+		.map_err(|(field, value, err)| crate::DeserializeError::InvalidHeaderField { field, value: value.to_owned(), err })?;
 
 	deserializer.pad_to(8)?;
 
+	// The signature is implied by the body, so remove it from the header fields.
+	let signature = {
+		let fields = message_header.fields.to_mut();
+		let signature_index = fields.iter().position(|field| matches!(field, MessageHeaderField::Signature(_)));
+		match signature_index.map(|signature_index| fields.remove(signature_index)) {
+			Some(MessageHeaderField::Signature(signature)) => Some(signature),
+			_ => None,
+		}
+	};
+
 	let (message_body, read) =
 		if message_header.body_len > 0 {
 			let body_start_pos = deserializer.pos();
 			let body_len = message_header.body_len;
-			let body_end_pos = body_start_pos + body_len;
+			let body_end_pos = body_start_pos.saturating_add(body_len);
 
+			if body_end_pos > MAX_MESSAGE_LEN {
+				return Err(crate::DeserializeError::MessageTooLong { len: body_end_pos });
+			}
 			if buf.len() < body_end_pos {
 				return Err(crate::DeserializeError::EndOfInput);
 			}
 
-			let signature =
-				message_header.fields.iter()
-				.find_map(|message_header_field| match message_header_field {
-					MessageHeaderField::Signature(signature) => Some(signature),
-					_ => None,
-				})
-				.ok_or_else(|| serde::de::Error::custom("message has non-empty body but not signature field in its header"))?;
-			let deserialize_seed = crate::VariantDeserializeSeed::new(signature);
+			let signature = signature.ok_or_else(|| serde::de::Error::custom("message has non-empty body but not signature field in its header"))?;
 
 			let mut deserializer = crate::de::Deserializer::new(&buf[..body_end_pos], body_start_pos, endianness);
 
-			let message_body: crate::Variant<'static> = serde::de::DeserializeSeed::deserialize(deserialize_seed, &mut deserializer)?;
+			let message_body = deserialize_body(&mut deserializer, &signature)?;
 
 			(Some(message_body), body_end_pos)
 		}
@@ -60,89 +538,167 @@ pub fn deserialize_message(buf: &[u8]) -> Result<(MessageHeader<'static>, Option
 	Ok((message_header, message_body, read))
 }
 
+/// Serialize the given message into the given buffer.
+///
+/// Header fields corresponding to the required properties of the message type, and the `MessageHeaderField::Signature` field
+/// if the message has a body, are generated automatically. The `body_len` of the header is ignored and computed from the body.
+///
+/// Fails with `SerializeError::InvalidHeaderField` if any name or object path in the header is invalid.
 pub fn serialize_message(
-	header: &mut MessageHeader<'_>,
-	body: Option<&crate::Variant<'_>>,
+	message: &Message<'_>,
+	buf: &mut Vec<u8>,
+	endianness: crate::Endianness,
+) -> Result<(), crate::SerializeError> {
+	let Message { header, body } = message;
+
+	let body = body.as_ref().map(|body| (body.inner_signature(), move |serializer: &mut crate::ser::Serializer<'_>| serde::Serialize::serialize(body, serializer)));
+
+	serialize_message_inner(header, body, buf, endianness)
+}
+
+/// Serialize the given message into the given buffer, with the given value as its body.
+///
+/// This is faster than converting the value to a `Variant` and using [`serialize_message`], since the value is serialized directly into the buffer.
+/// The value is serialized the same way as [`crate::to_variant`] would convert it to a `Variant` of signature `T::signature()`.
+/// If `T::signature()` is an empty `Signature::Tuple`, such as for `()`, the message does not have a body.
+///
+/// The `body` of the message is ignored. Otherwise this behaves the same as [`serialize_message`].
+///
+/// # Example
+///
+/// ```rust
+/// let message =
+///     dbus_pure_proto::Message::signal(
+///         dbus_pure_proto::ObjectPath::new("/org/example/Foo")?,
+///         dbus_pure_proto::InterfaceName::new("org.example.Foo")?,
+///         dbus_pure_proto::MemberName::new("Changed")?,
+///     );
+///
+/// let mut buf = vec![];
+/// dbus_pure_proto::serialize_message_with_body(&message, &["bar", "baz"][..], &mut buf, dbus_pure_proto::Endianness::Little)?;
+///
+/// let (message, _) = dbus_pure_proto::deserialize_message(&buf)?;
+/// assert_eq!(message.signature().to_string(), "as");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn serialize_message_with_body<T>(
+	message: &Message<'_>,
+	body: &T,
+	buf: &mut Vec<u8>,
+	endianness: crate::Endianness,
+) -> Result<(), crate::SerializeError> where T: serde::Serialize + crate::AsVariant + ?Sized {
+	let signature = T::signature();
+
+	let body =
+		if matches!(&signature, crate::Signature::Tuple { elements } if elements.is_empty()) {
+			None
+		}
+		else {
+			Some((signature.clone(), |serializer: &mut crate::ser::Serializer<'_>| crate::ser::serialize_with_signature(body, &signature, serializer)))
+		};
+
+	serialize_message_inner(&message.header, body, buf, endianness)
+}
+
+/// Serializes a message with the given header into the given buffer, and its body of the given signature with the given function if it has one.
+///
+/// The body is serialized directly into the buffer after the header, and the header's `body_len` is filled in afterwards.
+/// If serialization fails, the buffer is truncated to its original length.
+fn serialize_message_inner(
+	header: &MessageHeader<'_>,
+	body: Option<(crate::Signature, impl FnOnce(&mut crate::ser::Serializer<'_>) -> Result<(), crate::SerializeError>)>,
 	buf: &mut Vec<u8>,
 	endianness: crate::Endianness,
 ) -> Result<(), crate::SerializeError> {
 	use serde::Serialize;
 
-	let header_fields = header.fields.to_mut();
+	let () =
+		header.validate()
+		.map_err(|(field, value, err)| crate::SerializeError::InvalidHeaderField { field, value: value.to_owned(), err })?;
+
+	let mut generated_fields = vec![];
 
-	match &mut header.r#type {
+	match &header.r#type {
 		MessageType::Error { name, reply_serial } => {
-			header_fields.push(MessageHeaderField::ErrorName(std::mem::take(name)));
-			header_fields.push(MessageHeaderField::ReplySerial(*reply_serial));
+			generated_fields.push(MessageHeaderField::ErrorName((&*name.0).into()));
+			generated_fields.push(MessageHeaderField::ReplySerial(*reply_serial));
 		},
 
 		MessageType::MethodCall { member, path } => {
-			header_fields.push(MessageHeaderField::Member(std::mem::take(member)));
-			header_fields.push(MessageHeaderField::Path(std::mem::take(path)));
+			generated_fields.push(MessageHeaderField::Member((&**member).into()));
+			generated_fields.push(MessageHeaderField::Path(path.borrowed()));
 		},
 
 		MessageType::MethodReturn { reply_serial } => {
-			header_fields.push(MessageHeaderField::ReplySerial(*reply_serial));
+			generated_fields.push(MessageHeaderField::ReplySerial(*reply_serial));
 		},
 
 		MessageType::Signal { interface, member, path } => {
-			header_fields.push(MessageHeaderField::Interface(std::mem::take(interface)));
-			header_fields.push(MessageHeaderField::Member(std::mem::take(member)));
-			header_fields.push(MessageHeaderField::Path(std::mem::take(path)));
+			generated_fields.push(MessageHeaderField::Interface((&**interface).into()));
+			generated_fields.push(MessageHeaderField::Member((&**member).into()));
+			generated_fields.push(MessageHeaderField::Path(path.borrowed()));
 		},
 	}
 
-	let body =
-		if let Some(body) = body {
-			let mut body_serialized = vec![];
-			let mut body_serializer = crate::ser::Serializer::new(&mut body_serialized, endianness);
-			body.serialize(&mut body_serializer)?;
-			drop(body_serializer);
-
-			let body_len = body_serialized.len();
-
-			let body_signature = body.inner_signature();
-
-			Some((body_serialized, body_len, body_signature))
-		}
-		else {
-			None
-		};
+	let serialize_body = body.map(|(signature, serialize_body)| {
+		generated_fields.push(MessageHeaderField::Signature(signature));
+		serialize_body
+	});
 
-	if let Some((body_serialized, body_len, body_signature)) = body {
-		header.body_len = body_len;
-
-		header_fields.push(MessageHeaderField::Signature(body_signature));
+	let message_start_pos = buf.len();
 
+	let result = (|| {
 		let mut message_serializer = crate::ser::Serializer::new(buf, endianness);
 
 		EndiannessMarker(endianness).serialize(&mut message_serializer)?;
 
-		header.serialize(&mut message_serializer)?;
+		// The body length is filled in after the body has been serialized.
+		SerializableMessageHeader {
+			header,
+			body_len: 0,
+			generated_fields: &generated_fields,
+			skip_signature_field: true,
+		}.serialize(&mut message_serializer)?;
 
 		message_serializer.pad_to(8);
 
-		buf.extend_from_slice(&body_serialized);
-	}
-	else {
-		let mut message_serializer = crate::ser::Serializer::new(buf, endianness);
+		if let Some(serialize_body) = serialize_body {
+			let body_start_pos = buf.len();
 
-		EndiannessMarker(endianness).serialize(&mut message_serializer)?;
+			let mut body_serializer = crate::ser::Serializer::new(buf, endianness);
+			let () = serialize_body(&mut body_serializer)?;
 
-		header.serialize(&mut message_serializer)?;
+			let body_len: u32 = std::convert::TryInto::try_into(buf.len() - body_start_pos).map_err(crate::SerializeError::ExceedsNumericLimits)?;
+			let body_len_pos = message_start_pos + 4;
+			buf[body_len_pos..(body_len_pos + 4)].copy_from_slice(&endianness.u32_to_bytes(body_len));
+		}
 
-		message_serializer.pad_to(8);
+		Ok(())
+	})();
+
+	if result.is_err() {
+		buf.truncate(message_start_pos);
 	}
 
-	Ok(())
+	result
 }
 
 impl<'de> serde::Deserialize<'de> for MessageHeader<'static> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		let BorrowedMessageHeader(header) = serde::Deserialize::deserialize(deserializer)?;
+		Ok(header.into_owned())
+	}
+}
+
+/// A message header that borrows its data from the deserializer's input where possible.
+struct BorrowedMessageHeader<'de>(MessageHeader<'de>);
+
+impl<'de> serde::Deserialize<'de> for BorrowedMessageHeader<'de> {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
 		struct Visitor;
 
 		impl<'de> serde::de::Visitor<'de> for Visitor {
-			type Value = MessageHeader<'static>;
+			type Value = BorrowedMessageHeader<'de>;
 
 			fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 				formatter.write_str("message header")
@@ -163,19 +719,20 @@ impl<'de> serde::Deserialize<'de> for MessageHeader<'static> {
 
 				let serial: u32 = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("serial"))?;
 
-				let fields: Vec<MessageHeaderField<'static>> =
+				let fields: Vec<BorrowedMessageHeaderField<'de>> =
 					seq.next_element_seed(crate::std2::VecDeserializeSeed::new(8))?
 					.ok_or_else(|| serde::de::Error::missing_field("fields"))?;
+				let fields = fields.into_iter().map(|BorrowedMessageHeaderField(field)| field).collect();
 
 				let (r#type, fields) = MessageType::from::<A>(r#type, fields)?;
 
-				Ok(MessageHeader {
+				Ok(BorrowedMessageHeader(MessageHeader {
 					r#type,
 					flags,
 					body_len,
 					serial,
 					fields: fields.into(),
-				})
+				}))
 			}
 		}
 
@@ -184,25 +741,66 @@ impl<'de> serde::Deserialize<'de> for MessageHeader<'static> {
 }
 
 impl serde::Serialize for MessageHeader<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		SerializableMessageHeader {
+			header: self,
+			body_len: self.body_len,
+			generated_fields: &[],
+			skip_signature_field: false,
+		}.serialize(serializer)
+	}
+}
+
+/// A message header with the given body length, and the given header fields appended to its own.
+///
+/// This allows serializing a header along with the fields generated from its message type and body, without mutating the header.
+/// Since the signature field is generated from the body, the header's own signature field, if any, can be skipped.
+struct SerializableMessageHeader<'h, 'a, 'b> {
+	header: &'h MessageHeader<'a>,
+	body_len: usize,
+	generated_fields: &'h [MessageHeaderField<'b>],
+	skip_signature_field: bool,
+}
+
+impl serde::Serialize for SerializableMessageHeader<'_, '_, '_> {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
 		use serde::ser::SerializeTuple;
 
 		let mut serializer = serializer.serialize_tuple(6)?;
 
-		serializer.serialize_element(&self.r#type)?;
+		serializer.serialize_element(&self.header.r#type)?;
 
-		serializer.serialize_element(&self.flags)?;
+		serializer.serialize_element(&self.header.flags)?;
 
 		serializer.serialize_element(&0x01_u8)?;
 
 		serializer.serialize_element(&crate::UsizeAsU32(self.body_len))?;
 
-		serializer.serialize_element(&self.serial)?;
+		serializer.serialize_element(&self.header.serial)?;
 
-		serializer.serialize_element(&crate::Slice {
-			inner: &self.fields,
-			alignment: 1,
-		})?;
+		serializer.serialize_element(&SerializableMessageHeaderFields(self))?;
+
+		serializer.end()
+	}
+}
+
+struct SerializableMessageHeaderFields<'s, 'h, 'a, 'b>(&'s SerializableMessageHeader<'h, 'a, 'b>);
+
+impl serde::Serialize for SerializableMessageHeaderFields<'_, '_, '_, '_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		use serde::ser::SerializeSeq;
+
+		let mut serializer = serializer.serialize_seq(Some(1))?;
+
+		for field in &*self.0.header.fields {
+			if !(self.0.skip_signature_field && matches!(field, MessageHeaderField::Signature(_))) {
+				serializer.serialize_element(field)?;
+			}
+		}
+
+		for field in self.0.generated_fields {
+			serializer.serialize_element(field)?;
+		}
 
 		serializer.end()
 	}
@@ -212,7 +810,7 @@ impl serde::Serialize for MessageHeader<'_> {
 #[derive(Clone, Debug)]
 pub enum MessageType<'a> {
 	Error {
-		name: std::borrow::Cow<'a, str>,
+		name: crate::ErrorName<'a>,
 		reply_serial: u32,
 	},
 
@@ -232,11 +830,39 @@ pub enum MessageType<'a> {
 	},
 }
 
-impl MessageType<'static> {
+impl<'a> MessageType<'a> {
+	/// Convert this message type into one that owns all its data, copying any data that it borrows.
+	pub fn into_owned(self) -> MessageType<'static> {
+		match self {
+			MessageType::Error { name, reply_serial } => MessageType::Error { name: name.into_owned(), reply_serial },
+			MessageType::MethodCall { member, path } => MessageType::MethodCall { member: member.into_owned().into(), path: path.into_owned() },
+			MessageType::MethodReturn { reply_serial } => MessageType::MethodReturn { reply_serial },
+			MessageType::Signal { interface, member, path } => MessageType::Signal {
+				interface: interface.into_owned().into(),
+				member: member.into_owned().into(),
+				path: path.into_owned(),
+			},
+		}
+	}
+
+	/// A message type that borrows all its data from this one.
+	pub fn borrowed(&self) -> MessageType<'_> {
+		match self {
+			MessageType::Error { name, reply_serial } => MessageType::Error { name: name.borrowed(), reply_serial: *reply_serial },
+			MessageType::MethodCall { member, path } => MessageType::MethodCall { member: (&**member).into(), path: path.borrowed() },
+			MessageType::MethodReturn { reply_serial } => MessageType::MethodReturn { reply_serial: *reply_serial },
+			MessageType::Signal { interface, member, path } => MessageType::Signal {
+				interface: (&**interface).into(),
+				member: (&**member).into(),
+				path: path.borrowed(),
+			},
+		}
+	}
+
 	fn from<'de, A>(
 		r#type: u8,
-		fields: Vec<MessageHeaderField<'static>>,
-	) -> Result<(Self, Vec<MessageHeaderField<'static>>), A::Error> where A: serde::de::SeqAccess<'de> {
+		fields: Vec<MessageHeaderField<'a>>,
+	) -> Result<(Self, Vec<MessageHeaderField<'a>>), A::Error> where A: serde::de::SeqAccess<'de> {
 		// TODO: Use `Vec::drain_filter` when that stabilizes to mutate `fields` in place
 
 		let mut other_fields = vec![];
@@ -312,7 +938,7 @@ impl MessageType<'static> {
 				let name = error_name_field.ok_or_else(|| serde::de::Error::custom("ERROR message does not have NAME header field"))?;
 				let reply_serial = reply_serial_field.ok_or_else(|| serde::de::Error::custom("ERROR message does not have REPLY_SERIAL header field"))?;
 				MessageType::Error {
-					name,
+					name: crate::ErrorName(name),
 					reply_serial,
 				}
 			},
@@ -421,12 +1047,56 @@ pub enum MessageHeaderField<'a> {
 	},
 }
 
+impl MessageHeaderField<'_> {
+	/// Convert this header field into one that owns all its data, copying any data that it borrows.
+	pub fn into_owned(self) -> MessageHeaderField<'static> {
+		match self {
+			MessageHeaderField::Destination(name) => MessageHeaderField::Destination(name.into_owned().into()),
+			MessageHeaderField::ErrorName(name) => MessageHeaderField::ErrorName(name.into_owned().into()),
+			MessageHeaderField::Interface(name) => MessageHeaderField::Interface(name.into_owned().into()),
+			MessageHeaderField::Member(name) => MessageHeaderField::Member(name.into_owned().into()),
+			MessageHeaderField::Path(path) => MessageHeaderField::Path(path.into_owned()),
+			MessageHeaderField::ReplySerial(reply_serial) => MessageHeaderField::ReplySerial(reply_serial),
+			MessageHeaderField::Sender(name) => MessageHeaderField::Sender(name.into_owned().into()),
+			MessageHeaderField::Signature(signature) => MessageHeaderField::Signature(signature),
+			MessageHeaderField::UnixFds(num_unix_fds) => MessageHeaderField::UnixFds(num_unix_fds),
+			MessageHeaderField::Unknown { code, value } => MessageHeaderField::Unknown { code, value: value.into_owned() },
+		}
+	}
+
+	/// A header field that borrows all its data from this one.
+	pub fn borrowed(&self) -> MessageHeaderField<'_> {
+		match self {
+			MessageHeaderField::Destination(name) => MessageHeaderField::Destination((&**name).into()),
+			MessageHeaderField::ErrorName(name) => MessageHeaderField::ErrorName((&**name).into()),
+			MessageHeaderField::Interface(name) => MessageHeaderField::Interface((&**name).into()),
+			MessageHeaderField::Member(name) => MessageHeaderField::Member((&**name).into()),
+			MessageHeaderField::Path(path) => MessageHeaderField::Path(path.borrowed()),
+			MessageHeaderField::ReplySerial(reply_serial) => MessageHeaderField::ReplySerial(*reply_serial),
+			MessageHeaderField::Sender(name) => MessageHeaderField::Sender((&**name).into()),
+			MessageHeaderField::Signature(signature) => MessageHeaderField::Signature(signature.clone()),
+			MessageHeaderField::UnixFds(num_unix_fds) => MessageHeaderField::UnixFds(*num_unix_fds),
+			MessageHeaderField::Unknown { code, value } => MessageHeaderField::Unknown { code: *code, value: value.borrowed() },
+		}
+	}
+}
+
 impl<'de> serde::Deserialize<'de> for MessageHeaderField<'static> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		let BorrowedMessageHeaderField(field) = serde::Deserialize::deserialize(deserializer)?;
+		Ok(field.into_owned())
+	}
+}
+
+/// A message header field that borrows its data from the deserializer's input where possible.
+struct BorrowedMessageHeaderField<'de>(MessageHeaderField<'de>);
+
+impl<'de> serde::Deserialize<'de> for BorrowedMessageHeaderField<'de> {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
 		struct Visitor;
 
 		impl<'de> serde::de::Visitor<'de> for Visitor {
-			type Value = MessageHeaderField<'static>;
+			type Value = BorrowedMessageHeaderField<'de>;
 
 			fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 				formatter.write_str("message header field")
@@ -440,76 +1110,76 @@ impl<'de> serde::Deserialize<'de> for MessageHeaderField<'static> {
 				let code: u8 = map.next_key()?.ok_or_else(|| serde::de::Error::missing_field("code"))?;
 
 				let signature: crate::Signature = map.next_value()?;
-				let seed = crate::VariantDeserializeSeed::new(&signature);
-				let value: crate::Variant<'static> = map.next_value_seed(seed)?;
+				let seed = crate::BorrowedVariantDeserializeSeed::new(&signature);
+				let value: crate::Variant<'de> = map.next_value_seed(seed)?;
 
 				#[allow(clippy::match_same_arms)]
 				match (code, value) {
 					(0x01, crate::Variant::ObjectPath(object_path)) =>
-						Ok(MessageHeaderField::Path(object_path)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Path(object_path))),
 					(0x01, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"object path"))
 					},
 
 					(0x02, crate::Variant::String(name)) =>
-						Ok(MessageHeaderField::Interface(name)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Interface(name))),
 					(0x02, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"string"))
 					},
 
 					(0x03, crate::Variant::String(name)) =>
-						Ok(MessageHeaderField::Member(name)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Member(name))),
 					(0x03, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"string"))
 					},
 
 					(0x04, crate::Variant::String(name)) =>
-						Ok(MessageHeaderField::ErrorName(name)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::ErrorName(name))),
 					(0x04, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"string"))
 					},
 
 					(0x05, crate::Variant::U32(serial)) =>
-						Ok(MessageHeaderField::ReplySerial(serial)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::ReplySerial(serial))),
 					(0x05, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"serial"))
 					},
 
 					(0x06, crate::Variant::String(name)) =>
-						Ok(MessageHeaderField::Destination(name)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Destination(name))),
 					(0x06, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"string"))
 					},
 
 					(0x07, crate::Variant::String(name)) =>
-						Ok(MessageHeaderField::Sender(name)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Sender(name))),
 					(0x07, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"string"))
 					},
 
 					(0x08, crate::Variant::Signature(signature)) =>
-						Ok(MessageHeaderField::Signature(signature)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Signature(signature))),
 					(0x08, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"signature"))
 					},
 
 					(0x09, crate::Variant::U32(num_unix_fds)) =>
-						Ok(MessageHeaderField::UnixFds(num_unix_fds)),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::UnixFds(num_unix_fds))),
 					(0x09, value) => {
 						let unexpected = format!("{:?}", value);
 						Err(serde::de::Error::invalid_value(serde::de::Unexpected::Other(&unexpected), &"u32"))
 					},
 
 					(code, value) =>
-						Ok(MessageHeaderField::Unknown { code, value }),
+						Ok(BorrowedMessageHeaderField(MessageHeaderField::Unknown { code, value })),
 				}
 			}
 		}
@@ -590,3 +1260,324 @@ impl serde::Serialize for EndiannessMarker {
 		endianness_marker.serialize(serializer)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	fn method_call(member: &str) -> super::Message<'_> {
+		super::Message::method_call(
+			crate::BusName::new("org.example.Foo").unwrap(),
+			crate::ObjectPath::new("/org/example/Foo").unwrap(),
+			crate::InterfaceName::new("org.example.Foo").unwrap(),
+			crate::MemberName::new(member).unwrap(),
+		)
+	}
+
+	fn signal<'a>() -> super::Message<'a> {
+		super::Message::signal(
+			crate::ObjectPath::new("/org/example/Foo").unwrap(),
+			crate::InterfaceName::new("org.example.Foo").unwrap(),
+			crate::MemberName::new("Changed").unwrap(),
+		)
+	}
+
+	fn deserialize_owned<T>(deserializer: &mut crate::de::Deserializer<'_>) -> T where T: serde::de::DeserializeOwned {
+		serde::Deserialize::deserialize(deserializer).unwrap()
+	}
+
+	#[test]
+	fn test_message_roundtrip() {
+		let message =
+			method_call("Bar")
+			.with_field(super::MessageHeaderField::Sender(":1.5".into()))
+			.with_body(crate::Variant::Tuple {
+				elements: vec![
+					crate::Variant::String("baz".into()),
+					crate::Variant::U32(5),
+				].into(),
+			});
+
+		let mut serialized = vec![];
+		super::serialize_message(&message, &mut serialized, crate::Endianness::Little).unwrap();
+
+		let (deserialized, read) = super::deserialize_message(&serialized).unwrap();
+		assert_eq!(read, serialized.len());
+
+		assert_eq!(deserialized.sender(), Some(":1.5"));
+		assert_eq!(deserialized.destination(), Some("org.example.Foo"));
+		assert_eq!(deserialized.interface(), Some("org.example.Foo"));
+		assert_eq!(deserialized.member(), Some("Bar"));
+		assert_eq!(deserialized.path().map(|path| &*path.0), Some("/org/example/Foo"));
+		assert_eq!(deserialized.signature().to_string(), "su");
+		assert_eq!(deserialized.unix_fds(), 0);
+		assert_eq!(deserialized.body, message.body);
+
+		// The header fields generated during serialization are not duplicated when the deserialized message is serialized again.
+		let mut reserialized = vec![];
+		super::serialize_message(&deserialized, &mut reserialized, crate::Endianness::Little).unwrap();
+		assert_eq!(serialized, reserialized);
+
+		let reply = super::Message::error(&deserialized, crate::ErrorName::new("org.example.Foo.Error.Failed").unwrap());
+		assert_eq!(reply.destination(), Some(":1.5"));
+		assert_eq!(reply.error_name().map(crate::ErrorName::as_str), Some("org.example.Foo.Error.Failed"));
+		assert_eq!(reply.reply_serial(), Some(0));
+	}
+
+	#[test]
+	fn test_message_borrowed() {
+		let message =
+			method_call("Bar")
+			.with_body(crate::Variant::Tuple {
+				elements: vec![
+					crate::Variant::String("baz".into()),
+					crate::Variant::ArrayU8((&b"contents"[..]).into()),
+					crate::Variant::ArrayString(vec!["a".into(), "bc".into()].into()),
+				].into(),
+			});
+
+		let mut serialized = vec![];
+		super::serialize_message(&message, &mut serialized, crate::Endianness::Big).unwrap();
+
+		assert_eq!(super::message_len(&serialized[..16]).unwrap(), serialized.len());
+		assert!(matches!(super::message_len(&serialized[..15]), Err(crate::DeserializeError::EndOfInput)));
+
+		let (deserialized, read) = super::deserialize_message_borrowed(&serialized).unwrap();
+		assert_eq!(read, serialized.len());
+		assert_eq!(deserialized.clone().into_owned().body, message.body);
+		assert_eq!(deserialized.borrowed().into_owned().body, message.body);
+		assert_eq!(message.borrowed().member(), Some("Bar"));
+
+		assert!(matches!(&deserialized.header.r#type, super::MessageType::MethodCall { member: std::borrow::Cow::Borrowed("Bar"), .. }));
+		let elements = match &deserialized.body {
+			Some(crate::Variant::Tuple { elements }) => elements,
+			body => panic!("{:?}", body),
+		};
+		assert!(matches!(&elements[0], crate::Variant::String(std::borrow::Cow::Borrowed("baz"))));
+		assert!(matches!(&elements[1], crate::Variant::ArrayU8(std::borrow::Cow::Borrowed(b"contents"))));
+		assert!(matches!(&elements[2], crate::Variant::ArrayString(elements) if matches!(&elements[1], std::borrow::Cow::Borrowed("bc"))));
+
+		// The owned header can still be deserialized by anything that requires `DeserializeOwned`.
+		let mut deserializer = crate::de::Deserializer::new(&serialized, 0, crate::Endianness::Big);
+		let super::EndiannessMarker(_) = deserialize_owned(&mut deserializer);
+		let header: super::MessageHeader<'static> = deserialize_owned(&mut deserializer);
+		assert!(matches!(&header.r#type, super::MessageType::MethodCall { member: std::borrow::Cow::Owned(member), .. } if member == "Bar"));
+
+		let deserialized: super::Message<'static> = deserialized.into_owned();
+		drop(serialized);
+		assert_eq!(deserialized.member(), Some("Bar"));
+		assert_eq!(deserialized.body, message.body);
+	}
+
+	#[test]
+	fn test_message_with_body() {
+		#[derive(Debug, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]
+		struct Item {
+			name: String,
+			id: u32,
+			tags: Vec<String>,
+			attrs: std::collections::BTreeMap<String, u64>,
+		}
+
+		impl crate::AsVariant for Item {
+			fn signature() -> crate::Signature {
+				"(suasa{st})".parse().unwrap()
+			}
+
+			fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+				crate::to_variant(self, &Self::signature()).unwrap()
+			}
+		}
+
+		#[derive(serde_derive::Serialize)]
+		struct U32AsU8(u32);
+
+		impl crate::AsVariant for U32AsU8 {
+			fn signature() -> crate::Signature {
+				crate::Signature::U8
+			}
+
+			fn as_variant<'a>(&'a self) -> crate::Variant<'a> {
+				unreachable!()
+			}
+		}
+
+		#[derive(Debug, PartialEq, serde_derive::Deserialize)]
+		enum Status {
+			Playing,
+			Paused(u32),
+		}
+
+		#[derive(Debug, PartialEq, serde_derive::Deserialize)]
+		struct Properties {
+			#[serde(rename = "Name")]
+			name: Option<String>,
+			#[serde(rename = "Volume")]
+			volume: Option<f64>,
+		}
+
+		let item = Item {
+			name: "foo".to_owned(),
+			id: 5,
+			tags: vec!["a".to_owned(), "bc".to_owned()],
+			attrs: vec![("x".to_owned(), 1), ("yz".to_owned(), 2)].into_iter().collect(),
+		};
+
+		let message = signal();
+
+		// The body serialized directly is identical to the body serialized via a `Variant`.
+		let mut serialized = vec![0xFF];
+		super::serialize_message_with_body(&message, &item, &mut serialized, crate::Endianness::Big).unwrap();
+		let serialized = &serialized[1..];
+
+		let mut expected = vec![];
+		super::serialize_message(&message.clone().with_body(crate::AsVariant::as_variant(&item)), &mut expected, crate::Endianness::Big).unwrap();
+		assert_eq!(serialized, &expected[..]);
+
+		let (deserialized, deserialized_item, read): (_, Item, _) = super::deserialize_message_with_body(serialized).unwrap();
+		assert_eq!(read, serialized.len());
+		assert_eq!(deserialized.member(), Some("Changed"));
+		assert!(deserialized.body.is_none());
+		assert_eq!(deserialized.signature().to_string(), "(suasa{st})");
+		assert_eq!(deserialized_item, item);
+
+		// The signature is kept in the header fields but not serialized twice.
+		let mut reserialized = vec![];
+		super::serialize_message_with_body(&deserialized, &deserialized_item, &mut reserialized, crate::Endianness::Big).unwrap();
+		assert_eq!(serialized, &reserialized[..]);
+
+		// A body that does not match the type fails to deserialize.
+		assert!(super::deserialize_message_with_body::<(u32, String)>(serialized).is_err());
+
+		// Variants in the body are deserialized with their wire signature.
+		let properties: std::collections::BTreeMap<String, crate::Variant<'static>> = vec![
+			("Name".to_owned(), crate::Variant::String("foo".into())),
+			("Ids".to_owned(), crate::Variant::ArrayU32(vec![1, 2].into())),
+		].into_iter().collect();
+		let mut serialized = vec![];
+		super::serialize_message(&message.clone().with_body(crate::AsVariant::as_variant(&properties)), &mut serialized, crate::Endianness::Little).unwrap();
+		let (_, deserialized_properties, _): (_, std::collections::BTreeMap<String, crate::Variant<'static>>, _) =
+			super::deserialize_message_with_body(&serialized).unwrap();
+		assert_eq!(deserialized_properties.len(), 2);
+		assert_eq!(deserialized_properties["Name"], properties["Name"]);
+		assert_eq!(deserialized_properties["Ids"].to_string(), properties["Ids"].to_string());
+
+		// Structs with `Option` fields are deserialized from `a{sv}` dictionaries.
+		let (_, deserialized_properties, _): (_, Properties, _) = super::deserialize_message_with_body(&serialized).unwrap();
+		assert_eq!(deserialized_properties, Properties { name: Some("foo".to_owned()), volume: None });
+
+		// Enums are deserialized from the name of the variant, or a struct of the name and a variant of its fields.
+		let body = crate::Variant::Tuple {
+			elements: vec![
+				crate::Variant::String("Playing".into()),
+				crate::Variant::Struct {
+					fields: vec![
+						crate::Variant::String("Paused".into()),
+						crate::Variant::Variant(Box::new(crate::Variant::U32(5)).into()),
+					].into(),
+				},
+			].into(),
+		};
+		let mut serialized = vec![];
+		super::serialize_message(&message.clone().with_body(body), &mut serialized, crate::Endianness::Little).unwrap();
+		let (_, statuses, _): (_, (Status, Status), _) = super::deserialize_message_with_body(&serialized).unwrap();
+		assert_eq!(statuses, (Status::Playing, Status::Paused(5)));
+
+		// A unit body is not serialized.
+		let mut serialized = vec![];
+		super::serialize_message_with_body(&message, &(), &mut serialized, crate::Endianness::Little).unwrap();
+		let (deserialized, read) = super::deserialize_message(&serialized).unwrap();
+		assert_eq!(read, serialized.len());
+		assert!(deserialized.body.is_none());
+		let (deserialized, (), _) = super::deserialize_message_with_body(&serialized).unwrap();
+		assert_eq!(deserialized.signature().to_string(), "");
+
+		// A body that does not match its signature is not serialized.
+		let mut serialized = vec![];
+		let err = super::serialize_message_with_body(&message, &U32AsU8(256), &mut serialized, crate::Endianness::Little).unwrap_err();
+		assert!(matches!(err, crate::SerializeError::Convert(crate::ToVariantError::ExceedsNumericLimits { .. })), "{:?}", err);
+		assert!(serialized.is_empty());
+	}
+
+	#[test]
+	fn test_message_invalid_header_field() {
+		let mut message = method_call("Bar");
+		message.header.r#type = super::MessageType::MethodCall { member: "Ba.r".into(), path: crate::ObjectPath::new("/org/example/Foo").unwrap() };
+
+		let mut serialized = vec![];
+		let err = super::serialize_message(&message, &mut serialized, crate::Endianness::Little).unwrap_err();
+		assert!(matches!(
+			err,
+			crate::SerializeError::InvalidHeaderField { field: "MEMBER", value, err: crate::NameError::InvalidCharacter { c: '.', pos: 2 } } if value == "Ba.r"
+		));
+
+		// Serialize a valid message and corrupt its member name, to check that the deserializer also validates it.
+		let message = method_call("Barr");
+
+		let mut serialized = vec![];
+		super::serialize_message(&message, &mut serialized, crate::Endianness::Little).unwrap();
+
+		let member_pos = serialized.windows(4).position(|window| window == b"Barr").unwrap();
+		serialized[member_pos + 2] = b'.';
+
+		let err = super::deserialize_message(&serialized).unwrap_err();
+		assert!(matches!(
+			err,
+			crate::DeserializeError::InvalidHeaderField { field: "MEMBER", value, err: crate::NameError::InvalidCharacter { c: '.', pos: 2 } } if value == "Ba.r"
+		));
+
+		// Corrupt the body signature so that the dict entry has a non-basic key.
+		let message = message.with_body(crate::Variant::Array {
+			element_signature: crate::Signature::DictEntry { key: Box::new(crate::Signature::String), value: Box::new(crate::Signature::Variant) },
+			elements: vec![].into(),
+		});
+
+		let mut serialized = vec![];
+		super::serialize_message(&message, &mut serialized, crate::Endianness::Little).unwrap();
+
+		let signature_pos = serialized.windows(5).position(|window| window == b"a{sv}").unwrap();
+		serialized[signature_pos + 2] = b'v';
+
+		let err = super::deserialize_message(&serialized).unwrap_err();
+		assert_eq!(err.to_string(), r#"invalid signature "a{vv}": dict entry key at position 2 is not a basic type"#);
+	}
+
+	#[test]
+	fn test_message_limits() {
+		fn nested_variant(depth: usize) -> crate::Variant<'static> {
+			(0..depth).fold(crate::Variant::U32(5), |value, _| crate::Variant::Variant(Box::new(value).into()))
+		}
+
+		let message = signal();
+
+		// Values nested in up to `MAX_DEPTH` containers are accepted.
+		let mut serialized = vec![];
+		super::serialize_message(&message.clone().with_body(nested_variant(crate::MAX_DEPTH)), &mut serialized, crate::Endianness::Little).unwrap();
+		let _ = super::deserialize_message(&serialized).unwrap();
+		let _: (_, crate::Variant<'_>, _) = super::deserialize_message_with_body(&serialized).unwrap();
+
+		let mut serialized = vec![];
+		super::serialize_message(&message.clone().with_body(nested_variant(crate::MAX_DEPTH + 1)), &mut serialized, crate::Endianness::Little).unwrap();
+		let err = super::deserialize_message(&serialized).unwrap_err();
+		assert!(matches!(err, crate::DeserializeError::NestingTooDeep));
+		let err = super::deserialize_message_with_body::<crate::Variant<'_>>(&serialized).unwrap_err();
+		assert!(matches!(err, crate::DeserializeError::NestingTooDeep));
+
+		// Corrupt the length of an empty array in the body.
+		let mut serialized = vec![];
+		super::serialize_message(
+			&message.clone().with_body(crate::Variant::ArrayU8((&[][..]).into())),
+			&mut serialized,
+			crate::Endianness::Little,
+		).unwrap();
+		let array_len_pos = serialized.len() - 4;
+		serialized[array_len_pos..].copy_from_slice(&(64 * 1024 * 1024 + 1_u32).to_le_bytes());
+		let err = super::deserialize_message(&serialized).unwrap_err();
+		assert!(matches!(err, crate::DeserializeError::ArrayTooLong { len } if len == 64 * 1024 * 1024 + 1));
+
+		// Corrupt the body length in the header.
+		serialized[4..8].copy_from_slice(&(128 * 1024 * 1024_u32).to_le_bytes());
+		let err = super::message_len(&serialized).unwrap_err();
+		assert!(matches!(err, crate::DeserializeError::MessageTooLong { .. }));
+		let err = super::deserialize_message(&serialized).unwrap_err();
+		assert!(matches!(err, crate::DeserializeError::MessageTooLong { .. }));
+	}
+}
@@ -0,0 +1,422 @@
+/// The maximum length of a bus, interface, member or error name, in bytes.
+pub const MAX_NAME_LEN: usize = 255;
+
+macro_rules! name_types {
+	($($(#[$meta:meta])* $name:ident => $validate:path,)*) => {
+		$(
+			$(#[$meta])*
+			#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+			pub struct $name<'a>(pub(crate) std::borrow::Cow<'a, str>);
+
+			impl<'a> $name<'a> {
+				/// Validate the given string.
+				pub fn new(name: impl Into<std::borrow::Cow<'a, str>>) -> Result<Self, NameError> {
+					let name = name.into();
+					let () = $validate(&name)?;
+					Ok($name(name))
+				}
+
+				/// The string.
+				pub fn as_str(&self) -> &str {
+					&self.0
+				}
+
+				/// A value that borrows its string from this one.
+				pub fn borrowed(&self) -> $name<'_> {
+					$name((&*self.0).into())
+				}
+
+				/// Convert this into its inner string.
+				pub fn into_inner(self) -> std::borrow::Cow<'a, str> {
+					self.0
+				}
+
+				/// Convert this into one that owns its string.
+				pub fn into_owned(self) -> $name<'static> {
+					$name(self.0.into_owned().into())
+				}
+			}
+
+			impl AsRef<str> for $name<'_> {
+				fn as_ref(&self) -> &str {
+					&self.0
+				}
+			}
+
+			impl std::ops::Deref for $name<'_> {
+				type Target = str;
+
+				fn deref(&self) -> &Self::Target {
+					&self.0
+				}
+			}
+
+			impl std::fmt::Display for $name<'_> {
+				fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+					f.write_str(&self.0)
+				}
+			}
+
+			impl<'a> From<$name<'a>> for std::borrow::Cow<'a, str> {
+				fn from(name: $name<'a>) -> Self {
+					name.0
+				}
+			}
+
+			impl<'a> std::convert::TryFrom<&'a str> for $name<'a> {
+				type Error = NameError;
+
+				fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+					$name::new(name)
+				}
+			}
+
+			impl std::convert::TryFrom<String> for $name<'static> {
+				type Error = NameError;
+
+				fn try_from(name: String) -> Result<Self, Self::Error> {
+					$name::new(name)
+				}
+			}
+		)*
+	};
+}
+
+name_types! {
+	/// A bus name, either a unique name like `:1.42` or a well-known name like `org.freedesktop.DBus`.
+	///
+	/// Bus names consist of two or more elements separated by `.`, where each element contains only `[A-Za-z0-9_-]`.
+	/// Unique names start with `:`. The elements of well-known names must not start with a digit.
+	BusName => validate_bus_name,
+
+	/// An error name, such as `org.freedesktop.DBus.Error.Failed`.
+	///
+	/// Error names have the same format as interface names.
+	ErrorName => validate_interface_name,
+
+	/// An interface name, such as `org.freedesktop.DBus.Properties`.
+	///
+	/// Interface names consist of two or more elements separated by `.`, where each element contains only `[A-Za-z0-9_]`
+	/// and does not start with a digit.
+	InterfaceName => validate_interface_name,
+
+	/// A member name, ie the name of a method or signal, such as `GetNameOwner`.
+	///
+	/// Member names contain only `[A-Za-z0-9_]` and do not start with a digit.
+	MemberName => validate_member_name,
+
+	/// An object path, such as `/org/freedesktop/DBus`.
+	///
+	/// Object paths start with `/` and consist of zero or more elements separated by `/`, where each element contains only `[A-Za-z0-9_]`.
+	/// Only the root path `/` ends with `/`.
+	ObjectPath => validate_object_path,
+}
+
+impl BusName<'_> {
+	/// Returns true if this is a unique name, ie a name that starts with `:`, as opposed to a well-known name.
+	pub fn is_unique(&self) -> bool {
+		self.0.starts_with(':')
+	}
+}
+
+impl ObjectPath<'_> {
+	/// The parent of this path, or `None` if this is the root path `/`.
+	pub fn parent(&self) -> Option<ObjectPath<'_>> {
+		match self.0.rfind('/') {
+			_ if self.0 == "/" => None,
+			Some(0) => Some(ObjectPath("/".into())),
+			Some(pos) => Some(ObjectPath(self.0[..pos].into())),
+			None => None,
+		}
+	}
+
+	/// Append the given relative path to this path.
+	///
+	/// `child` can be a single element, or multiple elements separated by `/`. Elements can be escaped with [`ObjectPath::escape_element`].
+	pub fn join(&self, child: &str) -> Result<ObjectPath<'static>, NameError> {
+		let path =
+			if self.0 == "/" {
+				format!("/{}", child)
+			}
+			else {
+				format!("{}/{}", self.0, child)
+			};
+		ObjectPath::new(path)
+	}
+
+	/// The elements of this path. This is empty for the root path `/`.
+	pub fn components(&self) -> impl Iterator<Item = &str> + '_ {
+		self.0.split('/').filter(|element| !element.is_empty())
+	}
+
+	/// Returns true if this path is equal to `namespace`, or is a descendant of it.
+	///
+	/// This is the same as the `path_namespace` match rule. Every path is in the namespace of the root path `/`.
+	pub fn starts_with_namespace(&self, namespace: &ObjectPath<'_>) -> bool {
+		namespace.0 == "/" ||
+		(self.0.starts_with(&*namespace.0) && (self.0.len() == namespace.0.len() || self.0[namespace.0.len()..].starts_with('/')))
+	}
+
+	/// Escape the given string into a valid path element. The original string can be recovered with [`ObjectPath::unescape_element`].
+	///
+	/// This uses the same scheme as systemd's `bus_label_escape`. Every byte other than `[A-Za-z0-9]`, and a digit at the start,
+	/// is written as `_` followed by two lowercase hex digits. The empty string is written as `_`.
+	pub fn escape_element(s: &str) -> String {
+		if s.is_empty() {
+			return "_".to_owned();
+		}
+
+		let mut result = String::with_capacity(s.len());
+		for (i, b) in s.bytes().enumerate() {
+			if b.is_ascii_alphabetic() || (i > 0 && b.is_ascii_digit()) {
+				result.push(b.into());
+			}
+			else {
+				const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+				result.push('_');
+				result.push(HEX_DIGITS[usize::from(b >> 4)].into());
+				result.push(HEX_DIGITS[usize::from(b & 0x0f)].into());
+			}
+		}
+		result
+	}
+
+	/// Unescape a path element that was escaped with [`ObjectPath::escape_element`].
+	///
+	/// Returns `None` if the element contains an invalid escape sequence, or if the unescaped string is not valid UTF-8.
+	pub fn unescape_element(element: &str) -> Option<String> {
+		if element == "_" {
+			return Some(String::new());
+		}
+
+		let mut result = Vec::with_capacity(element.len());
+		let mut bytes = element.bytes();
+		while let Some(b) = bytes.next() {
+			if b == b'_' {
+				let hi = char::from(bytes.next()?).to_digit(16)?;
+				let lo = char::from(bytes.next()?).to_digit(16)?;
+				#[allow(clippy::cast_possible_truncation)]
+				result.push((hi * 16 + lo) as u8);
+			}
+			else {
+				result.push(b);
+			}
+		}
+
+		String::from_utf8(result).ok()
+	}
+}
+
+impl serde::Serialize for ObjectPath<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		self.0.serialize(serializer)
+	}
+}
+
+impl<'de, 'a> serde::Deserialize<'de> for ObjectPath<'a> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		let path: std::borrow::Cow<'a, str> = serde::de::Deserialize::deserialize(deserializer)?;
+		ObjectPath::new(path).map_err(serde::de::Error::custom)
+	}
+}
+
+/// An error from validating a name or object path.
+///
+/// Positions are byte offsets into the name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NameError {
+	ElementStartsWithDigit { pos: usize },
+	Empty,
+	EmptyElement { pos: usize },
+	InvalidCharacter { c: char, pos: usize },
+	MissingLeadingSlash,
+	TooFewElements,
+	TooLong { len: usize },
+	TrailingSlash,
+}
+
+impl std::fmt::Display for NameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			NameError::ElementStartsWithDigit { pos } => write!(f, "element at position {} starts with a digit", pos),
+			NameError::Empty => f.write_str("name is empty"),
+			NameError::EmptyElement { pos } => write!(f, "element at position {} is empty", pos),
+			NameError::InvalidCharacter { c, pos } => write!(f, "invalid character {:?} at position {}", c, pos),
+			NameError::MissingLeadingSlash => f.write_str("object path does not start with '/'"),
+			NameError::TooFewElements => f.write_str("name does not have at least two elements"),
+			NameError::TooLong { len } => write!(f, "name is {} bytes long, which exceeds the maximum of {} bytes", len, MAX_NAME_LEN),
+			NameError::TrailingSlash => f.write_str("object path ends with '/'"),
+		}
+	}
+}
+
+impl std::error::Error for NameError {
+}
+
+pub(crate) fn validate_bus_name(name: &str) -> Result<(), NameError> {
+	let () = validate_len(name)?;
+
+	let (elements, offset, is_unique) = match name.strip_prefix(':') {
+		Some(elements) => (elements, 1, true),
+		None => (name, 0, false),
+	};
+
+	validate_elements(elements, offset, |c| is_element_char(c) || c == '-', is_unique)
+}
+
+pub(crate) fn validate_interface_name(name: &str) -> Result<(), NameError> {
+	let () = validate_len(name)?;
+	validate_elements(name, 0, is_element_char, false)
+}
+
+pub(crate) fn validate_member_name(name: &str) -> Result<(), NameError> {
+	let () = validate_len(name)?;
+	validate_element(name, 0, is_element_char, false)
+}
+
+pub(crate) fn validate_object_path(path: &str) -> Result<(), NameError> {
+	let elements = path.strip_prefix('/').ok_or(NameError::MissingLeadingSlash)?;
+	if elements.is_empty() {
+		return Ok(());
+	}
+
+	if elements.ends_with('/') {
+		return Err(NameError::TrailingSlash);
+	}
+
+	let mut pos = 1;
+	for element in elements.split('/') {
+		let () = validate_element(element, pos, is_element_char, true)?;
+		pos += element.len() + 1;
+	}
+
+	Ok(())
+}
+
+fn validate_len(name: &str) -> Result<(), NameError> {
+	if name.is_empty() {
+		Err(NameError::Empty)
+	}
+	else if name.len() > MAX_NAME_LEN {
+		Err(NameError::TooLong { len: name.len() })
+	}
+	else {
+		Ok(())
+	}
+}
+
+/// Validates `.`-separated elements, of which there must be at least two.
+fn validate_elements(elements: &str, mut pos: usize, is_valid_char: impl Fn(char) -> bool, allow_leading_digit: bool) -> Result<(), NameError> {
+	let mut num_elements = 0;
+
+	for element in elements.split('.') {
+		let () = validate_element(element, pos, &is_valid_char, allow_leading_digit)?;
+		pos += element.len() + 1;
+		num_elements += 1;
+	}
+
+	if num_elements < 2 {
+		return Err(NameError::TooFewElements);
+	}
+
+	Ok(())
+}
+
+fn validate_element(element: &str, pos: usize, is_valid_char: impl Fn(char) -> bool, allow_leading_digit: bool) -> Result<(), NameError> {
+	if element.is_empty() {
+		return Err(NameError::EmptyElement { pos });
+	}
+
+	if let Some((i, c)) = element.char_indices().find(|&(_, c)| !is_valid_char(c)) {
+		return Err(NameError::InvalidCharacter { c, pos: pos + i });
+	}
+
+	if !allow_leading_digit && element.starts_with(|c: char| c.is_ascii_digit()) {
+		return Err(NameError::ElementStartsWithDigit { pos });
+	}
+
+	Ok(())
+}
+
+fn is_element_char(c: char) -> bool {
+	c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_validate_names() {
+		assert_eq!(super::validate_bus_name("org.freedesktop.DBus"), Ok(()));
+		assert_eq!(super::validate_bus_name(":1.42"), Ok(()));
+		assert_eq!(super::validate_bus_name("org.example.foo-bar"), Ok(()));
+		assert_eq!(super::validate_bus_name(""), Err(super::NameError::Empty));
+		assert_eq!(super::validate_bus_name("org"), Err(super::NameError::TooFewElements));
+		assert_eq!(super::validate_bus_name("org..bar"), Err(super::NameError::EmptyElement { pos: 4 }));
+		assert_eq!(super::validate_bus_name("org.1example"), Err(super::NameError::ElementStartsWithDigit { pos: 4 }));
+		assert_eq!(super::validate_bus_name(":1"), Err(super::NameError::TooFewElements));
+		assert_eq!(super::validate_bus_name(&"a.".repeat(128)), Err(super::NameError::TooLong { len: 256 }));
+
+		assert_eq!(super::validate_interface_name("org.freedesktop.DBus.Properties"), Ok(()));
+		assert_eq!(super::validate_interface_name("org.example.foo-bar"), Err(super::NameError::InvalidCharacter { c: '-', pos: 15 }));
+		assert_eq!(super::validate_interface_name(".org.example"), Err(super::NameError::EmptyElement { pos: 0 }));
+
+		assert_eq!(super::validate_member_name("GetNameOwner"), Ok(()));
+		assert_eq!(super::validate_member_name("Get.NameOwner"), Err(super::NameError::InvalidCharacter { c: '.', pos: 3 }));
+		assert_eq!(super::validate_member_name("1Get"), Err(super::NameError::ElementStartsWithDigit { pos: 0 }));
+
+		assert_eq!(super::validate_object_path("/"), Ok(()));
+		assert_eq!(super::validate_object_path("/org/freedesktop/DBus"), Ok(()));
+		assert_eq!(super::validate_object_path("/org/1_example"), Ok(()));
+		assert_eq!(super::validate_object_path(""), Err(super::NameError::MissingLeadingSlash));
+		assert_eq!(super::validate_object_path("foo"), Err(super::NameError::MissingLeadingSlash));
+		assert_eq!(super::validate_object_path("/foo/"), Err(super::NameError::TrailingSlash));
+		assert_eq!(super::validate_object_path("/foo//bar"), Err(super::NameError::EmptyElement { pos: 5 }));
+		assert_eq!(super::validate_object_path("/foo/bar.baz"), Err(super::NameError::InvalidCharacter { c: '.', pos: 8 }));
+	}
+
+	#[test]
+	fn test_object_path() {
+		let root = super::ObjectPath::new("/").unwrap();
+		let path = super::ObjectPath::new("/org/example/Foo").unwrap();
+
+		assert_eq!(root.parent(), None);
+		assert_eq!(path.parent(), Some(super::ObjectPath::new("/org/example").unwrap()));
+		assert_eq!(super::ObjectPath::new("/org").unwrap().parent(), Some(root.clone()));
+
+		assert_eq!(root.join("org"), Ok(super::ObjectPath::new("/org").unwrap()));
+		assert_eq!(path.join("Bar/Baz"), Ok(super::ObjectPath::new("/org/example/Foo/Bar/Baz").unwrap()));
+		assert_eq!(path.join("Bar/"), Err(super::NameError::TrailingSlash));
+		assert_eq!(path.join("Bar.Baz"), Err(super::NameError::InvalidCharacter { c: '.', pos: 20 }));
+
+		assert_eq!(root.components().count(), 0);
+		assert_eq!(path.components().collect::<Vec<_>>(), ["org", "example", "Foo"]);
+
+		assert!(path.starts_with_namespace(&root));
+		assert!(path.starts_with_namespace(&super::ObjectPath::new("/org/example").unwrap()));
+		assert!(path.starts_with_namespace(&path));
+		assert!(!path.starts_with_namespace(&super::ObjectPath::new("/org/ex").unwrap()));
+		assert!(!root.starts_with_namespace(&path));
+
+		for (unescaped, escaped) in &[
+			("", "_"),
+			("foo", "foo"),
+			("foo_bar", "foo_5fbar"),
+			("1foo2", "_31foo2"),
+			("sshd.service", "sshd_2eservice"),
+			("caf\u{e9}", "caf_c3_a9"),
+		] {
+			assert_eq!(super::ObjectPath::escape_element(unescaped), *escaped);
+			assert_eq!(super::ObjectPath::unescape_element(escaped).as_deref(), Some(*unescaped));
+			assert_eq!(super::validate_object_path(&format!("/{}", escaped)), Ok(()));
+		}
+
+		assert_eq!(super::ObjectPath::unescape_element("foo_5"), None);
+		assert_eq!(super::ObjectPath::unescape_element("foo_zz"), None);
+		assert_eq!(super::ObjectPath::unescape_element("_ff"), None);
+
+		let deserialized: super::ObjectPath<'_> = serde::Deserialize::deserialize(crate::Variant::String("/org/example".into())).unwrap();
+		assert_eq!(deserialized.as_str(), "/org/example");
+		let err = <super::ObjectPath<'_> as serde::Deserialize<'_>>::deserialize(crate::Variant::String("org/example".into())).unwrap_err();
+		assert_eq!(err.to_string(), "object path does not start with '/'");
+	}
+}
@@ -9,9 +9,9 @@ impl<'ser> Serializer<'ser> {
 	pub(crate) fn new(buf: &'ser mut Vec<u8>, endianness: crate::Endianness) -> Self {
 		let start = buf.len();
 		Serializer {
-			endianness,
 			buf,
 			start,
+			endianness,
 		}
 	}
 
@@ -21,6 +21,36 @@ impl<'ser> Serializer<'ser> {
 		let new_len = self.start + new_pos;
 		self.buf.resize(new_len, 0);
 	}
+
+	/// Serializes the length of an array as a placeholder, followed by the padding before its first element.
+	///
+	/// The returned value must be passed to [`Serializer::end_array`] after the elements have been serialized, to fill in the length.
+	fn begin_array(&mut self, alignment: usize) -> Result<ArrayStart, SerializeError> {
+		serde::Serialize::serialize(&0_u32, &mut *self)?;
+		let data_len_pos = self.buf.len() - 4;
+
+		self.pad_to(alignment);
+
+		let data_start_pos = self.buf.len();
+
+		Ok(ArrayStart { data_len_pos, data_start_pos })
+	}
+
+	fn end_array(&mut self, ArrayStart { data_len_pos, data_start_pos }: ArrayStart) -> Result<(), SerializeError> {
+		let data_end_pos = self.buf.len();
+
+		let data_len: u32 = std::convert::TryInto::try_into(data_end_pos - data_start_pos).map_err(serde::ser::Error::custom)?;
+
+		self.buf[data_len_pos..(data_len_pos + 4)].copy_from_slice(&self.endianness.u32_to_bytes(data_len));
+
+		Ok(())
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ArrayStart {
+	data_len_pos: usize,
+	data_start_pos: usize,
 }
 
 impl<'ser, 'a> serde::Serializer for &'a mut Serializer<'ser> {
@@ -35,7 +65,7 @@ impl<'ser, 'a> serde::Serializer for &'a mut Serializer<'ser> {
 	type SerializeStructVariant = StructVariantSerializer;
 
 	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-		let v: u32 = if v { 1 } else { 0 };
+		let v: u32 = v.into();
 		self.serialize_u32(v)
 	}
 
@@ -152,17 +182,11 @@ impl<'ser, 'a> serde::Serializer for &'a mut Serializer<'ser> {
 			alignment => panic!("unexpected alignment {:?}", alignment),
 		};
 
-		serde::Serialize::serialize(&0_u32, &mut *self)?;
-		let data_len_pos = self.buf.len() - 4;
-
-		self.pad_to(alignment);
-
-		let data_start_pos = self.buf.len();
+		let array_start = self.begin_array(alignment)?;
 
 		Ok(SeqSerializer {
 			inner: self,
-			data_len_pos,
-			data_start_pos,
+			array_start,
 		})
 	}
 
@@ -198,8 +222,7 @@ impl<'ser, 'a> serde::Serializer for &'a mut Serializer<'ser> {
 
 pub(crate) struct SeqSerializer<'ser, 'a> {
 	inner: &'a mut Serializer<'ser>,
-	data_len_pos: usize,
-	data_start_pos: usize,
+	array_start: ArrayStart,
 }
 
 impl<'ser, 'a> serde::ser::SerializeSeq for SeqSerializer<'ser, 'a> {
@@ -212,13 +235,7 @@ impl<'ser, 'a> serde::ser::SerializeSeq for SeqSerializer<'ser, 'a> {
 	}
 
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		let data_end_pos = self.inner.buf.len();
-
-		let data_len: u32 = std::convert::TryInto::try_into(data_end_pos - self.data_start_pos).map_err(serde::ser::Error::custom)?;
-
-		self.inner.buf[self.data_len_pos..(self.data_len_pos + 4)].copy_from_slice(&self.inner.endianness.u32_to_bytes(data_len));
-
-		Ok(())
+		self.inner.end_array(self.array_start)
 	}
 }
 
@@ -316,19 +333,557 @@ impl serde::ser::SerializeStructVariant for StructVariantSerializer {
 	}
 }
 
+/// Serialize the given value with the given signature, converting it the same way as [`crate::to_variant`] does.
+///
+/// Unlike serializing the value with [`Serializer`] directly, this does not require the value's `Serialize` impl to follow the conventions
+/// of `Serializer`, such as passing the alignment of array elements to `serialize_seq`, so it works for any `Serialize` type.
+pub(crate) fn serialize_with_signature<T>(value: &T, signature: &crate::Signature, serializer: &mut Serializer<'_>) -> Result<(), SerializeError> where T: serde::Serialize + ?Sized {
+	if let crate::Signature::Variant = signature {
+		// The inner signature of a variant precedes its value, so the only way to infer it is to convert the value to a `Variant` first.
+		let value = crate::to_variant(value, signature).map_err(SerializeError::Convert)?;
+		serde::Serialize::serialize(&value, serializer)
+	}
+	else {
+		value.serialize(SignatureSerializer { inner: serializer, signature })
+	}
+}
+
+struct SignatureSerializer<'ser, 'a, 's> {
+	inner: &'a mut Serializer<'ser>,
+	/// Never `crate::Signature::Variant`, since that is handled by [`serialize_with_signature`]
+	signature: &'s crate::Signature,
+}
+
+impl SignatureSerializer<'_, '_, '_> {
+	fn integer(self, value: i128, found: &'static str) -> Result<(), SerializeError> {
+		let value = crate::variant_serializer::integer(Some(self.signature), self.signature, value, found).map_err(SerializeError::Convert)?;
+		serde::Serialize::serialize(&value, self.inner)
+	}
+
+	fn invalid_value(&self, found: &'static str) -> SerializeError {
+		SerializeError::Convert(crate::variant_serializer::invalid_value(self.signature, found))
+	}
+}
+
+impl<'ser, 'a, 's> serde::Serializer for SignatureSerializer<'ser, 'a, 's> {
+	type Ok = ();
+	type Error = SerializeError;
+	type SerializeSeq = SignatureCompound<'ser, 'a, 's>;
+	type SerializeTuple = SignatureCompound<'ser, 'a, 's>;
+	type SerializeTupleStruct = SignatureCompound<'ser, 'a, 's>;
+	type SerializeTupleVariant = SignatureCompound<'ser, 'a, 's>;
+	type SerializeMap = SignatureCompound<'ser, 'a, 's>;
+	type SerializeStruct = SignatureCompound<'ser, 'a, 's>;
+	type SerializeStructVariant = SignatureCompound<'ser, 'a, 's>;
+
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			crate::Signature::Bool => serde::Serializer::serialize_bool(self.inner, v),
+			_ => Err(self.invalid_value("bool")),
+		}
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "i8")
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "i16")
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "i32")
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "i64")
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "u8")
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "u16")
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "u32")
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		self.integer(v.into(), "u64")
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		self.serialize_f64(v.into())
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			crate::Signature::F64 => serde::Serializer::serialize_f64(self.inner, v),
+			_ => Err(self.invalid_value("float")),
+		}
+	}
+
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		self.serialize_str(v.encode_utf8(&mut [0_u8; 4]))
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			crate::Signature::String => serde::Serializer::serialize_str(self.inner, v),
+
+			crate::Signature::ObjectPath => {
+				let () = crate::names::validate_object_path(v).map_err(|err| SerializeError::Convert(crate::ToVariantError::InvalidObjectPath(err)))?;
+				serde::Serializer::serialize_str(self.inner, v)
+			},
+
+			crate::Signature::Signature => {
+				let signature: crate::Signature = v.parse().map_err(|_| SerializeError::Convert(crate::ToVariantError::InvalidSignature(v.to_owned())))?;
+				serde::Serialize::serialize(&signature, self.inner)
+			},
+
+			_ => Err(self.invalid_value("str")),
+		}
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		use serde::ser::SerializeSeq;
+
+		let mut serializer = self.serialize_seq(Some(v.len()))?;
+		for b in v {
+			serializer.serialize_element(b)?;
+		}
+		serializer.end()
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Err(self.invalid_value("none"))
+	}
+
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			crate::Signature::Tuple { elements } if elements.is_empty() => Ok(()),
+			_ => Err(self.invalid_value("unit")),
+		}
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		self.serialize_unit()
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		let tag = crate::variant_serializer::tag(Some(self.signature), variant_index, variant).map_err(SerializeError::Convert)?;
+		serde::Serialize::serialize(&tag, self.inner)
+	}
+
+	fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T>(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		let (tag_signature, content_signature) =
+			crate::variant_serializer::tagged_signatures(self.signature, "newtype variant")
+			.map_err(SerializeError::Convert)?;
+		let tag = crate::variant_serializer::tag(Some(tag_signature), variant_index, variant).map_err(SerializeError::Convert)?;
+
+		let array_start = begin_tagged(self.inner, self.signature)?;
+		serde::Serialize::serialize(&tag, &mut *self.inner)?;
+		let () = serialize_with_signature(value, content_signature, self.inner)?;
+		if let Some(array_start) = array_start {
+			self.inner.end_array(array_start)?;
+		}
+
+		Ok(())
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		match self.signature {
+			crate::Signature::Array { .. } => SignatureCompound::begin(self.inner, self.signature, crate::variant_serializer::Kind::Seq),
+			_ => Err(self.invalid_value("sequence")),
+		}
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		let kind = crate::variant_serializer::Kind::Tuple;
+		let () = crate::variant_serializer::check_struct(Some(self.signature), len, kind).map_err(SerializeError::Convert)?;
+		SignatureCompound::begin(self.inner, self.signature, kind)
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.serialize_tuple(len)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		SignatureCompound::begin_variant(self.inner, self.signature, variant_index, variant, len, crate::variant_serializer::Kind::Tuple)
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		match self.signature {
+			crate::Signature::Array { element } if matches!(**element, crate::Signature::DictEntry { .. }) =>
+				SignatureCompound::begin(self.inner, self.signature, crate::variant_serializer::Kind::Map),
+			_ => Err(self.invalid_value("map")),
+		}
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		let kind = crate::variant_serializer::Kind::Struct;
+		let () = crate::variant_serializer::check_struct(Some(self.signature), len, kind).map_err(SerializeError::Convert)?;
+		SignatureCompound::begin(self.inner, self.signature, kind)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		SignatureCompound::begin_variant(self.inner, self.signature, variant_index, variant, len, crate::variant_serializer::Kind::Struct)
+	}
+
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+/// Serializes the start of a non-unit enum variant with the given signature, up to its tag.
+///
+/// Returns the start of the array if the signature is an array of dict entries, which must be ended after the content of the variant is serialized.
+fn begin_tagged(serializer: &mut Serializer<'_>, signature: &crate::Signature) -> Result<Option<ArrayStart>, SerializeError> {
+	let array_start =
+		if let crate::Signature::Array { element } = signature {
+			Some(serializer.begin_array(element.alignment())?)
+		}
+		else {
+			None
+		};
+
+	// Both structs and dict entries are 8-byte aligned.
+	serializer.pad_to(8);
+
+	Ok(array_start)
+}
+
+enum SignatureCompound<'ser, 'a, 's> {
+	Stream {
+		inner: &'a mut Serializer<'ser>,
+		signature: &'s crate::Signature,
+		kind: crate::variant_serializer::Kind,
+		index: usize,
+		array_start: Option<ArrayStart>,
+		/// The start of the array of dict entries that holds this compound, if this compound is the content of an enum variant.
+		tagged_array_start: Option<ArrayStart>,
+	},
+
+	/// The content of an enum variant whose signature is `v`. The signature of the content can only be inferred
+	/// by converting it to a `Variant` first.
+	Buffer {
+		inner: &'a mut Serializer<'ser>,
+		compound: crate::variant_serializer::Compound<'s, 'static>,
+	},
+}
+
+impl<'ser, 'a, 's> SignatureCompound<'ser, 'a, 's> {
+	fn begin(inner: &'a mut Serializer<'ser>, signature: &'s crate::Signature, kind: crate::variant_serializer::Kind) -> Result<Self, SerializeError> {
+		let array_start = match signature {
+			crate::Signature::Array { element } => Some(inner.begin_array(element.alignment())?),
+
+			crate::Signature::DictEntry { .. } |
+			crate::Signature::Struct { .. } => {
+				inner.pad_to(8);
+				None
+			},
+
+			_ => None,
+		};
+
+		Ok(SignatureCompound::Stream {
+			inner,
+			signature,
+			kind,
+			index: 0,
+			array_start,
+			tagged_array_start: None,
+		})
+	}
+
+	fn begin_variant(
+		inner: &'a mut Serializer<'ser>,
+		signature: &'s crate::Signature,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+		kind: crate::variant_serializer::Kind,
+	) -> Result<Self, SerializeError> {
+		let (tag_signature, content_signature) =
+			crate::variant_serializer::tagged_signatures(signature, kind.name())
+			.map_err(SerializeError::Convert)?;
+
+		if let crate::Signature::Variant = content_signature {
+			let compound =
+				crate::variant_serializer::Compound::new_variant(Some(signature), variant_index, variant, len, kind)
+				.map_err(SerializeError::Convert)?;
+			return Ok(SignatureCompound::Buffer { inner, compound });
+		}
+
+		let tag = crate::variant_serializer::tag(Some(tag_signature), variant_index, variant).map_err(SerializeError::Convert)?;
+		let () = crate::variant_serializer::check_struct(Some(content_signature), len, kind).map_err(SerializeError::Convert)?;
+
+		let tagged_array_start = begin_tagged(inner, signature)?;
+		serde::Serialize::serialize(&tag, &mut *inner)?;
+
+		let mut result = SignatureCompound::begin(inner, content_signature, kind)?;
+		if let SignatureCompound::Stream { tagged_array_start: result_tagged_array_start, .. } = &mut result {
+			*result_tagged_array_start = tagged_array_start;
+		}
+		Ok(result)
+	}
+
+	/// Serializes the next element of this compound. The key is the name of the field if this compound is a struct.
+	fn element<T>(&mut self, key: Option<&'static str>, value: &T) -> Result<(), SerializeError> where T: serde::Serialize + ?Sized {
+		let (inner, signature, kind, index) = match self {
+			SignatureCompound::Stream { inner, signature, kind, index, .. } => (inner, *signature, *kind, index),
+
+			SignatureCompound::Buffer { inner: _, compound } => {
+				let result = match key {
+					Some(key) => serde::ser::SerializeStruct::serialize_field(compound, key, value),
+					None => serde::ser::SerializeTuple::serialize_element(compound, value),
+				};
+				return result.map_err(SerializeError::Convert);
+			},
+		};
+
+		let invalid_length = |len| SerializeError::Convert(crate::ToVariantError::InvalidLength { expected: signature.clone(), found: kind.name(), len });
+
+		let element_signature = match (signature, key) {
+			(crate::Signature::Array { element }, Some(key)) => match &**element {
+				crate::Signature::DictEntry { key: _, value: value_signature } => {
+					inner.pad_to(8);
+					serde::Serializer::serialize_str(&mut **inner, key)?;
+					value_signature
+				},
+
+				_ => return Err(SerializeError::Convert(crate::variant_serializer::invalid_value(signature, kind.name()))),
+			},
+
+			(crate::Signature::Array { element }, None) => element,
+
+			(crate::Signature::Struct { fields: elements }, _) |
+			(crate::Signature::Tuple { elements }, _) =>
+				elements.get(*index).ok_or_else(|| invalid_length(*index + 1))?,
+
+			(crate::Signature::DictEntry { key, value }, _) => match *index {
+				0 => key,
+				1 => value,
+				_ => return Err(invalid_length(*index + 1)),
+			},
+
+			// `crate::Signature` serializes itself as a tuple of its wire bytes.
+			(crate::Signature::Signature, _) => &crate::Signature::U8,
+
+			_ => return Err(SerializeError::Convert(crate::variant_serializer::invalid_value(signature, kind.name()))),
+		};
+
+		*index += 1;
+
+		serialize_with_signature(value, element_signature, inner)
+	}
+
+	fn key<T>(&mut self, key: &T) -> Result<(), SerializeError> where T: serde::Serialize + ?Sized {
+		match self {
+			SignatureCompound::Stream { inner, signature: crate::Signature::Array { element }, .. } => match &**element {
+				crate::Signature::DictEntry { key: key_signature, value: _ } => {
+					inner.pad_to(8);
+					serialize_with_signature(key, key_signature, inner)
+				},
+
+				_ => Err(serde::ser::Error::custom("map key serialized with a signature that is not an array of dict entries")),
+			},
+
+			SignatureCompound::Stream { .. } => Err(serde::ser::Error::custom("map key serialized with a signature that is not an array of dict entries")),
+
+			SignatureCompound::Buffer { inner: _, compound } =>
+				serde::ser::SerializeMap::serialize_key(compound, key).map_err(SerializeError::Convert),
+		}
+	}
+
+	fn value<T>(&mut self, value: &T) -> Result<(), SerializeError> where T: serde::Serialize + ?Sized {
+		match self {
+			SignatureCompound::Stream { inner, signature: crate::Signature::Array { element }, .. } => match &**element {
+				crate::Signature::DictEntry { key: _, value: value_signature } => serialize_with_signature(value, value_signature, inner),
+
+				_ => Err(serde::ser::Error::custom("map value serialized with a signature that is not an array of dict entries")),
+			},
+
+			SignatureCompound::Stream { .. } => Err(serde::ser::Error::custom("map value serialized with a signature that is not an array of dict entries")),
+
+			SignatureCompound::Buffer { inner: _, compound } =>
+				serde::ser::SerializeMap::serialize_value(compound, value).map_err(SerializeError::Convert),
+		}
+	}
+
+	fn finish(self) -> Result<(), SerializeError> {
+		match self {
+			SignatureCompound::Stream { inner, signature, kind, index, array_start, tagged_array_start } => {
+				let expected_len = match signature {
+					crate::Signature::Struct { fields: elements } |
+					crate::Signature::Tuple { elements } => Some(elements.len()),
+					crate::Signature::DictEntry { .. } => Some(2),
+					_ => None,
+				};
+				if matches!(expected_len, Some(expected_len) if expected_len != index) {
+					return Err(SerializeError::Convert(crate::ToVariantError::InvalidLength { expected: signature.clone(), found: kind.name(), len: index }));
+				}
+
+				if let Some(array_start) = array_start {
+					inner.end_array(array_start)?;
+				}
+
+				if let Some(tagged_array_start) = tagged_array_start {
+					inner.end_array(tagged_array_start)?;
+				}
+
+				Ok(())
+			},
+
+			SignatureCompound::Buffer { inner, compound } => {
+				let value = compound.finish().map_err(SerializeError::Convert)?;
+				serde::Serialize::serialize(&value, inner)
+			},
+		}
+	}
+}
+
+impl serde::ser::SerializeSeq for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(None, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeTuple for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(None, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeTupleStruct for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(None, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeTupleVariant for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(None, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeMap for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.key(key)
+	}
+
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.value(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeStruct for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(Some(key), value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeStructVariant for SignatureCompound<'_, '_, '_> {
+	type Ok = ();
+	type Error = SerializeError;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.element(Some(key), value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
 /// An error from serializing a value using the D-Bus binary protocol.
 #[derive(Debug)]
 pub enum SerializeError {
+	Convert(crate::ToVariantError),
 	Custom(String),
 	ExceedsNumericLimits(std::num::TryFromIntError),
+	InvalidHeaderField { field: &'static str, value: String, err: crate::NameError },
 	Write(std::io::Error),
 }
 
 impl std::fmt::Display for SerializeError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
+			SerializeError::Convert(_) => f.write_str("value does not match its signature"),
 			SerializeError::Custom(message) => f.write_str(message),
 			SerializeError::ExceedsNumericLimits(_) => f.write_str("value exceeds numeric limits"),
+			SerializeError::InvalidHeaderField { field, value, err: _ } => write!(f, "{} header field {:?} is invalid", field, value),
 			SerializeError::Write(_) => f.write_str("could not write message"),
 		}
 	}
@@ -337,8 +892,10 @@ impl std::fmt::Display for SerializeError {
 impl std::error::Error for SerializeError {
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 		match self {
+			SerializeError::Convert(err) => Some(err),
 			SerializeError::Custom(_) => None,
 			SerializeError::ExceedsNumericLimits(err) => Some(err),
+			SerializeError::InvalidHeaderField { field: _, value: _, err } => Some(err),
 			SerializeError::Write(err) => Some(err),
 		}
 	}
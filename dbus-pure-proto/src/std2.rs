@@ -96,9 +96,9 @@ impl<T> From<Vec<T>> for CowSlice<'_, T> {
 	}
 }
 
-impl<'a, T> Into<std::borrow::Cow<'a, [T]>> for CowSlice<'a, T> where T: Clone {
-	fn into(self) -> std::borrow::Cow<'a, [T]> {
-		match self {
+impl<'a, T> From<CowSlice<'a, T>> for std::borrow::Cow<'a, [T]> where T: Clone {
+	fn from(s: CowSlice<'a, T>) -> Self {
+		match s {
 			CowSlice::Borrowed(s) => std::borrow::Cow::Borrowed(s),
 			CowSlice::Owned(v) => std::borrow::Cow::Owned(v),
 		}
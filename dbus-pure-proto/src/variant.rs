@@ -1,7 +1,10 @@
 /// A variant. It can store any kind of data type that D-Bus supports.
 ///
-/// Note that `Variant` does not impl `serde::Deserialize` since it needs to know the signature to be able to deserialize itself.
-/// Instead, use [`VariantDeserializeSeed`] to deserialize a `Variant`.
+/// Use [`VariantDeserializeSeed`] to deserialize a `Variant` from the D-Bus binary format, since it needs to know the signature to be able to
+/// deserialize itself. The `serde::Deserialize` impl of `Variant<'static>` is for variants nested in types that are deserialized from
+/// a message body or from another `Variant`.
+///
+/// Use [`crate::FromVariant`] to convert a `Variant` into a Rust type while checking its signature.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Variant<'a> {
 	/// An array of variants. All variants must have the same signature as `element_signature`.
@@ -60,6 +63,14 @@ pub enum Variant<'a> {
 
 	I64(i64),
 
+	/// A `GVariant` maybe value (`m`). `value` is `None` for `nothing`, and otherwise must have the signature `element_signature`.
+	///
+	/// Maybe values are not supported by the D-Bus wire format, only by the `GVariant` format.
+	Maybe {
+		element_signature: crate::Signature,
+		value: Option<crate::std2::CowRef<'a, Variant<'a>>>,
+	},
+
 	ObjectPath(crate::ObjectPath<'a>),
 
 	Signature(crate::Signature),
@@ -140,7 +151,156 @@ impl<'a> Variant<'a> {
 		}
 	}
 
-	pub(crate) fn inner_signature(&self) -> crate::Signature {
+	/// Convert this `Variant` into one that owns all its data, copying any data that it borrows.
+	///
+	/// This is useful to keep a `Variant` that was deserialized with [`BorrowedVariantDeserializeSeed`] after its input is gone.
+	pub fn into_owned(self) -> Variant<'static> {
+		fn into_owned_slice(elements: crate::std2::CowSlice<'_, Variant<'_>>) -> crate::std2::CowSlice<'static, Variant<'static>> {
+			match elements {
+				crate::std2::CowSlice::Borrowed(elements) => elements.iter().map(to_owned).collect::<Vec<_>>().into(),
+				crate::std2::CowSlice::Owned(elements) => elements.into_iter().map(Variant::into_owned).collect::<Vec<_>>().into(),
+			}
+		}
+
+		fn into_owned_ref(value: crate::std2::CowRef<'_, Variant<'_>>) -> crate::std2::CowRef<'static, Variant<'static>> {
+			let value = match value {
+				crate::std2::CowRef::Borrowed(value) => to_owned(value),
+				crate::std2::CowRef::Owned(value) => value.into_owned(),
+			};
+			Box::new(value).into()
+		}
+
+		/// Copies a borrowed `Variant` into an owned one, copying every string and array exactly once.
+		fn to_owned(value: &Variant<'_>) -> Variant<'static> {
+			fn to_owned_ref(value: &Variant<'_>) -> crate::std2::CowRef<'static, Variant<'static>> {
+				Box::new(to_owned(value)).into()
+			}
+
+			match value {
+				Variant::Array { element_signature, elements } =>
+					Variant::Array { element_signature: element_signature.clone(), elements: elements.iter().map(to_owned).collect::<Vec<_>>().into() },
+				Variant::ArrayBool(elements) => Variant::ArrayBool(elements.to_vec().into()),
+				Variant::ArrayF64(elements) => Variant::ArrayF64(elements.to_vec().into()),
+				Variant::ArrayI16(elements) => Variant::ArrayI16(elements.to_vec().into()),
+				Variant::ArrayI32(elements) => Variant::ArrayI32(elements.to_vec().into()),
+				Variant::ArrayI64(elements) => Variant::ArrayI64(elements.to_vec().into()),
+				Variant::ArrayString(elements) => Variant::ArrayString(elements.iter().map(|element| (**element).to_owned().into()).collect::<Vec<_>>().into()),
+				Variant::ArrayU8(elements) => Variant::ArrayU8(elements.to_vec().into()),
+				Variant::ArrayU16(elements) => Variant::ArrayU16(elements.to_vec().into()),
+				Variant::ArrayU32(elements) => Variant::ArrayU32(elements.to_vec().into()),
+				Variant::ArrayU64(elements) => Variant::ArrayU64(elements.to_vec().into()),
+				Variant::ArrayUnixFd(elements) => Variant::ArrayUnixFd(elements.to_vec().into()),
+				Variant::Bool(value) => Variant::Bool(*value),
+				Variant::DictEntry { key, value } => Variant::DictEntry { key: to_owned_ref(key), value: to_owned_ref(value) },
+				Variant::F64(value) => Variant::F64(*value),
+				Variant::I16(value) => Variant::I16(*value),
+				Variant::I32(value) => Variant::I32(*value),
+				Variant::I64(value) => Variant::I64(*value),
+				Variant::Maybe { element_signature, value } =>
+					Variant::Maybe { element_signature: element_signature.clone(), value: value.as_ref().map(|value| to_owned_ref(value)) },
+				Variant::ObjectPath(value) => Variant::ObjectPath(value.borrowed().into_owned()),
+				Variant::Signature(value) => Variant::Signature(value.clone()),
+				Variant::String(value) => Variant::String((**value).to_owned().into()),
+				Variant::Struct { fields } => Variant::Struct { fields: fields.iter().map(to_owned).collect::<Vec<_>>().into() },
+				Variant::Tuple { elements } => Variant::Tuple { elements: elements.iter().map(to_owned).collect::<Vec<_>>().into() },
+				Variant::U8(value) => Variant::U8(*value),
+				Variant::U16(value) => Variant::U16(*value),
+				Variant::U32(value) => Variant::U32(*value),
+				Variant::U64(value) => Variant::U64(*value),
+				Variant::UnixFd(value) => Variant::UnixFd(*value),
+				Variant::Variant(value) => Variant::Variant(to_owned_ref(value)),
+			}
+		}
+
+		match self {
+			Variant::Array { element_signature, elements } => Variant::Array { element_signature, elements: into_owned_slice(elements) },
+			Variant::ArrayBool(elements) => Variant::ArrayBool(elements.into_owned().into()),
+			Variant::ArrayF64(elements) => Variant::ArrayF64(elements.into_owned().into()),
+			Variant::ArrayI16(elements) => Variant::ArrayI16(elements.into_owned().into()),
+			Variant::ArrayI32(elements) => Variant::ArrayI32(elements.into_owned().into()),
+			Variant::ArrayI64(elements) => Variant::ArrayI64(elements.into_owned().into()),
+			Variant::ArrayString(std::borrow::Cow::Borrowed(elements)) =>
+				Variant::ArrayString(elements.iter().map(|element| (**element).to_owned().into()).collect::<Vec<_>>().into()),
+			Variant::ArrayString(std::borrow::Cow::Owned(elements)) =>
+				Variant::ArrayString(elements.into_iter().map(|element| element.into_owned().into()).collect::<Vec<_>>().into()),
+			Variant::ArrayU8(elements) => Variant::ArrayU8(elements.into_owned().into()),
+			Variant::ArrayU16(elements) => Variant::ArrayU16(elements.into_owned().into()),
+			Variant::ArrayU32(elements) => Variant::ArrayU32(elements.into_owned().into()),
+			Variant::ArrayU64(elements) => Variant::ArrayU64(elements.into_owned().into()),
+			Variant::ArrayUnixFd(elements) => Variant::ArrayUnixFd(elements.into_owned().into()),
+			Variant::Bool(value) => Variant::Bool(value),
+			Variant::DictEntry { key, value } => Variant::DictEntry { key: into_owned_ref(key), value: into_owned_ref(value) },
+			Variant::F64(value) => Variant::F64(value),
+			Variant::I16(value) => Variant::I16(value),
+			Variant::I32(value) => Variant::I32(value),
+			Variant::I64(value) => Variant::I64(value),
+			Variant::Maybe { element_signature, value } => Variant::Maybe { element_signature, value: value.map(into_owned_ref) },
+			Variant::ObjectPath(value) => Variant::ObjectPath(value.into_owned()),
+			Variant::Signature(value) => Variant::Signature(value),
+			Variant::String(value) => Variant::String(value.into_owned().into()),
+			Variant::Struct { fields } => Variant::Struct { fields: into_owned_slice(fields) },
+			Variant::Tuple { elements } => Variant::Tuple { elements: into_owned_slice(elements) },
+			Variant::U8(value) => Variant::U8(value),
+			Variant::U16(value) => Variant::U16(value),
+			Variant::U32(value) => Variant::U32(value),
+			Variant::U64(value) => Variant::U64(value),
+			Variant::UnixFd(value) => Variant::UnixFd(value),
+			Variant::Variant(value) => Variant::Variant(into_owned_ref(value)),
+		}
+	}
+
+	/// A `Variant` that borrows all its data from this one.
+	///
+	/// Unlike `.clone()`, this does not copy any strings or arrays of basic types. Only containers of `Variant`s are reallocated
+	/// to hold the borrowed elements.
+	pub fn borrowed(&self) -> Variant<'_> {
+		fn borrowed_slice<'a>(elements: &'a [Variant<'_>]) -> crate::std2::CowSlice<'a, Variant<'a>> {
+			elements.iter().map(Variant::borrowed).collect::<Vec<_>>().into()
+		}
+
+		fn borrowed_ref<'a>(value: &'a Variant<'_>) -> crate::std2::CowRef<'a, Variant<'a>> {
+			Box::new(value.borrowed()).into()
+		}
+
+		match self {
+			Variant::Array { element_signature, elements } => Variant::Array { element_signature: element_signature.clone(), elements: borrowed_slice(elements) },
+			Variant::ArrayBool(elements) => Variant::ArrayBool((&**elements).into()),
+			Variant::ArrayF64(elements) => Variant::ArrayF64((&**elements).into()),
+			Variant::ArrayI16(elements) => Variant::ArrayI16((&**elements).into()),
+			Variant::ArrayI32(elements) => Variant::ArrayI32((&**elements).into()),
+			Variant::ArrayI64(elements) => Variant::ArrayI64((&**elements).into()),
+			Variant::ArrayString(elements) => Variant::ArrayString(elements.iter().map(|element| (&**element).into()).collect::<Vec<_>>().into()),
+			Variant::ArrayU8(elements) => Variant::ArrayU8((&**elements).into()),
+			Variant::ArrayU16(elements) => Variant::ArrayU16((&**elements).into()),
+			Variant::ArrayU32(elements) => Variant::ArrayU32((&**elements).into()),
+			Variant::ArrayU64(elements) => Variant::ArrayU64((&**elements).into()),
+			Variant::ArrayUnixFd(elements) => Variant::ArrayUnixFd((&**elements).into()),
+			Variant::Bool(value) => Variant::Bool(*value),
+			Variant::DictEntry { key, value } => Variant::DictEntry { key: borrowed_ref(key), value: borrowed_ref(value) },
+			Variant::F64(value) => Variant::F64(*value),
+			Variant::I16(value) => Variant::I16(*value),
+			Variant::I32(value) => Variant::I32(*value),
+			Variant::I64(value) => Variant::I64(*value),
+			Variant::Maybe { element_signature, value } =>
+				Variant::Maybe { element_signature: element_signature.clone(), value: value.as_ref().map(|value| borrowed_ref(value)) },
+			Variant::ObjectPath(value) => Variant::ObjectPath(value.borrowed()),
+			Variant::Signature(value) => Variant::Signature(value.clone()),
+			Variant::String(value) => Variant::String((&**value).into()),
+			Variant::Struct { fields } => Variant::Struct { fields: borrowed_slice(fields) },
+			Variant::Tuple { elements } => Variant::Tuple { elements: borrowed_slice(elements) },
+			Variant::U8(value) => Variant::U8(*value),
+			Variant::U16(value) => Variant::U16(*value),
+			Variant::U32(value) => Variant::U32(*value),
+			Variant::U64(value) => Variant::U64(*value),
+			Variant::UnixFd(value) => Variant::UnixFd(*value),
+			Variant::Variant(value) => Variant::Variant(borrowed_ref(value)),
+		}
+	}
+
+	/// The signature of the value in this `Variant`.
+	///
+	/// For example, this is `s` for a `Variant::String`. Only a `Variant::Variant` has the signature `v`.
+	pub fn inner_signature(&self) -> crate::Signature {
 		match self {
 			Variant::Array { element_signature, elements: _ } =>
 				crate::Signature::Array { element: Box::new(element_signature.clone()) },
@@ -199,6 +359,9 @@ impl<'a> Variant<'a> {
 			Variant::I64(_) =>
 				crate::Signature::I64,
 
+			Variant::Maybe { element_signature, value: _ } =>
+				crate::Signature::Maybe { element: Box::new(element_signature.clone()) },
+
 			Variant::ObjectPath(_) =>
 				crate::Signature::ObjectPath,
 
@@ -233,6 +396,105 @@ impl<'a> Variant<'a> {
 				crate::Signature::Variant,
 		}
 	}
+
+	/// Converts this `Variant` to a `Variant` of the given signature, if the two signatures differ only in the kinds of their strings and integers.
+	///
+	/// Strings (`s`), object paths (`o`) and signatures (`g`) can be converted to each other, and so can `u32`s (`u`) and unix fds (`h`).
+	/// This applies to the elements of arrays, the fields of structs, and so on. For example, a `Variant` of signature `a{sas}`
+	/// can be converted to one of signature `a{oao}`.
+	pub fn with_signature(self, signature: &crate::Signature) -> Result<Variant<'a>, crate::FromVariantError> {
+		match (self, signature) {
+			(variant @ Variant::ObjectPath(_), crate::Signature::ObjectPath) =>
+				Ok(variant),
+
+			(Variant::String(value), crate::Signature::ObjectPath) => {
+				let () = crate::names::validate_object_path(&value).map_err(|err| crate::FromVariantError::InvalidObjectPath(value.to_string(), err))?;
+				Ok(Variant::ObjectPath(crate::ObjectPath(value)))
+			},
+
+			(Variant::ObjectPath(crate::ObjectPath(value)) | Variant::String(value), crate::Signature::Signature) =>
+				value.parse().map(Variant::Signature).map_err(|_| crate::FromVariantError::InvalidSignature(value.into_owned())),
+
+			(Variant::ObjectPath(crate::ObjectPath(value)) | Variant::String(value), crate::Signature::String) =>
+				Ok(Variant::String(value)),
+
+			(Variant::Signature(value), crate::Signature::ObjectPath) => {
+				let value = value.to_string();
+				let () = crate::names::validate_object_path(&value).map_err(|err| crate::FromVariantError::InvalidObjectPath(value.clone(), err))?;
+				Ok(Variant::ObjectPath(crate::ObjectPath(value.into())))
+			},
+
+			(Variant::Signature(value), crate::Signature::String) =>
+				Ok(Variant::String(value.to_string().into())),
+
+			(Variant::U32(value), crate::Signature::UnixFd) =>
+				Ok(Variant::UnixFd(crate::UnixFd(value))),
+
+			(Variant::UnixFd(crate::UnixFd(value)), crate::Signature::U32) =>
+				Ok(Variant::U32(value)),
+
+			(variant, crate::Signature::Array { element }) if crate::from_variant::is_array(&variant) => {
+				if variant.inner_signature() == *signature {
+					return Ok(variant);
+				}
+
+				let elements: Result<Vec<_>, _> =
+					crate::from_variant::into_elements(variant)
+					.into_iter()
+					.map(|value| value.with_signature(element))
+					.collect();
+				Ok(Variant::Array {
+					element_signature: (**element).clone(),
+					elements: elements?.into(),
+				})
+			},
+
+			(Variant::DictEntry { key, value }, crate::Signature::DictEntry { key: key_signature, value: value_signature }) =>
+				Ok(Variant::DictEntry {
+					key: Box::new(crate::from_variant::ref_into_variant(key).with_signature(key_signature)?).into(),
+					value: Box::new(crate::from_variant::ref_into_variant(value).with_signature(value_signature)?).into(),
+				}),
+
+			(Variant::Maybe { element_signature: _, value }, crate::Signature::Maybe { element }) =>
+				Ok(Variant::Maybe {
+					element_signature: (**element).clone(),
+					value:
+						value
+						.map(|value| crate::from_variant::ref_into_variant(value).with_signature(element).map(|value| Box::new(value).into()))
+						.transpose()?,
+				}),
+
+			(Variant::Struct { fields }, crate::Signature::Struct { fields: field_signatures }) if fields.len() == field_signatures.len() => {
+				let fields: Result<Vec<_>, _> =
+					crate::from_variant::slice_into_variants(fields)
+					.into_iter()
+					.zip(field_signatures)
+					.map(|(field, signature)| field.with_signature(signature))
+					.collect();
+				Ok(Variant::Struct { fields: fields?.into() })
+			},
+
+			(Variant::Tuple { elements }, crate::Signature::Tuple { elements: element_signatures }) if elements.len() == element_signatures.len() => {
+				let elements: Result<Vec<_>, _> =
+					crate::from_variant::slice_into_variants(elements)
+					.into_iter()
+					.zip(element_signatures)
+					.map(|(element, signature)| element.with_signature(signature))
+					.collect();
+				Ok(Variant::Tuple { elements: elements?.into() })
+			},
+
+			(variant, signature) => {
+				let actual = variant.inner_signature();
+				if actual == *signature {
+					Ok(variant)
+				}
+				else {
+					Err(crate::FromVariantError::UnexpectedSignature { expected: signature.clone(), actual })
+				}
+			},
+		}
+	}
 }
 
 impl serde::Serialize for Variant<'_> {
@@ -298,6 +560,9 @@ impl serde::Serialize for Variant<'_> {
 			Variant::I64(value) =>
 				value.serialize(serializer),
 
+			Variant::Maybe { .. } =>
+				Err(serde::ser::Error::custom("maybe values cannot be serialized in the D-Bus format")),
+
 			Variant::ObjectPath(value) =>
 				value.serialize(serializer),
 
@@ -350,6 +615,9 @@ impl serde::Serialize for Variant<'_> {
 }
 
 /// Used to deserialize a [`Variant`] using its [`serde::de::DeserializeSeed`] impl.
+///
+/// The deserialized `Variant` owns all its data. Use [`BorrowedVariantDeserializeSeed`] to deserialize a `Variant`
+/// that borrows its strings and byte arrays from the input instead.
 #[derive(Debug)]
 pub struct VariantDeserializeSeed<'input, 'output>(&'input crate::Signature, std::marker::PhantomData<fn() -> Variant<'output>>);
 
@@ -364,284 +632,591 @@ impl<'de, 'input, 'output> serde::de::DeserializeSeed<'de> for VariantDeserializ
 	type Value = Variant<'output>;
 
 	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
-		struct Visitor<'input, 'output>(&'input crate::Signature, std::marker::PhantomData<fn() -> Variant<'output>>);
+		deserialize_variant::<Owned, D>(self.0, deserializer)
+	}
+}
 
-		impl<'de, 'input, 'output> serde::de::Visitor<'de> for Visitor<'input, 'output> {
-			type Value = Variant<'output>;
+/// Used to deserialize a [`Variant`] that borrows from the input using its [`serde::de::DeserializeSeed`] impl.
+///
+/// Strings, object paths and byte arrays in the deserialized `Variant` borrow from the input instead of being copied,
+/// so the `Variant` has the lifetime of the input. Use [`Variant::into_owned`] to detach it from the input.
+#[derive(Debug)]
+pub struct BorrowedVariantDeserializeSeed<'input>(&'input crate::Signature);
 
-			fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-				formatter.write_str("variant")
-			}
+impl<'input> BorrowedVariantDeserializeSeed<'input> {
+	/// Construct a `BorrowedVariantDeserializeSeed` that will deserialize a [`Variant`] of the given signature.
+	pub fn new(signature: &'input crate::Signature) -> Self {
+		BorrowedVariantDeserializeSeed(signature)
+	}
+}
 
-			fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
-				match self.0 {
-					crate::Signature::Array { element } => {
-						let element_seed = ArrayDeserializeSeed((&**element).clone(), self.1);
-						let value = seq.next_element_seed(element_seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(value)
-					},
+impl<'de, 'input> serde::de::DeserializeSeed<'de> for BorrowedVariantDeserializeSeed<'input> {
+	type Value = Variant<'de>;
 
-					crate::Signature::Bool => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::Bool(value))
-					},
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+		deserialize_variant::<Borrowed, D>(self.0, deserializer)
+	}
+}
 
-					crate::Signature::DictEntry { key, value } => {
-						let () = seq.next_element_seed(StructDeserializeSeed)?.expect("cannot fail");
+/// Whether a deserialized [`Variant`] copies the strings and byte arrays of the input, or borrows them.
+trait Borrow<'de, 'output> {
+	fn borrow<T>(value: &'de T) -> std::borrow::Cow<'output, T> where T: ToOwned + ?Sized;
+}
 
-						let seed = VariantDeserializeSeed(key, self.1);
-						let key = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+enum Owned {}
 
-						let seed = VariantDeserializeSeed(value, self.1);
-						let value = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+impl<'de, 'output> Borrow<'de, 'output> for Owned {
+	fn borrow<T>(value: &'de T) -> std::borrow::Cow<'output, T> where T: ToOwned + ?Sized {
+		std::borrow::Cow::Owned(value.to_owned())
+	}
+}
 
-						Ok(Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
-					},
+enum Borrowed {}
 
-					crate::Signature::F64 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::F64(value))
-					},
+impl<'de> Borrow<'de, 'de> for Borrowed {
+	fn borrow<T>(value: &'de T) -> std::borrow::Cow<'de, T> where T: ToOwned + ?Sized {
+		std::borrow::Cow::Borrowed(value)
+	}
+}
 
-					crate::Signature::I16 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::I16(value))
-					},
+type Output<'output, B> = std::marker::PhantomData<fn() -> (Variant<'output>, B)>;
 
-					crate::Signature::I32 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::I32(value))
-					},
+fn deserialize_variant<'de, 'output, B, D>(signature: &crate::Signature, deserializer: D) -> Result<Variant<'output>, D::Error>
+where
+	B: Borrow<'de, 'output>,
+	D: serde::Deserializer<'de>,
+{
+	struct Visitor<'input, 'output, B>(&'input crate::Signature, Output<'output, B>);
 
-					crate::Signature::I64 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::I64(value))
-					},
+	impl<'de, 'input, 'output, B> serde::de::Visitor<'de> for Visitor<'input, 'output, B> where B: Borrow<'de, 'output> {
+		type Value = Variant<'output>;
 
-					crate::Signature::ObjectPath => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::ObjectPath(value))
-					},
+		fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+			formatter.write_str("variant")
+		}
 
-					crate::Signature::Signature => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::Signature(value))
-					},
+		fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+			match self.0 {
+				crate::Signature::Array { element } => {
+					let element_seed = ArrayDeserializeSeed((**element).clone(), self.1);
+					let value = seq.next_element_seed(element_seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(value)
+				},
 
-					crate::Signature::String => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::String(value))
-					},
+				crate::Signature::Bool => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::Bool(value))
+				},
 
-					crate::Signature::Struct { fields } => {
-						let () = seq.next_element_seed(StructDeserializeSeed)?.expect("cannot fail");
-
-						let fields: Result<Vec<_>, _> =
-							fields.iter()
-							.map(|field| {
-								let seed = VariantDeserializeSeed(field, self.1);
-								let field = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-								Ok(field)
-							})
-							.collect();
-						let fields = fields?;
-						Ok(Variant::Struct { fields: fields.into() })
-					},
+				crate::Signature::DictEntry { key, value } => {
+					let () = seq.next_element_seed(StructDeserializeSeed)?.expect("cannot fail");
 
-					crate::Signature::Tuple { elements } => {
-						let elements: Result<Vec<_>, _> =
-							elements.iter()
-							.map(|element| {
-								let seed = VariantDeserializeSeed(element, self.1);
-								let element = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-								Ok(element)
-							})
-							.collect();
-						let elements = elements?;
-						Ok(Variant::Tuple { elements: elements.into() })
-					},
+					let seed = VariantSeed(key, self.1);
+					let key = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
 
-					crate::Signature::U8 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::U8(value))
-					},
+					let seed = VariantSeed(value, self.1);
+					let value = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
 
-					crate::Signature::U16 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::U16(value))
-					},
+					Ok(Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
+				},
 
-					crate::Signature::U32 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::U32(value))
-					},
+				crate::Signature::F64 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::F64(value))
+				},
 
-					crate::Signature::U64 => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::U64(value))
-					},
+				crate::Signature::I16 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::I16(value))
+				},
 
-					crate::Signature::UnixFd => {
-						let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::UnixFd(value))
-					},
+				crate::Signature::I32 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::I32(value))
+				},
 
-					crate::Signature::Variant => {
-						let signature: crate::Signature = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						let seed = VariantDeserializeSeed(&signature, self.1);
-						let value: Variant<'output> = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
-						Ok(Variant::Variant(Box::new(value).into()))
-					},
-				}
+				crate::Signature::I64 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::I64(value))
+				},
+
+				crate::Signature::Maybe { .. } =>
+					Err(serde::de::Error::custom("maybe values cannot be deserialized from the D-Bus format")),
+
+				crate::Signature::ObjectPath => {
+					let value = seq.next_element_seed(StrDeserializeSeed(self.1))?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					let value = crate::ObjectPath::new(value).map_err(serde::de::Error::custom)?;
+					Ok(Variant::ObjectPath(value))
+				},
+
+				crate::Signature::Signature => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::Signature(value))
+				},
+
+				crate::Signature::String => {
+					let value = seq.next_element_seed(StrDeserializeSeed(self.1))?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::String(value))
+				},
+
+				crate::Signature::Struct { fields } => {
+					let () = seq.next_element_seed(StructDeserializeSeed)?.expect("cannot fail");
+
+					let fields: Result<Vec<_>, _> =
+						fields.iter()
+						.map(|field| {
+							let seed = VariantSeed(field, self.1);
+							let field = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+							Ok(field)
+						})
+						.collect();
+					let fields = fields?;
+					Ok(Variant::Struct { fields: fields.into() })
+				},
+
+				crate::Signature::Tuple { elements } => {
+					let elements: Result<Vec<_>, _> =
+						elements.iter()
+						.map(|element| {
+							let seed = VariantSeed(element, self.1);
+							let element = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+							Ok(element)
+						})
+						.collect();
+					let elements = elements?;
+					Ok(Variant::Tuple { elements: elements.into() })
+				},
+
+				crate::Signature::U8 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::U8(value))
+				},
+
+				crate::Signature::U16 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::U16(value))
+				},
+
+				crate::Signature::U32 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::U32(value))
+				},
+
+				crate::Signature::U64 => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::U64(value))
+				},
+
+				crate::Signature::UnixFd => {
+					let value = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::UnixFd(value))
+				},
+
+				crate::Signature::Variant => {
+					let signature: crate::Signature = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					let seed = VariantSeed(&signature, self.1);
+					let value: Variant<'output> = seq.next_element_seed(seed)?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+					Ok(Variant::Variant(Box::new(value).into()))
+				},
 			}
 		}
+	}
 
-		struct ArrayDeserializeSeed<'output>(crate::Signature, std::marker::PhantomData<fn() -> Variant<'output>>);
-
-		impl<'de, 'output> serde::de::DeserializeSeed<'de> for ArrayDeserializeSeed<'output> {
-			type Value = Variant<'output>;
+	struct ArrayDeserializeSeed<'output, B>(crate::Signature, Output<'output, B>);
 
-			fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
-				struct Visitor<'output>(crate::Signature, std::marker::PhantomData<fn() -> Variant<'output>>);
+	impl<'de, 'output, B> serde::de::DeserializeSeed<'de> for ArrayDeserializeSeed<'output, B> where B: Borrow<'de, 'output> {
+		type Value = Variant<'output>;
 
-				impl<'de, 'output> serde::de::Visitor<'de> for Visitor<'output> {
-					type Value = Variant<'output>;
+		fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+			struct Visitor<'output, B>(crate::Signature, Output<'output, B>);
 
-					fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-						write!(formatter, "Array({:?})", self.0)
-					}
+			impl<'de, 'output, B> serde::de::Visitor<'de> for Visitor<'output, B> where B: Borrow<'de, 'output> {
+				type Value = Variant<'output>;
 
-					fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
-						match self.0 {
-							crate::Signature::Bool => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayBool(elements.into()))
-							},
+				fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+					write!(formatter, "Array({:?})", self.0)
+				}
 
-							crate::Signature::F64 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayF64(elements.into()))
-							},
+				fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+					match self.0 {
+						crate::Signature::Bool => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayBool(elements.into()))
+						},
 
-							crate::Signature::I16 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayI16(elements.into()))
-							},
+						crate::Signature::F64 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayF64(elements.into()))
+						},
 
-							crate::Signature::I32 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayI32(elements.into()))
-							},
+						crate::Signature::I16 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayI16(elements.into()))
+						},
 
-							crate::Signature::I64 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayI64(elements.into()))
-							},
+						crate::Signature::I32 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayI32(elements.into()))
+						},
 
-							crate::Signature::String => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayString(elements.into()))
-							},
+						crate::Signature::I64 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayI64(elements.into()))
+						},
 
-							crate::Signature::U8 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayU8(elements.into()))
-							},
+						crate::Signature::String => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element_seed(StrDeserializeSeed(self.1))? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayString(elements.into()))
+						},
 
-							crate::Signature::U16 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayU16(elements.into()))
-							},
+						crate::Signature::U16 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayU16(elements.into()))
+						},
 
-							crate::Signature::U32 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayU32(elements.into()))
-							},
+						crate::Signature::U32 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayU32(elements.into()))
+						},
 
-							crate::Signature::U64 => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayU64(elements.into()))
-							},
+						crate::Signature::U64 => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayU64(elements.into()))
+						},
 
-							crate::Signature::UnixFd => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element()? {
-									elements.push(element);
-								}
-								Ok(Variant::ArrayUnixFd(elements.into()))
-							},
+						crate::Signature::UnixFd => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element()? {
+								elements.push(element);
+							}
+							Ok(Variant::ArrayUnixFd(elements.into()))
+						},
 
-							element_signature => {
-								let mut elements = vec![];
-								while let Some(element) = seq.next_element_seed(VariantDeserializeSeed(&element_signature, self.1))? {
-									elements.push(element);
-								}
-								Ok(Variant::Array { element_signature, elements: elements.into() })
-							},
-						}
+						element_signature => {
+							let mut elements = vec![];
+							while let Some(element) = seq.next_element_seed(VariantSeed(&element_signature, self.1))? {
+								elements.push(element);
+							}
+							Ok(Variant::Array { element_signature, elements: elements.into() })
+						},
 					}
 				}
+			}
 
-				deserializer.deserialize_tuple_struct("", self.0.alignment(), Visitor(self.0, self.1))
+			// Byte arrays are deserialized as a single slice, so that they can be borrowed from the input.
+			if let crate::Signature::U8 = self.0 {
+				return deserializer.deserialize_bytes(BytesVisitor(self.1));
 			}
+
+			deserializer.deserialize_tuple_struct("", self.0.alignment(), Visitor(self.0, self.1))
 		}
+	}
 
-		// Instantiated once when beginning to deserialize a struct. Doesn't actually deserialize anything, but enforces struct padding.
-		struct StructDeserializeSeed;
+	// Instantiated once when beginning to deserialize a struct. Doesn't actually deserialize anything, but enforces struct padding.
+	struct StructDeserializeSeed;
 
-		impl<'de> serde::de::DeserializeSeed<'de> for StructDeserializeSeed {
-			type Value = ();
+	impl<'de> serde::de::DeserializeSeed<'de> for StructDeserializeSeed {
+		type Value = ();
 
-			fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
-				struct Visitor;
+		fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+			struct Visitor;
 
-				impl<'de> serde::de::Visitor<'de> for Visitor {
-					type Value = ();
+			impl<'de> serde::de::Visitor<'de> for Visitor {
+				type Value = ();
 
-					fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-						formatter.write_str("")
-					}
+				fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+					formatter.write_str("")
+				}
 
-					fn visit_map<A>(self, _map: A) -> Result<Self::Value, A::Error> where A: serde::de::MapAccess<'de> {
-						Ok(())
-					}
+				fn visit_map<A>(self, _map: A) -> Result<Self::Value, A::Error> where A: serde::de::MapAccess<'de> {
+					Ok(())
 				}
+			}
+
+			deserializer.deserialize_struct("", &[], Visitor)
+		}
+	}
+
+	struct VariantSeed<'input, 'output, B>(&'input crate::Signature, Output<'output, B>);
+
+	impl<'de, 'input, 'output, B> serde::de::DeserializeSeed<'de> for VariantSeed<'input, 'output, B> where B: Borrow<'de, 'output> {
+		type Value = Variant<'output>;
+
+		fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+			deserialize_variant::<B, D>(self.0, deserializer)
+		}
+	}
+
+	struct StrDeserializeSeed<'output, B>(Output<'output, B>);
+
+	impl<'de, 'output, B> serde::de::DeserializeSeed<'de> for StrDeserializeSeed<'output, B> where B: Borrow<'de, 'output> {
+		type Value = std::borrow::Cow<'output, str>;
 
-				deserializer.deserialize_struct("", &[], Visitor)
+		fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+			deserializer.deserialize_str(self)
+		}
+	}
+
+	impl<'de, 'output, B> serde::de::Visitor<'de> for StrDeserializeSeed<'output, B> where B: Borrow<'de, 'output> {
+		type Value = std::borrow::Cow<'output, str>;
+
+		fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+			formatter.write_str("string")
+		}
+
+		fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(B::borrow(v))
+		}
+
+		fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(std::borrow::Cow::Owned(v.to_owned()))
+		}
+
+		fn visit_string<E>(self, v: String) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(std::borrow::Cow::Owned(v))
+		}
+	}
+
+	struct BytesVisitor<'output, B>(Output<'output, B>);
+
+	impl<'de, 'output, B> serde::de::Visitor<'de> for BytesVisitor<'output, B> where B: Borrow<'de, 'output> {
+		type Value = Variant<'output>;
+
+		fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+			formatter.write_str("byte array")
+		}
+
+		fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(Variant::ArrayU8(B::borrow(v)))
+		}
+
+		fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(Variant::ArrayU8(std::borrow::Cow::Owned(v.to_owned())))
+		}
+
+		fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> where E: serde::de::Error {
+			Ok(Variant::ArrayU8(std::borrow::Cow::Owned(v)))
+		}
+	}
+
+	deserializer.deserialize_tuple(0, Visitor::<'_, 'output, B>(signature, std::marker::PhantomData))
+}
+
+/// The name that the `serde::Deserialize` impl of [`Variant`] passes to `deserialize_newtype_struct`.
+///
+/// The deserializers of this crate recognize it and visit a sequence of the signature of the value as a string, followed by the value itself.
+/// If the value is a `v`, the signature and value are those of the value inside it.
+pub(crate) const NEWTYPE_NAME: &str = "dbus_pure_proto::Variant";
+
+/// Deserializes a value of any type into a `Variant`. A value of type `v` is deserialized into the value inside it,
+/// so this is the inverse of the [`AsVariant`](crate::AsVariant) impl of `Variant`.
+///
+/// This allows a `Variant` to be a field of a type that is deserialized from a message body, such as the `v` of an `a{sv}`.
+/// It only works with the deserializers of this crate, ie `Variant` itself and [`deserialize_message_with_body`](crate::deserialize_message_with_body),
+/// since other deserializers cannot tell the D-Bus type of the value.
+impl<'de> serde::Deserialize<'de> for Variant<'static> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		struct Visitor;
+
+		impl<'de> serde::de::Visitor<'de> for Visitor {
+			type Value = Variant<'static>;
+
+			fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				formatter.write_str("a D-Bus value")
 			}
+
+			fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+				let signature: String = seq.next_element()?.ok_or_else(|| serde::de::Error::missing_field("signature"))?;
+				let signature: crate::Signature =
+					signature.parse()
+					.map_err(|err| serde::de::Error::custom(format_args!("invalid signature {:?}: {}", signature, err)))?;
+				let value = seq.next_element_seed(TypedSeed(&signature))?.ok_or_else(|| serde::de::Error::missing_field("value"))?;
+				Ok(value)
+			}
+		}
+
+		deserializer.deserialize_newtype_struct(NEWTYPE_NAME, Visitor)
+	}
+}
+
+/// Deserializes a `Variant` of the given signature from a self-describing deserializer.
+struct TypedSeed<'s>(&'s crate::Signature);
+
+impl<'de> serde::de::DeserializeSeed<'de> for TypedSeed<'_> {
+	type Value = Variant<'static>;
+
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+		if let crate::Signature::Variant = self.0 {
+			let value: Variant<'static> = serde::Deserialize::deserialize(deserializer)?;
+			return Ok(Variant::Variant(Box::new(value).into()));
 		}
 
-		deserializer.deserialize_tuple(0, Visitor(&*self.0, self.1))
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de> serde::de::Visitor<'de> for TypedSeed<'_> {
+	type Value = Variant<'static>;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(formatter, "a value of type {:?}", self.0.to_string())
+	}
+
+	fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> where E: serde::de::Error {
+		match self.0 {
+			crate::Signature::Bool => Ok(Variant::Bool(v)),
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Bool(v), &self)),
+		}
+	}
+
+	fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: serde::de::Error {
+		self.integer(v.into(), serde::de::Unexpected::Signed(v))
+	}
+
+	fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: serde::de::Error {
+		self.integer(v.into(), serde::de::Unexpected::Unsigned(v))
+	}
+
+	fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> where E: serde::de::Error {
+		match self.0 {
+			crate::Signature::F64 => Ok(Variant::F64(v)),
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Float(v), &self)),
+		}
+	}
+
+	fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: serde::de::Error {
+		match self.0 {
+			crate::Signature::ObjectPath => Ok(Variant::ObjectPath(crate::ObjectPath::new(v.to_owned()).map_err(serde::de::Error::custom)?)),
+			crate::Signature::Signature => Ok(Variant::Signature(v.parse().map_err(|err| serde::de::Error::custom(format_args!("invalid signature {:?}: {}", v, err)))?)),
+			crate::Signature::String => Ok(Variant::String(v.to_owned().into())),
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Str(v), &self)),
+		}
+	}
+
+	fn visit_none<E>(self) -> Result<Self::Value, E> where E: serde::de::Error {
+		match self.0 {
+			crate::Signature::Maybe { element } => Ok(Variant::Maybe { element_signature: (**element).clone(), value: None }),
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Option, &self)),
+		}
+	}
+
+	fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: serde::Deserializer<'de> {
+		match self.0 {
+			crate::Signature::Maybe { element } => {
+				let value = serde::de::DeserializeSeed::deserialize(TypedSeed(element), deserializer)?;
+				Ok(Variant::Maybe { element_signature: (**element).clone(), value: Some(Box::new(value).into()) })
+			},
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Option, &self)),
+		}
+	}
+
+	fn visit_unit<E>(self) -> Result<Self::Value, E> where E: serde::de::Error {
+		match self.0 {
+			crate::Signature::Tuple { elements } if elements.is_empty() => Ok(Variant::Tuple { elements: vec![].into() }),
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Unit, &self)),
+		}
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+		fn next_elements<'de, A>(seq: &mut A, signatures: &[&crate::Signature]) -> Result<Vec<Variant<'static>>, A::Error> where A: serde::de::SeqAccess<'de> {
+			signatures.iter()
+				.enumerate()
+				.map(|(i, signature)| seq.next_element_seed(TypedSeed(signature))?.ok_or_else(|| serde::de::Error::invalid_length(i, &"more elements")))
+				.collect()
+		}
+
+		match self.0 {
+			crate::Signature::Array { element } => {
+				let mut elements = vec![];
+				while let Some(element) = seq.next_element_seed(TypedSeed(element))? {
+					elements.push(element);
+				}
+				Ok(Variant::Array { element_signature: (**element).clone(), elements: elements.into() })
+			},
+
+			crate::Signature::DictEntry { key, value } => {
+				let mut elements = next_elements(&mut seq, &[key, value])?.into_iter();
+				let key = elements.next().expect("two elements");
+				let value = elements.next().expect("two elements");
+				Ok(Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
+			},
+
+			crate::Signature::Struct { fields } => {
+				let fields: Vec<_> = fields.iter().collect();
+				Ok(Variant::Struct { fields: next_elements(&mut seq, &fields)?.into() })
+			},
+
+			crate::Signature::Tuple { elements } => {
+				let elements: Vec<_> = elements.iter().collect();
+				Ok(Variant::Tuple { elements: next_elements(&mut seq, &elements)?.into() })
+			},
+
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Seq, &self)),
+		}
+	}
+
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error> where A: serde::de::MapAccess<'de> {
+		match self.0 {
+			crate::Signature::Array { element } => match &**element {
+				crate::Signature::DictEntry { key: key_signature, value: value_signature } => {
+					let mut elements = vec![];
+					while let Some(key) = map.next_key_seed(TypedSeed(key_signature))? {
+						let value = map.next_value_seed(TypedSeed(value_signature))?;
+						elements.push(Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() });
+					}
+					Ok(Variant::Array { element_signature: (**element).clone(), elements: elements.into() })
+				},
+
+				_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Map, &self)),
+			},
+
+			_ => Err(serde::de::Error::invalid_type(serde::de::Unexpected::Map, &self)),
+		}
+	}
+}
+
+impl TypedSeed<'_> {
+	fn integer<E>(self, v: i128, unexpected: serde::de::Unexpected<'_>) -> Result<Variant<'static>, E> where E: serde::de::Error {
+		let invalid_value = |_| serde::de::Error::invalid_value(unexpected, &self);
+
+		match self.0 {
+			crate::Signature::I16 => Ok(Variant::I16(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::I32 => Ok(Variant::I32(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::I64 => Ok(Variant::I64(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::U8 => Ok(Variant::U8(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::U16 => Ok(Variant::U16(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::U32 => Ok(Variant::U32(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::U64 => Ok(Variant::U64(std::convert::TryInto::try_into(v).map_err(invalid_value)?)),
+			crate::Signature::UnixFd => Ok(Variant::UnixFd(crate::UnixFd(std::convert::TryInto::try_into(v).map_err(invalid_value)?))),
+			_ => Err(serde::de::Error::invalid_type(unexpected, &self)),
+		}
 	}
 }
 
@@ -649,6 +1224,7 @@ impl<'de, 'input, 'output> serde::de::DeserializeSeed<'de> for VariantDeserializ
 mod tests {
 	#[test]
 	fn test_variant_serde() {
+		#[allow(clippy::assertions_on_constants, clippy::needless_pass_by_value)]
 		fn test(
 			signature: &str,
 			expected_serialized: &[u8],
@@ -667,6 +1243,14 @@ mod tests {
 
 			assert_eq!(deserializer.pos(), expected_serialized.len());
 
+			let deserialize_seed = crate::BorrowedVariantDeserializeSeed::new(&signature);
+
+			let mut deserializer = crate::de::Deserializer::new(expected_serialized, 0, crate::Endianness::Little);
+			let actual_borrowed_variant: super::Variant<'_> = serde::de::DeserializeSeed::deserialize(deserialize_seed, &mut deserializer).unwrap();
+			assert_eq!(expected_variant.clone().into_owned(), actual_borrowed_variant.into_owned());
+
+			assert_eq!(deserializer.pos(), expected_serialized.len());
+
 			let mut actual_serialized = vec![];
 			let mut serializer = crate::ser::Serializer::new(&mut actual_serialized, crate::Endianness::Little);
 			serde::Serialize::serialize(&actual_variant, &mut serializer).unwrap();
@@ -1,4 +1,4 @@
-impl<'de, 'a> serde::Deserializer<'de> for crate::Variant<'de> {
+impl<'de> serde::Deserializer<'de> for crate::Variant<'de> {
 	type Error = crate::DeserializeError;
 
 	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
@@ -81,6 +81,12 @@ impl<'de, 'a> serde::Deserializer<'de> for crate::Variant<'de> {
 			crate::Variant::I64(value) =>
 				visitor.visit_i64(value),
 
+			crate::Variant::Maybe { element_signature: _, value: Some(value) } =>
+				visitor.visit_some(value.into_owned()),
+
+			crate::Variant::Maybe { element_signature: _, value: None } =>
+				visitor.visit_none(),
+
 			crate::Variant::ObjectPath(crate::ObjectPath(value)) =>
 				crate::Variant::String(value).deserialize_any(visitor),
 
@@ -118,6 +124,59 @@ impl<'de, 'a> serde::Deserializer<'de> for crate::Variant<'de> {
 		}
 	}
 
+	fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		if name == crate::variant::NEWTYPE_NAME {
+			let value = match self {
+				crate::Variant::Variant(value) => value.into_owned(),
+				value => value,
+			};
+			let signature = value.inner_signature();
+			return visitor.visit_seq(SeqAccess(vec![crate::Variant::String(signature.to_string().into()), value].into_iter()));
+		}
+
+		self.deserialize_any(visitor)
+	}
+
+	// Values other than maybes are treated as present, so that `Option` fields of structs deserialized from `a{sv}` dictionaries
+	// can be deserialized from the values that are in the dictionary. Absent keys are left as `None` by the `Deserialize` impl of the struct.
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		match self {
+			crate::Variant::Maybe { element_signature: _, value: Some(value) } =>
+				visitor.visit_some(value.into_owned()),
+
+			crate::Variant::Maybe { element_signature: _, value: None } =>
+				visitor.visit_none(),
+
+			crate::Variant::Variant(value) =>
+				value.into_owned().deserialize_option(visitor),
+
+			value => visitor.visit_some(value),
+		}
+	}
+
+	// Unit variants are represented by a string of their name. Other variants are represented by a struct of signature `(sv)`
+	// of their name and their fields.
+	fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		match self {
+			crate::Variant::ObjectPath(crate::ObjectPath(value)) |
+			crate::Variant::String(value) =>
+				visitor.visit_enum(serde::de::IntoDeserializer::into_deserializer(value)),
+
+			crate::Variant::Struct { fields } if matches!(&*fields, [crate::Variant::String(_), crate::Variant::Variant(_)]) => {
+				let mut fields = fields.into_owned().into_iter();
+				match (fields.next(), fields.next()) {
+					(Some(tag), Some(crate::Variant::Variant(value))) => visitor.visit_enum(EnumAccess { tag, value: value.into_owned() }),
+					_ => unreachable!(),
+				}
+			},
+
+			crate::Variant::Variant(value) =>
+				value.into_owned().deserialize_enum(name, variants, visitor),
+
+			value => value.deserialize_any(visitor),
+		}
+	}
+
 	serde::forward_to_deserialize_any! {
 		bool
 		i8 i16 i32 i64 i128
@@ -126,13 +185,10 @@ impl<'de, 'a> serde::Deserializer<'de> for crate::Variant<'de> {
 		char
 		str string
 		bytes byte_buf
-		option
 		unit unit_struct
-		newtype_struct
 		seq tuple tuple_struct
 		map
 		struct
-		enum
 		identifier
 		ignored_any
 	}
@@ -176,10 +232,48 @@ impl<'de, I> serde::de::MapAccess<'de> for MapAccess<'de, I> where I: Iterator<I
 	}
 }
 
+struct EnumAccess<'de> {
+	tag: crate::Variant<'de>,
+	value: crate::Variant<'de>,
+}
+
+impl<'de> serde::de::EnumAccess<'de> for EnumAccess<'de> {
+	type Error = crate::DeserializeError;
+	type Variant = VariantAccess<'de>;
+
+	fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> where V: serde::de::DeserializeSeed<'de> {
+		let tag = seed.deserialize(self.tag)?;
+		Ok((tag, VariantAccess(self.value)))
+	}
+}
+
+struct VariantAccess<'de>(crate::Variant<'de>);
+
+impl<'de> serde::de::VariantAccess<'de> for VariantAccess<'de> {
+	type Error = crate::DeserializeError;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error> where T: serde::de::DeserializeSeed<'de> {
+		seed.deserialize(self.0)
+	}
+
+	fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		serde::Deserializer::deserialize_any(self.0, visitor)
+	}
+
+	fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+		serde::Deserializer::deserialize_any(self.0, visitor)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	#[test]
 	fn test_variant_deserializer() {
+		#[allow(clippy::needless_pass_by_value)]
 		fn test<T>(
 			variant: crate::Variant<'_>,
 			expected_deserialize: T,
@@ -191,8 +285,8 @@ mod tests {
 		test(
 			crate::Variant::Array {
 				element_signature: crate::Signature::DictEntry {
-					key: Box::new(crate::Signature::String).into(),
-					value: Box::new(crate::Signature::U32).into(),
+					key: Box::new(crate::Signature::String),
+					value: Box::new(crate::Signature::U32),
 				},
 				elements: vec![
 					crate::Variant::DictEntry {
@@ -280,5 +374,100 @@ mod tests {
 			crate::Variant::U32(0x01020304),
 			0x01020304_u32,
 		);
+
+		{
+			#[derive(Debug, PartialEq, serde_derive::Deserialize)]
+			enum Foo {
+				#[serde(rename = "bar")]
+				Bar,
+				Baz(u32),
+				Qux(String, bool),
+				Quux { a: u8 },
+			}
+
+			test(
+				crate::Variant::String("bar".into()),
+				Foo::Bar,
+			);
+
+			test(
+				crate::Variant::Struct {
+					fields: vec![
+						crate::Variant::String("Baz".into()),
+						crate::Variant::Variant(Box::new(crate::Variant::U32(3)).into()),
+					].into(),
+				},
+				Foo::Baz(3),
+			);
+
+			test(
+				crate::Variant::Struct {
+					fields: vec![
+						crate::Variant::String("Qux".into()),
+						crate::Variant::Variant(Box::new(crate::Variant::Struct {
+							fields: vec![crate::Variant::String("abc".into()), crate::Variant::Bool(true)].into(),
+						}).into()),
+					].into(),
+				},
+				Foo::Qux("abc".to_owned(), true),
+			);
+
+			test(
+				crate::Variant::Variant(Box::new(crate::Variant::Struct {
+					fields: vec![
+						crate::Variant::String("Quux".into()),
+						crate::Variant::Variant(Box::new(crate::Variant::Struct { fields: vec![crate::Variant::U8(5)].into() }).into()),
+					].into(),
+				}).into()),
+				Foo::Quux { a: 5 },
+			);
+		}
+	}
+
+	#[test]
+	fn test_as_variant_round_trip() {
+		#[allow(clippy::needless_pass_by_value)]
+		fn test<T>(value: T) where T: crate::AsVariant + std::fmt::Debug + PartialEq + serde::de::DeserializeOwned {
+			let variant = value.as_variant();
+			assert_eq!(variant.inner_signature(), T::signature());
+			let deserialized: T = serde::de::Deserialize::deserialize(variant).unwrap();
+			assert_eq!(value, deserialized);
+		}
+
+		#[derive(Debug, PartialEq, serde_derive::Deserialize)]
+		struct Hints {
+			urgency: Option<u8>,
+			#[serde(rename = "desktop-entry")]
+			desktop_entry: Option<String>,
+			category: Option<String>,
+		}
+
+		test((3_u32, "foo".to_owned()));
+		test((true, 5_i16, (0.5_f64, vec![1_u64, 2])));
+		test(Box::new(7_u32));
+		test([1_u16, 2, 3]);
+		test((Some(1_u8), None::<String>));
+		test(vec![("foo".to_owned(), 3_u32), ("bar".to_owned(), 5)].into_iter().collect::<std::collections::BTreeMap<_, _>>());
+		test(vec![(1_u8, vec!["a".to_owned()])].into_iter().collect::<std::collections::HashMap<_, _>>());
+
+		// Variants keep their inner signature through the round trip.
+		let value: std::collections::BTreeMap<String, crate::Variant<'static>> = vec![
+			("foo".to_owned(), crate::Variant::U32(3)),
+			("bar".to_owned(), crate::Variant::Array { element_signature: crate::Signature::String, elements: vec![crate::Variant::String("baz".into())].into() }),
+			("qux".to_owned(), crate::Variant::Variant(Box::new(crate::Variant::Bool(true)).into())),
+		].into_iter().collect();
+		let variant = crate::AsVariant::as_variant(&value);
+		assert_eq!(variant.inner_signature().to_string(), "a{sv}");
+		let deserialized: std::collections::BTreeMap<String, crate::Variant<'static>> = serde::de::Deserialize::deserialize(variant).unwrap();
+		assert_eq!(value, deserialized);
+
+		// Structs with `Option` fields are deserialized from `a{sv}` dictionaries. Absent keys are `None` and unknown keys are ignored.
+		let variant = crate::dict_as_variant(vec![
+			("urgency".into(), crate::Variant::U8(2)),
+			("desktop-entry".into(), crate::Variant::String("foo".into())),
+			("x-unknown".into(), crate::Variant::Bool(true)),
+		]);
+		let deserialized: Hints = serde::de::Deserialize::deserialize(variant).unwrap();
+		assert_eq!(deserialized, Hints { urgency: Some(2), desktop_entry: Some("foo".to_owned()), category: None });
 	}
 }
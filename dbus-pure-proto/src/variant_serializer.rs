@@ -0,0 +1,853 @@
+/// Serialize the given value into a `Variant` of the given signature.
+///
+/// This allows types that impl `serde::Serialize` to be used as message bodies and method arguments without a separate
+/// [`AsVariant`](crate::AsVariant) impl. Values of the serde data model are converted to D-Bus types according to the signature:
+///
+/// - Booleans are `b`, and floating-point numbers are `d`.
+///
+/// - Integers are any of `y`, `n`, `q`, `i`, `u`, `x`, `t` and `h`, as long as the value is in range of the type.
+///
+/// - Strings and chars are `s`, `o` or `g`. Object paths and signatures are validated.
+///
+/// - `Some` is the type of its inner value, or a maybe `m..` of it. `None` can only be a maybe, since D-Bus has no equivalent.
+///
+/// - Newtype structs are the type of their inner value.
+///
+/// - Sequences and byte arrays are arrays `a..`, and maps are arrays of dict entries `a{..}`.
+///
+/// - Tuples and structs are structs `(..)` of their fields in order. They can also be a signature of more than one type,
+///   ie a message body, or a dict entry `{..}` if they have two fields. `()` is the empty signature.
+///
+/// - Structs can also be `a{s..}`, with the field names as the keys.
+///
+/// - Unit variants of enums are `s` of the name of the variant, or an integer of the index of the variant.
+///   Other variants are a struct of that tag and the content, such as `(sv)` or `(u(si))`,
+///   or a single-entry `a{s..}` from that tag to the content. The content of tuple and struct variants is a struct of their fields.
+///
+/// - `v` is any value other than `None` and `()`, with the inner signature inferred from the serde data model:
+///   `n`, `i` or `x` for signed integers, `y`, `q`, `u` or `t` for unsigned integers, `s` for strings,
+///   `a` of the element signature for sequences (or `av` if the elements do not all have the same signature),
+///   `a{..}` for maps (or `a{sv}` if the map is empty), structs for tuples and structs, and `s` or `(s..)` for enum variants.
+///
+/// # Example
+///
+/// ```rust
+/// #[derive(serde_derive::Serialize)]
+/// struct Item {
+///     name: String,
+///     id: u32,
+///     tags: Vec<&'static str>,
+/// }
+///
+/// let item = Item { name: "foo".to_owned(), id: 5, tags: vec!["a", "b"] };
+///
+/// let value = dbus_pure_proto::to_variant(&item, &"(suas)".parse().unwrap()).unwrap();
+/// assert_eq!(value.to_string(), "('foo', uint32 5, ['a', 'b'])");
+///
+/// let value = dbus_pure_proto::to_variant(&item, &"a{sv}".parse().unwrap()).unwrap();
+/// assert_eq!(value.to_string(), "{'name': <'foo'>, 'id': <uint32 5>, 'tags': <['a', 'b']>}");
+///
+/// let err = dbus_pure_proto::to_variant(&item, &"(sau)".parse().unwrap()).unwrap_err();
+/// assert_eq!(err.to_string(), r#"expected a value of type "(sau)", found a struct with 3 fields"#);
+/// ```
+pub fn to_variant<'a, T>(value: &T, signature: &crate::Signature) -> Result<crate::Variant<'a>, ToVariantError> where T: serde::Serialize + ?Sized {
+	serialize(value, Some(signature))
+}
+
+/// A `None` signature means the signature is inferred from the value.
+fn serialize<'a, T>(value: &T, signature: Option<&crate::Signature>) -> Result<crate::Variant<'a>, ToVariantError> where T: serde::Serialize + ?Sized {
+	match signature {
+		Some(crate::Signature::Variant) => {
+			let inner = value.serialize(Serializer { signature: None, _output: Default::default() })?;
+			Ok(crate::Variant::Variant(Box::new(inner).into()))
+		},
+
+		signature => value.serialize(Serializer { signature, _output: Default::default() }),
+	}
+}
+
+struct Serializer<'s, 'a> {
+	/// Never `Some(crate::Signature::Variant)`, since that is handled by [`serialize`]
+	signature: Option<&'s crate::Signature>,
+	_output: std::marker::PhantomData<fn() -> crate::Variant<'a>>,
+}
+
+impl<'s, 'a> serde::Serializer for Serializer<'s, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+	type SerializeSeq = Compound<'s, 'a>;
+	type SerializeTuple = Compound<'s, 'a>;
+	type SerializeTupleStruct = Compound<'s, 'a>;
+	type SerializeTupleVariant = Compound<'s, 'a>;
+	type SerializeMap = Compound<'s, 'a>;
+	type SerializeStruct = Compound<'s, 'a>;
+	type SerializeStructVariant = Compound<'s, 'a>;
+
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			None | Some(crate::Signature::Bool) => Ok(crate::Variant::Bool(v)),
+			Some(signature) => Err(invalid_value(signature, "bool")),
+		}
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::I16, v.into(), "i8")
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::I16, v.into(), "i16")
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::I32, v.into(), "i32")
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::I64, v.into(), "i64")
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::U8, v.into(), "u8")
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::U16, v.into(), "u16")
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::U32, v.into(), "u32")
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		integer(self.signature, &crate::Signature::U64, v.into(), "u64")
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		self.serialize_f64(v.into())
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			None | Some(crate::Signature::F64) => Ok(crate::Variant::F64(v)),
+			Some(signature) => Err(invalid_value(signature, "float")),
+		}
+	}
+
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		self.serialize_str(v.encode_utf8(&mut [0_u8; 4]))
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			None | Some(crate::Signature::String) => Ok(crate::Variant::String(v.to_owned().into())),
+
+			Some(crate::Signature::ObjectPath) => {
+				let path = crate::ObjectPath::new(v.to_owned()).map_err(ToVariantError::InvalidObjectPath)?;
+				Ok(crate::Variant::ObjectPath(path))
+			},
+
+			Some(crate::Signature::Signature) => {
+				let signature = v.parse().map_err(|_| ToVariantError::InvalidSignature(v.to_owned()))?;
+				Ok(crate::Variant::Signature(signature))
+			},
+
+			Some(signature) => Err(invalid_value(signature, "str")),
+		}
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		use serde::ser::SerializeSeq;
+
+		let mut serializer = self.serialize_seq(Some(v.len()))?;
+		for b in v {
+			serializer.serialize_element(b)?;
+		}
+		serializer.end()
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			Some(crate::Signature::Maybe { element }) => Ok(crate::Variant::Maybe { element_signature: (**element).clone(), value: None }),
+			signature => Err(invalid_value(signature.unwrap_or(&crate::Signature::Variant), "none")),
+		}
+	}
+
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		match self.signature {
+			Some(crate::Signature::Maybe { element }) => {
+				let value = serialize(value, Some(element))?;
+				Ok(crate::Variant::Maybe { element_signature: (**element).clone(), value: Some(Box::new(value).into()) })
+			},
+			_ => value.serialize(self),
+		}
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		match self.signature {
+			Some(crate::Signature::Tuple { elements }) if elements.is_empty() => Ok(crate::Variant::Tuple { elements: vec![].into() }),
+			signature => Err(invalid_value(signature.unwrap_or(&crate::Signature::Variant), "unit")),
+		}
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		self.serialize_unit()
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		tag(self.signature, variant_index, variant)
+	}
+
+	fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T>(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> where T: serde::Serialize + ?Sized {
+		let (tag_signature, content_signature) = tagged_signatures_or_inferred(self.signature, "newtype variant")?;
+		let tag = tag(tag_signature, variant_index, variant)?;
+		let content = serialize(value, content_signature)?;
+		Ok(tagged(self.signature, tag, content))
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		match self.signature {
+			None | Some(crate::Signature::Array { .. }) => Ok(Compound::new(self.signature, Kind::Seq)),
+			Some(signature) => Err(invalid_value(signature, "sequence")),
+		}
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		let () = check_struct(self.signature, len, Kind::Tuple)?;
+		Ok(Compound::new(self.signature, Kind::Tuple))
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.serialize_tuple(len)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Compound::new_variant(self.signature, variant_index, variant, len, Kind::Tuple)
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		match self.signature {
+			None | Some(crate::Signature::Array { element: _ }) if dict_entry_signatures(self.signature).is_some() =>
+				Ok(Compound::new(self.signature, Kind::Map)),
+			signature => Err(invalid_value(signature.unwrap_or(&crate::Signature::Variant), "map")),
+		}
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		let () = check_struct(self.signature, len, Kind::Struct)?;
+		Ok(Compound::new(self.signature, Kind::Struct))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Compound::new_variant(self.signature, variant_index, variant, len, Kind::Struct)
+	}
+
+	fn is_human_readable(&self) -> bool {
+		// Same as `crate::ser::Serializer`, so that a type is serialized the same way whether it is serialized via a `Variant` or not.
+		false
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Kind {
+	Map,
+	Seq,
+	Struct,
+	Tuple,
+}
+
+impl Kind {
+	pub(crate) fn name(self) -> &'static str {
+		match self {
+			Kind::Map => "map",
+			Kind::Seq => "sequence",
+			Kind::Struct => "struct",
+			Kind::Tuple => "tuple",
+		}
+	}
+}
+
+pub(crate) struct Compound<'s, 'a> {
+	signature: Option<&'s crate::Signature>,
+	kind: Kind,
+	elements: Vec<crate::Variant<'a>>,
+	entries: Vec<(crate::Variant<'a>, crate::Variant<'a>)>,
+	next_key: Option<crate::Variant<'a>>,
+	variant: Option<VariantTag<'s, 'a>>,
+}
+
+/// The tag of an enum variant whose fields are being serialized by a `Compound`.
+struct VariantTag<'s, 'a> {
+	/// The signature of the whole enum value
+	signature: Option<&'s crate::Signature>,
+	tag: crate::Variant<'a>,
+	/// Whether the content is a `v` rather than the struct of the fields
+	content_is_variant: bool,
+}
+
+impl<'s, 'a> Compound<'s, 'a> {
+	fn new(signature: Option<&'s crate::Signature>, kind: Kind) -> Self {
+		Compound {
+			signature,
+			kind,
+			elements: vec![],
+			entries: vec![],
+			next_key: None,
+			variant: None,
+		}
+	}
+
+	pub(crate) fn new_variant(signature: Option<&'s crate::Signature>, variant_index: u32, variant: &'static str, len: usize, kind: Kind) -> Result<Self, ToVariantError> {
+		let (tag_signature, content_signature) = tagged_signatures_or_inferred(signature, kind.name())?;
+		let tag = tag(tag_signature, variant_index, variant)?;
+
+		let (content_signature, content_is_variant) = match content_signature {
+			Some(crate::Signature::Variant) => (None, true),
+			content_signature => (content_signature, false),
+		};
+		let () = check_struct(content_signature, len, kind)?;
+
+		let mut result = Compound::new(content_signature, kind);
+		result.variant = Some(VariantTag { signature, tag, content_is_variant });
+		Ok(result)
+	}
+
+	fn push_element<T>(&mut self, value: &T) -> Result<(), ToVariantError> where T: serde::Serialize + ?Sized {
+		let index = self.elements.len();
+
+		let element_signature = match self.signature {
+			None => None,
+
+			Some(crate::Signature::Array { element }) => Some(&**element),
+
+			Some(signature @ crate::Signature::Struct { fields: elements }) |
+			Some(signature @ crate::Signature::Tuple { elements }) =>
+				Some(elements.get(index).ok_or_else(|| ToVariantError::InvalidLength { expected: signature.clone(), found: self.kind.name(), len: index + 1 })?),
+
+			Some(signature @ crate::Signature::DictEntry { key, value }) => match index {
+				0 => Some(&**key),
+				1 => Some(&**value),
+				_ => return Err(ToVariantError::InvalidLength { expected: signature.clone(), found: self.kind.name(), len: index + 1 }),
+			},
+
+			// `crate::Signature` serializes itself as a tuple of its wire bytes.
+			Some(crate::Signature::Signature) => Some(&crate::Signature::U8),
+
+			Some(signature) => return Err(invalid_value(signature, self.kind.name())),
+		};
+
+		self.elements.push(serialize(value, element_signature)?);
+		Ok(())
+	}
+
+	pub(crate) fn finish(self) -> Result<crate::Variant<'a>, ToVariantError> {
+		let value = match (self.signature, self.kind) {
+			(None, Kind::Map) => {
+				let (keys, values): (Vec<_>, Vec<_>) = self.entries.into_iter().unzip();
+
+				let key_signature = match common_signature(&keys) {
+					Some(key_signature) if key_signature.is_basic() => key_signature,
+					None if keys.is_empty() => crate::Signature::String,
+					_ => return Err(invalid_value(&crate::Signature::Variant, "map with keys of different or non-basic types")),
+				};
+				let (value_signature, values) = common_signature_or_variants(values);
+
+				crate::Variant::Array {
+					element_signature: crate::Signature::DictEntry { key: Box::new(key_signature), value: Box::new(value_signature) },
+					elements:
+						keys.into_iter().zip(values)
+						.map(|(key, value)| crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
+						.collect::<Vec<_>>()
+						.into(),
+				}
+			},
+
+			(None, Kind::Seq) => {
+				let (element_signature, elements) = common_signature_or_variants(self.elements);
+				crate::Variant::Array { element_signature, elements: elements.into() }
+			},
+
+			(None, kind) => {
+				if self.elements.is_empty() {
+					return Err(invalid_value(&crate::Signature::Variant, kind.name()));
+				}
+
+				crate::Variant::Struct { fields: self.elements.into() }
+			},
+
+			(Some(crate::Signature::Array { element }), Kind::Map) =>
+				crate::Variant::Array {
+					element_signature: (**element).clone(),
+					elements:
+						self.entries.into_iter()
+						.map(|(key, value)| crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() })
+						.collect::<Vec<_>>()
+						.into(),
+				},
+
+			(Some(crate::Signature::Array { element }), _) =>
+				crate::Variant::Array { element_signature: (**element).clone(), elements: self.elements.into() },
+
+			(Some(signature @ crate::Signature::Struct { fields: elements }), kind) |
+			(Some(signature @ crate::Signature::Tuple { elements }), kind) => {
+				if self.elements.len() != elements.len() {
+					return Err(ToVariantError::InvalidLength { expected: signature.clone(), found: kind.name(), len: self.elements.len() });
+				}
+
+				if let crate::Signature::Struct { .. } = signature {
+					crate::Variant::Struct { fields: self.elements.into() }
+				}
+				else {
+					crate::Variant::Tuple { elements: self.elements.into() }
+				}
+			},
+
+			(Some(signature @ crate::Signature::DictEntry { .. }), kind) => {
+				let len = self.elements.len();
+				let mut elements = self.elements.into_iter();
+				match (elements.next(), elements.next()) {
+					(Some(key), Some(value)) => crate::Variant::DictEntry { key: Box::new(key).into(), value: Box::new(value).into() },
+					_ => return Err(ToVariantError::InvalidLength { expected: signature.clone(), found: kind.name(), len }),
+				}
+			},
+
+			(Some(crate::Signature::Signature), _) => {
+				// `crate::Signature` serializes itself as its length, the signature string and a nul terminator.
+				let bytes: Vec<u8> =
+					self.elements.into_iter()
+					.filter_map(|element| if let crate::Variant::U8(b) = element { Some(b) } else { None })
+					.collect();
+				let signature_string = match bytes.split_first() {
+					Some((_, rest)) => match rest.split_last() {
+						Some((b'\0', rest)) => rest,
+						_ => rest,
+					},
+					None => &[],
+				};
+				let signature_string = String::from_utf8_lossy(signature_string);
+				let signature = signature_string.parse().map_err(|_| ToVariantError::InvalidSignature(signature_string.into_owned()))?;
+				crate::Variant::Signature(signature)
+			},
+
+			(Some(signature), kind) => return Err(invalid_value(signature, kind.name())),
+		};
+
+		Ok(match self.variant {
+			Some(VariantTag { signature, tag, content_is_variant }) => {
+				let content = if content_is_variant { crate::Variant::Variant(Box::new(value).into()) } else { value };
+				tagged(signature, tag, content)
+			},
+
+			None => value,
+		})
+	}
+}
+
+impl<'a> serde::ser::SerializeSeq for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.push_element(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeTuple for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.push_element(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeTupleStruct for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.push_element(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeTupleVariant for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		self.push_element(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeMap for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		let key_signature = dict_entry_signatures(self.signature).and_then(|(key_signature, _)| key_signature);
+		self.next_key = Some(serialize(key, key_signature)?);
+		Ok(())
+	}
+
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		let value_signature = dict_entry_signatures(self.signature).and_then(|(_, value_signature)| value_signature);
+		let key = self.next_key.take().ok_or_else(|| <ToVariantError as serde::ser::Error>::custom("map value serialized before its key"))?;
+		let value = serialize(value, value_signature)?;
+		self.entries.push((key, value));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeStruct for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		if let Some(crate::Signature::Array { element }) = self.signature {
+			if let crate::Signature::DictEntry { key: _, value: value_signature } = &**element {
+				let value = serialize(value, Some(value_signature))?;
+				self.elements.push(crate::Variant::DictEntry {
+					key: Box::new(crate::Variant::String(key.into())).into(),
+					value: Box::new(value).into(),
+				});
+				return Ok(());
+			}
+		}
+
+		self.push_element(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl<'a> serde::ser::SerializeStructVariant for Compound<'_, 'a> {
+	type Ok = crate::Variant<'a>;
+	type Error = ToVariantError;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> where T: serde::Serialize + ?Sized {
+		serde::ser::SerializeStruct::serialize_field(self, key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+pub(crate) fn invalid_value(expected: &crate::Signature, found: &'static str) -> ToVariantError {
+	ToVariantError::InvalidValue { expected: expected.clone(), found }
+}
+
+pub(crate) fn integer<'a>(signature: Option<&crate::Signature>, inferred: &crate::Signature, value: i128, found: &'static str) -> Result<crate::Variant<'a>, ToVariantError> {
+	let signature = signature.unwrap_or(inferred);
+
+	let exceeds_numeric_limits = |_| ToVariantError::ExceedsNumericLimits { expected: signature.clone(), value };
+
+	Ok(match signature {
+		crate::Signature::I16 => crate::Variant::I16(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::I32 => crate::Variant::I32(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::I64 => crate::Variant::I64(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::U8 => crate::Variant::U8(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::U16 => crate::Variant::U16(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::U32 => crate::Variant::U32(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::U64 => crate::Variant::U64(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?),
+		crate::Signature::UnixFd => crate::Variant::UnixFd(crate::UnixFd(std::convert::TryInto::try_into(value).map_err(exceeds_numeric_limits)?)),
+		signature => return Err(invalid_value(signature, found)),
+	})
+}
+
+/// Serializes the tag of an enum variant, ie the name of the variant for `s` or the index of the variant for integers.
+pub(crate) fn tag<'a>(signature: Option<&crate::Signature>, variant_index: u32, variant: &'static str) -> Result<crate::Variant<'a>, ToVariantError> {
+	match signature {
+		None | Some(crate::Signature::String) => Ok(crate::Variant::String(variant.into())),
+		Some(signature) => integer(Some(signature), signature, variant_index.into(), "unit variant"),
+	}
+}
+
+/// Gets the signatures of the tag and the content of a non-unit enum variant,
+/// from either a two-field struct or an array of dict entries.
+pub(crate) fn tagged_signatures<'s>(
+	signature: &'s crate::Signature,
+	found: &'static str,
+) -> Result<(&'s crate::Signature, &'s crate::Signature), ToVariantError> {
+	match signature {
+		crate::Signature::Struct { fields } if fields.len() == 2 => Ok((&fields[0], &fields[1])),
+
+		crate::Signature::Array { element } => match &**element {
+			crate::Signature::DictEntry { key, value } => Ok((key, value)),
+			_ => Err(invalid_value(signature, found)),
+		},
+
+		signature => Err(invalid_value(signature, found)),
+	}
+}
+
+/// Same as [`tagged_signatures`], except that the signatures are inferred if the given signature is.
+fn tagged_signatures_or_inferred<'s>(
+	signature: Option<&'s crate::Signature>,
+	found: &'static str,
+) -> Result<(Option<&'s crate::Signature>, Option<&'s crate::Signature>), ToVariantError> {
+	match signature {
+		Some(signature) => {
+			let (tag_signature, content_signature) = tagged_signatures(signature, found)?;
+			Ok((Some(tag_signature), Some(content_signature)))
+		},
+
+		None => Ok((None, None)),
+	}
+}
+
+/// The inverse of [`tagged_signatures`]
+fn tagged<'a>(signature: Option<&crate::Signature>, tag: crate::Variant<'a>, content: crate::Variant<'a>) -> crate::Variant<'a> {
+	match signature {
+		Some(crate::Signature::Array { element }) =>
+			crate::Variant::Array {
+				element_signature: (**element).clone(),
+				elements: vec![crate::Variant::DictEntry { key: Box::new(tag).into(), value: Box::new(content).into() }].into(),
+			},
+
+		_ => crate::Variant::Struct { fields: vec![tag, content].into() },
+	}
+}
+
+/// Gets the signatures of the key and value of the given array of dict entries, or `None` if the signature is not an array of dict entries.
+///
+/// A `None` signature is an array of dict entries with inferred key and value signatures.
+fn dict_entry_signatures(signature: Option<&crate::Signature>) -> Option<(Option<&crate::Signature>, Option<&crate::Signature>)> {
+	match signature {
+		None => Some((None, None)),
+
+		Some(crate::Signature::Array { element }) => match &**element {
+			crate::Signature::DictEntry { key, value } => Some((Some(key), Some(value))),
+			_ => None,
+		},
+
+		Some(_) => None,
+	}
+}
+
+/// Checks that a tuple or struct of the given length can be serialized with the given signature.
+pub(crate) fn check_struct(signature: Option<&crate::Signature>, len: usize, kind: Kind) -> Result<(), ToVariantError> {
+	let signature = match signature {
+		Some(signature) => signature,
+		None => return Ok(()),
+	};
+
+	let expected_len = match signature {
+		crate::Signature::Signature => return Ok(()),
+
+		crate::Signature::Array { element: _ } if kind == Kind::Tuple => return Ok(()),
+
+		crate::Signature::Array { element } if kind == Kind::Struct && matches!(&**element, crate::Signature::DictEntry { key, value: _ } if **key == crate::Signature::String) =>
+			return Ok(()),
+
+		crate::Signature::DictEntry { .. } => 2,
+
+		crate::Signature::Struct { fields: elements } |
+		crate::Signature::Tuple { elements } => elements.len(),
+
+		signature => return Err(invalid_value(signature, kind.name())),
+	};
+
+	if len == expected_len {
+		Ok(())
+	}
+	else {
+		Err(ToVariantError::InvalidLength { expected: signature.clone(), found: kind.name(), len })
+	}
+}
+
+/// The signature shared by all the given values, or `None` if there are no values or they don't all have the same signature.
+fn common_signature(values: &[crate::Variant<'_>]) -> Option<crate::Signature> {
+	let (first, rest) = values.split_first()?;
+	let signature = first.inner_signature();
+	if rest.iter().all(|value| value.inner_signature() == signature) {
+		Some(signature)
+	}
+	else {
+		None
+	}
+}
+
+/// Returns the signature shared by all the given values along with the values themselves,
+/// or wraps each value in a `v` if there is no such signature.
+fn common_signature_or_variants(values: Vec<crate::Variant<'_>>) -> (crate::Signature, Vec<crate::Variant<'_>>) {
+	match common_signature(&values) {
+		Some(signature) => (signature, values),
+		None => (crate::Signature::Variant, values.into_iter().map(|value| crate::Variant::Variant(Box::new(value).into())).collect()),
+	}
+}
+
+/// An error from serializing a value into a `Variant` with [`to_variant`].
+#[derive(Debug)]
+pub enum ToVariantError {
+	Custom(String),
+
+	/// The integer is out of range of the expected type.
+	ExceedsNumericLimits { expected: crate::Signature, value: i128 },
+
+	/// The tuple or struct has a different number of fields than the expected type.
+	InvalidLength { expected: crate::Signature, found: &'static str, len: usize },
+
+	InvalidObjectPath(crate::NameError),
+
+	InvalidSignature(String),
+
+	/// The value cannot be serialized as the expected type.
+	///
+	/// The expected type is `v` if the signature of the value was being inferred.
+	InvalidValue { expected: crate::Signature, found: &'static str },
+}
+
+impl std::fmt::Display for ToVariantError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ToVariantError::Custom(message) => f.write_str(message),
+			ToVariantError::ExceedsNumericLimits { expected, value } =>
+				write!(f, "value {} exceeds numeric limits of type {:?}", value, expected.to_string()),
+			ToVariantError::InvalidLength { expected, found, len } =>
+				write!(f, "expected a value of type {:?}, found a {} with {} fields", expected.to_string(), found, len),
+			ToVariantError::InvalidObjectPath(_) => f.write_str("invalid object path"),
+			ToVariantError::InvalidSignature(signature) => write!(f, "invalid signature {:?}", signature),
+			ToVariantError::InvalidValue { expected, found } =>
+				write!(f, "expected a value of type {:?}, found {}", expected.to_string(), found),
+		}
+	}
+}
+
+impl std::error::Error for ToVariantError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			ToVariantError::InvalidObjectPath(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+/// Errors of converting a variant with [`crate::Variant::with_signature`] while sending it, such as in [`crate::AsVariant::try_as_variant`]
+impl From<crate::FromVariantError> for ToVariantError {
+	fn from(err: crate::FromVariantError) -> Self {
+		match err {
+			crate::FromVariantError::InvalidObjectPath(_, err) => ToVariantError::InvalidObjectPath(err),
+			crate::FromVariantError::InvalidSignature(signature) => ToVariantError::InvalidSignature(signature),
+			err => ToVariantError::Custom(err.to_string()),
+		}
+	}
+}
+
+impl serde::ser::Error for ToVariantError {
+	fn custom<T>(msg: T) -> Self where T: std::fmt::Display {
+		ToVariantError::Custom(msg.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_to_variant() {
+		#[derive(serde_derive::Serialize)]
+		struct Foo<'a> {
+			a: u8,
+			b: Option<&'a str>,
+			c: std::collections::BTreeMap<&'a str, u32>,
+		}
+
+		#[derive(serde_derive::Serialize)]
+		enum Bar {
+			A,
+			B(i32),
+			C(u8, bool),
+			D { e: String },
+		}
+
+		fn to_variant<T>(value: &T, signature: &str) -> Result<String, String> where T: serde::Serialize + ?Sized {
+			let signature = crate::Signature::from_gvariant_type(signature).unwrap();
+			let value = super::to_variant(value, &signature).map_err(|err| err.to_string())?;
+			assert_eq!(value.inner_signature(), signature);
+			Ok(value.to_string())
+		}
+
+		let foo = Foo { a: 5, b: Some("/foo"), c: vec![("x", 1), ("y", 2)].into_iter().collect() };
+
+		assert_eq!(to_variant(&foo, "(yoa{su})"), Ok("(byte 0x05, objectpath '/foo', {'x': uint32 1, 'y': 2})".to_owned()));
+		assert_eq!(to_variant(&foo, "yga{st}"), Err(r#"invalid signature "/foo""#.to_owned()));
+		assert_eq!(to_variant(&foo, "a{sv}"), Ok("{'a': <byte 0x05>, 'b': <'/foo'>, 'c': <{'x': uint32 1, 'y': 2}>}".to_owned()));
+		assert_eq!(to_variant(&foo, "(ysa{sy})"), Ok("(byte 0x05, '/foo', {'x': byte 0x01, 'y': 0x02})".to_owned()));
+		assert_eq!(to_variant(&foo, "(ysa{ty})"), Err(r#"expected a value of type "t", found str"#.to_owned()));
+		assert_eq!(to_variant(&foo, "a{ss}"), Err(r#"expected a value of type "s", found u8"#.to_owned()));
+		assert_eq!(to_variant(&foo, "(ys)"), Err(r#"expected a value of type "(ys)", found a struct with 3 fields"#.to_owned()));
+
+		assert_eq!(to_variant(&foo, "(ymsa{su})"), Ok("(byte 0x05, just '/foo', {'x': uint32 1, 'y': 2})".to_owned()));
+		assert_eq!(to_variant(&Foo { b: None, ..foo }, "(ymsa{su})"), Ok("(byte 0x05, @ms nothing, {'x': uint32 1, 'y': 2})".to_owned()));
+
+		assert_eq!(to_variant(&300_u32, "n"), Ok("int16 300".to_owned()));
+		assert_eq!(to_variant(&300_u32, "y"), Err(r#"value 300 exceeds numeric limits of type "y""#.to_owned()));
+		assert_eq!(to_variant(&-1_i32, "t"), Err(r#"value -1 exceeds numeric limits of type "t""#.to_owned()));
+
+		assert_eq!(to_variant(&(), ""), Ok("()".to_owned()));
+		assert_eq!(to_variant(&("a", 5_u32), "su"), Ok("('a', uint32 5)".to_owned()));
+		assert_eq!(to_variant(&("a", 5_u32), "{su}"), Ok("{'a', uint32 5}".to_owned()));
+		assert_eq!(to_variant(&[1_u8, 2], "ay"), Ok("[byte 0x01, 0x02]".to_owned()));
+		assert_eq!(to_variant(&None::<u8>, "y"), Err(r#"expected a value of type "y", found none"#.to_owned()));
+		assert_eq!(to_variant(&"a{sv}".parse::<crate::Signature>().unwrap(), "g"), Ok("signature 'a{sv}'".to_owned()));
+
+		assert_eq!(to_variant(&Bar::A, "s"), Ok("'A'".to_owned()));
+		assert_eq!(to_variant(&Bar::A, "u"), Ok("uint32 0".to_owned()));
+		assert_eq!(to_variant(&Bar::B(5), "(sv)"), Ok("('B', <5>)".to_owned()));
+		assert_eq!(to_variant(&Bar::B(5), "(qi)"), Ok("(uint16 1, 5)".to_owned()));
+		assert_eq!(to_variant(&Bar::C(5, true), "a{s(yb)}"), Ok("{'C': (byte 0x05, true)}".to_owned()));
+		assert_eq!(to_variant(&Bar::D { e: "f".to_owned() }, "(sa{ss})"), Ok("('D', {'e': 'f'})".to_owned()));
+		assert_eq!(to_variant(&Bar::D { e: "f".to_owned() }, "(s(s))"), Ok("('D', ('f',))".to_owned()));
+		assert_eq!(to_variant(&Bar::B(5), "i"), Err(r#"expected a value of type "i", found newtype variant"#.to_owned()));
+
+		assert_eq!(to_variant(&Bar::C(5, true), "v"), Ok("<('C', (byte 0x05, true))>".to_owned()));
+		assert_eq!(to_variant(&vec![vec![1_u32], vec![]], "v"), Ok("<[<[uint32 1]>, <@av []>]>".to_owned()));
+		assert_eq!(to_variant(&(vec![1_i64], vec![1_i64, 2]), "v"), Ok("<([int64 1], [int64 1, 2])>".to_owned()));
+		assert_eq!(to_variant(&vec![Some(1_u32), Some(2)], "av"), Ok("[<uint32 1>, <uint32 2>]".to_owned()));
+		assert_eq!(to_variant(&vec![()], "v"), Err(r#"expected a value of type "v", found unit"#.to_owned()));
+		assert_eq!(to_variant(&std::collections::BTreeMap::<u8, u8>::new(), "v"), Ok("<@a{sv} {}>".to_owned()));
+		assert_eq!(to_variant(&None::<u8>, "v"), Err(r#"expected a value of type "v", found none"#.to_owned()));
+	}
+}
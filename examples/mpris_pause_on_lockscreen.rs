@@ -5,6 +5,8 @@
 	clippy::let_and_return,
 	clippy::let_unit_value,
 	clippy::too_many_lines,
+	clippy::uninlined_format_args,
+	clippy::unnecessary_debug_formatting,
 	clippy::unneeded_field_pattern,
 )]
 
@@ -35,34 +37,26 @@ fn main() -> Result<(), Error> {
 	let mut client = dbus_pure::Client::new(connection)?;
 
 	// Add a match for all screen lock and unlock events. These events manifest as the `org.freedesktop.ScreenSaver.ActiveChanged` signal
-	// from the `/org/freedesktop/ScreenSaver` object.
+	// from the `/org/freedesktop/ScreenSaver` object, sent by the owner of the `org.freedesktop.ScreenSaver` name.
 	//
-	// Adding a match is done by calling the `org.freedesktop.DBus.AddMatch` method on the `/org/freedesktop/DBus` object
-	// at the destination `org.freedesktop.DBus`. The method takes a single string parameter for the match rule.
-	{
-		let obj = OrgFreeDesktopDbusObject {
-			name: "org.freedesktop.DBus".into(),
-			path: dbus_pure::proto::ObjectPath("/org/freedesktop/DBus".into()),
-		};
-		let () =
-			obj.add_match(
-				&mut client,
-				"type='signal',path='/org/freedesktop/ScreenSaver',interface='org.freedesktop.ScreenSaver',member='ActiveChanged'",
-			)?;
-	}
+	// Adding a match calls the `org.freedesktop.DBus.AddMatch` method on the `/org/freedesktop/DBus` object
+	// at the destination `org.freedesktop.DBus`. Since the sender is a well-known name, the client also tracks the name's owner,
+	// so that `recv_signal` ignores `ActiveChanged` signals sent by any other connection.
+	let screen_saver_active_changed = dbus_pure::MatchRule {
+		sender: Some("org.freedesktop.ScreenSaver".into()),
+		path: Some(dbus_pure::proto::ObjectPath::new("/org/freedesktop/ScreenSaver")?),
+		interface: Some("org.freedesktop.ScreenSaver".into()),
+		member: Some("ActiveChanged".into()),
+	};
+	let () = client.add_match(&screen_saver_active_changed)?;
 
 	let mut players_to_resume: std::collections::BTreeSet<_> = Default::default();
 
 	loop {
 		let locked = {
-			let (header, body) = client.recv()?;
-			match header.r#type {
-				dbus_pure::proto::MessageType::Signal { interface, member, path: _ }
-					if interface == "org.freedesktop.ScreenSaver" && member == "ActiveChanged" => (),
-				_ => continue,
-			}
+			let message = client.recv_signal(&screen_saver_active_changed)?;
 
-			let body = body.ok_or("ActiveChanged signal does not have a body")?;
+			let body = message.body.ok_or("ActiveChanged signal does not have a body")?;
 			let body: bool = serde::Deserialize::deserialize(body)?;
 			body
 		};
@@ -74,8 +68,8 @@ fn main() -> Result<(), Error> {
 			// on the `/org/freedesktop/DBus` object at the destination `org.freedesktop.DBus`.
 			let names = {
 				let obj = OrgFreeDesktopDbusObject {
-					name: "org.freedesktop.DBus".into(),
-					path: dbus_pure::proto::ObjectPath("/org/freedesktop/DBus".into()),
+					name: dbus_pure::proto::BusName::new("org.freedesktop.DBus")?,
+					path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus")?,
 				};
 				let names = obj.list_names(&mut client)?;
 				names
@@ -88,8 +82,8 @@ fn main() -> Result<(), Error> {
 
 			for media_player_name in media_player_names {
 				let obj = OrgMprisMediaPlayer2Object {
-					name: (&*media_player_name).into(),
-					path: dbus_pure::proto::ObjectPath("/org/mpris/MediaPlayer2".into()),
+					name: dbus_pure::proto::BusName::new(&*media_player_name)?,
+					path: dbus_pure::proto::ObjectPath::new("/org/mpris/MediaPlayer2")?,
 				};
 
 				// Get the playback status of the media player by gettings its `PlaybackStatus` property.
@@ -120,8 +114,8 @@ fn main() -> Result<(), Error> {
 		else {
 			for media_player_name in std::mem::take(&mut players_to_resume) {
 				let obj = OrgMprisMediaPlayer2Object {
-					name: (&*media_player_name).into(),
-					path: dbus_pure::proto::ObjectPath("/org/mpris/MediaPlayer2".into()),
+					name: dbus_pure::proto::BusName::new(&*media_player_name)?,
+					path: dbus_pure::proto::ObjectPath::new("/org/mpris/MediaPlayer2")?,
 				};
 
 				println!("Unpausing {} ...", media_player_name);
@@ -161,9 +155,6 @@ impl std::fmt::Debug for Error {
 
 #[dbus_pure_macros::interface("org.freedesktop.DBus")]
 trait OrgFreeDesktopDbusInterface {
-	#[name = "AddMatch"]
-	fn add_match(rule: &str);
-
 	#[name = "ListNames"]
 	fn list_names() -> Vec<String>;
 }
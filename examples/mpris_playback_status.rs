@@ -2,6 +2,8 @@
 #![deny(clippy::all, clippy::pedantic)]
 #![allow(
 	clippy::let_and_return,
+	clippy::uninlined_format_args,
+	clippy::unnecessary_debug_formatting,
 )]
 
 // Connects to the session bus, enumerates all media players that implement MPRIS, and prints their playback status.
@@ -32,8 +34,8 @@ fn main() -> Result<(), Error> {
 	// on the `/org/freedesktop/DBus` object at the destination `org.freedesktop.DBus`.
 	let names = {
 		let obj = OrgFreeDesktopDbusObject {
-			name: "org.freedesktop.DBus".into(),
-			path: dbus_pure::proto::ObjectPath("/org/freedesktop/DBus".into()),
+			name: dbus_pure::proto::BusName::new("org.freedesktop.DBus")?,
+			path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus")?,
 		};
 		let names = obj.list_names(&mut client)?;
 		names
@@ -54,8 +56,8 @@ fn main() -> Result<(), Error> {
 		// with two parameters - the interface name and the property name.
 		let playback_status = {
 			let obj = OrgMprisMediaPlayer2Object {
-				name: (&**media_player_name).into(),
-				path: dbus_pure::proto::ObjectPath("/org/mpris/MediaPlayer2".into()),
+				name: dbus_pure::proto::BusName::new(&**media_player_name)?,
+				path: dbus_pure::proto::ObjectPath::new("/org/mpris/MediaPlayer2")?,
 			};
 			let playback_status = obj.get(&mut client, "org.mpris.MediaPlayer2.Player", "PlaybackStatus")?;
 			let playback_status: String = serde::Deserialize::deserialize(playback_status)?;
@@ -0,0 +1,51 @@
+#![deny(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::uninlined_format_args,
+)]
+
+// Connects to the session bus and prints media players that implement MPRIS as they start and quit.
+
+fn main() -> Result<(), Error> {
+	let connection =
+		dbus_pure::Connection::new(
+			dbus_pure::BusPath::Session,
+			dbus_pure::SaslAuthType::Uid,
+		)?;
+
+	let mut client = dbus_pure::Client::new(connection)?;
+
+	// MPRIS media players have names that start with "org.mpris.MediaPlayer2."
+	//
+	// The watcher reports the players that are already running first, then players that start or quit later.
+	let mut watcher = dbus_pure::NameWatcher::watch_namespace(&mut client, "org.mpris.MediaPlayer2")?;
+
+	loop {
+		match watcher.next_event(&mut client)? {
+			dbus_pure::NameEvent::Appeared { name, owner } => println!("{} appeared with owner {}", name, owner),
+			dbus_pure::NameEvent::Vanished { name } => println!("{} vanished", name),
+		}
+	}
+}
+
+struct Error(Box<dyn std::error::Error>);
+
+impl<E> From<E> for Error where E: Into<Box<dyn std::error::Error>> {
+	fn from(err: E) -> Self {
+		Error(err.into())
+	}
+}
+
+impl std::fmt::Debug for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{}", self.0)?;
+
+		let mut source = self.0.source();
+		while let Some(err) = source {
+			writeln!(f, "caused by: {}", err)?;
+			source = err.source();
+		}
+
+		Ok(())
+	}
+}
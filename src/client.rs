@@ -3,7 +3,29 @@ pub struct Client {
 	connection: crate::conn::Connection,
 	last_serial: u32,
 	name: Option<String>,
-	received_messages: std::collections::VecDeque<(crate::proto::MessageHeader<'static>, Option<crate::proto::Variant<'static>>)>,
+	received_messages: std::collections::VecDeque<crate::proto::Message<'static>>,
+
+	/// The retained message that was last returned by [`Client::recv_borrowed`], which the returned message borrows from.
+	borrowed_message: Option<crate::proto::Message<'static>>,
+
+	tracked_names: std::collections::BTreeMap<String, TrackedName>,
+
+	/// The number of match rules and [`crate::NameWatcher`]s that subscribe the client to `NameOwnerChanged` signals.
+	///
+	/// If this is zero, `NameOwnerChanged` signals for tracked names were only received because of the client's own tracking rules,
+	/// so they are consumed rather than being returned to the caller.
+	pub(crate) num_name_owner_changed_subscriptions: usize,
+}
+
+/// The maximum number of received messages that are retained for subsequent calls to [`Client::recv`] while waiting for
+/// a different message. Once this is exceeded, the oldest retained messages are discarded.
+const MAX_RECEIVED_MESSAGES: usize = 1024;
+
+/// A well-known name whose owner is being tracked because it's used as the sender of a match rule.
+#[derive(Debug)]
+struct TrackedName {
+	owner: Option<String>,
+	num_match_rules: usize,
 }
 
 impl Client {
@@ -16,14 +38,13 @@ impl Client {
 			last_serial: 0,
 			name: None,
 			received_messages: Default::default(),
+			borrowed_message: None,
+			tracked_names: Default::default(),
+			num_name_owner_changed_subscriptions: 0,
 		};
 
 		client.name = Some({
-			let obj = OrgFreeDesktopDbusObject {
-				name: "org.freedesktop.DBus".into(),
-				path: crate::proto::ObjectPath("/org/freedesktop/DBus".into()),
-			};
-
+			let obj = OrgFreeDesktopDbusObject::bus();
 			let name = obj.hello(&mut client).map_err(CreateClientError::Hello)?;
 			name
 		});
@@ -37,32 +58,53 @@ impl Client {
 		self.name = Some(name);
 	}
 
-	/// Send a message with the given header and body.
+	/// Send the given message.
 	///
 	/// - The header serial will be overwritten to a unique serial number, and does not need to be set to any specific value by the caller.
 	///
-	/// - Header fields corresponding to the required properties of the message type will be automatically inserted, and *must not* be inserted by the caller.
-	///   For example, if `header.type` is `MethodCall { member, path }`, the `MessageHeaderField::Member` and `MessageHeaderField::Path` fields
-	///   will be inserted automatically.
+	/// - The `MessageHeaderField::Sender` field will be set to the name of this client, replacing any existing one.
 	///
-	/// - The `MessageHeaderField::Sender` field will be automatically inserted and must not be inserted by the caller.
+	/// Returns the serial of the message.
+	pub fn send(&mut self, message: &mut crate::proto::Message<'_>) -> Result<u32, crate::conn::SendError> {
+		let serial = self.prepare_send(message);
+
+		let () = self.connection.send(message)?;
+
+		Ok(serial)
+	}
+
+	/// Send the given message with the given value as its body.
 	///
-	/// - The `MessageHeaderField::Signature` field will be automatically inserted if a body is specified, and must not be inserted by the caller.
+	/// This serializes the body directly without converting it to a `Variant`, and ignores the `body` of the message.
+	/// See [`crate::proto::serialize_message_with_body`] for details. Otherwise this behaves the same as [`Client::send`].
 	///
 	/// Returns the serial of the message.
-	pub fn send(&mut self, header: &mut crate::proto::MessageHeader<'_>, body: Option<&crate::proto::Variant<'_>>) -> Result<u32, crate::conn::SendError> {
+	pub fn send_with_body<T>(&mut self, message: &mut crate::proto::Message<'_>, body: &T) -> Result<u32, crate::conn::SendError>
+	where
+		T: serde::Serialize + crate::proto::AsVariant + ?Sized,
+	{
+		let serial = self.prepare_send(message);
+
+		let () = self.connection.send_with_body(message, body)?;
+
+		Ok(serial)
+	}
+
+	/// Sets the serial and sender of the given message before it's sent, and returns the serial.
+	fn prepare_send(&mut self, message: &mut crate::proto::Message<'_>) -> u32 {
 		// Serial is in the range 1..=u32::max_value() , ie it rolls over to 1 rather than 0
 		self.last_serial = self.last_serial % u32::max_value() + 1;
-		header.serial = self.last_serial;
+		message.header.serial = self.last_serial;
 
 		if let Some(name) = &self.name {
-			// name is cloned because the lifetime of self.name needs to be independent of the lifetime of header
-			header.fields.to_mut().push(crate::proto::MessageHeaderField::Sender(name.clone().into()));
-		}
+			let fields = message.header.fields.to_mut();
+			fields.retain(|field| !matches!(field, crate::proto::MessageHeaderField::Sender(_)));
 
-		let () = self.connection.send(header, body)?;
+			// name is cloned because the lifetime of self.name needs to be independent of the lifetime of the message
+			fields.push(crate::proto::MessageHeaderField::Sender(name.clone().into()));
+		}
 
-		Ok(self.last_serial)
+		self.last_serial
 	}
 
 	/// A convenience wrapper around sending a `METHOD_CALL` message and receiving the corresponding `METHOD_RETURN` or `ERROR` response.
@@ -74,43 +116,31 @@ impl Client {
 	///   `Some(&Variant::Tuple { elements: (&[Variant::String(...), Variant::U8(...)][..]).into() })`
 	pub fn method_call(
 		&mut self,
-		destination: &str,
+		destination: crate::proto::BusName<'_>,
 		path: crate::proto::ObjectPath<'_>,
-		interface: &str,
-		member: &str,
+		interface: crate::proto::InterfaceName<'_>,
+		member: crate::proto::MemberName<'_>,
 		parameters: Option<&crate::proto::Variant<'_>>,
 	) -> Result<Option<crate::proto::Variant<'static>>, MethodCallError> {
-		let request_header_fields = &[
-			crate::proto::MessageHeaderField::Destination(destination.into()),
-			crate::proto::MessageHeaderField::Interface(interface.into()),
-		][..];
-		let mut request_header = crate::proto::MessageHeader {
-			r#type: crate::proto::MessageType::MethodCall {
-				member: member.into(),
-				path,
-			},
-			flags: crate::proto::message_flags::NONE,
-			body_len: 0,
-			serial: 0,
-			fields: request_header_fields.into(),
-		};
+		let mut request = crate::proto::Message::method_call(destination, path, interface, member);
+		request.body = parameters.map(crate::proto::Variant::borrowed);
 
-		self.send(&mut request_header, parameters).map_err(MethodCallError::SendRequest)?;
+		let request_serial = self.send(&mut request).map_err(MethodCallError::SendRequest)?;
 
-		let response = self.recv_matching(|header, _| {
-			match header.r#type {
-				crate::proto::MessageType::Error { reply_serial, .. } if reply_serial == request_header.serial => true,
-				crate::proto::MessageType::MethodReturn { reply_serial, .. } if reply_serial == request_header.serial => true,
+		let response = self.recv_matching(|message| {
+			match message.header.r#type {
+				crate::proto::MessageType::Error { reply_serial, .. } if reply_serial == request_serial => true,
+				crate::proto::MessageType::MethodReturn { reply_serial, .. } if reply_serial == request_serial => true,
 				_ => false,
 			}
 		}).map_err(MethodCallError::RecvResponse)?;
 
-		match response.0.r#type {
+		match response.header.r#type {
 			crate::proto::MessageType::Error { name, reply_serial: _ } =>
-				Err(MethodCallError::Error(name.into_owned(), response.1)),
+				Err(MethodCallError::Error(name.into_inner().into_owned().into(), response.body)),
 
 			crate::proto::MessageType::MethodReturn { reply_serial: _ } =>
-				Ok(response.1),
+				Ok(response.body),
 
 			_ => unreachable!(),
 		}
@@ -119,7 +149,7 @@ impl Client {
 	/// Receive a message from the message bus.
 	///
 	/// Blocks until a message is received.
-	pub fn recv(&mut self) -> Result<(crate::proto::MessageHeader<'static>, Option<crate::proto::Variant<'static>>), crate::conn::RecvError> {
+	pub fn recv(&mut self) -> Result<crate::proto::Message<'static>, crate::conn::RecvError> {
 		if let Some(message) = self.received_messages.pop_front() {
 			return Ok(message);
 		}
@@ -127,33 +157,409 @@ impl Client {
 		self.recv_new()
 	}
 
+	/// Receive a message from the message bus, borrowing its data from the connection's read buffer where possible.
+	///
+	/// Unlike [`Client::recv`], strings, object paths and byte arrays in a newly received message are not copied out of the read buffer,
+	/// so the message can only be used until the next message is received. Use [`crate::proto::Message::into_owned`] to keep it for longer.
+	///
+	/// Blocks until a message is received.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// #
+	/// let connection = dbus_pure::Connection::new(dbus_pure::BusPath::Session, dbus_pure::SaslAuthType::Uid)?;
+	/// let mut client = dbus_pure::Client::new(connection)?;
+	///
+	/// client.add_match(&dbus_pure::MatchRule { interface: Some("org.example.Foo".into()), ..Default::default() })?;
+	///
+	/// let mut signal =
+	///     dbus_pure::proto::Message::signal(
+	///         dbus_pure::proto::ObjectPath::new("/org/example/Foo")?,
+	///         dbus_pure::proto::InterfaceName::new("org.example.Foo")?,
+	///         dbus_pure::proto::MemberName::new("Changed")?,
+	///     )
+	///     .with_body(dbus_pure::proto::Variant::ArrayU8((&b"contents"[..]).into()));
+	/// let _ = client.send(&mut signal)?;
+	///
+	/// let contents = loop {
+	///     let message = client.recv_borrowed()?;
+	///     if message.member() == Some("Changed") {
+	///         match message.body {
+	///             // The contents were not copied out of the read buffer, so copy them to keep them.
+	///             Some(dbus_pure::proto::Variant::ArrayU8(std::borrow::Cow::Borrowed(contents))) => break contents.to_vec(),
+	///             body => return Err(format!("unexpected body {:?}", body).into()),
+	///         }
+	///     }
+	/// };
+	/// assert_eq!(contents, b"contents");
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn recv_borrowed(&mut self) -> Result<crate::proto::Message<'_>, crate::conn::RecvError> {
+		if let Some(message) = self.received_messages.pop_front() {
+			let message = self.borrowed_message.insert(message);
+			return Ok(message.borrowed());
+		}
+
+		if self.tracked_names.is_empty() || self.num_name_owner_changed_subscriptions > 0 {
+			// No message will be consumed, so the first one received is the one to return.
+			let message = self.connection.recv_borrowed()?;
+			let _ = track_name_owner_changed(&mut self.tracked_names, self.num_name_owner_changed_subscriptions, &message);
+			return Ok(message);
+		}
+
+		// A message that's returned can't be borrowed in a loop that might receive another one instead,
+		// so skip the consumed messages first and then deserialize the last one again.
+		loop {
+			let message = self.connection.recv_borrowed()?;
+			if !track_name_owner_changed(&mut self.tracked_names, self.num_name_owner_changed_subscriptions, &message) {
+				break;
+			}
+		}
+
+		let message = self.connection.last_message_borrowed().map_err(crate::conn::RecvError::Deserialize)?;
+		Ok(message)
+	}
+
+	/// Receive a message from the message bus, deserializing its body into a value of type `T`.
+	///
+	/// The `body` of the returned message is always `None`, but [`crate::proto::Message::signature`] is still the signature of the body.
+	/// A message without a body is deserialized as `()`.
+	///
+	/// Blocks until a message is received. If the body cannot be deserialized into a `T`, this function fails with `RecvError::Deserialize`,
+	/// and the message is retained so that it can be received with [`Client::recv`] instead.
+	///
+	/// Messages that were not already received by a previous call are deserialized directly without converting the body to a `Variant` first.
+	/// See [`crate::proto::deserialize_message_with_body`] for details.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// #
+	/// let connection = dbus_pure::Connection::new(dbus_pure::BusPath::Session, dbus_pure::SaslAuthType::Uid)?;
+	/// let mut client = dbus_pure::Client::new(connection)?;
+	///
+	/// client.add_match(&dbus_pure::MatchRule { interface: Some("org.example.Foo".into()), ..Default::default() })?;
+	///
+	/// let mut signal =
+	///     dbus_pure::proto::Message::signal(
+	///         dbus_pure::proto::ObjectPath::new("/org/example/Foo")?,
+	///         dbus_pure::proto::InterfaceName::new("org.example.Foo")?,
+	///         dbus_pure::proto::MemberName::new("Changed")?,
+	///     )
+	///     .with_body(dbus_pure::proto::Variant::U32(5));
+	/// let _ = client.send(&mut signal)?;
+	///
+	/// let (message, body) = loop {
+	///     match client.recv_with_body::<u32>() {
+	///         Ok((message, body)) if message.member() == Some("Changed") => break (message, body),
+	///         Ok(_) => (),
+	///
+	///         // Messages whose body is not a `u32`, such as the `NameAcquired` signal that the bus sends to every new client,
+	///         // are kept for `recv`.
+	///         Err(dbus_pure::RecvError::Deserialize(_)) => {
+	///             let message = client.recv()?;
+	///             assert_eq!(message.member(), Some("NameAcquired"));
+	///         },
+	///
+	///         Err(err) => return Err(err.into()),
+	///     }
+	/// };
+	/// assert_eq!(body, 5);
+	/// assert_eq!(message.signature(), dbus_pure::proto::Signature::U32);
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn recv_with_body<T>(&mut self) -> Result<(crate::proto::Message<'static>, T), crate::conn::RecvError> where T: serde::de::DeserializeOwned {
+		if let Some(mut message) = self.received_messages.pop_front() {
+			let body = match &message.body {
+				Some(body) => serde::Deserialize::deserialize(body.borrowed()),
+				None => serde::Deserialize::deserialize(serde::de::IntoDeserializer::<crate::proto::DeserializeError>::into_deserializer(())),
+			};
+			return match body {
+				Ok(body) => {
+					// Keep the signature of the body in the header fields, the same as `deserialize_message_with_body` does.
+					if let Some(message_body) = message.body.take() {
+						message.header.fields.to_mut().push(crate::proto::MessageHeaderField::Signature(message_body.inner_signature()));
+					}
+					Ok((message, body))
+				},
+				Err(err) => {
+					self.received_messages.push_front(message);
+					Err(crate::conn::RecvError::Deserialize(err))
+				},
+			};
+		}
+
+		loop {
+			match self.connection.recv_with_body() {
+				Ok((message, body)) => {
+					// Only NameOwnerChanged signals for tracked names need their body as a `Variant`,
+					// so the message is only deserialized again if it's such a signal.
+					if !self.tracked_names.is_empty() && is_name_owner_changed_signal(&message) {
+						let message = self.connection.last_message().map_err(crate::conn::RecvError::Deserialize)?;
+						if track_name_owner_changed(&mut self.tracked_names, self.num_name_owner_changed_subscriptions, &message) {
+							continue;
+						}
+					}
+
+					return Ok((message, body));
+				},
+
+				Err(crate::conn::RecvError::Deserialize(err)) => {
+					// The body didn't match `T`, so keep the message for `recv`. If the message itself is invalid, there's nothing to keep.
+					if let Ok(message) = self.connection.last_message() {
+						if !track_name_owner_changed(&mut self.tracked_names, self.num_name_owner_changed_subscriptions, &message) {
+							self.retain_received_message(message);
+						}
+					}
+
+					return Err(crate::conn::RecvError::Deserialize(err));
+				},
+
+				Err(err) => return Err(err),
+			}
+		}
+	}
+
 	/// Receive a message from the message bus that satisfies the given predicate.
 	///
 	/// Messages that do not match the predicate will not be discarded. Instead they will be returned
-	/// from subsequent calls to [`Client::recv`] or `recv_matching`.
+	/// from subsequent calls to [`Client::recv`] or `recv_matching`. At most 1024 such messages are retained;
+	/// beyond that, the oldest ones are discarded.
 	pub fn recv_matching(
 		&mut self,
-		mut predicate: impl FnMut(&crate::proto::MessageHeader<'static>, Option<&crate::proto::Variant<'static>>) -> bool,
-	) -> Result<(crate::proto::MessageHeader<'static>, Option<crate::proto::Variant<'static>>), crate::conn::RecvError> {
+		mut predicate: impl FnMut(&crate::proto::Message<'static>) -> bool,
+	) -> Result<crate::proto::Message<'static>, crate::conn::RecvError> {
 		for (i, already_received_message) in self.received_messages.iter().enumerate() {
-			if predicate(&already_received_message.0, already_received_message.1.as_ref()) {
+			if predicate(already_received_message) {
 				let result = self.received_messages.remove(i).unwrap();
 				return Ok(result);
 			}
 		}
 
 		loop {
-			let (header, body) = self.recv_new()?;
-			if predicate(&header, body.as_ref()) {
-				return Ok((header, body));
+			let message = self.recv_new()?;
+			if predicate(&message) {
+				return Ok(message);
+			}
+
+			self.retain_received_message(message);
+		}
+	}
+
+	/// Subscribe to signals matching the given rule, by calling the `org.freedesktop.DBus.AddMatch` method.
+	///
+	/// If the rule's sender is a well-known name, the client also starts tracking the unique name of that name's owner,
+	/// so that [`Client::recv_signal`] only matches signals that were actually sent by the owner. The `NameOwnerChanged` signals
+	/// that the client receives for this purpose are consumed internally, unless the client is also subscribed to them
+	/// by another match rule or a [`crate::NameWatcher`].
+	///
+	/// If this fails, any subscriptions it made are undone.
+	pub fn add_match(&mut self, rule: &crate::MatchRule<'_>) -> Result<(), MethodCallError> {
+		let obj = OrgFreeDesktopDbusObject::bus();
+
+		let rule_string = rule.to_string();
+
+		let () = obj.add_match(self, &rule_string)?;
+
+		if let Some(name) = rule.well_known_sender() {
+			if let Some(tracked_name) = self.tracked_names.get_mut(name) {
+				tracked_name.num_match_rules += 1;
+			}
+			else {
+				// Subscribe before querying the current owner, so that no changes are missed in between.
+				let name_owner_changed_match_rule = crate::match_rule::name_owner_changed_match_rule("arg0", name);
+				if let Err(err) = obj.add_match(self, &name_owner_changed_match_rule) {
+					let _ = obj.remove_match(self, &rule_string);
+					return Err(err);
+				}
+
+				let _ = self.tracked_names.insert(name.to_owned(), TrackedName {
+					owner: None,
+					num_match_rules: 1,
+				});
+
+				let owner = match obj.get_name_owner(self, name) {
+					Ok(owner) => Some(owner),
+					Err(MethodCallError::Error(crate::ErrorKind::NameHasNoOwner, _)) => None,
+					Err(err) => {
+						let _ = self.tracked_names.remove(name);
+						let _ = obj.remove_match(self, &name_owner_changed_match_rule);
+						let _ = obj.remove_match(self, &rule_string);
+						return Err(err);
+					},
+				};
+
+				// The bus sends the GetNameOwner response after any NameOwnerChanged signals that were received while waiting for it,
+				// so the response is the most recent information and overrides whatever owner those signals set.
+				let tracked_name = self.tracked_names.get_mut(name).expect("just inserted");
+				tracked_name.owner = owner;
+			}
+		}
+
+		if rule.could_match_name_owner_changed() {
+			self.num_name_owner_changed_subscriptions += 1;
+		}
+
+		Ok(())
+	}
+
+	/// Unsubscribe from signals matching the given rule, by calling the `org.freedesktop.DBus.RemoveMatch` method.
+	///
+	/// The rule must be identical to one that was previously passed to [`Client::add_match`].
+	pub fn remove_match(&mut self, rule: &crate::MatchRule<'_>) -> Result<(), MethodCallError> {
+		let obj = OrgFreeDesktopDbusObject::bus();
+
+		let () = obj.remove_match(self, &rule.to_string())?;
+
+		if rule.could_match_name_owner_changed() {
+			self.num_name_owner_changed_subscriptions = self.num_name_owner_changed_subscriptions.saturating_sub(1);
+		}
+
+		if let Some(name) = rule.well_known_sender() {
+			if let Some(tracked_name) = self.tracked_names.get_mut(name) {
+				tracked_name.num_match_rules -= 1;
+				if tracked_name.num_match_rules == 0 {
+					let _ = self.tracked_names.remove(name);
+					let () = obj.remove_match(self, &crate::match_rule::name_owner_changed_match_rule("arg0", name))?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// The unique name of the current owner of the given well-known name, if it's being tracked because it's the sender
+	/// of a match rule added with [`Client::add_match`] and it currently has an owner.
+	pub fn tracked_name_owner(&self, name: &str) -> Option<&str> {
+		self.tracked_names.get(name).and_then(|tracked_name| tracked_name.owner.as_deref())
+	}
+
+	/// Receive a signal that matches the given rule.
+	///
+	/// If the rule's sender is a well-known name, the signal's sender must be the unique name of the current owner of that name.
+	/// Thus the rule must have been added with [`Client::add_match`], since that is what starts tracking the owner.
+	///
+	/// Messages that do not match the rule will not be discarded. Instead they will be returned
+	/// from subsequent calls to [`Client::recv`] or [`Client::recv_matching`].
+	pub fn recv_signal(
+		&mut self,
+		rule: &crate::MatchRule<'_>,
+	) -> Result<crate::proto::Message<'static>, crate::conn::RecvError> {
+		let tracked_names = &self.tracked_names;
+		let owner_of = |name: &str| tracked_names.get(name).and_then(|tracked_name| tracked_name.owner.as_deref());
+		let position = self.received_messages.iter().position(|message| rule.matches(message, owner_of));
+		if let Some(position) = position {
+			let result = self.received_messages.remove(position).unwrap();
+			return Ok(result);
+		}
+
+		loop {
+			let message = self.recv_new()?;
+
+			let tracked_names = &self.tracked_names;
+			let owner_of = |name: &str| tracked_names.get(name).and_then(|tracked_name| tracked_name.owner.as_deref());
+			if rule.matches(&message, owner_of) {
+				return Ok(message);
+			}
+
+			self.retain_received_message(message);
+		}
+	}
+
+	/// Set the timeout for receiving a message.
+	///
+	/// See [`crate::Connection::set_read_timeout`] for details.
+	pub fn set_read_timeout(&mut self, timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+		self.connection.set_read_timeout(timeout)
+	}
+
+	/// Get the timeout for receiving a message.
+	///
+	/// See [`crate::Connection::read_timeout`] for details.
+	pub fn read_timeout(&self) -> std::io::Result<Option<std::time::Duration>> {
+		self.connection.read_timeout()
+	}
+
+	/// Wait until the given bus name has an owner, and return the owner's unique name.
+	///
+	/// Returns immediately if the name already has an owner. Returns `None` if the name still does not have an owner
+	/// after the given timeout has elapsed.
+	pub fn wait_for_name(&mut self, name: &str, timeout: std::time::Duration) -> Result<Option<String>, WaitForNameError> {
+		let deadline = std::time::Instant::now() + timeout;
+
+		let previous_timeout = self.read_timeout().map_err(|err| WaitForNameError::RecvSignal(crate::RecvError::Io(err)))?;
+
+		let mut watcher = crate::NameWatcher::watch_name(self, name).map_err(WaitForNameError::Watch)?;
+
+		let result = loop {
+			if let Some(owner) = watcher.owner(name) {
+				break Ok(Some(owner.to_owned()));
+			}
+
+			let now = std::time::Instant::now();
+			if now >= deadline {
+				break Ok(None);
+			}
+
+			if let Err(err) = self.set_read_timeout(Some(deadline - now)) {
+				break Err(WaitForNameError::RecvSignal(crate::RecvError::Io(err)));
+			}
+
+			match watcher.next_event(self) {
+				Ok(_) => (),
+				Err(crate::RecvError::Timeout) => break Ok(None),
+				Err(err) => break Err(WaitForNameError::RecvSignal(err)),
+			}
+		};
+
+		// Restore the caller's timeout before stopping the watcher, so that the RemoveMatch call isn't subject to
+		// whatever remains of the deadline.
+		let restore_result = self.set_read_timeout(previous_timeout);
+
+		let () = watcher.stop(self).map_err(WaitForNameError::Watch)?;
+
+		let () = restore_result.map_err(|err| WaitForNameError::RecvSignal(crate::RecvError::Io(err)))?;
+
+		result
+	}
+
+	/// Introspect the given object at the given destination by calling its `org.freedesktop.DBus.Introspectable.Introspect` method.
+	pub fn introspect(&mut self, destination: crate::proto::BusName<'_>, path: crate::proto::ObjectPath<'_>) -> Result<crate::introspection::Node, IntrospectError> {
+		let obj = OrgFreeDesktopDbusIntrospectableObject {
+			name: destination,
+			path,
+		};
+		let xml = obj.introspect(self).map_err(IntrospectError::MethodCall)?;
+		let node = xml.parse().map_err(IntrospectError::Parse)?;
+		Ok(node)
+	}
+
+	fn recv_new(&mut self) -> Result<crate::proto::Message<'static>, crate::conn::RecvError> {
+		loop {
+			let message = self.connection.recv()?;
+			if track_name_owner_changed(&mut self.tracked_names, self.num_name_owner_changed_subscriptions, &message) {
+				continue;
 			}
 
-			self.received_messages.push_back((header, body));
+			return Ok(message);
 		}
 	}
 
-	fn recv_new(&mut self) -> Result<(crate::proto::MessageHeader<'static>, Option<crate::proto::Variant<'static>>), crate::conn::RecvError> {
-		self.connection.recv()
+	/// Retains a message that was received while waiting for a different message, so that it can be returned by a subsequent call
+	/// to [`Client::recv`]. If too many messages are already retained, the oldest one is discarded.
+	fn retain_received_message(&mut self, message: crate::proto::Message<'static>) {
+		if self.received_messages.len() >= MAX_RECEIVED_MESSAGES {
+			let _ = self.received_messages.pop_front();
+		}
+
+		self.received_messages.push_back(message);
 	}
 }
 
@@ -163,10 +569,60 @@ impl std::fmt::Debug for Client {
 			.field("connection", &())
 			.field("last_serial", &self.last_serial)
 			.field("name", &self.name)
+			.field("tracked_names", &self.tracked_names)
+			.field("num_name_owner_changed_subscriptions", &self.num_name_owner_changed_subscriptions)
 			.finish()
 	}
 }
 
+pub(crate) fn is_name_owner_changed_signal(message: &crate::proto::Message<'_>) -> bool {
+	let is_signal = match &message.header.r#type {
+		crate::proto::MessageType::Signal { interface, member, path } =>
+			interface == "org.freedesktop.DBus" && member == "NameOwnerChanged" && path.as_str() == "/org/freedesktop/DBus",
+		_ => false,
+	};
+
+	is_signal && message.sender() == Some("org.freedesktop.DBus")
+}
+
+/// Returns the `(name, old_owner, new_owner)` arguments of the given message if it is a `NameOwnerChanged` signal.
+pub(crate) fn name_owner_changed_args<'a>(message: &'a crate::proto::Message<'_>) -> Option<(&'a str, &'a str, &'a str)> {
+	if !is_name_owner_changed_signal(message) {
+		return None;
+	}
+
+	match message.body.as_ref()? {
+		crate::proto::Variant::Tuple { elements } => match &**elements {
+			[name, old_owner, new_owner] => Some((name.as_string()?, old_owner.as_string()?, new_owner.as_string()?)),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Updates the tracked owner of a name if the given message is a `NameOwnerChanged` signal for it.
+///
+/// Returns `true` if the message should be consumed instead of being returned to the caller.
+fn track_name_owner_changed(
+	tracked_names: &mut std::collections::BTreeMap<String, TrackedName>,
+	num_name_owner_changed_subscriptions: usize,
+	message: &crate::proto::Message<'_>,
+) -> bool {
+	// Update tracked names as soon as the NameOwnerChanged signal is received, rather than when it's consumed,
+	// so that the owner is correct for all subsequently received signals.
+	if let Some((name, _, new_owner)) = name_owner_changed_args(message) {
+		if let Some(tracked_name) = tracked_names.get_mut(name) {
+			tracked_name.owner = if new_owner.is_empty() { None } else { Some(new_owner.to_owned()) };
+
+			// If nothing else subscribed to this signal, it was only received because of the tracking rule,
+			// so it's of no interest to the caller.
+			return num_name_owner_changed_subscriptions == 0;
+		}
+	}
+
+	false
+}
+
 /// An error from creating a [`Client`].
 #[derive(Debug)]
 pub enum CreateClientError {
@@ -192,16 +648,32 @@ impl std::error::Error for CreateClientError {
 /// An error from calling a method using a [`Client`].
 #[derive(Debug)]
 pub enum MethodCallError {
-	Error(String, Option<crate::proto::Variant<'static>>),
+	/// The method call returned an error with the given name and body.
+	Error(crate::ErrorKind, Option<crate::proto::Variant<'static>>),
 	RecvResponse(crate::conn::RecvError),
 	SendRequest(crate::conn::SendError),
 	UnexpectedResponse(Option<crate::proto::DeserializeError>),
 }
 
+impl MethodCallError {
+	/// The human-readable message of the error returned by the method call, if any.
+	///
+	/// By convention, this is the first argument of the error's body if it's a string.
+	pub fn message(&self) -> Option<&str> {
+		match self {
+			MethodCallError::Error(_, body) => crate::error::error_message(body.as_ref()),
+			_ => None,
+		}
+	}
+}
+
 impl std::fmt::Display for MethodCallError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
-			MethodCallError::Error(error_name, body) => write!(f, "method call failed with an error: {} {:?}", error_name, body),
+			MethodCallError::Error(error_name, body) => match crate::error::error_message(body.as_ref()) {
+				Some(message) => write!(f, "method call failed with an error: {}: {}", error_name, message),
+				None => write!(f, "method call failed with an error: {}", error_name),
+			},
 			MethodCallError::RecvResponse(_) => f.write_str("could not receive response"),
 			MethodCallError::SendRequest(_) => f.write_str("could not send request"),
 			MethodCallError::UnexpectedResponse(Some(_)) => write!(f, "could not deserialize response body"),
@@ -223,13 +695,94 @@ impl std::error::Error for MethodCallError {
 	}
 }
 
+/// An error from introspecting an object using [`Client::introspect`].
+#[derive(Debug)]
+pub enum IntrospectError {
+	MethodCall(MethodCallError),
+	Parse(crate::introspection::ParseError),
+}
+
+impl std::fmt::Display for IntrospectError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			IntrospectError::MethodCall(_) => f.write_str("could not call Introspect method"),
+			IntrospectError::Parse(_) => f.write_str("could not parse introspection data"),
+		}
+	}
+}
+
+impl std::error::Error for IntrospectError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			IntrospectError::MethodCall(err) => Some(err),
+			IntrospectError::Parse(err) => Some(err),
+		}
+	}
+}
+
+/// An error from waiting for a name using [`Client::wait_for_name`].
+#[derive(Debug)]
+pub enum WaitForNameError {
+	RecvSignal(crate::conn::RecvError),
+	Watch(MethodCallError),
+}
+
+impl std::fmt::Display for WaitForNameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			WaitForNameError::RecvSignal(_) => f.write_str("could not receive NameOwnerChanged signal"),
+			WaitForNameError::Watch(_) => f.write_str("could not watch name"),
+		}
+	}
+}
+
+impl std::error::Error for WaitForNameError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			WaitForNameError::RecvSignal(err) => Some(err),
+			WaitForNameError::Watch(err) => Some(err),
+		}
+	}
+}
+
 use crate as dbus_pure;
 
 #[dbus_pure_macros::interface("org.freedesktop.DBus")]
-trait OrgFreeDesktopDbusInterface {
+pub(crate) trait OrgFreeDesktopDbusInterface {
+	#[name = "AddMatch"]
+	fn add_match(rule: &str);
+
+	#[name = "GetNameOwner"]
+	fn get_name_owner(name: &str) -> String;
+
 	#[name = "Hello"]
 	fn hello() -> String;
+
+	#[name = "ListNames"]
+	fn list_names() -> Vec<String>;
+
+	#[name = "RemoveMatch"]
+	fn remove_match(rule: &str);
 }
 
 #[dbus_pure_macros::object(OrgFreeDesktopDbusInterface)]
-struct OrgFreeDesktopDbusObject;
+pub(crate) struct OrgFreeDesktopDbusObject;
+
+impl OrgFreeDesktopDbusObject<'static> {
+	/// The `/org/freedesktop/DBus` object of the message bus itself.
+	pub(crate) fn bus() -> Self {
+		OrgFreeDesktopDbusObject {
+			name: crate::proto::BusName::new("org.freedesktop.DBus").expect("hard-coded name is valid"),
+			path: crate::proto::ObjectPath::new("/org/freedesktop/DBus").expect("hard-coded path is valid"),
+		}
+	}
+}
+
+#[dbus_pure_macros::interface("org.freedesktop.DBus.Introspectable")]
+trait OrgFreeDesktopDbusIntrospectableInterface {
+	#[name = "Introspect"]
+	fn introspect() -> String;
+}
+
+#[dbus_pure_macros::object(OrgFreeDesktopDbusIntrospectableInterface)]
+struct OrgFreeDesktopDbusIntrospectableObject;
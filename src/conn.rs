@@ -2,6 +2,9 @@
 pub struct Connection {
 	reader: std::io::BufReader<std::os::unix::net::UnixStream>,
 	read_buf: Vec<u8>,
+	/// The length of the previously received message at the start of `read_buf`, which is discarded at the start of the next receive.
+	/// It's retained until then because the message returned by [`Connection::recv_borrowed`] borrows it.
+	read_consumed: usize,
 	read_end: usize,
 	writer: std::os::unix::net::UnixStream,
 	write_buf: Vec<u8>,
@@ -21,6 +24,11 @@ pub enum BusPath<'a> {
 
 	/// A unix domain socket file at the specified filesystem path.
 	UnixSocketFile(&'a std::path::Path),
+
+	/// The specified D-Bus server address, such as `unix:path=/run/user/1000/bus`.
+	///
+	/// Only `unix:path=` addresses are supported. If the address has multiple `;`-separated entries, they are tried in order.
+	Address(&'a std::ffi::OsStr),
 }
 
 /// The string to send for SASL EXTERNAL authentication with the message bus.
@@ -45,7 +53,7 @@ impl Connection {
 
 		let stream = match bus_path {
 			BusPath::Session => {
-				let bus_address = std::env::var_os("DBUS_SESSION_BUS_ADDRESS").ok_or_else(|| ConnectError::MissingSessionBusEnvVar)?;
+				let bus_address = std::env::var_os("DBUS_SESSION_BUS_ADDRESS").ok_or(ConnectError::MissingSessionBusEnvVar)?;
 				connect(&bus_address)?
 			},
 
@@ -56,6 +64,8 @@ impl Connection {
 				connect(&bus_address)?
 			},
 
+			BusPath::Address(bus_address) => connect(bus_address)?,
+
 			BusPath::UnixSocketFile(bus_path) => {
 				let stream =
 					std::os::unix::net::UnixStream::connect(bus_path)
@@ -113,6 +123,7 @@ impl Connection {
 		Ok(Connection {
 			reader,
 			read_buf,
+			read_consumed: 0,
 			read_end: 0,
 			writer,
 			write_buf,
@@ -126,17 +137,31 @@ impl Connection {
 		&self.server_guid
 	}
 
-	/// Send a message with the given header and body to the message bus.
+	/// Send the given message to the message bus.
 	///
-	/// - Header fields corresponding to the required properties of the message type will be automatically inserted, and *must not* be inserted by the caller.
-	///   For example, if `header.type` is `MethodCall { member, path }`, the `MessageHeaderField::Member` and `MessageHeaderField::Path` fields
-	///   will be inserted automatically.
+	/// See [`crate::proto::serialize_message`] for the header fields that are generated automatically.
+	pub fn send(&mut self, message: &crate::proto::Message<'_>) -> Result<(), SendError> {
+		self.send_inner(|buf, endianness| crate::proto::serialize_message(message, buf, endianness))
+	}
+
+	/// Send the given message to the message bus, with the given value as its body.
 	///
-	/// - The `MessageHeaderField::Signature` field will be automatically inserted if a body is specified, and must not be inserted by the caller.
-	pub fn send(&mut self, header: &mut crate::proto::MessageHeader<'_>, body: Option<&crate::proto::Variant<'_>>) -> Result<(), SendError> {
+	/// This serializes the body directly without converting it to a `Variant`.
+	/// See [`crate::proto::serialize_message_with_body`] for details.
+	pub fn send_with_body<T>(&mut self, message: &crate::proto::Message<'_>, body: &T) -> Result<(), SendError>
+	where
+		T: serde::Serialize + crate::proto::AsVariant + ?Sized,
+	{
+		self.send_inner(|buf, endianness| crate::proto::serialize_message_with_body(message, body, buf, endianness))
+	}
+
+	fn send_inner(
+		&mut self,
+		serialize: impl FnOnce(&mut Vec<u8>, crate::proto::Endianness) -> Result<(), crate::proto::SerializeError>,
+	) -> Result<(), SendError> {
 		use std::io::Write;
 
-		let () = crate::proto::serialize_message(header, body, &mut self.write_buf, self.write_endianness).map_err(SendError::Serialize)?;
+		let () = serialize(&mut self.write_buf, self.write_endianness).map_err(SendError::Serialize)?;
 
 		let _ = self.writer.write_all(&self.write_buf).map_err(SendError::Io)?;
 		self.write_buf.clear();
@@ -147,23 +172,77 @@ impl Connection {
 	}
 
 	/// Receive a message from the message bus.
-	pub fn recv(&mut self) -> Result<(crate::proto::MessageHeader<'static>, Option<crate::proto::Variant<'static>>), RecvError> {
+	pub fn recv(&mut self) -> Result<crate::proto::Message<'static>, RecvError> {
+		let message_len = self.recv_inner()?;
+		let (message, _) = crate::proto::deserialize_message(&self.read_buf[..message_len]).map_err(RecvError::Deserialize)?;
+		Ok(message)
+	}
+
+	/// Receive a message from the message bus, borrowing its data from the connection's read buffer.
+	///
+	/// Unlike [`Connection::recv`], strings, object paths and byte arrays in the message are not copied out of the read buffer,
+	/// so the message can only be used until the next message is received. Use [`crate::proto::Message::into_owned`] to keep it for longer.
+	pub fn recv_borrowed(&mut self) -> Result<crate::proto::Message<'_>, RecvError> {
+		let message_len = self.recv_inner()?;
+		let (message, _) = crate::proto::deserialize_message_borrowed(&self.read_buf[..message_len]).map_err(RecvError::Deserialize)?;
+		Ok(message)
+	}
+
+	/// Receive a message from the message bus, deserializing its body directly into a value of type `T`.
+	///
+	/// The `body` of the returned message is always `None`. See [`crate::proto::deserialize_message_with_body`] for details.
+	///
+	/// If the body cannot be deserialized into a `T`, the message is discarded and this function fails with `RecvError::Deserialize`.
+	pub fn recv_with_body<T>(&mut self) -> Result<(crate::proto::Message<'static>, T), RecvError> where T: serde::de::DeserializeOwned {
+		let message_len = self.recv_inner()?;
+		let (message, body, _) = crate::proto::deserialize_message_with_body(&self.read_buf[..message_len]).map_err(RecvError::Deserialize)?;
+		Ok((message, body))
+	}
+
+	/// Deserialize the message that was last received again, with its body as a `Variant`.
+	///
+	/// The message is still in the read buffer until the next message is received.
+	pub(crate) fn last_message(&self) -> Result<crate::proto::Message<'static>, crate::proto::DeserializeError> {
+		let (message, _) = crate::proto::deserialize_message(&self.read_buf[..self.read_consumed])?;
+		Ok(message)
+	}
+
+	/// Deserialize the message that was last received again, borrowing its data from the read buffer.
+	pub(crate) fn last_message_borrowed(&self) -> Result<crate::proto::Message<'_>, crate::proto::DeserializeError> {
+		let (message, _) = crate::proto::deserialize_message_borrowed(&self.read_buf[..self.read_consumed])?;
+		Ok(message)
+	}
+
+	/// Reads from the message bus until the read buffer contains a complete message at its start, and returns the length of that message.
+	///
+	/// The message is discarded at the start of the next call, whether or not it could be deserialized.
+	fn recv_inner(&mut self) -> Result<usize, RecvError> {
 		use std::io::Read;
 
+		self.read_buf.copy_within(self.read_consumed..self.read_end, 0);
+		self.read_end -= self.read_consumed;
+		self.read_consumed = 0;
+
 		loop {
-			match crate::proto::deserialize_message(&self.read_buf[..self.read_end]) {
-				Ok((message_header, message_body, read)) => {
-					self.read_buf.copy_within(read..self.read_end, 0);
-					self.read_end -= read;
-					return Ok((message_header, message_body));
+			match crate::proto::message_len(&self.read_buf[..self.read_end]) {
+				Ok(message_len) if message_len <= self.read_end => {
+					self.read_consumed = message_len;
+					return Ok(message_len);
 				},
 
-				Err(crate::proto::DeserializeError::EndOfInput) => {
+				result @ (Ok(_) | Err(crate::proto::DeserializeError::EndOfInput)) => {
+					// Grow the buffer at most up to the length the peer says the message has, which `message_len` has already checked
+					// against `MAX_MESSAGE_LEN`, so that a bogus length can't make us allocate more than we actually receive.
+					let needed = result.unwrap_or(MESSAGE_FIXED_HEADER_LEN);
 					if self.read_end == self.read_buf.len() {
-						self.read_buf.resize(self.read_buf.len() * 2, 0);
+						let new_len = std::cmp::min(self.read_buf.len() * 2, needed.max(self.read_buf.len() + 1));
+						self.read_buf.resize(new_len, 0);
 					}
 
-					let read = self.reader.read(&mut self.read_buf[self.read_end..]).map_err(RecvError::Io)?;
+					let read = self.reader.read(&mut self.read_buf[self.read_end..]).map_err(|err| match err.kind() {
+						std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => RecvError::Timeout,
+						_ => RecvError::Io(err),
+					})?;
 					if read == 0 {
 						return Err(RecvError::Io(std::io::ErrorKind::UnexpectedEof.into()));
 					}
@@ -176,6 +255,21 @@ impl Connection {
 		}
 	}
 
+	/// Set the timeout for receiving a message.
+	///
+	/// If a message does not arrive within the timeout, [`Connection::recv`] fails with [`RecvError::Timeout`].
+	/// Any partially received message is retained, and will be completed by the next call to `recv`.
+	///
+	/// By default there is no timeout, ie `recv` blocks until a message is received. Set the timeout to `None` to restore this.
+	pub fn set_read_timeout(&mut self, timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+		self.reader.get_ref().set_read_timeout(timeout)
+	}
+
+	/// Get the timeout for receiving a message, as set by [`Connection::set_read_timeout`].
+	pub fn read_timeout(&self) -> std::io::Result<Option<std::time::Duration>> {
+		self.reader.get_ref().read_timeout()
+	}
+
 	/// Set the endianness used for sending messages.
 	///
 	/// By default, the connection uses the target endianness. Use this method to override that.
@@ -184,6 +278,9 @@ impl Connection {
 	}
 }
 
+/// The length of the fixed-size part of a message header, which is all that [`crate::proto::message_len`] needs to compute the length of the message.
+const MESSAGE_FIXED_HEADER_LEN: usize = 16;
+
 /// An error from connecting to a message bus.
 #[derive(Debug)]
 pub enum ConnectError {
@@ -263,6 +360,7 @@ impl std::error::Error for SendError {
 pub enum RecvError {
 	Deserialize(crate::proto::DeserializeError),
 	Io(std::io::Error),
+	Timeout,
 }
 
 impl std::fmt::Display for RecvError {
@@ -270,6 +368,7 @@ impl std::fmt::Display for RecvError {
 		match self {
 			RecvError::Deserialize(_) => f.write_str("could not deserialize message"),
 			RecvError::Io(_) => f.write_str("could not receive message"),
+			RecvError::Timeout => f.write_str("timed out waiting for message"),
 		}
 	}
 }
@@ -279,12 +378,13 @@ impl std::error::Error for RecvError {
 		match self {
 			RecvError::Deserialize(err) => Some(err),
 			RecvError::Io(err) => Some(err),
+			RecvError::Timeout => None,
 		}
 	}
 }
 
 fn connect(bus_address: &std::ffi::OsStr) -> Result<std::os::unix::net::UnixStream, ConnectError> {
-	let bus_address_bytes = std::os::unix::ffi::OsStrExt::as_bytes(&*bus_address);
+	let bus_address_bytes = std::os::unix::ffi::OsStrExt::as_bytes(bus_address);
 
 	let mut connect_errs = vec![];
 
@@ -0,0 +1,205 @@
+macro_rules! error_kinds {
+	($($(#[$meta:meta])* $variant:ident => $name:literal,)*) => {
+		/// The name of an error returned by a method call.
+		///
+		/// The standard `org.freedesktop.DBus.Error.*` names have their own variants. All other names are `ErrorKind::Other`.
+		#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+		pub enum ErrorKind {
+			$(
+				$(#[$meta])*
+				#[doc = concat!("`", $name, "`")]
+				$variant,
+			)*
+
+			/// Any other error name.
+			Other(String),
+		}
+
+		impl ErrorKind {
+			/// The error name.
+			pub fn as_str(&self) -> &str {
+				match self {
+					$(ErrorKind::$variant => $name,)*
+					ErrorKind::Other(name) => name,
+				}
+			}
+		}
+
+		impl From<&'_ str> for ErrorKind {
+			fn from(name: &str) -> Self {
+				match name {
+					$($name => ErrorKind::$variant,)*
+					name => ErrorKind::Other(name.to_owned()),
+				}
+			}
+		}
+
+		impl From<String> for ErrorKind {
+			fn from(name: String) -> Self {
+				match &*name {
+					$($name => ErrorKind::$variant,)*
+					_ => ErrorKind::Other(name),
+				}
+			}
+		}
+	};
+}
+
+error_kinds! {
+	AccessDenied => "org.freedesktop.DBus.Error.AccessDenied",
+	AddressInUse => "org.freedesktop.DBus.Error.AddressInUse",
+	AdtAuditDataUnknown => "org.freedesktop.DBus.Error.AdtAuditDataUnknown",
+	AuthFailed => "org.freedesktop.DBus.Error.AuthFailed",
+	BadAddress => "org.freedesktop.DBus.Error.BadAddress",
+	Disconnected => "org.freedesktop.DBus.Error.Disconnected",
+	Failed => "org.freedesktop.DBus.Error.Failed",
+	FileExists => "org.freedesktop.DBus.Error.FileExists",
+	FileNotFound => "org.freedesktop.DBus.Error.FileNotFound",
+	InconsistentMessage => "org.freedesktop.DBus.Error.InconsistentMessage",
+	InteractiveAuthorizationRequired => "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired",
+	InvalidArgs => "org.freedesktop.DBus.Error.InvalidArgs",
+	InvalidFileContent => "org.freedesktop.DBus.Error.InvalidFileContent",
+	InvalidSignature => "org.freedesktop.DBus.Error.InvalidSignature",
+	IoError => "org.freedesktop.DBus.Error.IOError",
+	LimitsExceeded => "org.freedesktop.DBus.Error.LimitsExceeded",
+	MatchRuleInvalid => "org.freedesktop.DBus.Error.MatchRuleInvalid",
+	MatchRuleNotFound => "org.freedesktop.DBus.Error.MatchRuleNotFound",
+	NameHasNoOwner => "org.freedesktop.DBus.Error.NameHasNoOwner",
+	NoMemory => "org.freedesktop.DBus.Error.NoMemory",
+	NoNetwork => "org.freedesktop.DBus.Error.NoNetwork",
+	NoReply => "org.freedesktop.DBus.Error.NoReply",
+	NoServer => "org.freedesktop.DBus.Error.NoServer",
+	NotContainer => "org.freedesktop.DBus.Error.NotContainer",
+	NotSupported => "org.freedesktop.DBus.Error.NotSupported",
+	ObjectPathInUse => "org.freedesktop.DBus.Error.ObjectPathInUse",
+	PropertyReadOnly => "org.freedesktop.DBus.Error.PropertyReadOnly",
+	SELinuxSecurityContextUnknown => "org.freedesktop.DBus.Error.SELinuxSecurityContextUnknown",
+	ServiceUnknown => "org.freedesktop.DBus.Error.ServiceUnknown",
+	Timeout => "org.freedesktop.DBus.Error.Timeout",
+	TimedOut => "org.freedesktop.DBus.Error.TimedOut",
+	UnixProcessIdUnknown => "org.freedesktop.DBus.Error.UnixProcessIdUnknown",
+	UnknownInterface => "org.freedesktop.DBus.Error.UnknownInterface",
+	UnknownMethod => "org.freedesktop.DBus.Error.UnknownMethod",
+	UnknownObject => "org.freedesktop.DBus.Error.UnknownObject",
+	UnknownProperty => "org.freedesktop.DBus.Error.UnknownProperty",
+}
+
+impl std::fmt::Display for ErrorKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Get the human-readable message of an error from the error's body.
+///
+/// By convention, this is the first argument of the body if it's a string.
+pub(crate) fn error_message<'a>(body: Option<&'a crate::proto::Variant<'_>>) -> Option<&'a str> {
+	match body? {
+		crate::proto::Variant::String(message) => Some(message),
+		crate::proto::Variant::Tuple { elements } => elements.first()?.as_string(),
+		_ => None,
+	}
+}
+
+/// A trait for Rust error types that correspond to D-Bus errors.
+///
+/// This allows a value of this type to be sent as the `ERROR` reply to a method call, and to be recovered from the error returned by a method call.
+///
+/// Consider using `#[derive(dbus_pure_macros::DBusError)]` to implement this trait for your custom enum types.
+pub trait DBusError: Sized {
+	/// The error name of this error, such as `com.example.Foo.NotFound`
+	///
+	/// This must be a valid D-Bus error name.
+	fn name(&self) -> &str;
+
+	/// The human-readable message of this error, if any.
+	fn message(&self) -> Option<&str>;
+
+	/// Convert an error with the given name and message to a value of this type.
+	///
+	/// Returns `None` if the name does not correspond to any value of this type.
+	fn from_error(name: &ErrorKind, message: Option<&str>) -> Option<Self>;
+
+	/// Create the `ERROR` reply to the given method call.
+	///
+	/// The reply's body is the error message, if any. It can be sent using [`crate::Client::send`].
+	///
+	/// # Panics
+	///
+	/// Panics if [`DBusError::name`] is not a valid error name.
+	fn error_reply(&self, method_call: &crate::proto::Message<'_>) -> crate::proto::Message<'static> {
+		let name = crate::proto::ErrorName::new(self.name().to_owned()).expect("DBusError::name is not a valid error name");
+		let mut reply = crate::proto::Message::error(method_call, name);
+		reply.body = self.message().map(|message| crate::proto::Variant::String(message.to_owned().into()));
+		reply
+	}
+
+	/// Convert the error returned by a method call to a value of this type.
+	///
+	/// Returns the original error if it's not an error returned by the remote, or if [`DBusError::from_error`] does not recognize its name.
+	fn from_method_call_error(err: crate::MethodCallError) -> Result<Self, crate::MethodCallError> {
+		if let crate::MethodCallError::Error(name, body) = &err {
+			if let Some(value) = Self::from_error(name, error_message(body.as_ref())) {
+				return Ok(value);
+			}
+		}
+
+		Err(err)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_error_kind() {
+		fn test(name: &str, expected: &super::ErrorKind) {
+			let kind = super::ErrorKind::from(name);
+			assert_eq!(kind, *expected);
+			assert_eq!(super::ErrorKind::from(name.to_owned()), *expected);
+			assert_eq!(kind.as_str(), name);
+			assert_eq!(kind.to_string(), name);
+		}
+
+		test("org.freedesktop.DBus.Error.AccessDenied", &super::ErrorKind::AccessDenied);
+		test("org.freedesktop.DBus.Error.IOError", &super::ErrorKind::IoError);
+		test("org.freedesktop.DBus.Error.UnknownProperty", &super::ErrorKind::UnknownProperty);
+		test("com.example.Foo.NotFound", &super::ErrorKind::Other("com.example.Foo.NotFound".to_owned()));
+
+		// Names are case-sensitive.
+		test("org.freedesktop.DBus.Error.IoError", &super::ErrorKind::Other("org.freedesktop.DBus.Error.IoError".to_owned()));
+	}
+
+	#[test]
+	fn test_error_message() {
+		let string = |value: &'static str| crate::proto::Variant::String(value.into());
+		let tuple = |elements: Vec<crate::proto::Variant<'static>>| crate::proto::Variant::Tuple { elements: elements.into() };
+
+		assert_eq!(super::error_message(None), None);
+		assert_eq!(super::error_message(Some(&string("foo"))), Some("foo"));
+		assert_eq!(super::error_message(Some(&tuple(vec![string("foo"), crate::proto::Variant::U32(5)]))), Some("foo"));
+		assert_eq!(super::error_message(Some(&tuple(vec![crate::proto::Variant::U32(5), string("foo")]))), None);
+		assert_eq!(super::error_message(Some(&tuple(vec![]))), None);
+		assert_eq!(super::error_message(Some(&crate::proto::Variant::U32(5))), None);
+	}
+
+	#[test]
+	fn test_method_call_error_message() {
+		let err = crate::MethodCallError::Error(
+			crate::ErrorKind::NameHasNoOwner,
+			Some(crate::proto::Variant::Tuple { elements: vec![crate::proto::Variant::String("no owner".into())].into() }),
+		);
+		assert_eq!(err.message(), Some("no owner"));
+		assert_eq!(err.to_string(), "method call failed with an error: org.freedesktop.DBus.Error.NameHasNoOwner: no owner");
+
+		let err = crate::MethodCallError::Error(crate::ErrorKind::Other("com.example.Foo.NotFound".to_owned()), None);
+		assert_eq!(err.message(), None);
+		assert_eq!(err.to_string(), "method call failed with an error: com.example.Foo.NotFound");
+
+		let err = crate::MethodCallError::Error(crate::ErrorKind::Failed, Some(crate::proto::Variant::U32(5)));
+		assert_eq!(err.message(), None);
+		assert_eq!(err.to_string(), "method call failed with an error: org.freedesktop.DBus.Error.Failed");
+
+		let err = crate::MethodCallError::UnexpectedResponse(None);
+		assert_eq!(err.message(), None);
+	}
+}
@@ -0,0 +1,753 @@
+//! Types for the XML data returned by the `org.freedesktop.DBus.Introspectable.Introspect` method.
+//!
+//! Parse the XML into a [`Node`] with `.parse()`, and use `.to_string()` to write a [`Node`] back out as XML.
+//! Use [`crate::Client::introspect`] to introspect a remote object.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #
+//! let xml = r#"
+//!     <node>
+//!         <interface name="org.example.Foo">
+//!             <method name="Bar">
+//!                 <arg name="baz" type="a{sv}" direction="in"/>
+//!             </method>
+//!             <property name="Quux" type="s" access="read"/>
+//!         </interface>
+//!     </node>
+//! "#;
+//!
+//! let node: dbus_pure::introspection::Node = xml.parse()?;
+//!
+//! let interface = &node.interfaces[0];
+//! assert_eq!(interface.name, "org.example.Foo");
+//! assert_eq!(interface.methods[0].args[0].r#type.to_string(), "a{sv}");
+//! assert_eq!(interface.properties[0].access, dbus_pure::introspection::Access::Read);
+//!
+//! // The written XML can be parsed back into the same node.
+//! let reparsed: dbus_pure::introspection::Node = node.to_string().parse()?;
+//! assert_eq!(node, reparsed);
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+/// An object, along with its interfaces and child objects.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Node {
+	/// The name of the node. This is usually absent for the root node, and relative to the parent node's path for child nodes.
+	pub name: Option<String>,
+
+	pub interfaces: Vec<Interface>,
+
+	pub nodes: Vec<Node>,
+}
+
+/// An interface implemented by an object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interface {
+	pub name: String,
+	pub methods: Vec<Method>,
+	pub signals: Vec<Signal>,
+	pub properties: Vec<Property>,
+	pub annotations: Vec<Annotation>,
+}
+
+/// A method of an interface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Method {
+	pub name: String,
+	pub args: Vec<Arg>,
+	pub annotations: Vec<Annotation>,
+}
+
+/// An argument of a method or signal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arg {
+	pub name: Option<String>,
+
+	/// The type of the argument. This is always a single complete type, ie never a `Signature::Tuple`.
+	pub r#type: crate::proto::Signature,
+
+	/// Whether the argument is a parameter or a return value. This is always `Direction::Out` for the arguments of signals.
+	pub direction: Direction,
+
+	pub annotations: Vec<Annotation>,
+}
+
+/// The direction of an [`Arg`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+	In,
+	Out,
+}
+
+/// A signal of an interface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signal {
+	pub name: String,
+	pub args: Vec<Arg>,
+	pub annotations: Vec<Annotation>,
+}
+
+/// A property of an interface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+	pub name: String,
+
+	/// The type of the property. This is always a single complete type, ie never a `Signature::Tuple`.
+	pub r#type: crate::proto::Signature,
+
+	pub access: Access,
+
+	pub annotations: Vec<Annotation>,
+}
+
+/// Whether a [`Property`] can be read, written, or both.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Access {
+	Read,
+	ReadWrite,
+	Write,
+}
+
+/// An annotation of an interface or one of its members, such as `org.freedesktop.DBus.Deprecated`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+	pub name: String,
+	pub value: String,
+}
+
+impl std::str::FromStr for Node {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut reader = xml::reader::EventReader::new(s.as_bytes());
+
+		loop {
+			match reader.next().map_err(ParseError::Xml)? {
+				xml::reader::XmlEvent::StartElement { name, attributes, .. } =>
+					if name.local_name == "node" {
+						return parse_node(&mut reader, &attributes, 0);
+					}
+					else {
+						return Err(ParseError::UnexpectedRootElement(name.local_name));
+					},
+
+				xml::reader::XmlEvent::EndDocument => return Err(ParseError::MissingRootElement),
+
+				_ => (),
+			}
+		}
+	}
+}
+
+type Reader<'a> = xml::reader::EventReader<&'a [u8]>;
+
+/// The maximum number of nodes that a node can be nested in.
+///
+/// The XML comes from the introspected peer, so this prevents it from overflowing the stack with deeply nested nodes.
+const MAX_NODE_DEPTH: usize = 64;
+
+/// Parse a node that is nested in `depth` other nodes.
+fn parse_node(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute], depth: usize) -> Result<Node, ParseError> {
+	let mut node = Node {
+		name: optional_attribute(attributes, "name").map(ToOwned::to_owned),
+		interfaces: vec![],
+		nodes: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"interface" => {
+			node.interfaces.push(parse_interface(reader, attributes)?);
+			Ok(true)
+		},
+
+		"node" => {
+			if depth >= MAX_NODE_DEPTH {
+				return Err(ParseError::NestingTooDeep);
+			}
+
+			node.nodes.push(parse_node(reader, attributes, depth + 1)?);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(node)
+}
+
+fn parse_interface(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute]) -> Result<Interface, ParseError> {
+	let mut interface = Interface {
+		name: attribute(attributes, "interface", "name")?.to_owned(),
+		methods: vec![],
+		signals: vec![],
+		properties: vec![],
+		annotations: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"annotation" => {
+			interface.annotations.push(parse_annotation(reader, attributes)?);
+			Ok(true)
+		},
+
+		"method" => {
+			interface.methods.push(parse_method(reader, attributes)?);
+			Ok(true)
+		},
+
+		"property" => {
+			interface.properties.push(parse_property(reader, attributes)?);
+			Ok(true)
+		},
+
+		"signal" => {
+			interface.signals.push(parse_signal(reader, attributes)?);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(interface)
+}
+
+fn parse_method(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute]) -> Result<Method, ParseError> {
+	let mut method = Method {
+		name: attribute(attributes, "method", "name")?.to_owned(),
+		args: vec![],
+		annotations: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"annotation" => {
+			method.annotations.push(parse_annotation(reader, attributes)?);
+			Ok(true)
+		},
+
+		"arg" => {
+			method.args.push(parse_arg(reader, attributes, Direction::In)?);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(method)
+}
+
+fn parse_signal(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute]) -> Result<Signal, ParseError> {
+	let mut signal = Signal {
+		name: attribute(attributes, "signal", "name")?.to_owned(),
+		args: vec![],
+		annotations: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"annotation" => {
+			signal.annotations.push(parse_annotation(reader, attributes)?);
+			Ok(true)
+		},
+
+		"arg" => {
+			let mut arg = parse_arg(reader, attributes, Direction::Out)?;
+			// Signal arguments are always emitted by the signal, regardless of any direction attribute.
+			arg.direction = Direction::Out;
+			signal.args.push(arg);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(signal)
+}
+
+fn parse_property(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute]) -> Result<Property, ParseError> {
+	let access = match attribute(attributes, "property", "access")? {
+		"read" => Access::Read,
+		"readwrite" => Access::ReadWrite,
+		"write" => Access::Write,
+		value => return Err(ParseError::InvalidAttribute { element: "property", attribute: "access", value: value.to_owned() }),
+	};
+
+	let mut property = Property {
+		name: attribute(attributes, "property", "name")?.to_owned(),
+		r#type: type_attribute(attributes, "property")?,
+		access,
+		annotations: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"annotation" => {
+			property.annotations.push(parse_annotation(reader, attributes)?);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(property)
+}
+
+fn parse_arg(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute], default_direction: Direction) -> Result<Arg, ParseError> {
+	let direction = match optional_attribute(attributes, "direction") {
+		Some("in") => Direction::In,
+		Some("out") => Direction::Out,
+		Some(value) => return Err(ParseError::InvalidAttribute { element: "arg", attribute: "direction", value: value.to_owned() }),
+		None => default_direction,
+	};
+
+	let mut arg = Arg {
+		name: optional_attribute(attributes, "name").map(ToOwned::to_owned),
+		r#type: type_attribute(attributes, "arg")?,
+		direction,
+		annotations: vec![],
+	};
+
+	let () = parse_children(reader, |reader, element, attributes| match element {
+		"annotation" => {
+			arg.annotations.push(parse_annotation(reader, attributes)?);
+			Ok(true)
+		},
+
+		_ => Ok(false),
+	})?;
+
+	Ok(arg)
+}
+
+fn parse_annotation(reader: &mut Reader<'_>, attributes: &[xml::attribute::OwnedAttribute]) -> Result<Annotation, ParseError> {
+	let annotation = Annotation {
+		name: attribute(attributes, "annotation", "name")?.to_owned(),
+		value: attribute(attributes, "annotation", "value")?.to_owned(),
+	};
+
+	let () = skip_element(reader)?;
+
+	Ok(annotation)
+}
+
+/// Parse the children of the current element, up to and including the current element's end tag.
+///
+/// `f` is called with the name and attributes of each child element. If it recognizes the element, it must parse it up to and including
+/// the element's end tag and return `true`. Otherwise it must return `false`, and the element is skipped. This allows elements that are not
+/// part of the introspection DTD, such as documentation elements, to be ignored.
+fn parse_children(
+	reader: &mut Reader<'_>,
+	mut f: impl FnMut(&mut Reader<'_>, &str, &[xml::attribute::OwnedAttribute]) -> Result<bool, ParseError>,
+) -> Result<(), ParseError> {
+	loop {
+		match reader.next().map_err(ParseError::Xml)? {
+			xml::reader::XmlEvent::StartElement { name, attributes, .. } => {
+				let recognized = f(reader, &name.local_name, &attributes)?;
+				if !recognized {
+					let () = skip_element(reader)?;
+				}
+			},
+
+			xml::reader::XmlEvent::EndElement { .. } => return Ok(()),
+
+			xml::reader::XmlEvent::EndDocument => return Err(ParseError::UnexpectedEndOfDocument),
+
+			_ => (),
+		}
+	}
+}
+
+/// Skip the children of the current element, up to and including the current element's end tag.
+fn skip_element(reader: &mut Reader<'_>) -> Result<(), ParseError> {
+	let mut depth = 0_usize;
+
+	loop {
+		match reader.next().map_err(ParseError::Xml)? {
+			xml::reader::XmlEvent::StartElement { .. } => depth += 1,
+
+			xml::reader::XmlEvent::EndElement { .. } =>
+				if depth == 0 {
+					return Ok(());
+				}
+				else {
+					depth -= 1;
+				},
+
+			xml::reader::XmlEvent::EndDocument => return Err(ParseError::UnexpectedEndOfDocument),
+
+			_ => (),
+		}
+	}
+}
+
+fn attribute<'a>(attributes: &'a [xml::attribute::OwnedAttribute], element: &'static str, name: &'static str) -> Result<&'a str, ParseError> {
+	optional_attribute(attributes, name).ok_or(ParseError::MissingAttribute { element, attribute: name })
+}
+
+fn optional_attribute<'a>(attributes: &'a [xml::attribute::OwnedAttribute], name: &str) -> Option<&'a str> {
+	attributes.iter().find(|attribute| attribute.name.local_name == name).map(|attribute| &*attribute.value)
+}
+
+fn type_attribute(attributes: &[xml::attribute::OwnedAttribute], element: &'static str) -> Result<crate::proto::Signature, ParseError> {
+	let value = attribute(attributes, element, "type")?;
+	match value.parse() {
+		Ok(crate::proto::Signature::Tuple { .. }) | Err(_) =>
+			Err(ParseError::InvalidAttribute { element, attribute: "type", value: value.to_owned() }),
+		Ok(signature) => Ok(signature),
+	}
+}
+
+impl std::fmt::Display for Node {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN""#)?;
+		f.write_str("\n")?;
+		f.write_str(r#" "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">"#)?;
+		f.write_str("\n")?;
+		write_node(f, self, 0)
+	}
+}
+
+fn write_node(f: &mut std::fmt::Formatter<'_>, node: &Node, depth: usize) -> std::fmt::Result {
+	let has_children = !node.interfaces.is_empty() || !node.nodes.is_empty();
+	write_start_tag(f, depth, "node", &[("name", node.name.as_deref())], has_children)?;
+	if !has_children {
+		return Ok(());
+	}
+
+	for interface in &node.interfaces {
+		let has_children =
+			!interface.methods.is_empty() || !interface.signals.is_empty() || !interface.properties.is_empty() || !interface.annotations.is_empty();
+		write_start_tag(f, depth + 1, "interface", &[("name", Some(&interface.name))], has_children)?;
+		if !has_children {
+			continue;
+		}
+
+		for method in &interface.methods {
+			let has_children = !method.args.is_empty() || !method.annotations.is_empty();
+			write_start_tag(f, depth + 2, "method", &[("name", Some(&method.name))], has_children)?;
+			if has_children {
+				for arg in &method.args {
+					write_arg(f, depth + 3, arg, true)?;
+				}
+				write_annotations(f, depth + 3, &method.annotations)?;
+				write_end_tag(f, depth + 2, "method")?;
+			}
+		}
+
+		for signal in &interface.signals {
+			let has_children = !signal.args.is_empty() || !signal.annotations.is_empty();
+			write_start_tag(f, depth + 2, "signal", &[("name", Some(&signal.name))], has_children)?;
+			if has_children {
+				for arg in &signal.args {
+					write_arg(f, depth + 3, arg, false)?;
+				}
+				write_annotations(f, depth + 3, &signal.annotations)?;
+				write_end_tag(f, depth + 2, "signal")?;
+			}
+		}
+
+		for property in &interface.properties {
+			let r#type = property.r#type.to_string();
+			let access = match property.access {
+				Access::Read => "read",
+				Access::ReadWrite => "readwrite",
+				Access::Write => "write",
+			};
+			let has_children = !property.annotations.is_empty();
+			write_start_tag(
+				f,
+				depth + 2,
+				"property",
+				&[("name", Some(&property.name)), ("type", Some(&r#type)), ("access", Some(access))],
+				has_children,
+			)?;
+			if has_children {
+				write_annotations(f, depth + 3, &property.annotations)?;
+				write_end_tag(f, depth + 2, "property")?;
+			}
+		}
+
+		write_annotations(f, depth + 2, &interface.annotations)?;
+
+		write_end_tag(f, depth + 1, "interface")?;
+	}
+
+	for child in &node.nodes {
+		write_node(f, child, depth + 1)?;
+	}
+
+	write_end_tag(f, depth, "node")
+}
+
+fn write_arg(f: &mut std::fmt::Formatter<'_>, depth: usize, arg: &Arg, write_direction: bool) -> std::fmt::Result {
+	let r#type = arg.r#type.to_string();
+	let direction = match (write_direction, arg.direction) {
+		(true, Direction::In) => Some("in"),
+		(true, Direction::Out) => Some("out"),
+		(false, _) => None,
+	};
+	let has_children = !arg.annotations.is_empty();
+	write_start_tag(f, depth, "arg", &[("name", arg.name.as_deref()), ("type", Some(&r#type)), ("direction", direction)], has_children)?;
+	if has_children {
+		write_annotations(f, depth + 1, &arg.annotations)?;
+		write_end_tag(f, depth, "arg")?;
+	}
+	Ok(())
+}
+
+fn write_annotations(f: &mut std::fmt::Formatter<'_>, depth: usize, annotations: &[Annotation]) -> std::fmt::Result {
+	for annotation in annotations {
+		write_start_tag(f, depth, "annotation", &[("name", Some(&annotation.name)), ("value", Some(&annotation.value))], false)?;
+	}
+	Ok(())
+}
+
+/// Write the start tag of an element with the given attributes. Attributes whose values are `None` are skipped.
+///
+/// If the element does not have children, the tag is written as an empty-element tag.
+fn write_start_tag(
+	f: &mut std::fmt::Formatter<'_>,
+	depth: usize,
+	element: &str,
+	attributes: &[(&str, Option<&str>)],
+	has_children: bool,
+) -> std::fmt::Result {
+	write!(f, "{:indent$}<{}", "", element, indent = depth * 2)?;
+
+	for (name, value) in attributes {
+		if let Some(value) = value {
+			write!(f, r#" {}=""#, name)?;
+			for c in value.chars() {
+				match c {
+					'&' => f.write_str("&amp;")?,
+					'<' => f.write_str("&lt;")?,
+					'>' => f.write_str("&gt;")?,
+					'"' => f.write_str("&quot;")?,
+					c => std::fmt::Write::write_char(f, c)?,
+				}
+			}
+			f.write_str(r#"""#)?;
+		}
+	}
+
+	if has_children {
+		f.write_str(">\n")
+	}
+	else {
+		f.write_str("/>\n")
+	}
+}
+
+fn write_end_tag(f: &mut std::fmt::Formatter<'_>, depth: usize, element: &str) -> std::fmt::Result {
+	writeln!(f, "{:indent$}</{}>", "", element, indent = depth * 2)
+}
+
+/// An error from parsing introspection XML into a [`Node`].
+#[derive(Debug)]
+pub enum ParseError {
+	InvalidAttribute { element: &'static str, attribute: &'static str, value: String },
+	MissingAttribute { element: &'static str, attribute: &'static str },
+	MissingRootElement,
+	NestingTooDeep,
+	UnexpectedEndOfDocument,
+	UnexpectedRootElement(String),
+	Xml(xml::reader::Error),
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseError::InvalidAttribute { element, attribute, value } => write!(f, "{} element has invalid {} attribute {:?}", element, attribute, value),
+			ParseError::MissingAttribute { element, attribute } => write!(f, "{} element is missing the {} attribute", element, attribute),
+			ParseError::MissingRootElement => f.write_str("document does not have a root node element"),
+			ParseError::NestingTooDeep => write!(f, "node element is nested in more than {} node elements", MAX_NODE_DEPTH),
+			ParseError::UnexpectedEndOfDocument => f.write_str("unexpected end of document"),
+			ParseError::UnexpectedRootElement(element) => write!(f, "expected root element to be node but it is {}", element),
+			ParseError::Xml(_) => f.write_str("could not parse XML"),
+		}
+	}
+}
+
+impl std::error::Error for ParseError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		#[allow(clippy::match_same_arms)]
+		match self {
+			ParseError::InvalidAttribute { .. } => None,
+			ParseError::MissingAttribute { .. } => None,
+			ParseError::MissingRootElement => None,
+			ParseError::NestingTooDeep => None,
+			ParseError::UnexpectedEndOfDocument => None,
+			ParseError::UnexpectedRootElement(_) => None,
+			ParseError::Xml(err) => Some(err),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	fn parse(xml: &str) -> Result<super::Node, super::ParseError> {
+		xml.parse()
+	}
+
+	#[test]
+	fn test_parse_malformed() {
+		assert!(matches!(parse(""), Err(super::ParseError::Xml(_))));
+		assert!(matches!(parse("<node><interface name=\"org.example.Foo\"></node>"), Err(super::ParseError::Xml(_))));
+		assert!(matches!(parse("<interface/>"), Err(super::ParseError::UnexpectedRootElement(element)) if element == "interface"));
+		assert!(matches!(
+			parse("<node><interface/></node>"),
+			Err(super::ParseError::MissingAttribute { element: "interface", attribute: "name" }),
+		));
+		assert!(matches!(
+			parse(r#"<node><interface name="org.example.Foo"><property name="Bar" type="s" access="execute"/></interface></node>"#),
+			Err(super::ParseError::InvalidAttribute { element: "property", attribute: "access", value }) if value == "execute",
+		));
+	}
+
+	#[test]
+	fn test_parse_direction() {
+		let node = parse(r#"
+			<node>
+				<interface name="org.example.Foo">
+					<method name="Bar">
+						<arg name="a" type="s"/>
+						<arg name="b" type="s" direction="out"/>
+					</method>
+					<signal name="Baz">
+						<arg name="c" type="s" direction="in"/>
+					</signal>
+				</interface>
+			</node>
+		"#).unwrap();
+		let interface = &node.interfaces[0];
+		assert_eq!(interface.methods[0].args[0].direction, super::Direction::In);
+		assert_eq!(interface.methods[0].args[1].direction, super::Direction::Out);
+		assert_eq!(interface.signals[0].args[0].direction, super::Direction::Out);
+
+		assert!(matches!(
+			parse(r#"<node><interface name="org.example.Foo"><method name="Bar"><arg type="s" direction="inout"/></method></interface></node>"#),
+			Err(super::ParseError::InvalidAttribute { element: "arg", attribute: "direction", value }) if value == "inout",
+		));
+	}
+
+	#[test]
+	fn test_parse_type() {
+		for r#type in &["", "si", "a", "a{vs}", "(s", "m"] {
+			let xml = format!(r#"<node><interface name="org.example.Foo"><method name="Bar"><arg type="{}"/></method></interface></node>"#, r#type);
+			assert!(matches!(
+				parse(&xml),
+				Err(super::ParseError::InvalidAttribute { element: "arg", attribute: "type", value }) if value == *r#type,
+			), "{:?}", r#type);
+
+			let xml = format!(r#"<node><interface name="org.example.Foo"><property name="Bar" type="{}" access="read"/></interface></node>"#, r#type);
+			assert!(matches!(
+				parse(&xml),
+				Err(super::ParseError::InvalidAttribute { element: "property", attribute: "type", value }) if value == *r#type,
+			), "{:?}", r#type);
+		}
+
+		assert!(matches!(
+			parse(r#"<node><interface name="org.example.Foo"><method name="Bar"><arg name="a"/></method></interface></node>"#),
+			Err(super::ParseError::MissingAttribute { element: "arg", attribute: "type" }),
+		));
+	}
+
+	#[test]
+	fn test_parse_nesting_too_deep() {
+		fn nested_nodes(depth: usize) -> String {
+			format!("{}<node/>{}", "<node>".repeat(depth), "</node>".repeat(depth))
+		}
+
+		let mut node = parse(&nested_nodes(super::MAX_NODE_DEPTH)).unwrap();
+		for _ in 0..super::MAX_NODE_DEPTH {
+			node = node.nodes.pop().unwrap();
+		}
+		assert!(node.nodes.is_empty());
+
+		assert!(matches!(parse(&nested_nodes(super::MAX_NODE_DEPTH + 1)), Err(super::ParseError::NestingTooDeep)));
+		assert!(matches!(parse(&nested_nodes(100_000)), Err(super::ParseError::NestingTooDeep)));
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let annotations = || vec![super::Annotation { name: "org.freedesktop.DBus.Deprecated".to_owned(), value: "true".to_owned() }];
+
+		let node = super::Node {
+			name: None,
+			interfaces: vec![super::Interface {
+				name: "org.example.Foo".to_owned(),
+				methods: vec![
+					super::Method {
+						name: "Bar".to_owned(),
+						args: vec![
+							super::Arg {
+								name: Some("a & <b>".to_owned()),
+								r#type: "a{sv}".parse().unwrap(),
+								direction: super::Direction::In,
+								annotations: annotations(),
+							},
+							super::Arg {
+								name: None,
+								r#type: "(uo)".parse().unwrap(),
+								direction: super::Direction::Out,
+								annotations: vec![],
+							},
+						],
+						annotations: annotations(),
+					},
+					super::Method {
+						name: "Baz".to_owned(),
+						args: vec![],
+						annotations: vec![],
+					},
+				],
+				signals: vec![super::Signal {
+					name: "Changed".to_owned(),
+					args: vec![super::Arg {
+						name: Some("value".to_owned()),
+						r#type: "v".parse().unwrap(),
+						direction: super::Direction::Out,
+						annotations: vec![],
+					}],
+					annotations: vec![],
+				}],
+				properties: vec![
+					super::Property {
+						name: "Quux".to_owned(),
+						r#type: "s".parse().unwrap(),
+						access: super::Access::Read,
+						annotations: vec![],
+					},
+					super::Property {
+						name: "Quuz".to_owned(),
+						r#type: "ah".parse().unwrap(),
+						access: super::Access::ReadWrite,
+						annotations: vec![super::Annotation { name: "org.example.Note".to_owned(), value: r#"say "hi""#.to_owned() }],
+					},
+					super::Property {
+						name: "Corge".to_owned(),
+						r#type: "b".parse().unwrap(),
+						access: super::Access::Write,
+						annotations: vec![],
+					},
+				],
+				annotations: annotations(),
+			}],
+			nodes: vec![
+				super::Node {
+					name: Some("child".to_owned()),
+					interfaces: vec![],
+					nodes: vec![super::Node { name: Some("grandchild".to_owned()), ..Default::default() }],
+				},
+				super::Node { name: Some("empty".to_owned()), ..Default::default() },
+			],
+		};
+
+		let xml = node.to_string();
+		let reparsed = parse(&xml).unwrap();
+		assert_eq!(node, reparsed);
+		assert_eq!(xml, reparsed.to_string());
+	}
+}
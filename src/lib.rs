@@ -2,11 +2,21 @@
 #![deny(clippy::all, clippy::pedantic)]
 #![allow(
 	clippy::default_trait_access,
+	clippy::format_collect,
+	clippy::ignored_unit_patterns,
+	clippy::io_other_error,
+	clippy::legacy_numeric_constants,
 	clippy::let_and_return,
 	clippy::let_unit_value,
+	clippy::manual_let_else,
 	clippy::missing_errors_doc,
+	clippy::missing_fields_in_debug,
+	clippy::missing_panics_doc,
 	clippy::must_use_candidate,
+	clippy::needless_continue,
 	clippy::shadow_unrelated,
+	clippy::uninlined_format_args,
+	clippy::unnecessary_debug_formatting,
 	clippy::unneeded_field_pattern,
 )]
 
@@ -44,8 +54,8 @@
 //! // on the `/org/freedesktop/DBus` object at the destination `org.freedesktop.DBus`.
 //! let names = {
 //!     let obj = OrgFreeDesktopDbusObject {
-//!         name: "org.freedesktop.DBus".into(),
-//!         path: dbus_pure::proto::ObjectPath("/org/freedesktop/DBus".into()),
+//!         name: dbus_pure::proto::BusName::new("org.freedesktop.DBus")?,
+//!         path: dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus")?,
 //!     };
 //!     let names = obj.list_names(&mut client)?;
 //!     names
@@ -76,10 +86,10 @@
 //! let names = {
 //!     let body =
 //!         client.method_call(
-//!             "org.freedesktop.DBus",
-//!             dbus_pure::proto::ObjectPath("/org/freedesktop/DBus".into()),
-//!             "org.freedesktop.DBus",
-//!             "ListNames",
+//!             dbus_pure::proto::BusName::new("org.freedesktop.DBus")?,
+//!             dbus_pure::proto::ObjectPath::new("/org/freedesktop/DBus")?,
+//!             dbus_pure::proto::InterfaceName::new("org.freedesktop.DBus")?,
+//!             dbus_pure::proto::MemberName::new("ListNames")?,
 //!             None,
 //!         )?
 //!         .ok_or("ListNames response does not have a body")?;
@@ -101,7 +111,9 @@ mod client;
 pub use client::{
 	Client,
 	CreateClientError,
+	IntrospectError,
 	MethodCallError,
+	WaitForNameError,
 };
 
 mod conn;
@@ -113,3 +125,22 @@ pub use conn::{
 	SaslAuthType,
 	SendError,
 };
+
+mod error;
+pub use error::{
+	DBusError,
+	ErrorKind,
+};
+
+pub mod introspection;
+
+mod match_rule;
+pub use match_rule::{
+	MatchRule,
+};
+
+mod name_watcher;
+pub use name_watcher::{
+	NameEvent,
+	NameWatcher,
+};
@@ -0,0 +1,261 @@
+/// A rule for subscribing to signals using [`crate::Client::add_match`].
+///
+/// Fields that are `None` are not used to filter signals.
+///
+/// Use `.to_string()` to get the string representation of the rule, as used by the `org.freedesktop.DBus.AddMatch` method.
+#[derive(Clone, Debug, Default)]
+pub struct MatchRule<'a> {
+	/// The sender of the signal.
+	///
+	/// This can be a unique name or a well-known name. In the latter case, the client tracks the unique name
+	/// of the well-known name's current owner, so that signals sent by any other connection are not matched.
+	pub sender: Option<std::borrow::Cow<'a, str>>,
+
+	/// The object path that emitted the signal.
+	pub path: Option<crate::proto::ObjectPath<'a>>,
+
+	/// The interface of the signal.
+	pub interface: Option<std::borrow::Cow<'a, str>>,
+
+	/// The name of the signal.
+	pub member: Option<std::borrow::Cow<'a, str>>,
+}
+
+impl MatchRule<'_> {
+	/// The well-known name in the `sender` field, if it is one.
+	///
+	/// Unique names and the name of the message bus itself are excluded, since they are never owned by any other connection.
+	pub(crate) fn well_known_sender(&self) -> Option<&str> {
+		match self.sender.as_deref() {
+			Some(sender) if !sender.starts_with(':') && sender != "org.freedesktop.DBus" => Some(sender),
+			_ => None,
+		}
+	}
+
+	/// Returns true if this rule can match an `org.freedesktop.DBus.NameOwnerChanged` signal.
+	pub(crate) fn could_match_name_owner_changed(&self) -> bool {
+		matches!(self.sender.as_deref(), None | Some("org.freedesktop.DBus")) &&
+		matches!(self.path.as_deref(), None | Some("/org/freedesktop/DBus")) &&
+		matches!(self.interface.as_deref(), None | Some("org.freedesktop.DBus")) &&
+		matches!(self.member.as_deref(), None | Some("NameOwnerChanged"))
+	}
+
+	/// Returns true if this rule matches the given message.
+	///
+	/// `owner_of` is used to look up the unique name of the current owner of the well-known name in the `sender` field.
+	pub(crate) fn matches<'b>(
+		&self,
+		message: &crate::proto::Message<'_>,
+		owner_of: impl FnOnce(&str) -> Option<&'b str>,
+	) -> bool {
+		let (interface, member, path) = match &message.header.r#type {
+			crate::proto::MessageType::Signal { interface, member, path } => (interface, member, path),
+			_ => return false,
+		};
+
+		if matches!(&self.interface, Some(expected) if expected != interface) {
+			return false;
+		}
+
+		if matches!(&self.member, Some(expected) if expected != member) {
+			return false;
+		}
+
+		if matches!(&self.path, Some(expected) if expected != path) {
+			return false;
+		}
+
+		if let Some(expected) = &self.sender {
+			let sender = match message.sender() {
+				Some(sender) => sender,
+				None => return false,
+			};
+
+			let expected = match self.well_known_sender() {
+				Some(well_known_name) => match owner_of(well_known_name) {
+					Some(owner) => owner,
+					None => return false,
+				},
+				None => expected,
+			};
+
+			if sender != expected {
+				return false;
+			}
+		}
+
+		true
+	}
+}
+
+impl std::fmt::Display for MatchRule<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("type='signal'")?;
+
+		let fields = [
+			("sender", self.sender.as_deref()),
+			("path", self.path.as_deref()),
+			("interface", self.interface.as_deref()),
+			("member", self.member.as_deref()),
+		];
+		for (key, value) in &fields {
+			if let Some(value) = value {
+				write_field(f, key, value)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// The rule for the `org.freedesktop.DBus.NameOwnerChanged` signals of the given names.
+///
+/// `key` is `arg0` to match the given name exactly, or `arg0namespace` to match the given name and all names in its namespace.
+pub(crate) fn name_owner_changed_match_rule(key: &str, name: &str) -> String {
+	let rule = MatchRule {
+		sender: Some("org.freedesktop.DBus".into()),
+		path: Some(crate::proto::ObjectPath::new("/org/freedesktop/DBus").expect("hard-coded path is valid")),
+		interface: Some("org.freedesktop.DBus".into()),
+		member: Some("NameOwnerChanged".into()),
+	};
+	let mut rule = rule.to_string();
+	write_field(&mut rule, key, name).expect("writing to a String cannot fail");
+	rule
+}
+
+fn write_field(f: &mut impl std::fmt::Write, key: &str, value: &str) -> std::fmt::Result {
+	write!(f, ",{}='", key)?;
+
+	// Apostrophes cannot be escaped inside a quoted value, so they have to be written as an unquoted escaped apostrophe
+	// between two quoted values instead.
+	let mut parts = value.split('\'');
+	f.write_str(parts.next().expect("split returns at least one subslice"))?;
+	for part in parts {
+		f.write_str("'\\''")?;
+		f.write_str(part)?;
+	}
+
+	f.write_str("'")
+}
+
+#[cfg(test)]
+mod tests {
+	fn signal(sender: &str, path: &str, interface: &str, member: &str) -> crate::proto::Message<'static> {
+		crate::proto::Message::signal(
+			crate::proto::ObjectPath::new(path.to_owned()).unwrap(),
+			crate::proto::InterfaceName::new(interface.to_owned()).unwrap(),
+			crate::proto::MemberName::new(member.to_owned()).unwrap(),
+		)
+		.with_field(crate::proto::MessageHeaderField::Sender(sender.to_owned().into()))
+	}
+
+	#[test]
+	fn test_display() {
+		assert_eq!(super::MatchRule::default().to_string(), "type='signal'");
+
+		let rule = super::MatchRule {
+			sender: Some("org.example.Foo".into()),
+			path: Some(crate::proto::ObjectPath::new("/org/example/Foo").unwrap()),
+			interface: Some("org.example.Foo".into()),
+			member: Some("Bar".into()),
+		};
+		assert_eq!(
+			rule.to_string(),
+			"type='signal',sender='org.example.Foo',path='/org/example/Foo',interface='org.example.Foo',member='Bar'",
+		);
+
+		let rule = super::MatchRule {
+			member: Some("it's".into()),
+			..Default::default()
+		};
+		assert_eq!(rule.to_string(), r"type='signal',member='it'\''s'");
+
+		let rule = super::MatchRule {
+			member: Some("'a''".into()),
+			..Default::default()
+		};
+		assert_eq!(rule.to_string(), r"type='signal',member=''\''a'\'''\'''");
+	}
+
+	#[test]
+	fn test_matches() {
+		let rule = super::MatchRule {
+			path: Some(crate::proto::ObjectPath::new("/org/example/Foo").unwrap()),
+			interface: Some("org.example.Foo".into()),
+			member: Some("Bar".into()),
+			..Default::default()
+		};
+		let no_owner = |_: &str| -> Option<&str> { panic!("sender is not a well-known name") };
+		assert!(rule.matches(&signal(":1.5", "/org/example/Foo", "org.example.Foo", "Bar"), no_owner));
+		assert!(!rule.matches(&signal(":1.5", "/org/example/Other", "org.example.Foo", "Bar"), no_owner));
+		assert!(!rule.matches(&signal(":1.5", "/org/example/Foo", "org.example.Other", "Bar"), no_owner));
+		assert!(!rule.matches(&signal(":1.5", "/org/example/Foo", "org.example.Foo", "Other"), no_owner));
+
+		let method_call = crate::proto::Message::method_call(
+			crate::proto::BusName::new("org.example.Foo").unwrap(),
+			crate::proto::ObjectPath::new("/org/example/Foo").unwrap(),
+			crate::proto::InterfaceName::new("org.example.Foo").unwrap(),
+			crate::proto::MemberName::new("Bar").unwrap(),
+		);
+		assert!(!rule.matches(&method_call, no_owner));
+
+		let rule = super::MatchRule {
+			sender: Some(":1.5".into()),
+			..Default::default()
+		};
+		assert!(rule.matches(&signal(":1.5", "/", "org.example.Foo", "Bar"), no_owner));
+		assert!(!rule.matches(&signal(":1.6", "/", "org.example.Foo", "Bar"), no_owner));
+		let mut without_sender = signal(":1.5", "/", "org.example.Foo", "Bar");
+		without_sender.header.fields = vec![].into();
+		assert!(!rule.matches(&without_sender, no_owner));
+
+		let rule = super::MatchRule {
+			sender: Some("org.example.Foo".into()),
+			..Default::default()
+		};
+		let owner_of = |name: &str| { assert_eq!(name, "org.example.Foo"); Some(":1.5") };
+		assert!(rule.matches(&signal(":1.5", "/", "org.example.Foo", "Bar"), owner_of));
+		assert!(!rule.matches(&signal(":1.6", "/", "org.example.Foo", "Bar"), owner_of));
+		assert!(!rule.matches(&signal("org.example.Foo", "/", "org.example.Foo", "Bar"), owner_of));
+		assert!(!rule.matches(&signal(":1.5", "/", "org.example.Foo", "Bar"), |_| None));
+	}
+
+	#[test]
+	fn test_name_owner_changed_match_rule() {
+		assert_eq!(
+			super::name_owner_changed_match_rule("arg0", "org.example.Foo"),
+			"type='signal',sender='org.freedesktop.DBus',path='/org/freedesktop/DBus',interface='org.freedesktop.DBus',member='NameOwnerChanged',arg0='org.example.Foo'",
+		);
+
+		// Apostrophes in the name are escaped, so they cannot add more keys to the rule.
+		assert_eq!(
+			super::name_owner_changed_match_rule("arg0namespace", "org.example',member='Foo"),
+			r"type='signal',sender='org.freedesktop.DBus',path='/org/freedesktop/DBus',interface='org.freedesktop.DBus',member='NameOwnerChanged',arg0namespace='org.example'\'',member='\''Foo'",
+		);
+	}
+
+	#[test]
+	fn test_could_match_name_owner_changed() {
+		assert!(super::MatchRule::default().could_match_name_owner_changed());
+
+		let rule = super::MatchRule {
+			sender: Some("org.freedesktop.DBus".into()),
+			path: Some(crate::proto::ObjectPath::new("/org/freedesktop/DBus").unwrap()),
+			interface: Some("org.freedesktop.DBus".into()),
+			member: Some("NameOwnerChanged".into()),
+		};
+		assert!(rule.could_match_name_owner_changed());
+
+		let rule = super::MatchRule {
+			member: Some("NameAcquired".into()),
+			..rule
+		};
+		assert!(!rule.could_match_name_owner_changed());
+
+		let rule = super::MatchRule {
+			sender: Some("org.example.Foo".into()),
+			..Default::default()
+		};
+		assert!(!rule.could_match_name_owner_changed());
+	}
+}
@@ -0,0 +1,178 @@
+/// Watches a bus name, or all bus names under a namespace, for their owners appearing and disappearing.
+///
+/// The watcher subscribes to the `org.freedesktop.DBus.NameOwnerChanged` signal for the watched names,
+/// and then seeds itself with the names' current owners. Use [`NameWatcher::next_event`] to receive
+/// the resulting [`NameEvent`]s.
+///
+/// Only one watcher should watch any particular name at a time, since the signals consumed by one watcher
+/// are not seen by the others.
+///
+/// Call [`NameWatcher::stop`] when the watcher is no longer needed, to unsubscribe from the signal.
+#[derive(Debug)]
+pub struct NameWatcher {
+	target: Target,
+	match_rule: String,
+	owners: std::collections::BTreeMap<String, String>,
+	pending_events: std::collections::VecDeque<NameEvent>,
+}
+
+/// An event emitted by a [`NameWatcher`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum NameEvent {
+	/// The name has a new owner. This is emitted both when the name is acquired and when it moves from one owner to another.
+	Appeared {
+		name: String,
+		owner: String,
+	},
+
+	/// The name no longer has an owner.
+	Vanished {
+		name: String,
+	},
+}
+
+#[derive(Debug)]
+enum Target {
+	Name(String),
+	Namespace(String),
+}
+
+impl NameWatcher {
+	/// Watch the owner of the given bus name.
+	///
+	/// If the name currently has an owner, the first event returned by [`NameWatcher::next_event`] will be a `NameEvent::Appeared` for it.
+	pub fn watch_name(client: &mut crate::Client, name: &str) -> Result<Self, crate::MethodCallError> {
+		let match_rule = crate::match_rule::name_owner_changed_match_rule("arg0", name);
+		NameWatcher::new(client, Target::Name(name.to_owned()), match_rule)
+	}
+
+	/// Watch the owners of all bus names in the given namespace, such as `org.mpris.MediaPlayer2`.
+	///
+	/// A name is in the namespace if it is equal to the namespace, or starts with the namespace followed by a `.`.
+	/// This is the same as the `arg0namespace` match rule.
+	///
+	/// The first events returned by [`NameWatcher::next_event`] will be `NameEvent::Appeared` for all names in the namespace
+	/// that currently have owners.
+	pub fn watch_namespace(client: &mut crate::Client, namespace: &str) -> Result<Self, crate::MethodCallError> {
+		let match_rule = crate::match_rule::name_owner_changed_match_rule("arg0namespace", namespace);
+		NameWatcher::new(client, Target::Namespace(namespace.to_owned()), match_rule)
+	}
+
+	fn new(client: &mut crate::Client, target: Target, match_rule: String) -> Result<Self, crate::MethodCallError> {
+		use crate::client::OrgFreeDesktopDbusInterface;
+
+		let obj = crate::client::OrgFreeDesktopDbusObject::bus();
+
+		// Subscribe before querying the current owners, so that no changes are missed in between.
+		// Any signals that duplicate the queried state are filtered out by `NameWatcher::handle_name_owner_changed`.
+		let () = obj.add_match(client, &match_rule)?;
+		client.num_name_owner_changed_subscriptions += 1;
+
+		let mut watcher = NameWatcher {
+			target,
+			match_rule,
+			owners: Default::default(),
+			pending_events: Default::default(),
+		};
+
+		if let Err(err) = watcher.query_owners(client) {
+			let _ = watcher.stop(client);
+			return Err(err);
+		}
+
+		Ok(watcher)
+	}
+
+	fn query_owners(&mut self, client: &mut crate::Client) -> Result<(), crate::MethodCallError> {
+		use crate::client::OrgFreeDesktopDbusInterface;
+
+		let obj = crate::client::OrgFreeDesktopDbusObject::bus();
+
+		let names = match &self.target {
+			Target::Name(name) => vec![name.clone()],
+
+			Target::Namespace(_) => {
+				let mut names = obj.list_names(client)?;
+				names.retain(|name| self.target.matches(name));
+				names
+			},
+		};
+
+		for name in names {
+			let owner = match obj.get_name_owner(client, &name) {
+				Ok(owner) => owner,
+				Err(crate::MethodCallError::Error(crate::ErrorKind::NameHasNoOwner, _)) => continue,
+				Err(err) => return Err(err),
+			};
+			self.handle_name_owner_changed(&name, &owner);
+		}
+
+		Ok(())
+	}
+
+	/// The current owner of the given name, if it is being watched by this watcher and has an owner.
+	pub fn owner(&self, name: &str) -> Option<&str> {
+		self.owners.get(name).map(String::as_str)
+	}
+
+	/// The names being watched by this watcher that currently have owners, along with their owners.
+	pub fn owners(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+		self.owners.iter().map(|(name, owner)| (&**name, &**owner))
+	}
+
+	/// Receive the next event for the watched names.
+	///
+	/// Blocks until an event is available, or until the client's read timeout expires.
+	/// Messages that are not relevant to this watcher are left for subsequent calls to [`crate::Client::recv`].
+	pub fn next_event(&mut self, client: &mut crate::Client) -> Result<NameEvent, crate::RecvError> {
+		loop {
+			if let Some(event) = self.pending_events.pop_front() {
+				return Ok(event);
+			}
+
+			let target = &self.target;
+			let message = client.recv_matching(|message| match crate::client::name_owner_changed_args(message) {
+				Some((name, _, _)) => target.matches(name),
+				None => false,
+			})?;
+
+			let (name, _, new_owner) =
+				crate::client::name_owner_changed_args(&message)
+				.expect("message was matched by the predicate");
+			self.handle_name_owner_changed(name, new_owner);
+		}
+	}
+
+	/// Unsubscribe from the `NameOwnerChanged` signal that this watcher subscribed to.
+	pub fn stop(self, client: &mut crate::Client) -> Result<(), crate::MethodCallError> {
+		use crate::client::OrgFreeDesktopDbusInterface;
+
+		client.num_name_owner_changed_subscriptions = client.num_name_owner_changed_subscriptions.saturating_sub(1);
+
+		let obj = crate::client::OrgFreeDesktopDbusObject::bus();
+		obj.remove_match(client, &self.match_rule)
+	}
+
+	fn handle_name_owner_changed(&mut self, name: &str, new_owner: &str) {
+		if new_owner.is_empty() {
+			if self.owners.remove(name).is_some() {
+				self.pending_events.push_back(NameEvent::Vanished { name: name.to_owned() });
+			}
+		}
+		else if self.owners.get(name).map(String::as_str) != Some(new_owner) {
+			let _ = self.owners.insert(name.to_owned(), new_owner.to_owned());
+			self.pending_events.push_back(NameEvent::Appeared { name: name.to_owned(), owner: new_owner.to_owned() });
+		}
+	}
+}
+
+impl Target {
+	fn matches(&self, name: &str) -> bool {
+		match self {
+			Target::Name(expected) => name == expected,
+			Target::Namespace(namespace) =>
+				name.starts_with(&**namespace) &&
+				(name.len() == namespace.len() || name[namespace.len()..].starts_with('.')),
+		}
+	}
+}